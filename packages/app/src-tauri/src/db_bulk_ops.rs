@@ -4,7 +4,9 @@
 //! significantly reduce IPC overhead compared to individual row operations.
 
 use anyhow::Result;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
+use rusqlite::types::Value as SqlValue;
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
@@ -41,7 +43,7 @@ where
 }
 
 /// A single channel to be inserted/updated
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BulkChannel {
     pub stream_id: String,
     pub source_id: String,
@@ -160,6 +162,8 @@ pub struct BulkProgram {
     pub start: String, // ISO 8601 datetime string
     pub end: String,   // ISO 8601 datetime string
     pub source_id: String,
+    #[serde(default)]
+    pub icon: Option<String>,
 }
 
 /// A single VOD movie to be inserted/updated
@@ -277,17 +281,27 @@ pub struct BulkResult {
     pub updated: usize,
     pub deleted: usize,
     pub duration_ms: u64,
+    /// Keys of newly inserted rows (only when ids were requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inserted_ids: Option<Vec<String>>,
+    /// Keys of rows that already existed and were updated (only when ids were requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_ids: Option<Vec<String>>,
 }
 
 /// Bulk insert or replace channels (upsert operation)
 /// Uses a single prepared statement in a transaction for maximum performance
-pub fn bulk_upsert_channels(db: &DvrDatabase, channels: Vec<BulkChannel>) -> Result<BulkResult> {
-    with_db_retry(|| bulk_upsert_channels_inner(db, channels.clone()))
+///
+/// With `return_ids`, the stream_ids of inserted and updated channels are collected
+/// into the result. This costs an existence check per row, so leave it off for plain syncs.
+pub fn bulk_upsert_channels(db: &DvrDatabase, channels: Vec<BulkChannel>, return_ids: bool) -> Result<BulkResult> {
+    with_db_retry(|| bulk_upsert_channels_inner(db, channels.clone(), return_ids))
 }
 
-fn bulk_upsert_channels_inner(db: &DvrDatabase, channels: Vec<BulkChannel>) -> Result<BulkResult> {
+fn bulk_upsert_channels_inner(db: &DvrDatabase, channels: Vec<BulkChannel>, return_ids: bool) -> Result<BulkResult> {
     let start = std::time::Instant::now();
     let mut conn = db.get_conn()?;
+    crate::db_queries::ensure_channels_fts(&conn)?;
 
     let tx = conn.transaction()?;
 
@@ -318,11 +332,24 @@ fn bulk_upsert_channels_inner(db: &DvrDatabase, channels: Vec<BulkChannel>) -> R
             live = excluded.live",
     )?;
 
+    let mut exists_stmt = if return_ids {
+        Some(tx.prepare("SELECT EXISTS(SELECT 1 FROM channels WHERE stream_id = ?1)")?)
+    } else {
+        None
+    };
+
     let mut inserted = 0;
     let mut updated = 0;
+    let mut inserted_ids = Vec::new();
+    let mut updated_ids = Vec::new();
 
     for channel in channels {
-        match stmt.execute(params![
+        // The upsert reports one changed row either way, so check beforehand when ids are wanted
+        let existed = match exists_stmt.as_mut() {
+            Some(stmt) => Some(stmt.query_row(params![channel.stream_id], |row| row.get::<_, bool>(0))?),
+            None => None,
+        };
+        let changes = stmt.execute(params![
             channel.stream_id,
             channel.source_id,
             channel.category_ids,
@@ -341,13 +368,24 @@ fn bulk_upsert_channels_inner(db: &DvrDatabase, channels: Vec<BulkChannel>) -> R
             channel.xmltv_id,
             channel.series_no,
             channel.live,
-        ])? {
-            1 => inserted += 1,
-            _ => updated += 1,
+        ])?;
+
+        match existed {
+            Some(false) => {
+                inserted += 1;
+                inserted_ids.push(channel.stream_id);
+            }
+            Some(true) => {
+                updated += 1;
+                updated_ids.push(channel.stream_id);
+            }
+            None if changes == 1 => inserted += 1,
+            None => updated += 1,
         }
     }
 
     stmt.finalize()?;
+    exists_stmt.map(|stmt| stmt.finalize()).transpose()?;
     tx.commit()?;
 
     let duration_ms = start.elapsed().as_millis() as u64;
@@ -362,6 +400,8 @@ fn bulk_upsert_channels_inner(db: &DvrDatabase, channels: Vec<BulkChannel>) -> R
         updated,
         deleted: 0,
         duration_ms,
+        inserted_ids: return_ids.then_some(inserted_ids),
+        updated_ids: return_ids.then_some(updated_ids),
     })
 }
 
@@ -431,6 +471,8 @@ fn bulk_upsert_categories_inner(
         updated,
         deleted: 0,
         duration_ms,
+        inserted_ids: None,
+        updated_ids: None,
     })
 }
 
@@ -488,6 +530,8 @@ pub fn bulk_upsert_vod_categories(
         updated,
         deleted: 0,
         duration_ms,
+        inserted_ids: None,
+        updated_ids: None,
     })
 }
 
@@ -520,8 +564,8 @@ fn bulk_replace_programs_inner(
     // Insert new programs (use OR IGNORE to skip duplicates)
     let mut stmt = tx.prepare(
         "INSERT OR IGNORE INTO programs (
-            id, stream_id, title, description, start, end, source_id
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            id, stream_id, title, description, start, end, source_id, icon
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
     )?;
 
     let mut inserted = 0;
@@ -536,6 +580,7 @@ fn bulk_replace_programs_inner(
             program.start,
             program.end,
             program.source_id,
+            program.icon,
         ]) {
             Ok(1) => inserted += 1,
             Ok(_) => duplicates += 1, // Row was ignored (duplicate)
@@ -562,6 +607,105 @@ fn bulk_replace_programs_inner(
         updated: 0,
         deleted: deleted as usize,
         duration_ms,
+        inserted_ids: None,
+        updated_ids: None,
+    })
+}
+
+/// How long merged EPG data is kept after a program has ended
+pub const DEFAULT_PROGRAM_RETENTION_DAYS: i64 = 7;
+
+/// Bulk merge EPG programs with transaction
+/// Upserts by program id (`stream_id` + `start`) instead of clearing the source first,
+/// so catch-up data survives and the guide never goes empty mid-sync. Only programs
+/// that ended more than `retention_days` ago are pruned.
+pub fn bulk_merge_programs(
+    db: &DvrDatabase,
+    source_id: &str,
+    programs: Vec<BulkProgram>,
+    retention_days: i64,
+) -> Result<BulkResult> {
+    with_db_retry(|| bulk_merge_programs_inner(db, source_id, programs.clone(), retention_days))
+}
+
+fn bulk_merge_programs_inner(
+    db: &DvrDatabase,
+    source_id: &str,
+    programs: Vec<BulkProgram>,
+    retention_days: i64,
+) -> Result<BulkResult> {
+    let start = std::time::Instant::now();
+    let mut conn = db.get_conn()?;
+
+    let tx = conn.transaction()?;
+
+    let mut stmt = tx.prepare(
+        "INSERT INTO programs (
+            id, stream_id, title, description, start, end, source_id, icon
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        ON CONFLICT(id) DO UPDATE SET
+            stream_id = excluded.stream_id,
+            title = excluded.title,
+            description = excluded.description,
+            start = excluded.start,
+            end = excluded.end,
+            source_id = excluded.source_id,
+            icon = COALESCE(excluded.icon, programs.icon)",
+    )?;
+
+    // changes() is 1 for both branches of an upsert, so check for the row first
+    let mut exists_stmt = tx.prepare("SELECT EXISTS(SELECT 1 FROM programs WHERE id = ?1)")?;
+
+    let mut inserted = 0;
+    let mut updated = 0;
+
+    for program in programs {
+        let existed: bool = exists_stmt.query_row([&program.id], |row| row.get(0))?;
+        stmt.execute(params![
+            program.id,
+            program.stream_id,
+            program.title,
+            program.description,
+            program.start,
+            program.end,
+            program.source_id,
+            program.icon,
+        ])?;
+        if existed {
+            updated += 1;
+        } else {
+            inserted += 1;
+        }
+    }
+
+    stmt.finalize()?;
+    exists_stmt.finalize()?;
+
+    // Prune only programs that ended before the retention window
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(retention_days.max(0)))
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string();
+    let deleted = tx.execute(
+        "DELETE FROM programs WHERE source_id = ?1 AND datetime(end) < datetime(?2)",
+        params![source_id, cutoff],
+    )?;
+
+    tx.commit()?;
+
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    info!(
+        "Bulk merge programs for {}: {} inserted, {} updated, {} pruned in {}ms",
+        source_id, inserted, updated, deleted, duration_ms
+    );
+
+    Ok(BulkResult {
+        inserted,
+        updated,
+        deleted,
+        duration_ms,
+        inserted_ids: None,
+        updated_ids: None,
     })
 }
 
@@ -653,6 +797,8 @@ pub fn bulk_upsert_movies(db: &DvrDatabase, movies: Vec<BulkMovie>) -> Result<Bu
         updated,
         deleted: 0,
         duration_ms,
+        inserted_ids: None,
+        updated_ids: None,
     })
 }
 
@@ -757,12 +903,117 @@ pub fn bulk_upsert_series(db: &DvrDatabase, series: Vec<BulkSeries>) -> Result<B
         updated,
         deleted: 0,
         duration_ms,
+        inserted_ids: None,
+        updated_ids: None,
+    })
+}
+
+/// Provider-supplied channel columns compared by `diff_channels`, in select order.
+/// is_favorite and enabled are user state and never count as a change.
+const CHANNEL_DIFF_COLUMNS: &str = "category_ids, name, channel_num, stream_type, stream_icon, \
+     epg_channel_id, added, custom_sid, tv_archive, direct_source, direct_url, xmltv_id, series_no, live";
+
+/// Render a column value as text so rows written as numbers or as strings compare equal
+fn sql_value_text(value: SqlValue) -> Option<String> {
+    match value {
+        SqlValue::Null => None,
+        SqlValue::Integer(n) => Some(n.to_string()),
+        SqlValue::Real(f) => Some(f.to_string()),
+        SqlValue::Text(t) => Some(t),
+        SqlValue::Blob(_) => None,
+    }
+}
+
+impl BulkChannel {
+    /// Values for `CHANNEL_DIFF_COLUMNS`, rendered like `sql_value_text`
+    fn diff_fields(&self) -> Vec<Option<String>> {
+        let num = |v: Option<i32>| v.map(|n| n.to_string());
+        vec![
+            self.category_ids.clone(),
+            Some(self.name.clone()),
+            num(self.channel_num),
+            self.stream_type.clone(),
+            self.stream_icon.clone(),
+            self.epg_channel_id.clone(),
+            self.added.clone(),
+            self.custom_sid.clone(),
+            num(self.tv_archive),
+            self.direct_source.clone(),
+            self.direct_url.clone(),
+            self.xmltv_id.clone(),
+            num(self.series_no),
+            num(self.live),
+        ]
+    }
+}
+
+/// Difference between a provider's channel list and what is stored for the source
+#[derive(Debug, Serialize)]
+pub struct ChannelDiff {
+    /// Channels not stored yet
+    pub to_insert: Vec<BulkChannel>,
+    /// Stored channels whose provider fields changed
+    pub to_update: Vec<BulkChannel>,
+    /// stream_ids stored for the source but missing from the incoming list
+    pub to_delete: Vec<String>,
+    pub unchanged: usize,
+}
+
+/// Compare incoming channels against the stored rows for `source_id`,
+/// so a sync only has to write what actually changed
+pub fn diff_channels(db: &DvrDatabase, source_id: &str, incoming: Vec<BulkChannel>) -> Result<ChannelDiff> {
+    let conn = db.get_conn()?;
+    let field_count = CHANNEL_DIFF_COLUMNS.split(',').count();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT stream_id, {} FROM channels WHERE source_id = ?1",
+        CHANNEL_DIFF_COLUMNS
+    ))?;
+    let mut existing: HashMap<String, Vec<Option<String>>> = stmt
+        .query_map(params![source_id], |row| {
+            let stream_id: String = row.get(0)?;
+            let fields = (1..=field_count)
+                .map(|i| row.get::<_, SqlValue>(i).map(sql_value_text))
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok((stream_id, fields))
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut to_insert = Vec::new();
+    let mut to_update = Vec::new();
+    let mut unchanged = 0;
+
+    // Matched rows are removed from the map, so whatever is left afterwards is gone upstream
+    for channel in incoming {
+        match existing.remove(&channel.stream_id) {
+            None => to_insert.push(channel),
+            Some(stored) if stored != channel.diff_fields() => to_update.push(channel),
+            Some(_) => unchanged += 1,
+        }
+    }
+    let to_delete: Vec<String> = existing.into_keys().collect();
+
+    info!(
+        "Channel diff for {}: {} new, {} changed, {} removed, {} unchanged",
+        source_id,
+        to_insert.len(),
+        to_update.len(),
+        to_delete.len(),
+        unchanged
+    );
+
+    Ok(ChannelDiff {
+        to_insert,
+        to_update,
+        to_delete,
+        unchanged,
     })
 }
 
 /// Delete channels by stream_id
 pub fn bulk_delete_channels(db: &DvrDatabase, stream_ids: Vec<String>) -> Result<usize> {
     let mut conn = db.get_conn()?;
+    crate::db_queries::ensure_channels_fts(&conn)?;
     let tx = conn.transaction()?;
 
     let placeholders: Vec<String> = stream_ids.iter().map(|_| "?".to_string()).collect();
@@ -771,12 +1022,12 @@ pub fn bulk_delete_channels(db: &DvrDatabase, stream_ids: Vec<String>) -> Result
         placeholders.join(", ")
     );
 
-    let mut stmt = tx.prepare(&sql)?;
     let params: Vec<&dyn rusqlite::ToSql> = stream_ids
         .iter()
         .map(|id| id as &dyn rusqlite::ToSql)
         .collect();
 
+    let mut stmt = tx.prepare(&sql)?;
     let deleted = stmt.execute(rusqlite::params_from_iter(params.iter()))?;
     stmt.finalize()?;
     tx.commit()?;
@@ -786,6 +1037,33 @@ pub fn bulk_delete_channels(db: &DvrDatabase, stream_ids: Vec<String>) -> Result
     Ok(deleted as usize)
 }
 
+/// Whether a channel exists and has neither a provider logo nor a captured one
+pub fn channel_missing_icon(db: &DvrDatabase, stream_id: &str) -> Result<bool> {
+    let conn = db.get_conn()?;
+    let missing = conn
+        .query_row(
+            "SELECT COALESCE(stream_icon, '') = '' AND COALESCE(captured_icon, '') = ''
+             FROM channels WHERE stream_id = ?1",
+            [stream_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(missing.unwrap_or(false))
+}
+
+/// Save a logo captured from the stream, only while the channel has no logo at all.
+/// It lives in `captured_icon`, which provider syncs don't touch; the UI falls back
+/// to it when `stream_icon` is empty.
+pub fn set_captured_icon_if_missing(db: &DvrDatabase, stream_id: &str, icon: &str) -> Result<bool> {
+    let conn = db.get_conn()?;
+    let updated = conn.execute(
+        "UPDATE channels SET captured_icon = ?1
+         WHERE stream_id = ?2 AND COALESCE(stream_icon, '') = '' AND COALESCE(captured_icon, '') = ''",
+        params![icon, stream_id],
+    )?;
+    Ok(updated > 0)
+}
+
 /// Delete categories by category_id
 pub fn bulk_delete_categories(db: &DvrDatabase, category_ids: Vec<String>) -> Result<usize> {
     let mut conn = db.get_conn()?;
@@ -812,6 +1090,234 @@ pub fn bulk_delete_categories(db: &DvrDatabase, category_ids: Vec<String>) -> Re
     Ok(deleted as usize)
 }
 
+/// Delete a source's channels and categories that are not in the given id lists
+///
+/// Both deletes run in one transaction. An empty list leaves that table alone, so a
+/// provider briefly returning nothing doesn't wipe the source. Returns the number of
+/// (channels, categories) deleted.
+pub fn prune_source_channels(
+    db: &DvrDatabase,
+    source_id: &str,
+    keep_channel_ids: &[String],
+    keep_category_ids: &[String],
+) -> Result<(usize, usize)> {
+    let mut conn = db.get_conn()?;
+    crate::db_queries::ensure_channels_fts(&conn)?;
+    let tx = conn.transaction()?;
+
+    let prune = |table: &str, key: &str, keep: &[String]| -> Result<usize> {
+        if keep.is_empty() {
+            return Ok(0);
+        }
+        let sql = format!(
+            "DELETE FROM {} WHERE source_id = ?1 AND {} NOT IN (SELECT value FROM json_each(?2))",
+            table, key
+        );
+        Ok(tx.execute(&sql, params![source_id, serde_json::to_string(keep)?])?)
+    };
+    let channels = prune("channels", "stream_id", keep_channel_ids)?;
+    let categories = prune("categories", "category_id", keep_category_ids)?;
+    tx.commit()?;
+
+    info!(
+        "Pruned {} channels and {} categories no longer offered by {}",
+        channels, categories, source_id
+    );
+    Ok((channels, categories))
+}
+
+/// Operations accepted by `bulk_insert`
+const BULK_OPERATIONS: &[&str] = &["insert", "replace", "upsert", "delete"];
+
+/// Generic bulk write to any table, used by the frontend's SQLite adapter
+///
+/// - `insert` / `replace`: `INSERT` / `INSERT OR REPLACE` each row
+/// - `upsert`: insert, or update the non-key columns when `conflict_columns` match
+/// - `delete`: `columns` are the key columns and each row is one key to delete
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkInsertRequest {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    pub operation: String,
+    /// Columns of the unique constraint to upsert on (required for `upsert`, and used by
+    /// `replace` instead of the primary key to tell updates from inserts)
+    #[serde(default)]
+    pub conflict_columns: Vec<String>,
+}
+
+/// Positions in `request.columns` of the key a replace/upsert conflicts on
+///
+/// `conflict_columns` when given, else the table's primary key. `None` when a key column
+/// isn't among the written columns, in which case every written row counts as inserted.
+fn conflict_key_positions(conn: &rusqlite::Connection, request: &BulkInsertRequest) -> Result<Option<Vec<usize>>> {
+    let keys = if request.conflict_columns.is_empty() {
+        let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1) WHERE pk > 0 ORDER BY pk")?;
+        let names = stmt
+            .query_map([&request.table], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        names
+    } else {
+        request.conflict_columns.clone()
+    };
+    if keys.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(keys
+        .iter()
+        .map(|key| request.columns.iter().position(|c| c == key))
+        .collect())
+}
+
+/// Table and column names are interpolated into SQL, so only plain identifiers are allowed
+fn quote_identifier(name: &str) -> Result<String> {
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(anyhow::anyhow!("Invalid identifier '{}'", name));
+    }
+    Ok(format!("\"{}\"", name))
+}
+
+/// Convert a JSON value from the frontend to a SQLite value (objects/arrays are stored as JSON text)
+fn json_to_sql(value: &serde_json::Value) -> SqlValue {
+    match value {
+        serde_json::Value::Null => SqlValue::Null,
+        serde_json::Value::Bool(b) => SqlValue::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+/// Run a generic bulk insert/replace/upsert/delete in a single transaction
+///
+/// Goes through the DVR database's r2d2 pool like the other bulk ops, so sync writes
+/// share its WAL connection settings and busy timeout instead of opening their own.
+pub fn bulk_insert(db: &DvrDatabase, request: &BulkInsertRequest) -> Result<BulkResult> {
+    with_db_retry(|| bulk_insert_inner(db, request))
+}
+
+fn bulk_insert_inner(db: &DvrDatabase, request: &BulkInsertRequest) -> Result<BulkResult> {
+    let start = std::time::Instant::now();
+    let operation = request.operation.to_lowercase();
+    if !BULK_OPERATIONS.contains(&operation.as_str()) {
+        return Err(anyhow::anyhow!(
+            "Unknown bulk operation '{}' (expected one of: {})",
+            request.operation,
+            BULK_OPERATIONS.join(", ")
+        ));
+    }
+    if request.columns.is_empty() {
+        return Err(anyhow::anyhow!("No columns given for bulk {}", operation));
+    }
+
+    let table = quote_identifier(&request.table)?;
+    let columns = request.columns.iter().map(|c| quote_identifier(c)).collect::<Result<Vec<_>>>()?;
+    let placeholders = (1..=columns.len()).map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ");
+
+    let sql = match operation.as_str() {
+        "insert" => format!("INSERT INTO {} ({}) VALUES ({})", table, columns.join(", "), placeholders),
+        "replace" => format!("INSERT OR REPLACE INTO {} ({}) VALUES ({})", table, columns.join(", "), placeholders),
+        "upsert" => {
+            if request.conflict_columns.is_empty() {
+                return Err(anyhow::anyhow!("Bulk upsert needs conflict_columns"));
+            }
+            let conflict = request.conflict_columns.iter().map(|c| quote_identifier(c)).collect::<Result<Vec<_>>>()?;
+            let updates: Vec<String> = columns
+                .iter()
+                .filter(|c| !conflict.contains(c))
+                .map(|c| format!("{} = excluded.{}", c, c))
+                .collect();
+            let action = if updates.is_empty() {
+                "NOTHING".to_string()
+            } else {
+                format!("UPDATE SET {}", updates.join(", "))
+            };
+            format!(
+                "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT({}) DO {}",
+                table, columns.join(", "), placeholders, conflict.join(", "), action
+            )
+        }
+        _ => {
+            let conditions: Vec<String> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("{} = ?{}", c, i + 1))
+                .collect();
+            format!("DELETE FROM {} WHERE {}", table, conditions.join(" AND "))
+        }
+    };
+
+    let mut conn = db.get_conn()?;
+    let tx = conn.transaction()?;
+
+    // changes() is 1 whether a replace/upsert inserted or overwrote, so look the key up first
+    let key_positions = match operation.as_str() {
+        "replace" | "upsert" => conflict_key_positions(&tx, request)?,
+        _ => None,
+    };
+    let exists_sql = key_positions.as_ref().map(|positions| {
+        let conditions: Vec<String> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, &pos)| format!("{} = ?{}", columns[pos], i + 1))
+            .collect();
+        format!("SELECT EXISTS(SELECT 1 FROM {} WHERE {})", table, conditions.join(" AND "))
+    });
+
+    let mut inserted = 0;
+    let mut updated = 0;
+    let mut deleted = 0;
+    {
+        let mut stmt = tx.prepare(&sql)?;
+        let mut exists_stmt = exists_sql.map(|sql| tx.prepare(&sql)).transpose()?;
+        for (index, row) in request.rows.iter().enumerate() {
+            if row.len() != columns.len() {
+                return Err(anyhow::anyhow!(
+                    "Row {} has {} values but {} columns were given",
+                    index, row.len(), columns.len()
+                ));
+            }
+            let existed = match (exists_stmt.as_mut(), key_positions.as_ref()) {
+                (Some(exists), Some(positions)) => exists.query_row(
+                    rusqlite::params_from_iter(positions.iter().map(|&pos| json_to_sql(&row[pos]))),
+                    |r| r.get::<_, bool>(0),
+                )?,
+                _ => false,
+            };
+            let changes = stmt.execute(rusqlite::params_from_iter(row.iter().map(json_to_sql)))?;
+            if operation == "delete" {
+                deleted += changes;
+            } else if existed {
+                updated += changes;
+            } else {
+                inserted += changes;
+            }
+        }
+    }
+    tx.commit()?;
+
+    let duration_ms = start.elapsed().as_millis() as u64;
+    info!(
+        "Bulk {} on {}: {} inserted, {} updated, {} deleted of {} rows in {}ms",
+        operation, request.table, inserted, updated, deleted, request.rows.len(), duration_ms
+    );
+
+    Ok(BulkResult {
+        inserted,
+        updated,
+        deleted,
+        duration_ms,
+        inserted_ids: None,
+        updated_ids: None,
+    })
+}
+
 /// Update sourcesMeta
 #[derive(Debug, Clone, Deserialize)]
 pub struct SourceMetaUpdate {
@@ -913,3 +1419,119 @@ fn update_source_meta_inner(db: &DvrDatabase, meta: SourceMetaUpdate) -> Result<
     tx.commit()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dvr::database::test_database;
+
+    fn program(id: &str, title: &str) -> BulkProgram {
+        BulkProgram {
+            id: id.to_string(),
+            stream_id: "101".to_string(),
+            title: title.to_string(),
+            description: None,
+            start: "2099-01-01T20:00:00Z".to_string(),
+            end: "2099-01-01T21:00:00Z".to_string(),
+            source_id: "source".to_string(),
+            icon: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_programs_counts_updates() {
+        let db = test_database();
+        db.get_conn().unwrap().execute(
+            "CREATE TABLE IF NOT EXISTS programs (
+                id TEXT PRIMARY KEY, stream_id TEXT, title TEXT, description TEXT,
+                start TEXT, end TEXT, source_id TEXT, icon TEXT
+            )",
+            [],
+        ).unwrap();
+
+        let first = bulk_merge_programs(&db, "source", vec![program("p1", "News")], 7).unwrap();
+        assert_eq!((first.inserted, first.updated), (1, 0));
+
+        let second = bulk_merge_programs(
+            &db,
+            "source",
+            vec![program("p1", "News (updated)"), program("p2", "Weather")],
+            7,
+        ).unwrap();
+        assert_eq!(second.updated, 1);
+        assert_eq!(second.inserted, 1);
+    }
+
+    #[test]
+    fn test_bulk_insert_counts_updates() {
+        let db = test_database();
+        db.get_conn().unwrap().execute(
+            "CREATE TABLE watchlist (id TEXT PRIMARY KEY, title TEXT)",
+            [],
+        ).unwrap();
+        let request = |operation: &str, rows: &[(&str, &str)]| BulkInsertRequest {
+            table: "watchlist".to_string(),
+            columns: vec!["id".to_string(), "title".to_string()],
+            rows: rows.iter().map(|(id, title)| vec![serde_json::json!(id), serde_json::json!(title)]).collect(),
+            operation: operation.to_string(),
+            conflict_columns: if operation == "upsert" { vec!["id".to_string()] } else { Vec::new() },
+        };
+
+        let first = bulk_insert(&db, &request("insert", &[("a", "One")])).unwrap();
+        assert_eq!((first.inserted, first.updated), (1, 0));
+
+        let replaced = bulk_insert(&db, &request("replace", &[("a", "One (HD)"), ("b", "Two")])).unwrap();
+        assert_eq!((replaced.inserted, replaced.updated), (1, 1));
+
+        let upserted = bulk_insert(&db, &request("upsert", &[("b", "Two (HD)"), ("c", "Three")])).unwrap();
+        assert_eq!((upserted.inserted, upserted.updated), (1, 1));
+
+        let deleted = bulk_insert(&db, &request("delete", &[("a", "One (HD)")])).unwrap();
+        assert_eq!((deleted.inserted, deleted.deleted), (0, 1));
+    }
+
+    #[test]
+    fn test_prune_source_channels_keeps_listed_and_other_sources() {
+        let db = test_database();
+        db.get_conn().unwrap().execute_batch(
+            "CREATE TABLE channels (stream_id TEXT PRIMARY KEY, source_id TEXT, name TEXT);
+             CREATE TABLE categories (category_id TEXT PRIMARY KEY, source_id TEXT);
+             INSERT INTO channels VALUES ('a_1', 'a', 'One'), ('a_2', 'a', 'Two'), ('b_1', 'b', 'Other');
+             INSERT INTO categories VALUES ('a_c1', 'a'), ('a_c2', 'a'), ('b_c1', 'b');",
+        ).unwrap();
+
+        let deleted = prune_source_channels(&db, "a", &["a_1".to_string()], &[]).unwrap();
+        assert_eq!(deleted, (1, 0));
+
+        let conn = db.get_conn().unwrap();
+        let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, i64>(0)).unwrap();
+        assert_eq!(count("SELECT COUNT(*) FROM channels"), 2);
+        assert_eq!(count("SELECT COUNT(*) FROM categories"), 3);
+    }
+
+    #[test]
+    fn test_captured_icon_only_fills_missing_logos() {
+        let db = test_database();
+        db.get_conn().unwrap().execute_batch(
+            "CREATE TABLE channels (stream_id TEXT PRIMARY KEY, stream_icon TEXT, captured_icon TEXT);
+             INSERT INTO channels VALUES ('bare', '', NULL), ('logo', 'http://logo/1.png', NULL);",
+        ).unwrap();
+
+        assert!(channel_missing_icon(&db, "bare").unwrap());
+        assert!(!channel_missing_icon(&db, "logo").unwrap());
+        assert!(!channel_missing_icon(&db, "unknown").unwrap());
+
+        assert!(set_captured_icon_if_missing(&db, "bare", "asset://bare.jpg").unwrap());
+        assert!(!set_captured_icon_if_missing(&db, "bare", "asset://other.jpg").unwrap());
+        assert!(!set_captured_icon_if_missing(&db, "logo", "asset://logo.jpg").unwrap());
+        assert!(!channel_missing_icon(&db, "bare").unwrap());
+
+        let conn = db.get_conn().unwrap();
+        let (icon, captured): (String, String) = conn
+            .query_row("SELECT stream_icon, captured_icon FROM channels WHERE stream_id = 'bare'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((icon.as_str(), captured.as_str()), ("", "asset://bare.jpg"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Database maintenance operations (compaction, integrity checks, backup/restore)
+//!
+//! These run against the shared ynotv.db through the pool, so they are safe
+//! to call while syncs and the UI are using the database.
+
+use anyhow::{anyhow, Result};
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::db_queries;
+use crate::dvr::database::DvrDatabase;
+
+/// Suffix of the staged file written by `restore_database`
+const RESTORE_SUFFIX: &str = ".restore";
+
+/// Outcome of a vacuum run
+#[derive(Debug, Serialize)]
+pub struct VacuumResult {
+    /// "incremental" when auto_vacuum is INCREMENTAL, otherwise "full"
+    pub mode: String,
+    pub size_before: u64,
+    pub size_after: u64,
+    pub reclaimed_bytes: u64,
+    pub duration_ms: u64,
+}
+
+/// Size of the main database in bytes, as SQLite sees it
+fn database_size(conn: &Connection) -> Result<u64> {
+    let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    Ok((page_count * page_size).max(0) as u64)
+}
+
+/// Compact the database file.
+///
+/// Uses `PRAGMA incremental_vacuum` when the database was created with
+/// auto_vacuum = INCREMENTAL, otherwise a full `VACUUM`. Blocks for as long
+/// as the rebuild takes, so call it off the async runtime.
+pub fn vacuum_database(db: &DvrDatabase) -> Result<VacuumResult> {
+    let start = std::time::Instant::now();
+    let conn = db.get_conn()?;
+
+    // VACUUM fails inside a transaction; a pooled connection should never be in one
+    if !conn.is_autocommit() {
+        return Err(anyhow!("Cannot vacuum: connection has an open transaction"));
+    }
+
+    let size_before = database_size(&conn)?;
+    let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+
+    let mode = if auto_vacuum == 2 {
+        // incremental_vacuum returns a row per freed page, so step through them all
+        let mut stmt = conn.prepare("PRAGMA incremental_vacuum")?;
+        let mut rows = stmt.query([])?;
+        while rows.next()?.is_some() {}
+        "incremental"
+    } else {
+        conn.execute_batch("VACUUM")?;
+        // VACUUM may renumber rowids of tables without an INTEGER PRIMARY KEY,
+        // which is what the channel search index is keyed on
+        if let Err(e) = db_queries::rebuild_channels_fts(&conn) {
+            warn!("Could not rebuild channel search index after vacuum: {}", e);
+        }
+        "full"
+    };
+
+    // In WAL mode the rewritten pages sit in the WAL until checkpointed
+    if let Err(e) = conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(())) {
+        warn!("WAL checkpoint after vacuum failed: {}", e);
+    }
+
+    let size_after = database_size(&conn)?;
+    let reclaimed_bytes = size_before.saturating_sub(size_after);
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    info!(
+        "Database {} vacuum reclaimed {} bytes ({} -> {}) in {}ms",
+        mode, reclaimed_bytes, size_before, size_after, duration_ms
+    );
+
+    Ok(VacuumResult {
+        mode: mode.to_string(),
+        size_before,
+        size_after,
+        reclaimed_bytes,
+        duration_ms,
+    })
+}
+
+/// Run `PRAGMA integrity_check` on an open connection and join the reported lines.
+/// Returns "ok" for a healthy database.
+pub fn integrity_check_conn(conn: &Connection) -> Result<String> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let lines = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(lines.join("\n"))
+}
+
+/// Run `PRAGMA integrity_check` against the live database
+pub fn integrity_check(db: &DvrDatabase) -> Result<String> {
+    let conn = db.get_conn()?;
+    let result = integrity_check_conn(&conn)?;
+    if result == "ok" {
+        info!("Database integrity check passed");
+    } else {
+        warn!("Database integrity check reported problems: {}", result);
+    }
+    Ok(result)
+}
+
+/// Path next to the database with `suffix` appended, e.g. the "-wal"/"-shm" files SQLite keeps in WAL mode
+fn sidecar_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Copy the live database to `dest` with SQLite's online backup API.
+/// Safe while other connections are reading and writing. Returns the backup size in bytes.
+pub fn backup_database(db: &DvrDatabase, dest: &Path) -> Result<u64> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let conn = db.get_conn()?;
+    conn.backup(DatabaseName::Main, dest, None)?;
+
+    let size = std::fs::metadata(dest)?.len();
+    info!("Database backed up to {:?} ({} bytes)", dest, size);
+    Ok(size)
+}
+
+/// Validate a backup file and stage it to replace `db_path` on next launch.
+///
+/// The backup must pass `PRAGMA integrity_check` and look like a ynotv database.
+/// The live database is left untouched until `apply_pending_restore` runs at startup.
+/// Returns the staged file size in bytes.
+pub fn restore_database(db_path: &Path, src: &Path) -> Result<u64> {
+    let src_conn = Connection::open_with_flags(src, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| anyhow!("Cannot open backup {:?}: {}", src, e))?;
+
+    let integrity = integrity_check_conn(&src_conn)?;
+    if integrity != "ok" {
+        return Err(anyhow!("Backup failed integrity check: {}", integrity));
+    }
+
+    let has_channels: bool = src_conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'channels')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_channels {
+        return Err(anyhow!("{:?} is not a ynotv database backup", src));
+    }
+
+    // Copy through the backup API so a backup with its own WAL is staged as one consistent file
+    let pending = sidecar_path(db_path, RESTORE_SUFFIX);
+    let _ = std::fs::remove_file(&pending);
+    src_conn.backup(DatabaseName::Main, &pending, None)?;
+
+    let size = std::fs::metadata(&pending)?.len();
+    info!("Database restore staged from {:?} ({} bytes), applies on next launch", src, size);
+    Ok(size)
+}
+
+/// Swap in a database staged by `restore_database`. Must run before anything opens `db_path`.
+/// The replaced database is kept alongside as `<name>.pre-restore`.
+pub fn apply_pending_restore(db_path: &Path) -> Result<bool> {
+    let pending = sidecar_path(db_path, RESTORE_SUFFIX);
+    if !pending.exists() {
+        return Ok(false);
+    }
+
+    // Move the old database together with its WAL files, so its WAL is never replayed onto the restored one
+    let previous = sidecar_path(db_path, ".pre-restore");
+    for suffix in ["", "-wal", "-shm"] {
+        let from = sidecar_path(db_path, suffix);
+        let to = sidecar_path(&previous, suffix);
+        let _ = std::fs::remove_file(&to);
+        if from.exists() {
+            std::fs::rename(&from, &to)?;
+        }
+    }
+    std::fs::rename(&pending, db_path)?;
+
+    info!("Restored database from staged backup");
+    Ok(true)
+}
@@ -0,0 +1,373 @@
+//! Native read queries for the large playlist tables
+//!
+//! Runs the heavy channel/VOD lookups on the Rust side and hands the webview
+//! only the rows it asked for, instead of pulling whole tables over the sql plugin.
+
+use anyhow::{anyhow, Result};
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, Row, ToSql};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::info;
+
+use crate::dvr::database::DvrDatabase;
+
+/// Sort orders understood by the channel queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelSort {
+    AlphaAsc,
+    AlphaDesc,
+    ChannelNum,
+    RecentlyAdded,
+    Relevance,
+}
+
+impl ChannelSort {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "alpha_asc" => Ok(Self::AlphaAsc),
+            "alpha_desc" => Ok(Self::AlphaDesc),
+            "channel_num" => Ok(Self::ChannelNum),
+            "recently_added" => Ok(Self::RecentlyAdded),
+            "relevance" => Ok(Self::Relevance),
+            other => Err(anyhow!("Unknown channel sort: {}", other)),
+        }
+    }
+
+    /// ORDER BY clause for a query over `channels c` (joined with `channels_fts` for relevance)
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::AlphaAsc => "c.name COLLATE NOCASE ASC",
+            Self::AlphaDesc => "c.name COLLATE NOCASE DESC",
+            Self::ChannelNum => "c.channel_num IS NULL, c.channel_num ASC, c.name COLLATE NOCASE ASC",
+            Self::RecentlyAdded => "c.added DESC, c.name COLLATE NOCASE ASC",
+            Self::Relevance => "bm25(channels_fts) ASC, c.name COLLATE NOCASE ASC",
+        }
+    }
+}
+
+/// Sort orders understood by the VOD queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VodSort {
+    AlphaAsc,
+    AlphaDesc,
+    RecentlyAdded,
+}
+
+impl VodSort {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "alpha_asc" => Ok(Self::AlphaAsc),
+            "alpha_desc" => Ok(Self::AlphaDesc),
+            "recently_added" => Ok(Self::RecentlyAdded),
+            other => Err(anyhow!("Unknown VOD sort: {}", other)),
+        }
+    }
+
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::AlphaAsc => "name COLLATE NOCASE ASC",
+            Self::AlphaDesc => "name COLLATE NOCASE DESC",
+            Self::RecentlyAdded => "added DESC, name COLLATE NOCASE ASC",
+        }
+    }
+}
+
+/// One page of rows plus the total number of matching rows
+#[derive(Debug, Serialize)]
+pub struct QueryPage {
+    pub items: Vec<Value>,
+    pub total: i64,
+}
+
+/// Convert a result row into a JSON object keyed by column name,
+/// matching what the sql plugin returns for `SELECT *`
+fn row_to_json(row: &Row, columns: &[String]) -> rusqlite::Result<Value> {
+    let mut object = Map::with_capacity(columns.len());
+    for (i, name) in columns.iter().enumerate() {
+        let value = match row.get_ref(i)? {
+            ValueRef::Null => Value::Null,
+            ValueRef::Integer(n) => Value::from(n),
+            ValueRef::Real(f) => Value::from(f),
+            ValueRef::Text(t) => Value::from(String::from_utf8_lossy(t).into_owned()),
+            ValueRef::Blob(_) => Value::Null,
+        };
+        object.insert(name.clone(), value);
+    }
+    Ok(Value::Object(object))
+}
+
+/// Turn free text into an FTS5 MATCH expression: every word must match as a prefix
+fn build_fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Triggers keeping `channels_fts` in step with `channels`
+const CHANNELS_FTS_TRIGGERS: &str = "
+    CREATE TRIGGER IF NOT EXISTS channels_fts_ai AFTER INSERT ON channels BEGIN
+        INSERT INTO channels_fts (rowid, name) VALUES (new.rowid, new.name);
+    END;
+    CREATE TRIGGER IF NOT EXISTS channels_fts_ad AFTER DELETE ON channels BEGIN
+        INSERT INTO channels_fts (channels_fts, rowid, name) VALUES ('delete', old.rowid, old.name);
+    END;
+    CREATE TRIGGER IF NOT EXISTS channels_fts_au AFTER UPDATE OF name ON channels BEGIN
+        INSERT INTO channels_fts (channels_fts, rowid, name) VALUES ('delete', old.rowid, old.name);
+        INSERT INTO channels_fts (rowid, name) VALUES (new.rowid, new.name);
+    END;
+";
+
+/// Install the channel search index triggers, filling the index when they're first added
+///
+/// `channels` is created by the frontend, possibly after the DVR schema, so this
+/// runs before the index is read and before Rust writes channels.
+pub fn ensure_channels_fts(conn: &Connection) -> Result<()> {
+    let installed: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'trigger' AND name = 'channels_fts_ai')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !installed {
+        conn.execute_batch(CHANNELS_FTS_TRIGGERS)?;
+        rebuild_channels_fts(conn)?;
+    }
+    Ok(())
+}
+
+/// Repopulate the channel search index from the channels table
+pub fn rebuild_channels_fts(conn: &Connection) -> Result<usize> {
+    conn.execute("INSERT INTO channels_fts (channels_fts) VALUES ('rebuild')", [])?;
+    let indexed: i64 = conn.query_row("SELECT COUNT(*) FROM channels", [], |row| row.get(0))?;
+    info!("Rebuilt channel search index with {} channels", indexed);
+    Ok(indexed as usize)
+}
+
+/// Full-text search over enabled channel names
+///
+/// Only channels in an enabled category are returned, and only from `source_ids` when given.
+pub fn search_channels(
+    db: &DvrDatabase,
+    query: &str,
+    source_ids: Option<&[String]>,
+    sort: ChannelSort,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<Value>> {
+    let Some(fts_query) = build_fts_query(query) else {
+        return Ok(Vec::new());
+    };
+
+    let conn = db.get_conn()?;
+    ensure_channels_fts(&conn)?;
+
+    let mut where_clause = String::from(
+        "channels_fts MATCH ?
+           AND (c.enabled IS NULL OR c.enabled != 0)
+           AND EXISTS (
+               SELECT 1 FROM json_each(c.category_ids) cat
+               JOIN categories k ON k.category_id = cat.value
+               WHERE k.enabled IS NULL OR k.enabled != 0
+           )",
+    );
+    let mut query_params: Vec<&dyn ToSql> = vec![&fts_query];
+    if let Some(source_ids) = source_ids {
+        if source_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; source_ids.len()].join(", ");
+        where_clause.push_str(&format!(" AND c.source_id IN ({})", placeholders));
+        query_params.extend(source_ids.iter().map(|id| id as &dyn ToSql));
+    }
+    query_params.push(&limit);
+    query_params.push(&offset);
+
+    let sql = format!(
+        "SELECT c.* FROM channels_fts
+         JOIN channels c ON c.rowid = channels_fts.rowid
+         WHERE {}
+         ORDER BY {}
+         LIMIT ? OFFSET ?",
+        where_clause,
+        sort.order_by()
+    );
+
+    select_rows(&conn, &sql, query_params.as_slice())
+}
+
+/// Run a query and collect every row as a JSON object
+fn select_rows(conn: &Connection, sql: &str, params: &[&dyn ToSql]) -> Result<Vec<Value>> {
+    let mut stmt = conn.prepare(sql)?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let rows = stmt
+        .query_map(params, |row| row_to_json(row, &columns))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+/// Category filter for tables that store `category_ids` as a JSON array string.
+/// Matches the quoted id so "cat1" does not match "cat10" (same as the UI adapter).
+fn category_pattern(category_id: &str) -> String {
+    format!("%\"{}\"%", category_id)
+}
+
+/// Page through enabled channels, optionally within one category
+pub fn query_channels(
+    db: &DvrDatabase,
+    category_id: Option<&str>,
+    sort: ChannelSort,
+    limit: u32,
+    offset: u32,
+) -> Result<QueryPage> {
+    // Relevance only means something for a text search
+    let sort = if sort == ChannelSort::Relevance { ChannelSort::AlphaAsc } else { sort };
+    let pattern = category_id.map(category_pattern);
+
+    let mut where_clause = String::from("(c.enabled IS NULL OR c.enabled != 0)");
+    let mut filter: Vec<&dyn ToSql> = Vec::new();
+    if let Some(pattern) = pattern.as_ref() {
+        where_clause.push_str(" AND c.category_ids LIKE ?");
+        filter.push(pattern);
+    }
+
+    let conn = db.get_conn()?;
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM channels c WHERE {}", where_clause),
+        filter.as_slice(),
+        |row| row.get(0),
+    )?;
+
+    let sql = format!(
+        "SELECT c.* FROM channels c WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+        where_clause,
+        sort.order_by()
+    );
+    let mut page_params = filter.clone();
+    page_params.push(&limit);
+    page_params.push(&offset);
+    let items = select_rows(&conn, &sql, &page_params)?;
+
+    Ok(QueryPage { items, total })
+}
+
+/// Page through VOD movies, optionally within one category
+pub fn query_movies(
+    db: &DvrDatabase,
+    category_id: Option<&str>,
+    sort: VodSort,
+    limit: u32,
+    offset: u32,
+) -> Result<QueryPage> {
+    let pattern = category_id.map(category_pattern);
+
+    let mut where_clause = String::from("1=1");
+    let mut filter: Vec<&dyn ToSql> = Vec::new();
+    if let Some(pattern) = pattern.as_ref() {
+        where_clause.push_str(" AND category_ids LIKE ?");
+        filter.push(pattern);
+    }
+
+    let conn = db.get_conn()?;
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM vodMovies WHERE {}", where_clause),
+        filter.as_slice(),
+        |row| row.get(0),
+    )?;
+
+    let sql = format!(
+        "SELECT * FROM vodMovies WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+        where_clause,
+        sort.order_by()
+    );
+    let mut page_params = filter.clone();
+    page_params.push(&limit);
+    page_params.push(&offset);
+    let items = select_rows(&conn, &sql, &page_params)?;
+
+    Ok(QueryPage { items, total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dvr::database::test_database;
+
+    fn names(rows: &[Value]) -> Vec<&str> {
+        rows.iter().map(|r| r["name"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_search_channels_follows_channel_writes() {
+        let db = test_database();
+        let conn = db.get_conn().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE channels (
+                stream_id TEXT PRIMARY KEY, source_id TEXT, category_ids TEXT, name TEXT,
+                enabled BOOLEAN, channel_num INTEGER, added TEXT
+            );
+            CREATE TABLE categories (category_id TEXT PRIMARY KEY, enabled BOOLEAN);
+            INSERT INTO categories VALUES ('news', 1), ('hidden', 0);
+            INSERT INTO channels (stream_id, source_id, category_ids, name) VALUES ('1', 'a', '[\"news\"]', 'BBC One');",
+        )
+        .unwrap();
+
+        // Written before the triggers existed, picked up when the index is first used
+        let found = search_channels(&db, "bbc", None, ChannelSort::AlphaAsc, 10, 0).unwrap();
+        assert_eq!(names(&found), ["BBC One"]);
+
+        conn.execute_batch(
+            "INSERT INTO channels (stream_id, source_id, category_ids, name) VALUES ('2', 'b', '[\"news\"]', 'BBC Two');
+             INSERT INTO channels (stream_id, source_id, category_ids, name) VALUES ('3', 'a', '[\"hidden\"]', 'BBC Three');
+             UPDATE channels SET name = 'BBC 1' WHERE stream_id = '1';",
+        )
+        .unwrap();
+        let found = search_channels(&db, "bbc", None, ChannelSort::AlphaAsc, 10, 0).unwrap();
+        assert_eq!(names(&found), ["BBC 1", "BBC Two"]);
+
+        let only_b = ["b".to_string()];
+        let found = search_channels(&db, "bbc", Some(&only_b), ChannelSort::AlphaAsc, 10, 0).unwrap();
+        assert_eq!(names(&found), ["BBC Two"]);
+
+        conn.execute("DELETE FROM channels WHERE stream_id = '2'", []).unwrap();
+        let found = search_channels(&db, "two", None, ChannelSort::AlphaAsc, 10, 0).unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_replace_keeps_channel_index_consistent() {
+        let db = test_database();
+        let conn = db.get_conn().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE channels (
+                stream_id TEXT PRIMARY KEY, source_id TEXT, category_ids TEXT, name TEXT,
+                enabled BOOLEAN, channel_num INTEGER, added TEXT
+            );
+            CREATE TABLE categories (category_id TEXT PRIMARY KEY, enabled BOOLEAN);
+            INSERT INTO categories VALUES ('news', 1);",
+        )
+        .unwrap();
+        ensure_channels_fts(&conn).unwrap();
+
+        // The frontend's upserts and bulk "replace" write channels this way
+        conn.execute_batch(
+            "INSERT OR REPLACE INTO channels (stream_id, source_id, category_ids, name) VALUES ('1', 'a', '[\"news\"]', 'BBC One');
+             INSERT OR REPLACE INTO channels (stream_id, source_id, category_ids, name) VALUES ('1', 'a', '[\"news\"]', 'ITV');",
+        )
+        .unwrap();
+
+        conn.execute("INSERT INTO channels_fts (channels_fts, rank) VALUES ('integrity-check', 1)", [])
+            .unwrap();
+        assert!(search_channels(&db, "bbc", None, ChannelSort::AlphaAsc, 10, 0).unwrap().is_empty());
+        let found = search_channels(&db, "itv", None, ChannelSort::AlphaAsc, 10, 0).unwrap();
+        assert_eq!(names(&found), ["ITV"]);
+    }
+}
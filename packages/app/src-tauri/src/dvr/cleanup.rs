@@ -1,6 +1,7 @@
 //! Cleanup manager for storage management
 //!
-//! Handles automatic deletion of old recordings and enforces disk quotas.
+//! Handles automatic deletion of old recordings, enforces disk quotas and
+//! prunes EPG programs past the retention window.
 
 use std::path::Path;
 use std::sync::Arc;
@@ -12,7 +13,8 @@ use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 
 use crate::dvr::database::DvrDatabase;
-use crate::dvr::models::DiskInfo;
+use crate::dvr::models::{DiskInfo, RecordingStatus, RecordingsDiskUsage};
+use crate::dvr::recorder::{delete_recording_with_files, resolve_storage_path};
 
 /// Cleanup interval (1 hour)
 const CLEANUP_INTERVAL_HOURS: u64 = 1;
@@ -20,6 +22,9 @@ const CLEANUP_INTERVAL_HOURS: u64 = 1;
 /// Minimum free space percentage before aggressive cleanup
 const MIN_FREE_SPACE_PERCENT: f64 = 10.0;
 
+/// Assumed stream bitrate for recording size estimates (8 Mbps, typical HD IPTV)
+const ASSUMED_RECORDING_BITRATE_BPS: u64 = 8_000_000;
+
 /// Manages storage cleanup
 pub struct CleanupManager {
     db: Arc<DvrDatabase>,
@@ -47,6 +52,10 @@ impl CleanupManager {
                 if let Err(e) = run_cleanup(&db).await {
                     error!("Cleanup failed: {}", e);
                 }
+
+                if let Err(e) = prune_old_programs(&db) {
+                    error!("EPG retention cleanup failed: {}", e);
+                }
             }
         });
 
@@ -57,8 +66,34 @@ impl CleanupManager {
     /// Run cleanup immediately (for manual trigger)
     pub async fn run_now(&self
     ) -> Result<()> {
-        run_cleanup(&self.db).await
+        let cleanup_result = run_cleanup(&self.db).await;
+        self.prune_old_programs()?;
+        cleanup_result
     }
+
+    /// Delete `space_needed` recordings until disk usage is back under
+    /// `max_disk_usage_percent`; with `dry_run`, only report what would go
+    pub async fn enforce_quota(&self, dry_run: bool) -> Result<QuotaCleanupReport> {
+        let settings = self.db.get_settings()?;
+        let storage_path = resolve_storage_path(&settings)?;
+        enforce_quota(&self.db, &storage_path, settings.max_disk_usage_percent as f64, dry_run).await
+    }
+
+    /// Delete EPG programs that ended more than `epg_retention_days` ago
+    pub fn prune_old_programs(&self) -> Result<usize> {
+        prune_old_programs(&self.db)
+    }
+}
+
+/// Delete EPG programs past the retention window
+fn prune_old_programs(db: &Arc<DvrDatabase>) -> Result<usize> {
+    let retention_days = db.get_settings()?.epg_retention_days;
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days as i64);
+
+    let pruned = db.delete_programs_ended_before(cutoff.timestamp())?;
+    info!("Pruned {} EPG programs older than {} days", pruned, retention_days);
+
+    Ok(pruned)
 }
 
 /// Run cleanup operations
@@ -68,21 +103,18 @@ async fn run_cleanup(db: &Arc<DvrDatabase>) -> Result<()> {
     let settings = db.get_settings()?;
 
     // Get storage path
-    let storage_path = if settings.storage_path.is_empty() {
-        let home = dirs::home_dir()
-            .ok_or_else(|| anyhow::anyhow!("Failed to get home directory"))?;
-        home.join("Videos").join("IPTV-Recordings")
-    } else {
-        std::path::PathBuf::from(&settings.storage_path)
-    };
+    let storage_path = resolve_storage_path(&settings)?;
 
     // Check disk usage
-    let disk_info = get_disk_info(&storage_path)?;
+    let usage = recordings_disk_usage(db, &storage_path)?;
+    let disk_info = usage.volume;
     info!(
-        "Disk usage: {:.1}% ({} GB free of {} GB)",
+        "Disk usage: {:.1}% ({} GB free of {} GB, {} recordings using {} GB)",
         disk_info.usage_percent,
         disk_info.available_bytes / 1_000_000_000,
-        disk_info.total_bytes / 1_000_000_000
+        disk_info.total_bytes / 1_000_000_000,
+        usage.recording_count,
+        usage.recordings_bytes / 1_000_000_000
     );
 
     // Delete old recordings based on age policy
@@ -93,12 +125,17 @@ async fn run_cleanup(db: &Arc<DvrDatabase>) -> Result<()> {
         }
     }
 
-    // Enforce disk quota if enabled
+    // Enforce disk quota if enabled (runs after the age pass, so expired recordings go first)
     if settings.auto_cleanup_enabled && disk_info.usage_percent > settings.max_disk_usage_percent as f64 {
         let target_usage = settings.max_disk_usage_percent as f64;
-        let deleted = enforce_quota(db, &storage_path, disk_info.used_bytes, target_usage).await?;
-        if deleted > 0 {
-            info!("Deleted {} recordings to enforce {:.0}% quota", deleted, target_usage);
+        let report = enforce_quota(db, &storage_path, target_usage, false).await?;
+        if !report.deletions.is_empty() {
+            info!(
+                "Deleted {} recordings ({} MB) to enforce {:.0}% quota",
+                report.deletions.len(),
+                report.bytes_freed / 1_000_000,
+                target_usage
+            );
         }
     }
 
@@ -119,31 +156,78 @@ async fn run_cleanup(db: &Arc<DvrDatabase>) -> Result<()> {
 }
 
 /// Get disk information for a path
-fn get_disk_info(path: &Path) -> Result<DiskInfo> {
+pub fn get_disk_info(path: &Path) -> Result<DiskInfo> {
     let disks = Disks::new_with_refreshed_list();
 
-    // Find the disk containing our path
-    for disk in &disks {
-        let mount_point = disk.mount_point();
-        if path.starts_with(mount_point) {
-            let total = disk.total_space();
-            let available = disk.available_space();
-            let used = total - available;
-            let percent = (used as f64 / total as f64) * 100.0;
-
-            return Ok(DiskInfo {
-                total_bytes: total,
-                available_bytes: available,
-                used_bytes: used,
-                usage_percent: percent,
-            });
-        }
+    // Find the disk containing our path (most specific mount point wins, so
+    // e.g. /home is preferred over / when both match)
+    let disk = disks
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len());
+
+    if let Some(disk) = disk {
+        let total = disk.total_space();
+        let available = disk.available_space();
+        let used = total - available;
+        let percent = (used as f64 / total as f64) * 100.0;
+
+        return Ok(DiskInfo {
+            total_bytes: total,
+            available_bytes: available,
+            used_bytes: used,
+            usage_percent: percent,
+        });
     }
 
     // Could not determine disk info
     Err(anyhow::anyhow!("Could not determine disk info for path"))
 }
 
+/// Get free bytes on the volume holding the DVR storage path
+pub fn get_storage_free_space(db: &DvrDatabase) -> Result<u64> {
+    let settings = db.get_settings()?;
+    let storage_path = resolve_storage_path(&settings)?;
+    Ok(get_disk_info(&storage_path)?.available_bytes)
+}
+
+/// Space used by finished recordings plus the totals of the volume they live on
+pub fn get_recordings_disk_usage(db: &DvrDatabase) -> Result<RecordingsDiskUsage> {
+    let settings = db.get_settings()?;
+    let storage_path = resolve_storage_path(&settings)?;
+    recordings_disk_usage(db, &storage_path)
+}
+
+fn recordings_disk_usage(db: &DvrDatabase, storage_path: &Path) -> Result<RecordingsDiskUsage> {
+    let (recordings_bytes, recording_count) = db.get_recordings_size_summary()?;
+    Ok(RecordingsDiskUsage {
+        recordings_bytes: recordings_bytes.max(0) as u64,
+        recording_count,
+        volume: get_disk_info(storage_path)?,
+    })
+}
+
+/// Estimate the size of a recording from its duration
+pub fn estimate_recording_bytes(duration_secs: i64) -> u64 {
+    duration_secs.max(0) as u64 * ASSUMED_RECORDING_BITRATE_BPS / 8
+}
+
+/// Estimate the size of a recording and look up the free space available for it
+///
+/// Returns (estimated_bytes, free_bytes); free_bytes is None if the storage
+/// volume couldn't be determined.
+pub fn check_space_for_recording(db: &DvrDatabase, duration_secs: i64) -> (u64, Option<u64>) {
+    let estimated = estimate_recording_bytes(duration_secs);
+    let free = match get_storage_free_space(db) {
+        Ok(free) => Some(free),
+        Err(e) => {
+            warn!("Could not determine free space on storage volume: {}", e);
+            None
+        }
+    };
+    (estimated, free)
+}
+
 /// Delete recordings older than specified days
 async fn delete_old_recordings(
     db: &Arc<DvrDatabase>,
@@ -157,6 +241,10 @@ async fn delete_old_recordings(
     let mut deleted_count = 0;
 
     for recording in recordings {
+        if recording.is_protected {
+            continue;
+        }
+
         // Check if recording is old enough
         let actual_end = recording.actual_end.unwrap_or(recording.created_at);
         if actual_end > cutoff_timestamp {
@@ -165,8 +253,8 @@ async fn delete_old_recordings(
 
         // Check auto-delete policy
         match recording.auto_delete_policy.as_str() {
-            "never" => continue,
-            _ => {} // Delete for "space_needed" or invalid policies
+            "keep_forever" | "never" => continue,
+            _ => {} // Delete for "keep_days", "space_needed" or invalid policies
         }
 
         // Delete file
@@ -197,64 +285,110 @@ async fn delete_old_recordings(
     Ok(deleted_count)
 }
 
-/// Enforce disk quota by deleting oldest recordings
+/// A recording removed (or, in a dry run, selected for removal) by the quota pass
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QuotaDeletion {
+    pub recording_id: i64,
+    pub program_title: String,
+    pub channel_name: String,
+    pub size_bytes: u64,
+}
+
+/// Outcome of a disk quota pass
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct QuotaCleanupReport {
+    pub dry_run: bool,
+    pub usage_percent: f64,
+    pub max_usage_percent: f64,
+    /// Bytes that had to be freed to get back under the limit
+    pub bytes_to_free: u64,
+    /// Bytes freed (or that would be freed) by the deletions below
+    pub bytes_freed: u64,
+    pub deletions: Vec<QuotaDeletion>,
+}
+
+/// Enforce disk quota by deleting the oldest `space_needed` recordings.
+///
+/// `keep_forever` recordings are never touched and `keep_days` recordings are
+/// left to the age pass, so only recordings that opted into space-based
+/// cleanup are removed here.
 async fn enforce_quota(
-    db: &Arc<DvrDatabase>,
+    db: &DvrDatabase,
     storage_path: &Path,
-    current_used: u64,
-    target_percent: f64
-) -> Result<usize> {
-    // Get disk info
+    target_percent: f64,
+    dry_run: bool,
+) -> Result<QuotaCleanupReport> {
     let disk_info = get_disk_info(storage_path)?;
+    let mut report = QuotaCleanupReport {
+        dry_run,
+        usage_percent: disk_info.usage_percent,
+        max_usage_percent: target_percent,
+        ..Default::default()
+    };
 
     // Calculate how much space we need to free
     let target_bytes = (disk_info.total_bytes as f64 * target_percent / 100.0) as u64;
-    if current_used <= target_bytes {
-        return Ok(0);
+    if disk_info.used_bytes <= target_bytes {
+        return Ok(report);
     }
+    report.bytes_to_free = disk_info.used_bytes - target_bytes;
 
-    let bytes_to_free = current_used - target_bytes;
-    let mut bytes_freed: u64 = 0;
-
-    // Get recordings ordered by age (oldest first)
-    let recordings = db.get_completed_recordings()?;
-    let mut deleted_count = 0;
+    // Oldest first
+    let mut recordings: Vec<_> = db
+        .get_completed_recordings()?
+        .into_iter()
+        .filter(|r| !r.is_protected && r.auto_delete_policy == "space_needed")
+        .collect();
+    recordings.sort_by_key(|r| r.actual_end.unwrap_or(r.created_at));
 
     for recording in recordings {
-        if bytes_freed >= bytes_to_free {
+        if report.bytes_freed >= report.bytes_to_free {
             break;
         }
 
-        // Skip if policy is "never"
-        if recording.auto_delete_policy == "never" {
-            continue;
-        }
-
-        // Delete file
-        let file_path = std::path::PathBuf::from(&recording.file_path);
-        if file_path.exists() {
-            if let Err(e) = tokio::fs::remove_file(&file_path).await {
-                warn!("Failed to delete recording file {:?}: {}", file_path, e);
+        let size_bytes = match recording.size_bytes {
+            Some(size) if size > 0 => size as u64,
+            _ => tokio::fs::metadata(&recording.file_path).await.map(|m| m.len()).unwrap_or(0),
+        };
+
+        if dry_run {
+            info!(
+                "Quota dry run: would delete recording {} '{}' ({} MB)",
+                recording.id,
+                recording.program_title,
+                size_bytes / 1_000_000
+            );
+        } else {
+            if let Err(e) = delete_recording_with_files(db, &recording).await {
+                warn!("Failed to delete recording {} for quota: {}", recording.id, e);
                 continue;
             }
-            bytes_freed += recording.size_bytes.unwrap_or(0) as u64;
+            info!(
+                "Deleted recording {} '{}' for quota (freed {} MB)",
+                recording.id,
+                recording.program_title,
+                size_bytes / 1_000_000
+            );
         }
 
-        // Delete from database
-        if let Err(e) = db.delete_recording(recording.id) {
-            warn!("Failed to delete recording {} from DB: {}", recording.id, e);
-            continue;
-        }
+        report.bytes_freed += size_bytes;
+        report.deletions.push(QuotaDeletion {
+            recording_id: recording.id,
+            program_title: recording.program_title,
+            channel_name: recording.channel_name,
+            size_bytes,
+        });
+    }
 
-        deleted_count += 1;
-        debug!(
-            "Deleted recording for quota: {} (freed {} MB)",
-            recording.program_title,
-            recording.size_bytes.unwrap_or(0) / 1_000_000
+    if report.bytes_freed < report.bytes_to_free {
+        warn!(
+            "Quota pass could only free {} of {} MB; remaining recordings are protected by their policy",
+            report.bytes_freed / 1_000_000,
+            report.bytes_to_free / 1_000_000
         );
     }
 
-    Ok(deleted_count)
+    Ok(report)
 }
 
 /// Emergency cleanup when critically low on space
@@ -262,8 +396,12 @@ async fn emergency_cleanup(
     db: &Arc<DvrDatabase>,
     _storage_path: &Path
 ) -> Result<usize> {
-    // Get ALL completed recordings, ignore policy
-    let recordings = db.get_completed_recordings()?;
+    // Get ALL completed recordings, ignore policy (but never protected ones)
+    let recordings: Vec<_> = db
+        .get_completed_recordings()?
+        .into_iter()
+        .filter(|r| !r.is_protected)
+        .collect();
     let mut deleted_count = 0;
 
     // Delete half of them (oldest first)
@@ -310,3 +448,103 @@ async fn update_recording_sizes(
 
     Ok(())
 }
+
+/// Video extensions picked up when importing loose files from storage
+pub(crate) const IMPORTABLE_EXTENSIONS: &[&str] = &["ts", "mkv", "mp4"];
+
+/// Files modified more recently than this may still be written by FFmpeg
+const IMPORT_MIN_AGE_SECS: u64 = 300;
+
+/// What `scan_recordings` found and fixed
+#[derive(Debug, Default, serde::Serialize)]
+pub struct RecordingScanReport {
+    /// Recording rows checked against the disk
+    pub checked: usize,
+    /// Rows newly marked orphaned because their file is missing
+    pub orphaned: Vec<i64>,
+    /// Previously orphaned rows whose file is back
+    pub restored: Vec<i64>,
+    /// New rows created for loose video files in storage
+    pub imported: Vec<i64>,
+}
+
+/// Cross-check recording rows against the storage directory.
+///
+/// Rows whose file is missing are marked orphaned (and restored if the file
+/// reappears, e.g. a reconnected network drive). With `import_loose`, video
+/// files in the storage directory that have no row are added as recordings.
+pub fn scan_recordings(db: &DvrDatabase, import_loose: bool) -> Result<RecordingScanReport> {
+    let mut report = RecordingScanReport::default();
+
+    for recording in db.get_recordings_for_scan()? {
+        report.checked += 1;
+        let exists = Path::new(&recording.file_path).exists();
+        match (exists, &recording.status) {
+            (false, RecordingStatus::Orphaned) | (true, RecordingStatus::Completed) | (true, RecordingStatus::Partial) => {}
+            (false, _) => {
+                warn!("Recording {} file is missing: {}", recording.id, recording.file_path);
+                db.set_recording_status(recording.id, RecordingStatus::Orphaned)?;
+                report.orphaned.push(recording.id);
+            }
+            (true, _) => {
+                info!("Recording {} file is back: {}", recording.id, recording.file_path);
+                // Only a partial recording keeps the error it ended with
+                let status = if recording.error_message.is_some() {
+                    RecordingStatus::Partial
+                } else {
+                    RecordingStatus::Completed
+                };
+                db.set_recording_status(recording.id, status)?;
+                report.restored.push(recording.id);
+            }
+        }
+    }
+
+    if import_loose {
+        let settings = db.get_settings()?;
+        let storage_path = resolve_storage_path(&settings)?;
+        // Rows may spell the path differently (case, separators, `..`) from the directory listing
+        let canonical = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let known: std::collections::HashSet<std::path::PathBuf> = db
+            .get_recording_file_paths()?
+            .into_iter()
+            .map(|path| canonical(Path::new(&path)))
+            .collect();
+
+        for entry in std::fs::read_dir(&storage_path)?.flatten() {
+            let path = entry.path();
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let importable = path
+                .extension()
+                .map(|ext| IMPORTABLE_EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+                .unwrap_or(false);
+            if !importable || filename.starts_with('.') || known.contains(&canonical(&path)) {
+                continue;
+            }
+
+            let Ok(metadata) = entry.metadata() else { continue };
+            let modified = metadata.modified().ok();
+            let age = modified.and_then(|m| m.elapsed().ok()).map(|d| d.as_secs()).unwrap_or(0);
+            if !metadata.is_file() || metadata.len() == 0 || age < IMPORT_MIN_AGE_SECS {
+                continue;
+            }
+
+            let modified_at = modified
+                .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or_else(|| chrono::Utc::now().timestamp());
+            let title = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| filename.clone());
+
+            match db.add_imported_recording(&path.to_string_lossy(), &filename, &title, metadata.len() as i64, modified_at) {
+                Ok(id) => report.imported.push(id),
+                Err(e) => warn!("Failed to import {:?}: {}", path, e),
+            }
+        }
+    }
+
+    info!(
+        "Recording scan: {} checked, {} orphaned, {} restored, {} imported",
+        report.checked, report.orphaned.len(), report.restored.len(), report.imported.len()
+    );
+    Ok(report)
+}
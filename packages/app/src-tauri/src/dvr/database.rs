@@ -19,7 +19,7 @@ pub struct Channel {
 }
 
 /// Sets busy_timeout on every new connection to prevent "database is locked" errors
-/// when multiple sync operations write concurrently.
+/// when multiple sync operations write concurrently, and enables recursive triggers.
 #[derive(Debug)]
 struct BusyTimeoutCustomizer;
 
@@ -27,10 +27,112 @@ impl CustomizeConnection<rusqlite::Connection, rusqlite::Error> for BusyTimeoutC
     fn on_acquire(&self, conn: &mut rusqlite::Connection) -> Result<(), rusqlite::Error> {
         // Wait up to 30 seconds for locks instead of immediately failing
         conn.busy_timeout(std::time::Duration::from_secs(30))?;
+        // Without this the row deleted by INSERT OR REPLACE skips its DELETE triggers,
+        // leaving stale entries in channels_fts
+        conn.execute_batch("PRAGMA recursive_triggers = ON")?;
         Ok(())
     }
 }
 
+/// Parse a program start/end time (ISO 8601) into a Unix timestamp
+fn parse_program_time(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .or_else(|_| chrono::DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%z"))
+        .map(|dt| dt.timestamp())
+        .ok()
+}
+
+/// Reject schedules that could never record anything
+fn validate_schedule_times(
+    request: &ScheduleRequest,
+    start_padding_sec: i32,
+    end_padding_sec: i32,
+    now: Option<i64>,
+) -> Result<()> {
+    if request.scheduled_end <= request.scheduled_start {
+        return Err(anyhow::anyhow!(
+            "Scheduled end ({}) must be after scheduled start ({})",
+            request.scheduled_end,
+            request.scheduled_start
+        ));
+    }
+
+    let actual_end = request.scheduled_end + end_padding_sec as i64;
+    if let Some(now) = now.filter(|now| actual_end <= *now) {
+        return Err(anyhow::anyhow!(
+            "'{}' already ended at {} (now {})",
+            request.program_title,
+            actual_end,
+            now
+        ));
+    }
+
+    let actual_start = request.scheduled_start - start_padding_sec as i64;
+    if actual_start < 0 {
+        return Err(anyhow::anyhow!(
+            "Start padding of {}s puts the recording start before the epoch (start {})",
+            start_padding_sec,
+            request.scheduled_start
+        ));
+    }
+
+    Ok(())
+}
+
+/// Map a `dvr_schedules` row to a Schedule
+fn schedule_from_row(row: &rusqlite::Row) -> rusqlite::Result<Schedule> {
+    let status_str: String = row.get("status")?;
+    Ok(Schedule {
+        id: row.get("id")?,
+        source_id: row.get("source_id")?,
+        channel_id: row.get("channel_id")?,
+        channel_name: row.get("channel_name")?,
+        program_title: row.get("program_title")?,
+        scheduled_start: row.get("scheduled_start")?,
+        scheduled_end: row.get("scheduled_end")?,
+        start_padding_sec: row.get("start_padding_sec")?,
+        end_padding_sec: row.get("end_padding_sec")?,
+        status: status_str.parse().unwrap_or(ScheduleStatus::Scheduled),
+        series_match_title: row.get("series_match_title")?,
+        recurrence: row.get("recurrence")?,
+        created_at: row.get("created_at")?,
+        started_at: row.get("started_at")?,
+        stream_url: row.get("stream_url")?,
+        auto_extend_minutes: row.get::<_, Option<i32>>("auto_extend_minutes")?.unwrap_or(0),
+        priority: row.get::<_, Option<i32>>("priority")?.unwrap_or(0),
+    })
+}
+
+/// Map a `dvr_recordings` row to a Recording
+fn recording_from_row(row: &rusqlite::Row) -> rusqlite::Result<Recording> {
+    let status_str: String = row.get("status")?;
+    Ok(Recording {
+        id: row.get("id")?,
+        schedule_id: row.get("schedule_id")?,
+        file_path: row.get("file_path")?,
+        filename: row.get("filename")?,
+        channel_name: row.get("channel_name")?,
+        program_title: row.get("program_title")?,
+        size_bytes: row.get("size_bytes")?,
+        scheduled_start: row.get("scheduled_start")?,
+        scheduled_end: row.get("scheduled_end")?,
+        actual_start: row.get("actual_start")?,
+        actual_end: row.get("actual_end")?,
+        status: status_str.parse().unwrap_or(RecordingStatus::Failed),
+        error_message: row.get("error_message")?,
+        auto_delete_policy: row.get("auto_delete_policy")?,
+        created_at: row.get("created_at")?,
+        thumbnail_path: row.get("thumbnail_path")?,
+        retry_count: row.get::<_, Option<i32>>("retry_count")?.unwrap_or(0),
+        sprite_path: row.get("sprite_path")?,
+        part_of: row.get("part_of")?,
+        part_number: row.get("part_number")?,
+        is_protected: row.get::<_, Option<bool>>("is_protected")?.unwrap_or(false),
+        watched_position: row.get::<_, Option<f64>>("watched_position")?.unwrap_or(0.0),
+        watched: row.get::<_, Option<bool>>("watched")?.unwrap_or(false),
+    })
+}
+
 /// Database connection pool for DVR operations
 #[derive(Clone)]
 pub struct DvrDatabase {
@@ -46,8 +148,11 @@ impl DvrDatabase {
             .app_data_dir()
             .context("Failed to get app data directory")?;
 
-        let db_path = app_data_dir.join("ynotv.db");
+        Self::open(&app_data_dir.join("ynotv.db"))
+    }
 
+    /// Open (creating if needed) the database at `db_path`
+    pub fn open(db_path: &std::path::Path) -> Result<Self> {
         info!("Initializing DVR database at: {:?}", db_path);
 
         // Ensure parent directory exists
@@ -56,7 +161,7 @@ impl DvrDatabase {
         }
 
         // Create connection manager
-        let manager = SqliteConnectionManager::file(&db_path);
+        let manager = SqliteConnectionManager::file(db_path);
 
         // Build connection pool with custom configuration
         let pool = Pool::builder()
@@ -171,6 +276,62 @@ impl DvrDatabase {
         let _ = conn.execute("ALTER TABLE dvr_schedules ADD COLUMN stream_url TEXT", []); // Ignore error if column already exists
         println!("[DVR DB] Migration check complete");
 
+        // Migration: Add auto_extend_minutes column for overrunning live programs
+        let _ = conn.execute(
+            "ALTER TABLE dvr_schedules ADD COLUMN auto_extend_minutes INTEGER DEFAULT 0",
+            [],
+        ); // Ignore error if column already exists
+
+        // Migration: Add priority column for connection contention between schedules
+        let _ = conn.execute(
+            "ALTER TABLE dvr_schedules ADD COLUMN priority INTEGER DEFAULT 0",
+            [],
+        ); // Ignore error if column already exists
+
+        // Migration: Add retry_count column for automatic recording retries
+        let _ = conn.execute(
+            "ALTER TABLE dvr_recordings ADD COLUMN retry_count INTEGER DEFAULT 0",
+            [],
+        ); // Ignore error if column already exists
+
+        // Migration: Add part columns linking the segments of a split recording
+        let _ = conn.execute(
+            "ALTER TABLE dvr_recordings ADD COLUMN part_of INTEGER",
+            [],
+        ); // Ignore error if column already exists
+        let _ = conn.execute(
+            "ALTER TABLE dvr_recordings ADD COLUMN part_number INTEGER",
+            [],
+        ); // Ignore error if column already exists
+
+        // Migration: Add sprite_path column for scrub bar previews
+        let _ = conn.execute(
+            "ALTER TABLE dvr_recordings ADD COLUMN sprite_path TEXT",
+            [],
+        ); // Ignore error if column already exists
+
+        // Migration: Add is_protected column so favorites survive auto-cleanup
+        let _ = conn.execute(
+            "ALTER TABLE dvr_recordings ADD COLUMN is_protected INTEGER DEFAULT 0",
+            [],
+        ); // Ignore error if column already exists
+
+        // Migration: Add watch state columns for resume / watched badges
+        let _ = conn.execute(
+            "ALTER TABLE dvr_recordings ADD COLUMN watched_position REAL DEFAULT 0",
+            [],
+        ); // Ignore error if column already exists
+        let _ = conn.execute(
+            "ALTER TABLE dvr_recordings ADD COLUMN watched INTEGER DEFAULT 0",
+            [],
+        ); // Ignore error if column already exists
+
+        // Migration: Add FFmpeg log tail for diagnosing failed recordings
+        let _ = conn.execute(
+            "ALTER TABLE dvr_recordings ADD COLUMN log TEXT",
+            [],
+        ); // Ignore error if column already exists
+
         // Migration: Add thumbnail_path column to existing databases
         println!("[DVR DB] Checking for thumbnail_path column migration...");
         let _ = conn.execute(
@@ -290,6 +451,77 @@ impl DvrDatabase {
             [],
         )?;
 
+        // VOD watch history, owned by the frontend (ui/src/db) which also reads and
+        // writes it; created here too so the player can save progress before first use
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS vod_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                media_id TEXT NOT NULL,
+                media_type TEXT NOT NULL CHECK(media_type IN ('movie', 'series')),
+                source_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                watched_at INTEGER NOT NULL,
+                progress_seconds INTEGER,
+                total_duration INTEGER,
+                poster_url TEXT,
+                season_num INTEGER,
+                episode_num INTEGER,
+                episode_title TEXT
+            )",
+            [],
+        )?;
+
+        // Series recording rules, expanded into schedules as the EPG is refreshed
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS series_rules (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                channel_name TEXT NOT NULL,
+                series_title TEXT NOT NULL COLLATE NOCASE,
+                start_padding_sec INTEGER DEFAULT 60,
+                end_padding_sec INTEGER DEFAULT 300,
+                created_at INTEGER NOT NULL,
+                UNIQUE (source_id, channel_id, series_title)
+            )",
+            [],
+        )?;
+
+        // Carry over rules that were previously only implied by series schedules
+        let _ = conn.execute(
+            "INSERT OR IGNORE INTO series_rules
+                (source_id, channel_id, channel_name, series_title, start_padding_sec, end_padding_sec, created_at)
+             SELECT source_id, channel_id, MAX(channel_name), series_match_title,
+                    MAX(start_padding_sec), MAX(end_padding_sec), MIN(created_at)
+             FROM dvr_schedules
+             WHERE series_match_title IS NOT NULL AND series_match_title != ''
+             GROUP BY source_id, channel_id, series_match_title COLLATE NOCASE",
+            [],
+        );
+
+        // Full-text index over channel names, reading from `channels` by rowid.
+        // Kept in step by triggers; see db_queries::ensure_channels_fts.
+        let fts_sql: Option<String> = conn
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'channels_fts'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if fts_sql.is_some_and(|sql| !sql.contains("content=")) {
+            // Earlier versions stored their own copy of the names
+            conn.execute("DROP TABLE channels_fts", [])?;
+        }
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS channels_fts USING fts5(
+                name,
+                content = 'channels',
+                content_rowid = 'rowid',
+                tokenize = 'unicode61 remove_diacritics 2'
+            )",
+            [],
+        )?;
+
         println!("[DVR DB] Schema initialized successfully");
         debug!("Database schema initialized");
         Ok(())
@@ -408,26 +640,7 @@ impl DvrDatabase {
         let window_start = now - window_seconds;
         let grace_start = now - grace_period_seconds;
 
-        let schedules = stmt.query_map(params![upcoming, window_start, grace_start], |row| {
-            let status_str: String = row.get("status")?;
-            Ok(Schedule {
-                id: row.get("id")?,
-                source_id: row.get("source_id")?,
-                channel_id: row.get("channel_id")?,
-                channel_name: row.get("channel_name")?,
-                program_title: row.get("program_title")?,
-                scheduled_start: row.get("scheduled_start")?,
-                scheduled_end: row.get("scheduled_end")?,
-                start_padding_sec: row.get("start_padding_sec")?,
-                end_padding_sec: row.get("end_padding_sec")?,
-                status: status_str.parse().unwrap_or(ScheduleStatus::Scheduled),
-                series_match_title: row.get("series_match_title")?,
-                recurrence: row.get("recurrence")?,
-                created_at: row.get("created_at")?,
-                started_at: row.get("started_at")?,
-                stream_url: row.get("stream_url")?,
-            })
-        })?;
+        let schedules = stmt.query_map(params![upcoming, window_start, grace_start], schedule_from_row)?;
 
         let mut result = Vec::new();
         for schedule in schedules {
@@ -461,13 +674,33 @@ impl DvrDatabase {
         Ok(count)
     }
 
+    /// Earliest padded start time among scheduled recordings that haven't started yet
+    pub fn next_scheduled_start(&self, now: i64) -> Result<Option<i64>> {
+        let conn = self.get_conn()?;
+        let next: Option<i64> = conn.query_row(
+            "SELECT MIN(scheduled_start - start_padding_sec) FROM dvr_schedules
+             WHERE status = 'scheduled' AND (scheduled_start - start_padding_sec) >= ?1",
+            params![now],
+            |row| row.get(0),
+        )?;
+        Ok(next)
+    }
+
     /// Add a new recording schedule
     pub fn add_schedule(&self, request: &ScheduleRequest) -> Result<i64> {
+        self.insert_schedule(request, Some(chrono::Utc::now().timestamp()))
+    }
+
+    /// Add a schedule, rejecting it if it has already ended at `now` (None skips that check)
+    fn insert_schedule(&self, request: &ScheduleRequest, now: Option<i64>) -> Result<i64> {
         println!(
             "[DVR DB] add_schedule called for: {}",
             request.program_title
         );
 
+        let (start_padding_sec, end_padding_sec) = self.resolve_padding(request)?;
+        validate_schedule_times(request, start_padding_sec, end_padding_sec, now)?;
+
         let conn = self.get_conn()?;
         println!("[DVR DB] Got database connection");
 
@@ -513,8 +746,9 @@ impl DvrDatabase {
             "INSERT INTO dvr_schedules (
                 source_id, channel_id, channel_name, program_title,
                 scheduled_start, scheduled_end, start_padding_sec, end_padding_sec,
-                series_match_title, recurrence, status, created_at, stream_url
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 'scheduled', ?11, ?12)",
+                series_match_title, recurrence, status, created_at, stream_url,
+                auto_extend_minutes, priority
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 'scheduled', ?11, ?12, ?13, ?14)",
             params![
                 request.source_id,
                 request.channel_id,
@@ -522,12 +756,14 @@ impl DvrDatabase {
                 request.program_title,
                 request.scheduled_start,
                 request.scheduled_end,
-                request.start_padding_sec,
-                request.end_padding_sec,
+                start_padding_sec,
+                end_padding_sec,
                 request.series_match_title,
                 request.recurrence,
                 chrono::Utc::now().timestamp(),
-                request.stream_url
+                request.stream_url,
+                request.auto_extend_minutes,
+                request.priority
             ],
         )?;
         println!("[DVR DB] INSERT affected {} rows", result);
@@ -625,6 +861,19 @@ impl DvrDatabase {
         Ok(())
     }
 
+    /// Update a schedule's contention priority
+    pub fn update_schedule_priority(&self, id: i64, priority: i32) -> Result<()> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "UPDATE dvr_schedules SET priority = ?1 WHERE id = ?2",
+            params![priority, id],
+        )?;
+
+        info!("Updated priority for schedule {}: {}", id, priority);
+        Ok(())
+    }
+
     /// Get schedule by ID
     pub fn get_schedule(&self, id: i64) -> Result<Option<Schedule>> {
         let conn = self.get_conn()?;
@@ -633,69 +882,13 @@ impl DvrDatabase {
             .query_row(
                 "SELECT * FROM dvr_schedules WHERE id = ?1",
                 params![id],
-                |row| {
-                    let status_str: String = row.get("status")?;
-                    Ok(Schedule {
-                        id: row.get("id")?,
-                        source_id: row.get("source_id")?,
-                        channel_id: row.get("channel_id")?,
-                        channel_name: row.get("channel_name")?,
-                        program_title: row.get("program_title")?,
-                        scheduled_start: row.get("scheduled_start")?,
-                        scheduled_end: row.get("scheduled_end")?,
-                        start_padding_sec: row.get("start_padding_sec")?,
-                        end_padding_sec: row.get("end_padding_sec")?,
-                        status: status_str.parse().unwrap_or(ScheduleStatus::Scheduled),
-                        series_match_title: row.get("series_match_title")?,
-                        recurrence: row.get("recurrence")?,
-                        created_at: row.get("created_at")?,
-                        started_at: row.get("started_at")?,
-                        stream_url: row.get("stream_url")?,
-                    })
-                },
+                schedule_from_row,
             )
             .optional()?;
 
         Ok(schedule)
     }
 
-    /// Add a new recording entry
-    pub fn add_recording(
-        &self,
-        schedule_id: i64,
-        file_path: &str,
-        filename: &str,
-        channel_name: &str,
-        program_title: &str,
-        scheduled_start: i64,
-        scheduled_end: i64,
-    ) -> Result<i64> {
-        let conn = self.get_conn()?;
-
-        conn.execute(
-            "INSERT INTO dvr_recordings (
-                schedule_id, file_path, filename, channel_name, program_title,
-                scheduled_start, scheduled_end, actual_start, status, created_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'recording', ?9)",
-            params![
-                schedule_id,
-                file_path,
-                filename,
-                channel_name,
-                program_title,
-                scheduled_start,
-                scheduled_end,
-                chrono::Utc::now().timestamp(),
-                chrono::Utc::now().timestamp()
-            ],
-        )?;
-
-        let id = conn.last_insert_rowid();
-        info!("Added recording {} for schedule {}", id, schedule_id);
-
-        Ok(id)
-    }
-
     /// Update recording status
     pub fn update_recording_status(
         &self,
@@ -726,145 +919,513 @@ impl DvrDatabase {
         Ok(())
     }
 
-    /// Update recording file size
-    pub fn update_recording_size(&self, id: i64, size_bytes: i64) -> Result<()> {
+    /// Recordings whose files should exist on disk (finished, partial, or previously orphaned)
+    pub fn get_recordings_for_scan(&self) -> Result<Vec<Recording>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT * FROM dvr_recordings WHERE status IN ('completed', 'partial', 'orphaned')",
+        )?;
+        let recordings = stmt
+            .query_map([], recording_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(recordings)
+    }
+
+    /// File paths of every recording row, whatever its status
+    pub fn get_recording_file_paths(&self) -> Result<Vec<String>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare("SELECT file_path FROM dvr_recordings")?;
+        let paths = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(paths)
+    }
+
+    /// Overwrite a recording's size and start/end times (metadata repair)
+    pub fn update_recording_metadata(&self, id: i64, size_bytes: i64, actual_start: i64, actual_end: i64) -> Result<()> {
         let conn = self.get_conn()?;
 
         conn.execute(
-            "UPDATE dvr_recordings SET size_bytes = ?1 WHERE id = ?2",
-            params![size_bytes, id],
+            "UPDATE dvr_recordings SET size_bytes = ?1, actual_start = ?2, actual_end = ?3 WHERE id = ?4",
+            params![size_bytes, actual_start, actual_end, id],
         )?;
 
+        debug!("Updated metadata for recording {}", id);
         Ok(())
     }
 
-    /// Update recording thumbnail path
-    pub fn update_recording_thumbnail(&self, id: i64, thumbnail_path: &str) -> Result<()> {
+    /// Set a recording's status without touching its size or end time
+    pub fn set_recording_status(&self, id: i64, status: RecordingStatus) -> Result<()> {
         let conn = self.get_conn()?;
 
         conn.execute(
-            "UPDATE dvr_recordings SET thumbnail_path = ?1 WHERE id = ?2",
-            params![thumbnail_path, id],
+            "UPDATE dvr_recordings SET status = ?1 WHERE id = ?2",
+            params![status.as_str(), id],
         )?;
 
-        info!("Updated thumbnail for recording {}: {}", id, thumbnail_path);
+        debug!("Set recording {} to {:?}", id, status);
         Ok(())
     }
 
-    /// Get recording by ID
-    pub fn get_recording(&self, id: i64) -> Result<Option<Recording>> {
+    /// Add a recording for a video file found in storage without a database row.
+    /// The file's modification time stands in for the recording time. Returns the new ID.
+    pub fn add_imported_recording(
+        &self,
+        file_path: &str,
+        filename: &str,
+        title: &str,
+        size_bytes: i64,
+        modified_at: i64,
+    ) -> Result<i64> {
         let conn = self.get_conn()?;
 
-        let recording = conn
-            .query_row(
-                "SELECT * FROM dvr_recordings WHERE id = ?1",
-                params![id],
-                |row| {
-                    let status_str: String = row.get("status")?;
-                    Ok(Recording {
-                        id: row.get("id")?,
-                        schedule_id: row.get("schedule_id")?,
-                        file_path: row.get("file_path")?,
-                        filename: row.get("filename")?,
-                        channel_name: row.get("channel_name")?,
-                        program_title: row.get("program_title")?,
-                        size_bytes: row.get("size_bytes")?,
-                        scheduled_start: row.get("scheduled_start")?,
-                        scheduled_end: row.get("scheduled_end")?,
-                        actual_start: row.get("actual_start")?,
-                        actual_end: row.get("actual_end")?,
-                        status: status_str.parse().unwrap_or(RecordingStatus::Failed),
-                        error_message: row.get("error_message")?,
-                        auto_delete_policy: row.get("auto_delete_policy")?,
-                        created_at: row.get("created_at")?,
-                        thumbnail_path: row.get("thumbnail_path")?,
-                    })
-                },
-            )
-            .optional()?;
+        conn.execute(
+            "INSERT INTO dvr_recordings (
+                schedule_id, file_path, filename, channel_name, program_title, size_bytes,
+                scheduled_start, scheduled_end, actual_start, actual_end, status, created_at
+            ) VALUES (NULL, ?1, ?2, 'Imported', ?3, ?4, ?5, ?5, ?5, ?5, 'completed', ?6)",
+            params![file_path, filename, title, size_bytes, modified_at, chrono::Utc::now().timestamp()],
+        )?;
 
-        Ok(recording)
+        let id = conn.last_insert_rowid();
+        info!("Imported recording {} from {}", id, file_path);
+        Ok(id)
     }
 
-    /// Get completed recordings for cleanup
-    pub fn get_completed_recordings(&self) -> Result<Vec<Recording>> {
+    /// Add a recording for a trimmed copy of `source_id`, keeping its channel, title and policy
+    pub fn add_trimmed_recording(
+        &self,
+        source_id: i64,
+        file_path: &str,
+        filename: &str,
+        size_bytes: i64,
+        actual_start: i64,
+        actual_end: i64,
+    ) -> Result<i64> {
         let conn = self.get_conn()?;
 
-        let mut stmt = conn.prepare(
-            "SELECT * FROM dvr_recordings
-             WHERE status IN ('completed', 'partial')
-             ORDER BY actual_end DESC",
+        conn.execute(
+            "INSERT INTO dvr_recordings (
+                schedule_id, file_path, filename, channel_name, program_title, size_bytes,
+                scheduled_start, scheduled_end, actual_start, actual_end, status,
+                auto_delete_policy, created_at
+            )
+            SELECT schedule_id, ?2, ?3, channel_name, program_title, ?4,
+                   scheduled_start, scheduled_end, ?5, ?6, 'completed',
+                   auto_delete_policy, ?7
+            FROM dvr_recordings WHERE id = ?1",
+            params![source_id, file_path, filename, size_bytes, actual_start, actual_end, chrono::Utc::now().timestamp()],
         )?;
 
-        let recordings = stmt.query_map([], |row| {
-            let status_str: String = row.get("status")?;
-            Ok(Recording {
-                id: row.get("id")?,
-                schedule_id: row.get("schedule_id")?,
-                file_path: row.get("file_path")?,
-                filename: row.get("filename")?,
-                channel_name: row.get("channel_name")?,
-                program_title: row.get("program_title")?,
-                size_bytes: row.get("size_bytes")?,
-                scheduled_start: row.get("scheduled_start")?,
-                scheduled_end: row.get("scheduled_end")?,
-                actual_start: row.get("actual_start")?,
-                actual_end: row.get("actual_end")?,
-                status: status_str.parse().unwrap_or(RecordingStatus::Failed),
-                error_message: row.get("error_message")?,
-                auto_delete_policy: row.get("auto_delete_policy")?,
-                created_at: row.get("created_at")?,
-                thumbnail_path: row.get("thumbnail_path")?,
-            })
-        })?;
+        let id = conn.last_insert_rowid();
+        info!("Added trimmed recording {} from recording {}", id, source_id);
+        Ok(id)
+    }
 
-        let mut result = Vec::new();
-        for recording in recordings {
-            result.push(recording?);
-        }
+    /// Update recording file size
+    pub fn update_recording_size(&self, id: i64, size_bytes: i64) -> Result<()> {
+        let conn = self.get_conn()?;
 
-        Ok(result)
+        conn.execute(
+            "UPDATE dvr_recordings SET size_bytes = ?1 WHERE id = ?2",
+            params![size_bytes, id],
+        )?;
+
+        Ok(())
     }
 
-    /// Delete a recording entry and return file path and thumbnail path for deletion
-    pub fn delete_recording(&self, id: i64) -> Result<Option<(String, Option<String>)>> {
+    /// Point a recording at a replacement file (e.g. after transcoding)
+    pub fn update_recording_file(&self, id: i64, file_path: &str, filename: &str, size_bytes: i64) -> Result<()> {
         let conn = self.get_conn()?;
 
-        // Get file path and thumbnail path first
-        let (file_path, thumbnail_path): (Option<String>, Option<String>) = conn
-            .query_row(
-                "SELECT file_path, thumbnail_path FROM dvr_recordings WHERE id = ?1",
-                params![id],
-                |row| Ok((row.get(0)?, row.get(1)?)),
-            )
-            .optional()?
-            .unwrap_or((None, None));
+        conn.execute(
+            "UPDATE dvr_recordings SET file_path = ?1, filename = ?2, size_bytes = ?3 WHERE id = ?4",
+            params![file_path, filename, size_bytes, id],
+        )?;
 
-        // Delete from database
-        conn.execute("DELETE FROM dvr_recordings WHERE id = ?1", params![id])?;
+        info!("Updated file for recording {}: {}", id, file_path);
+        Ok(())
+    }
 
-        info!("Deleted recording {} from database", id);
-        Ok(file_path.map(|fp| (fp, thumbnail_path)))
+    /// Point a recording at moved files (storage migration)
+    pub fn update_recording_paths(
+        &self,
+        id: i64,
+        file_path: &str,
+        thumbnail_path: Option<&str>,
+        sprite_path: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "UPDATE dvr_recordings SET file_path = ?1, thumbnail_path = ?2, sprite_path = ?3 WHERE id = ?4",
+            params![file_path, thumbnail_path, sprite_path, id],
+        )?;
+
+        Ok(())
     }
 
-    /// Get DVR settings
-    pub fn get_settings(&self) -> Result<DvrSettings> {
+    /// Register a later part of a split recording, copying its details from the first part.
+    /// Returns the new recording ID.
+    pub fn add_recording_part(
+        &self,
+        first_part_id: i64,
+        part_number: i32,
+        file_path: &str,
+        filename: &str,
+        size_bytes: i64,
+    ) -> Result<i64> {
         let conn = self.get_conn()?;
 
-        let mut settings = DvrSettings::default();
+        conn.execute(
+            "UPDATE dvr_recordings SET part_number = COALESCE(part_number, 1) WHERE id = ?1",
+            params![first_part_id],
+        )?;
 
-        let mut stmt = conn.prepare("SELECT key, value FROM dvr_settings")?;
-        let rows = stmt.query_map([], |row| {
-            let key: String = row.get(0)?;
-            let value: String = row.get(1)?;
-            Ok((key, value))
-        })?;
+        conn.execute(
+            "INSERT INTO dvr_recordings (
+                schedule_id, file_path, filename, channel_name, program_title, size_bytes,
+                scheduled_start, scheduled_end, actual_start, actual_end, status, error_message,
+                created_at, part_of, part_number
+            )
+            SELECT schedule_id, ?2, ?3, channel_name, program_title, ?4,
+                   scheduled_start, scheduled_end, actual_start, actual_end, status, error_message,
+                   created_at, id, ?5
+            FROM dvr_recordings WHERE id = ?1",
+            params![first_part_id, file_path, filename, size_bytes, part_number],
+        )?;
 
-        for row in rows {
-            let (key, value) = row?;
-            match key.as_str() {
-                "storage_path" => settings.storage_path = value,
-                "max_disk_usage_percent" => {
+        let id = conn.last_insert_rowid();
+        info!("Added part {} of recording {} as recording {}", part_number, first_part_id, id);
+        Ok(id)
+    }
+
+    /// Add the recording produced by joining several recordings, copying its details from the first one.
+    /// Returns the new recording ID.
+    pub fn add_joined_recording(
+        &self,
+        first_id: i64,
+        file_path: &str,
+        filename: &str,
+        size_bytes: i64,
+        actual_end: Option<i64>,
+    ) -> Result<i64> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "INSERT INTO dvr_recordings (
+                schedule_id, file_path, filename, channel_name, program_title, size_bytes,
+                scheduled_start, scheduled_end, actual_start, actual_end, status,
+                auto_delete_policy, created_at
+            )
+            SELECT schedule_id, ?2, ?3, channel_name, program_title, ?4,
+                   scheduled_start, scheduled_end, actual_start, COALESCE(?5, actual_end), 'completed',
+                   auto_delete_policy, created_at
+            FROM dvr_recordings WHERE id = ?1",
+            params![first_id, file_path, filename, size_bytes, actual_end],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        info!("Added joined recording {} from recording {}", id, first_id);
+        Ok(id)
+    }
+
+    /// Get every part of the split recording `id` belongs to, in part order.
+    /// An unsplit recording is returned on its own.
+    pub fn get_recording_parts(&self, id: i64) -> Result<Vec<Recording>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT * FROM dvr_recordings
+             WHERE id = (SELECT COALESCE(part_of, id) FROM dvr_recordings WHERE id = ?1)
+                OR part_of = (SELECT COALESCE(part_of, id) FROM dvr_recordings WHERE id = ?1)
+             ORDER BY COALESCE(part_number, 1)",
+        )?;
+
+        let parts = stmt
+            .query_map(params![id], recording_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(parts)
+    }
+
+    /// Update the number of retries made for a recording
+    pub fn update_recording_retry_count(&self, id: i64, retry_count: i32) -> Result<()> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "UPDATE dvr_recordings SET retry_count = ?1 WHERE id = ?2",
+            params![retry_count, id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Mark a recording as protected from (or eligible for) automatic cleanup
+    pub fn set_recording_protected(&self, id: i64, protected: bool) -> Result<()> {
+        let conn = self.get_conn()?;
+
+        let updated = conn.execute(
+            "UPDATE dvr_recordings SET is_protected = ?1 WHERE id = ?2",
+            params![protected, id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Recording {} not found", id));
+        }
+
+        Ok(())
+    }
+
+    /// Save how far a recording has been played. Within the last 5% (or 2 minutes)
+    /// of `duration` it is marked watched; once watched it stays watched.
+    pub fn update_recording_watch_position(&self, id: i64, position: f64, duration: Option<f64>) -> Result<()> {
+        let near_end = duration.is_some_and(|d| is_watched(position, d));
+        let conn = self.get_conn()?;
+
+        let updated = conn.execute(
+            "UPDATE dvr_recordings SET watched_position = ?1, watched = MAX(COALESCE(watched, 0), ?2) WHERE id = ?3",
+            params![position.max(0.0), near_end, id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Recording {} not found", id));
+        }
+
+        Ok(())
+    }
+
+    /// Mark a recording watched or unwatched; unwatching also resets the position
+    pub fn set_recording_watched(&self, id: i64, watched: bool) -> Result<()> {
+        let conn = self.get_conn()?;
+
+        let updated = conn.execute(
+            "UPDATE dvr_recordings SET watched = ?1,
+                watched_position = CASE WHEN ?1 THEN watched_position ELSE 0 END
+             WHERE id = ?2",
+            params![watched, id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow::anyhow!("Recording {} not found", id));
+        }
+
+        Ok(())
+    }
+
+    /// Append FFmpeg output to a recording's log, keeping only the last `max_chars`
+    pub fn append_recording_log(&self, id: i64, text: &str, max_chars: usize) -> Result<()> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "UPDATE dvr_recordings SET log = substr(COALESCE(log, '') || ?1, -?2) WHERE id = ?3",
+            params![text, max_chars as i64, id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get a recording's captured FFmpeg log (None if nothing was captured)
+    pub fn get_recording_log(&self, id: i64) -> Result<Option<String>> {
+        let conn = self.get_conn()?;
+
+        let log: Option<Option<String>> = conn
+            .query_row("SELECT log FROM dvr_recordings WHERE id = ?1", [id], |row| row.get(0))
+            .optional()?;
+
+        match log {
+            Some(log) => Ok(log),
+            None => Err(anyhow::anyhow!("Recording {} not found", id)),
+        }
+    }
+
+    /// Update recording thumbnail path
+    pub fn update_recording_thumbnail(&self, id: i64, thumbnail_path: &str) -> Result<()> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "UPDATE dvr_recordings SET thumbnail_path = ?1 WHERE id = ?2",
+            params![thumbnail_path, id],
+        )?;
+
+        info!("Updated thumbnail for recording {}: {}", id, thumbnail_path);
+        Ok(())
+    }
+
+    /// Update recording preview sprite path
+    pub fn update_recording_sprite(&self, id: i64, sprite_path: &str) -> Result<()> {
+        let conn = self.get_conn()?;
+
+        conn.execute(
+            "UPDATE dvr_recordings SET sprite_path = ?1 WHERE id = ?2",
+            params![sprite_path, id],
+        )?;
+
+        info!("Updated sprite for recording {}: {}", id, sprite_path);
+        Ok(())
+    }
+
+    /// Get recording by ID
+    pub fn get_recording(&self, id: i64) -> Result<Option<Recording>> {
+        let conn = self.get_conn()?;
+
+        let recording = conn
+            .query_row(
+                "SELECT * FROM dvr_recordings WHERE id = ?1",
+                params![id],
+                recording_from_row,
+            )
+            .optional()?;
+
+        Ok(recording)
+    }
+
+    /// Get completed recordings for cleanup
+    pub fn get_completed_recordings(&self) -> Result<Vec<Recording>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT * FROM dvr_recordings
+             WHERE status IN ('completed', 'partial')
+             ORDER BY actual_end DESC",
+        )?;
+
+        let recordings = stmt.query_map([], recording_from_row)?;
+
+        let mut result = Vec::new();
+        for recording in recordings {
+            result.push(recording?);
+        }
+
+        Ok(result)
+    }
+
+    /// Total size and count of completed/partial recordings
+    pub fn get_recordings_size_summary(&self) -> Result<(i64, i64)> {
+        let conn = self.get_conn()?;
+        let summary = conn.query_row(
+            "SELECT COALESCE(SUM(size_bytes), 0), COUNT(*) FROM dvr_recordings
+             WHERE status IN ('completed', 'partial')",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(summary)
+    }
+
+    /// Filtered, sorted, paginated listing of recordings for the library view.
+    /// Dates filter on when the recording started (falling back to its scheduled start).
+    #[allow(clippy::too_many_arguments)]
+    pub fn query_recordings(
+        &self,
+        status: Option<RecordingStatus>,
+        channel_name: Option<&str>,
+        from_ts: Option<i64>,
+        to_ts: Option<i64>,
+        sort: RecordingSort,
+        limit: u32,
+        offset: u32,
+    ) -> Result<RecordingPage> {
+        let status = status.map(|s| s.as_str());
+
+        let mut where_clause = String::from("1=1");
+        let mut filter: Vec<&dyn rusqlite::ToSql> = Vec::new();
+        if let Some(status) = status.as_ref() {
+            where_clause.push_str(" AND status = ?");
+            filter.push(status);
+        }
+        if let Some(channel_name) = channel_name.as_ref() {
+            where_clause.push_str(" AND channel_name = ? COLLATE NOCASE");
+            filter.push(channel_name);
+        }
+        if let Some(from_ts) = from_ts.as_ref() {
+            where_clause.push_str(" AND COALESCE(actual_start, scheduled_start) >= ?");
+            filter.push(from_ts);
+        }
+        if let Some(to_ts) = to_ts.as_ref() {
+            where_clause.push_str(" AND COALESCE(actual_start, scheduled_start) < ?");
+            filter.push(to_ts);
+        }
+
+        let conn = self.get_conn()?;
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM dvr_recordings WHERE {}", where_clause),
+            filter.as_slice(),
+            |row| row.get(0),
+        )?;
+
+        let sql = format!(
+            "SELECT * FROM dvr_recordings WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+            where_clause,
+            sort.order_by()
+        );
+        let mut page_params = filter.clone();
+        page_params.push(&limit);
+        page_params.push(&offset);
+
+        let mut stmt = conn.prepare(&sql)?;
+        let recordings = stmt
+            .query_map(page_params.as_slice(), recording_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(RecordingPage { recordings, total })
+    }
+
+    /// Delete a recording entry and return file path and thumbnail path for deletion
+    pub fn delete_recording(&self, id: i64) -> Result<Option<(String, Option<String>)>> {
+        let conn = self.get_conn()?;
+
+        // Get file path and thumbnail path first
+        let (file_path, thumbnail_path): (Option<String>, Option<String>) = conn
+            .query_row(
+                "SELECT file_path, thumbnail_path FROM dvr_recordings WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .unwrap_or((None, None));
+
+        // Delete from database
+        conn.execute("DELETE FROM dvr_recordings WHERE id = ?1", params![id])?;
+
+        info!("Deleted recording {} from database", id);
+        Ok(file_path.map(|fp| (fp, thumbnail_path)))
+    }
+
+    /// Delete many recording rows in one transaction
+    pub fn delete_recordings(&self, ids: &[i64]) -> Result<usize> {
+        let mut conn = self.get_conn()?;
+        let tx = conn.transaction()?;
+
+        let mut deleted = 0;
+        {
+            let mut stmt = tx.prepare("DELETE FROM dvr_recordings WHERE id = ?1")?;
+            for id in ids {
+                deleted += stmt.execute(params![id])?;
+            }
+        }
+        tx.commit()?;
+
+        info!("Deleted {} recordings from database", deleted);
+        Ok(deleted)
+    }
+
+    /// Get DVR settings
+    pub fn get_settings(&self) -> Result<DvrSettings> {
+        let conn = self.get_conn()?;
+
+        let mut settings = DvrSettings::default();
+
+        let mut stmt = conn.prepare("SELECT key, value FROM dvr_settings")?;
+        let rows = stmt.query_map([], |row| {
+            let key: String = row.get(0)?;
+            let value: String = row.get(1)?;
+            Ok((key, value))
+        })?;
+
+        for row in rows {
+            let (key, value) = row?;
+            match key.as_str() {
+                "storage_path" => settings.storage_path = value,
+                "max_disk_usage_percent" => {
                     if let Ok(v) = value.parse() {
                         settings.max_disk_usage_percent = v;
                     }
@@ -872,6 +1433,9 @@ impl DvrDatabase {
                 "auto_cleanup_enabled" => {
                     settings.auto_cleanup_enabled = value == "true" || value == "1";
                 }
+                "notifications_enabled" => {
+                    settings.notifications_enabled = value == "true" || value == "1";
+                }
                 "default_start_padding_sec" => {
                     if let Ok(v) = value.parse() {
                         settings.default_start_padding_sec = v;
@@ -887,6 +1451,78 @@ impl DvrDatabase {
                         settings.keep_recordings_days = Some(v);
                     }
                 }
+                "max_recording_retries" => {
+                    if let Ok(v) = value.parse() {
+                        settings.max_recording_retries = v;
+                    }
+                }
+                "retry_backoff_sec" => {
+                    if let Ok(v) = value.parse() {
+                        settings.retry_backoff_sec = v;
+                    }
+                }
+                "epg_retention_days" => {
+                    if let Ok(v) = value.parse() {
+                        settings.epg_retention_days = v;
+                    }
+                }
+                "filename_template" => {
+                    match crate::dvr::recorder::validate_filename_template(&value) {
+                        Ok(()) => settings.filename_template = value,
+                        Err(e) => warn!("Ignoring invalid filename_template '{}': {}", value, e),
+                    }
+                }
+                "transcode_profile" => {
+                    let profile = value.trim().to_lowercase();
+                    if crate::dvr::transcode::TRANSCODE_PROFILES.contains(&profile.as_str()) {
+                        settings.transcode_profile = profile;
+                    } else {
+                        warn!("Ignoring unknown transcode_profile '{}'", value);
+                    }
+                }
+                "segment_max_minutes" => {
+                    if let Ok(v) = value.parse() {
+                        settings.segment_max_minutes = v;
+                    }
+                }
+                "segment_max_gb" => {
+                    if let Ok(v) = value.parse::<f64>() {
+                        settings.segment_max_gb = v.max(0.0);
+                    }
+                }
+                "recording_user_agent" => settings.recording_user_agent = value.trim().to_string(),
+                "recording_reconnect" => {
+                    settings.recording_reconnect = value == "true" || value == "1";
+                }
+                "recording_timeout_sec" => {
+                    if let Ok(v) = value.parse::<u32>() {
+                        settings.recording_timeout_sec = v.max(1);
+                    }
+                }
+                "hls_start_mode" => {
+                    let mode = value.trim().to_lowercase();
+                    if crate::dvr::recorder::HLS_START_MODES.contains(&mode.as_str()) {
+                        settings.hls_start_mode = mode;
+                    } else {
+                        warn!("Ignoring unknown hls_start_mode '{}'", value);
+                    }
+                }
+                "timezone" => {
+                    let timezone = value.trim();
+                    if timezone.is_empty() || timezone.parse::<chrono_tz::Tz>().is_ok() {
+                        settings.timezone = timezone.to_string();
+                    } else {
+                        warn!("Ignoring unknown timezone '{}'", value);
+                    }
+                }
+                "recording_format" => {
+                    let format = value.trim().to_lowercase();
+                    if matches!(format.as_str(), "ts" | "mkv" | "mp4") {
+                        settings.recording_format = format;
+                    } else {
+                        warn!("Ignoring unknown recording_format '{}'", value);
+                    }
+                }
                 _ => {}
             }
         }
@@ -894,6 +1530,73 @@ impl DvrDatabase {
         Ok(settings)
     }
 
+    /// Get the padding override stored for a source
+    pub fn get_source_padding(&self, source_id: &str) -> Result<SourcePadding> {
+        let conn = self.get_conn()?;
+
+        let read = |key: String| -> Result<Option<i32>> {
+            let value: Option<String> = conn
+                .query_row("SELECT value FROM dvr_settings WHERE key = ?1", [key], |row| row.get(0))
+                .optional()?;
+            Ok(value.and_then(|v| v.parse().ok()))
+        };
+
+        Ok(SourcePadding {
+            source_id: source_id.to_string(),
+            start_padding_sec: read(format!("start_padding_sec:{}", source_id))?,
+            end_padding_sec: read(format!("end_padding_sec:{}", source_id))?,
+        })
+    }
+
+    /// Set or clear (None) the padding override for a source
+    pub fn set_source_padding(
+        &self,
+        source_id: &str,
+        start_padding_sec: Option<i32>,
+        end_padding_sec: Option<i32>,
+    ) -> Result<()> {
+        let entries = [
+            (format!("start_padding_sec:{}", source_id), start_padding_sec),
+            (format!("end_padding_sec:{}", source_id), end_padding_sec),
+        ];
+        for (key, value) in entries {
+            match value {
+                Some(v) => self.save_setting(&key, &v.max(0).to_string())?,
+                None => {
+                    self.get_conn()?
+                        .execute("DELETE FROM dvr_settings WHERE key = ?1", [key])?;
+                }
+            }
+        }
+
+        info!(
+            "Source {} padding set to start={:?} end={:?}",
+            source_id, start_padding_sec, end_padding_sec
+        );
+        Ok(())
+    }
+
+    /// Effective (start, end) padding for a request: request -> source -> global default
+    pub fn resolve_padding(&self, request: &ScheduleRequest) -> Result<(i32, i32)> {
+        if let (Some(start), Some(end)) = (request.start_padding_sec, request.end_padding_sec) {
+            return Ok((start, end));
+        }
+
+        let source = self.get_source_padding(&request.source_id)?;
+        // get_settings already falls back to the hardcoded 60/300 defaults
+        let settings = self.get_settings()?;
+
+        let start = request
+            .start_padding_sec
+            .or(source.start_padding_sec)
+            .unwrap_or(settings.default_start_padding_sec);
+        let end = request
+            .end_padding_sec
+            .or(source.end_padding_sec)
+            .unwrap_or(settings.default_end_padding_sec);
+        Ok((start, end))
+    }
+
     /// Save DVR setting
     pub fn save_setting(&self, key: &str, value: &str) -> Result<()> {
         let conn = self.get_conn()?;
@@ -919,14 +1622,15 @@ impl DvrDatabase {
     ) -> Result<(Vec<Schedule>, Option<i32>)> {
         let conn = self.get_conn()?;
 
-        // Get max_connections for this source
+        // Get max_connections for this source (the frontend stores it as nullable text)
         let max_connections: Option<i32> = conn
             .query_row(
-                "SELECT max_connections FROM sourcesMeta WHERE source_id = ?1",
+                "SELECT CAST(max_connections AS INTEGER) FROM sourcesMeta WHERE source_id = ?1",
                 [source_id],
                 |row| row.get(0),
             )
-            .optional()?;
+            .optional()?
+            .flatten();
 
         // Find overlapping schedules
         let mut stmt = conn.prepare(
@@ -936,26 +1640,7 @@ impl DvrDatabase {
              AND NOT (scheduled_end <= ?2 OR scheduled_start >= ?3)",
         )?;
 
-        let conflicts = stmt.query_map(params![source_id, start, end], |row| {
-            let status_str: String = row.get("status")?;
-            Ok(Schedule {
-                id: row.get("id")?,
-                source_id: row.get("source_id")?,
-                channel_id: row.get("channel_id")?,
-                channel_name: row.get("channel_name")?,
-                program_title: row.get("program_title")?,
-                scheduled_start: row.get("scheduled_start")?,
-                scheduled_end: row.get("scheduled_end")?,
-                start_padding_sec: row.get("start_padding_sec")?,
-                end_padding_sec: row.get("end_padding_sec")?,
-                status: status_str.parse().unwrap_or(ScheduleStatus::Scheduled),
-                series_match_title: row.get("series_match_title")?,
-                recurrence: row.get("recurrence")?,
-                created_at: row.get("created_at")?,
-                started_at: row.get("started_at")?,
-                stream_url: row.get("stream_url")?,
-            })
-        })?;
+        let conflicts = stmt.query_map(params![source_id, start, end], schedule_from_row)?;
 
         let mut result = Vec::new();
         for conflict in conflicts {
@@ -965,21 +1650,388 @@ impl DvrDatabase {
         Ok((result, max_connections))
     }
 
+    /// Earliest start at or after `start` where a recording of the same length leaves the source
+    /// under its connection limit. Candidates are the ends of the overlapping schedules; the
+    /// `playback` connections are assumed to stay open, since nothing says when viewing stops.
+    pub fn suggest_free_start(
+        &self,
+        source_id: &str,
+        start: i64,
+        end: i64,
+        max_conn: i32,
+        playback: usize,
+        conflicts: &[Schedule],
+    ) -> Option<i64> {
+        let duration = end - start;
+        let mut candidates: Vec<i64> = conflicts.iter().map(|s| s.scheduled_end).filter(|e| *e > start).collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates.into_iter().find(|&candidate| {
+            self.check_conflicts(source_id, candidate, candidate + duration)
+                .map(|(overlapping, _)| ((overlapping.len() + playback) as i32) < max_conn)
+                .unwrap_or(false)
+        })
+    }
+
+    /// Whether recording `request` would exceed its source's connection limit
+    ///
+    /// Compares the padded window the recorder will use with the padded windows of
+    /// scheduled and running recordings. Returns the limit if it is reached; unknown
+    /// (None/0) limits count as a single connection.
+    pub fn connection_limit_reached(&self, request: &ScheduleRequest) -> Result<Option<i32>> {
+        let (start_padding, end_padding) = self.resolve_padding(request)?;
+        let start = request.scheduled_start - start_padding as i64;
+        let end = request.scheduled_end + end_padding as i64;
+
+        let overlapping: i64 = self.get_conn()?.query_row(
+            "SELECT COUNT(*) FROM dvr_schedules
+             WHERE source_id = ?1
+             AND status IN ('scheduled', 'recording')
+             AND NOT (scheduled_end + COALESCE(end_padding_sec, 0) <= ?2
+                      OR scheduled_start - COALESCE(start_padding_sec, 0) >= ?3)",
+            params![request.source_id, start, end],
+            |row| row.get(0),
+        )?;
+
+        let max_conn = self.get_max_connections(&request.source_id)?.filter(|n| *n > 0).unwrap_or(1);
+        Ok((overlapping >= max_conn as i64).then_some(max_conn))
+    }
+
     /// Get max connections for a source
     pub fn get_max_connections(&self, source_id: &str) -> Result<Option<i32>> {
         let conn = self.get_conn()?;
 
         let max_connections: Option<i32> = conn
             .query_row(
-                "SELECT max_connections FROM sourcesMeta WHERE source_id = ?1",
+                "SELECT CAST(max_connections AS INTEGER) FROM sourcesMeta WHERE source_id = ?1",
                 [source_id],
                 |row| row.get(0),
             )
-            .optional()?;
+            .optional()?
+            .flatten();
 
         Ok(max_connections)
     }
 
+    /// Add a series rule, or update the padding of an existing rule for the same series
+    pub fn add_series_rule(&self, rule: &SeriesRule) -> Result<i64> {
+        let conn = self.get_conn()?;
+        let now = chrono::Utc::now().timestamp();
+
+        let id = conn.query_row(
+            "INSERT INTO series_rules
+                (source_id, channel_id, channel_name, series_title, start_padding_sec, end_padding_sec, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT (source_id, channel_id, series_title) DO UPDATE SET
+                channel_name = excluded.channel_name,
+                start_padding_sec = excluded.start_padding_sec,
+                end_padding_sec = excluded.end_padding_sec
+             RETURNING id",
+            params![
+                rule.source_id,
+                rule.channel_id,
+                rule.channel_name,
+                rule.series_title,
+                rule.start_padding_sec,
+                rule.end_padding_sec,
+                now
+            ],
+            |row| row.get(0),
+        )?;
+
+        debug!("Saved series rule {} for '{}'", id, rule.series_title);
+        Ok(id)
+    }
+
+    /// Get all series rules
+    pub fn get_series_rules(&self) -> Result<Vec<SeriesRule>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, source_id, channel_id, channel_name, series_title,
+                    start_padding_sec, end_padding_sec
+             FROM series_rules
+             ORDER BY series_title",
+        )?;
+
+        let rules = stmt
+            .query_map([], |row| {
+                Ok(SeriesRule {
+                    id: row.get(0)?,
+                    source_id: row.get(1)?,
+                    channel_id: row.get(2)?,
+                    channel_name: row.get(3)?,
+                    series_title: row.get(4)?,
+                    start_padding_sec: row.get(5)?,
+                    end_padding_sec: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rules)
+    }
+
+    /// Delete a series rule
+    ///
+    /// Schedules already created from the rule are left in place.
+    pub fn delete_series_rule(&self, id: i64) -> Result<()> {
+        let conn = self.get_conn()?;
+        conn.execute("DELETE FROM series_rules WHERE id = ?1", [id])?;
+        debug!("Deleted series rule {}", id);
+        Ok(())
+    }
+
+    /// Find upcoming EPG airings of a series on a channel
+    ///
+    /// Returns (title, start, end) as Unix timestamps for programs that haven't ended yet.
+    pub fn find_series_airings(
+        &self,
+        source_id: &str,
+        channel_id: &str,
+        series_title: &str,
+        now: i64,
+    ) -> Result<Vec<(String, i64, i64)>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT title, start, end FROM programs
+             WHERE source_id = ?1 AND stream_id = ?2 AND title = ?3 COLLATE NOCASE",
+        )?;
+
+        let rows = stmt
+            .query_map(params![source_id, channel_id, series_title], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Program times are stored as ISO 8601 strings, so filter after parsing
+        let mut airings: Vec<(String, i64, i64)> = rows
+            .into_iter()
+            .filter_map(|(title, start, end)| {
+                let start = parse_program_time(&start)?;
+                let end = parse_program_time(&end)?;
+                (end > now).then_some((title, start, end))
+            })
+            .collect();
+        airings.sort_by_key(|(_, start, _)| *start);
+
+        Ok(airings)
+    }
+
+    /// Delete EPG programs (for all sources) that ended before the cutoff timestamp
+    pub fn delete_programs_ended_before(&self, cutoff: i64) -> Result<usize> {
+        let conn = self.get_conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM programs WHERE datetime(end) < datetime(?1, 'unixepoch')",
+            params![cutoff],
+        )?;
+        Ok(deleted)
+    }
+
+    /// Get the program airing at `now` on a channel and the one after it
+    pub fn get_now_next(&self, source_id: &str, stream_id: &str, now: i64) -> Result<NowNext> {
+        let conn = self.get_conn()?;
+
+        // datetime() normalizes the stored ISO strings (Z or offset suffix) for comparison
+        let mut stmt = conn.prepare(
+            "SELECT title, description, start, end FROM programs
+             WHERE source_id = ?1 AND stream_id = ?2
+               AND datetime(end) > datetime(?3, 'unixepoch')
+             ORDER BY datetime(start)
+             LIMIT 2",
+        )?;
+
+        let programs: Vec<ProgramSummary> = stmt
+            .query_map(params![source_id, stream_id, now], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter_map(|(title, description, start, end)| {
+                Some(ProgramSummary {
+                    title,
+                    description: description.filter(|d| !d.is_empty()),
+                    start: parse_program_time(&start)?,
+                    end: parse_program_time(&end)?,
+                })
+            })
+            .collect();
+
+        let mut programs = programs.into_iter().peekable();
+        let now_program = programs.next_if(|p| p.start <= now);
+        let next_program = programs.next();
+
+        Ok(NowNext {
+            now: now_program,
+            next: next_program,
+        })
+    }
+
+    /// Check whether a schedule already exists for a channel at the given start time
+    ///
+    /// Matches any status so that canceled episodes are not re-created.
+    pub fn schedule_exists(&self, source_id: &str, channel_id: &str, scheduled_start: i64) -> Result<bool> {
+        let conn = self.get_conn()?;
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM dvr_schedules
+             WHERE source_id = ?1 AND channel_id = ?2 AND scheduled_start = ?3)",
+            params![source_id, channel_id, scheduled_start],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// Add a recording row unless its file path, or `alias_path`, is already in use
+    ///
+    /// The check and insert run in one immediate transaction, so two recordings
+    /// starting together can't claim the same file. Returns None if the path is taken.
+    pub fn claim_recording_path(
+        &self,
+        schedule: &Schedule,
+        file_path: &str,
+        filename: &str,
+        alias_path: &str,
+    ) -> Result<Option<i64>> {
+        let mut conn = self.get_conn()?;
+        let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+        let taken: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM dvr_recordings WHERE file_path IN (?1, ?2))",
+            params![file_path, alias_path],
+            |row| row.get(0),
+        )?;
+        if taken {
+            return Ok(None);
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        tx.execute(
+            "INSERT INTO dvr_recordings (
+                schedule_id, file_path, filename, channel_name, program_title,
+                scheduled_start, scheduled_end, actual_start, status, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 'recording', ?9)",
+            params![
+                schedule.id,
+                file_path,
+                filename,
+                schedule.channel_name,
+                schedule.program_title,
+                schedule.scheduled_start,
+                schedule.scheduled_end,
+                now,
+                now
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.commit()?;
+
+        info!("Added recording {} for schedule {}", id, schedule.id);
+        Ok(Some(id))
+    }
+
+    /// Create the next occurrence of a recurring schedule
+    ///
+    /// Supports "daily" and "weekly" recurrence, stepped in the configured timezone. Returns the new schedule ID, or None if
+    /// the schedule doesn't recur, the next occurrence already exists, or the source's
+    /// connection limit would be exceeded.
+    pub fn create_next_recurrence(&self, schedule: &Schedule) -> Result<Option<i64>> {
+        let timezone = self.get_settings()?.timezone;
+        let now = chrono::Utc::now().timestamp();
+
+        // Step over airings that are already over (the app was closed through them)
+        let mut occurrence = schedule.clone();
+        let (next_start, next_end) = loop {
+            let Some((start, end)) = crate::dvr::scheduler::next_recurrence(&occurrence, &timezone) else {
+                return Ok(None);
+            };
+            if end + occurrence.end_padding_sec as i64 > now {
+                break (start, end);
+            }
+            occurrence.scheduled_start = start;
+            occurrence.scheduled_end = end;
+        };
+
+        if self.schedule_exists(&schedule.source_id, &schedule.channel_id, next_start)? {
+            return Ok(None);
+        }
+
+        let request = ScheduleRequest {
+            source_id: schedule.source_id.clone(),
+            channel_id: schedule.channel_id.clone(),
+            channel_name: schedule.channel_name.clone(),
+            program_title: schedule.program_title.clone(),
+            scheduled_start: next_start,
+            scheduled_end: next_end,
+            start_padding_sec: Some(schedule.start_padding_sec),
+            end_padding_sec: Some(schedule.end_padding_sec),
+            series_match_title: schedule.series_match_title.clone(),
+            recurrence: schedule.recurrence.clone(),
+            stream_url: schedule.stream_url.clone(),
+            auto_extend_minutes: schedule.auto_extend_minutes,
+            priority: schedule.priority,
+        };
+
+        if let Some(max_conn) = self.connection_limit_reached(&request)? {
+            warn!(
+                "Not creating next recurrence of schedule {}: connection limit ({} max) reached",
+                schedule.id, max_conn
+            );
+            return Ok(None);
+        }
+
+        // Generated from a schedule that was already accepted, so it isn't checked against the clock
+        let id = self.insert_schedule(&request, None)?;
+        info!(
+            "Created next {} occurrence of schedule {} as schedule {}",
+            schedule.recurrence.as_deref().unwrap_or_default(), schedule.id, id
+        );
+        Ok(Some(id))
+    }
+
+    /// Get the current EPG end time of a program, if it is still listed
+    ///
+    /// Looks for an airing with the same title on the channel that started close to
+    /// the scheduled start, so a program whose end was pushed out can be detected.
+    pub fn get_program_end(
+        &self,
+        source_id: &str,
+        channel_id: &str,
+        title: &str,
+        scheduled_start: i64,
+    ) -> Result<Option<i64>> {
+        let conn = self.get_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT start, end FROM programs
+             WHERE source_id = ?1 AND stream_id = ?2 AND title = ?3 COLLATE NOCASE",
+        )?;
+
+        let rows = stmt
+            .query_map(params![source_id, channel_id, title], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let end = rows
+            .into_iter()
+            .filter_map(|(start, end)| Some((parse_program_time(&start)?, parse_program_time(&end)?)))
+            .filter(|(start, _)| (start - scheduled_start).abs() <= 3600)
+            .map(|(_, end)| end)
+            .max();
+
+        Ok(end)
+    }
+
     // TVMaze / TV Calendar methods
 
     pub fn tvmaze_add_favorite(
@@ -1302,6 +2354,54 @@ impl DvrDatabase {
         Ok(channel)
     }
 
+    /// Save the playback position of a VOD item in its watch history entry, which the
+    /// frontend creates when playback starts. A zero duration keeps the stored one.
+    /// Only the Windows player tracks progress itself (see `mpv_watch`).
+    #[cfg(any(target_os = "windows", test))]
+    pub fn save_watch_progress(&self, media_id: &str, media_type: &str, position: f64, duration: f64) -> Result<()> {
+        let conn = self.get_conn()?;
+        conn.execute(
+            "UPDATE vod_history SET
+                progress_seconds = ?1,
+                total_duration = CASE WHEN ?2 > 0 THEN ?2 ELSE total_duration END,
+                watched_at = ?3
+             WHERE media_id = ?4 AND media_type = ?5",
+            params![
+                position.max(0.0).floor() as i64,
+                duration.max(0.0).floor() as i64,
+                chrono::Utc::now().timestamp_millis(),
+                media_id,
+                media_type,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the saved playback position of a VOD item from its watch history entry
+    pub fn get_watch_progress(&self, media_id: &str, media_type: &str) -> Result<Option<WatchProgress>> {
+        let conn = self.get_conn()?;
+        let progress = conn
+            .query_row(
+                "SELECT COALESCE(progress_seconds, 0), COALESCE(total_duration, 0) FROM vod_history
+                 WHERE media_id = ?1 AND media_type = ?2
+                 ORDER BY watched_at DESC LIMIT 1",
+                params![media_id, media_type],
+                |row| {
+                    let position: i64 = row.get(0)?;
+                    let duration: i64 = row.get(1)?;
+                    Ok(WatchProgress {
+                        media_id: media_id.to_string(),
+                        media_type: media_type.to_string(),
+                        position: position as f64,
+                        duration: duration as f64,
+                        watched: is_watched(position as f64, duration as f64),
+                    })
+                },
+            )
+            .optional()?;
+        Ok(progress)
+    }
+
     /// Get show's assigned channel_id
     pub fn tvmaze_get_show_channel(&self, tvmaze_id: i64) -> Result<Option<String>> {
         let conn = self.get_conn()?;
@@ -1316,10 +2416,262 @@ impl DvrDatabase {
     }
 }
 
+/// Whether a position counts as watched: within the last 5% or the last 2 minutes
+pub fn is_watched(position: f64, duration: f64) -> bool {
+    duration > 0.0 && (position >= duration * 0.95 || duration - position <= 120.0)
+}
+
+/// Fresh database in a unique temp file, for tests
+#[cfg(test)]
+pub(crate) fn test_database() -> DvrDatabase {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = std::env::temp_dir().join(format!(
+        "ynotv-test-db-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    DvrDatabase::open(&dir.join("ynotv.db")).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    // Note: Tests would need a mock AppHandle or use temp files
-    // For now, we're skipping tests in this module
+    fn rule(title: &str) -> SeriesRule {
+        SeriesRule {
+            id: 0,
+            source_id: "source".to_string(),
+            channel_id: "101".to_string(),
+            channel_name: "News One".to_string(),
+            series_title: title.to_string(),
+            start_padding_sec: None,
+            end_padding_sec: None,
+        }
+    }
+
+    /// Insert a schedule like `Schedule::test_default()`, creating the frontend tables
+    /// (and rows) its foreign keys point at
+    fn insert_schedule(db: &DvrDatabase, start: i64, end: i64) -> Schedule {
+        let conn = db.get_conn().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sourcesMeta (source_id TEXT PRIMARY KEY, max_connections TEXT);
+             CREATE TABLE IF NOT EXISTS channels (stream_id TEXT PRIMARY KEY);
+             INSERT OR IGNORE INTO sourcesMeta (source_id) VALUES ('source');
+             INSERT OR IGNORE INTO channels VALUES ('channel');",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO dvr_schedules (source_id, channel_id, channel_name, program_title, scheduled_start, scheduled_end, created_at)
+             VALUES ('source', 'channel', 'Channel', 'Show', ?1, ?2, 0)",
+            params![start, end],
+        )
+        .unwrap();
+        Schedule { id: conn.last_insert_rowid(), scheduled_start: start, scheduled_end: end, ..Schedule::test_default() }
+    }
+
+    #[test]
+    fn test_series_rules_persist_and_dedupe() {
+        let db = test_database();
+
+        let id = db.add_series_rule(&rule("Evening News")).unwrap();
+        let mut updated = rule("evening news");
+        updated.end_padding_sec = Some(600);
+        assert_eq!(db.add_series_rule(&updated).unwrap(), id);
+
+        let rules = db.get_series_rules().unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].id, id);
+        assert_eq!(rules[0].start_padding_sec, None);
+        assert_eq!(rules[0].end_padding_sec, Some(600));
+
+        db.delete_series_rule(id).unwrap();
+        assert!(db.get_series_rules().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_claim_recording_path_is_exclusive() {
+        let db = std::sync::Arc::new(test_database());
+        let schedule = insert_schedule(&db, 1_767_225_600, 1_767_229_200);
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let db = db.clone();
+                let schedule = schedule.clone();
+                std::thread::spawn(move || {
+                    db.claim_recording_path(
+                        &schedule,
+                        "/recordings/Show.ts",
+                        "Show.ts",
+                        "/recordings/Show_part001.ts",
+                    )
+                    .unwrap()
+                })
+            })
+            .collect();
+        let claimed = handles
+            .into_iter()
+            .filter_map(|h| h.join().unwrap())
+            .count();
+        assert_eq!(claimed, 1);
+
+        // The first part of a split recording blocks the whole-file name too
+        assert!(db
+            .claim_recording_path(&schedule, "/recordings/Show_part001.ts", "Show_part001.ts", "/recordings/Show.ts")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_is_watched_threshold() {
+        // Last 5% of a long film
+        assert!(!is_watched(6_000.0, 7_200.0));
+        assert!(is_watched(6_840.0, 7_200.0));
+        // Last 2 minutes of a short episode, well before 95%
+        assert!(!is_watched(1_000.0, 1_200.0));
+        assert!(is_watched(1_080.0, 1_200.0));
+        // Unknown duration never counts as watched
+        assert!(!is_watched(500.0, 0.0));
+    }
+
+    #[test]
+    fn test_watch_progress_updates_frontend_history() {
+        let db = test_database();
+        // Progress for an item the frontend hasn't recorded yet is dropped
+        db.save_watch_progress("movie-1", "movie", 60.0, 5_400.0).unwrap();
+        assert!(db.get_watch_progress("movie-1", "movie").unwrap().is_none());
+
+        db.get_conn()
+            .unwrap()
+            .execute(
+                "INSERT INTO vod_history (media_id, media_type, source_id, title, watched_at)
+                 VALUES ('movie-1', 'movie', 'src', 'Movie', 0)",
+                [],
+            )
+            .unwrap();
+
+        db.save_watch_progress("movie-1", "movie", 1_800.5, 5_400.0).unwrap();
+        // A tick before the duration is known keeps the stored one
+        db.save_watch_progress("movie-1", "movie", 1_810.0, 0.0).unwrap();
+        let progress = db.get_watch_progress("movie-1", "movie").unwrap().unwrap();
+        assert_eq!(progress.position, 1_810.0);
+        assert_eq!(progress.duration, 5_400.0);
+        assert!(!progress.watched);
+
+        db.save_watch_progress("movie-1", "movie", 5_300.0, 5_400.0).unwrap();
+        assert!(db.get_watch_progress("movie-1", "movie").unwrap().unwrap().watched);
+        assert!(db.get_watch_progress("movie-1", "series").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_recording_watch_position_marks_watched_near_end() {
+        let db = test_database();
+        let schedule = insert_schedule(&db, 1_767_225_600, 1_767_229_200);
+        let id = db
+            .claim_recording_path(&schedule, "/recordings/Show.ts", "Show.ts", "/recordings/Show_part001.ts")
+            .unwrap()
+            .unwrap();
+
+        // Unknown duration saves the position without deciding watched
+        db.update_recording_watch_position(id, 3_500.0, None).unwrap();
+        let recording = db.get_recording(id).unwrap().unwrap();
+        assert_eq!(recording.watched_position, 3_500.0);
+        assert!(!recording.watched);
+
+        db.update_recording_watch_position(id, 3_500.0, Some(3_600.0)).unwrap();
+        assert!(db.get_recording(id).unwrap().unwrap().watched);
+
+        // Seeking back to rewatch a scene keeps it watched
+        db.update_recording_watch_position(id, 60.0, Some(3_600.0)).unwrap();
+        let recording = db.get_recording(id).unwrap().unwrap();
+        assert_eq!(recording.watched_position, 60.0);
+        assert!(recording.watched);
+
+        assert!(db.update_recording_watch_position(id + 1, 60.0, None).is_err());
+    }
+
+    fn limited_source(max_connections: i32, schedules: &[(i64, i64)]) -> DvrDatabase {
+        let db = test_database();
+        for (start, end) in schedules {
+            insert_schedule(&db, *start, *end);
+        }
+        db.get_conn()
+            .unwrap()
+            .execute("UPDATE sourcesMeta SET max_connections = ?1", [max_connections])
+            .unwrap();
+        db
+    }
+
+    #[test]
+    fn test_suggest_free_start_after_back_to_back_schedules() {
+        let db = limited_source(1, &[(1_000, 2_000), (2_000, 3_000)]);
+        let (conflicts, _) = db.check_conflicts("source", 1_500, 2_500).unwrap();
+        assert_eq!(conflicts.len(), 2);
+
+        // The gap-free end of the first schedule is still taken by the second
+        assert_eq!(db.suggest_free_start("source", 1_500, 2_500, 1, 0, &conflicts), Some(3_000));
+        // Playback holding the only connection leaves no free start
+        assert_eq!(db.suggest_free_start("source", 1_500, 2_500, 1, 1, &conflicts), None);
+    }
+
+    #[test]
+    fn test_suggest_free_start_counts_playback_with_overlapping_schedules() {
+        let db = limited_source(2, &[(1_000, 3_000), (2_000, 4_000)]);
+        let (conflicts, _) = db.check_conflicts("source", 2_500, 3_500).unwrap();
+        assert_eq!(conflicts.len(), 2);
+
+        assert_eq!(db.suggest_free_start("source", 2_500, 3_500, 2, 0, &conflicts), Some(3_000));
+        // With a viewer on the source, only the time after both schedules is free
+        assert_eq!(db.suggest_free_start("source", 2_500, 3_500, 2, 1, &conflicts), Some(4_000));
+    }
+
+    fn request(start: i64, end: i64) -> ScheduleRequest {
+        ScheduleRequest {
+            source_id: "source".to_string(),
+            channel_id: "channel".to_string(),
+            channel_name: "Channel".to_string(),
+            program_title: "Show".to_string(),
+            scheduled_start: start,
+            scheduled_end: end,
+            start_padding_sec: None,
+            end_padding_sec: None,
+            series_match_title: None,
+            recurrence: None,
+            stream_url: None,
+            auto_extend_minutes: 0,
+            priority: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_schedule_times() {
+        let now = 1_767_225_600;
+        assert!(validate_schedule_times(&request(now + 60, now + 3_600), 60, 300, Some(now)).is_ok());
+        assert!(validate_schedule_times(&request(now + 3_600, now + 60), 60, 300, Some(now)).is_err());
+        assert!(validate_schedule_times(&request(now - 3_600, now - 60), 0, 0, Some(now)).is_err());
+        // End padding still running counts as not over yet
+        assert!(validate_schedule_times(&request(now - 3_600, now - 60), 0, 300, Some(now)).is_ok());
+        assert!(validate_schedule_times(&request(30, 3_600), 60, 0, None).is_err());
+        // Without a clock only the times themselves are checked
+        assert!(validate_schedule_times(&request(now - 3_600, now - 60), 0, 0, None).is_ok());
+    }
+
+    #[test]
+    fn test_next_recurrence_skips_past_airings() {
+        let db = test_database();
+        let now = chrono::Utc::now().timestamp();
+        let mut schedule = insert_schedule(&db, now - 10 * 86_400, now - 10 * 86_400 + 1_800);
+        schedule.recurrence = Some("daily".to_string());
+
+        let id = db.create_next_recurrence(&schedule).unwrap().expect("next occurrence");
+        let next = db.get_schedule(id).unwrap().unwrap();
+        assert!(next.scheduled_end > now);
+        // Within a day, give or take a DST change
+        assert!(next.scheduled_start <= now + 90_000);
+        assert_eq!(next.scheduled_end - next.scheduled_start, 1_800);
+        assert_eq!(next.recurrence.as_deref(), Some("daily"));
+    }
 }
@@ -10,11 +10,14 @@ pub mod recorder;
 pub mod cleanup;
 pub mod stream_resolver;
 pub mod thumbnail;
+pub mod transcode;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use once_cell::sync::OnceCell;
 use tracing::{info, error};
-use tracing_subscriber;
+use tracing_subscriber::{reload, EnvFilter, Registry};
 
 use crate::dvr::database::DvrDatabase;
 use crate::dvr::scheduler::Scheduler;
@@ -31,6 +34,14 @@ pub struct PlayingStream {
     pub is_playing: bool,
 }
 
+/// Outcome of DVR initialization, managed even when `DvrState` could not be created
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct DvrInitStatus {
+    pub available: bool,
+    /// Why initialization failed, when it did
+    pub error: Option<String>,
+}
+
 /// Shared state for DVR operations
 #[derive(Clone)]
 pub struct DvrState {
@@ -39,6 +50,8 @@ pub struct DvrState {
     pub recorder: Arc<RecordingManager>,
     pub cleanup: Arc<CleanupManager>,
     pub playing_stream: Arc<RwLock<PlayingStream>>,
+    /// Connections held by playback, by source ID (recordings are counted by the recorder)
+    viewer_connections: Arc<parking_lot::Mutex<HashMap<String, usize>>>,
 }
 
 // SAFETY: DvrState is only accessed from the Tokio runtime and all internal
@@ -82,6 +95,11 @@ impl DvrState {
         };
         info!("Recording manager initialized");
 
+        // Flag recordings whose files went missing while the app was closed
+        if let Err(e) = cleanup::scan_recordings(&db, false) {
+            error!("Failed to scan recordings: {}", e);
+        }
+
         // Initialize cleanup manager
         println!("[DVR State] Creating CleanupManager...");
         let cleanup = Arc::new(CleanupManager::new(db.clone()));
@@ -90,7 +108,7 @@ impl DvrState {
 
         // Initialize scheduler
         println!("[DVR State] Creating Scheduler...");
-        let scheduler = Arc::new(RwLock::new(Scheduler::new(db.clone(), recorder.clone())));
+        let scheduler = Arc::new(RwLock::new(Scheduler::new(&app_handle, db.clone(), recorder.clone())));
         println!("[DVR State] Scheduler created successfully");
         info!("Scheduler initialized");
 
@@ -100,6 +118,7 @@ impl DvrState {
             recorder,
             cleanup,
             playing_stream: Arc::new(RwLock::new(PlayingStream::default())),
+            viewer_connections: Arc::new(parking_lot::Mutex::new(HashMap::new())),
         };
 
         info!("DVR system initialized successfully");
@@ -153,9 +172,35 @@ impl DvrState {
     /// Update the currently playing stream information
     pub async fn set_playing_stream(&self, stream: PlayingStream) {
         let mut playing = self.playing_stream.write().await;
+
+        // Move the viewer's connection from the old source to the new one
+        let mut viewers = self.viewer_connections.lock();
+        if let (true, Some(source_id)) = (playing.is_playing, &playing.source_id) {
+            if let Some(count) = viewers.get_mut(source_id) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    viewers.remove(source_id);
+                }
+            }
+        }
+        if let (true, Some(source_id)) = (stream.is_playing, &stream.source_id) {
+            *viewers.entry(source_id.clone()).or_insert(0) += 1;
+        }
+        drop(viewers);
+
         *playing = stream;
     }
 
+    /// Connections playback is using on a source
+    pub fn viewer_connections(&self, source_id: &str) -> usize {
+        self.viewer_connections.lock().get(source_id).copied().unwrap_or(0)
+    }
+
+    /// Connections in use on a source right now, by playback and recordings
+    pub fn connections_in_use(&self, source_id: &str) -> usize {
+        self.viewer_connections(source_id) + self.recorder.connections_in_use(source_id)
+    }
+
     /// Get the currently playing stream information
     pub async fn get_playing_stream(&self) -> PlayingStream {
         self.playing_stream.read().await.clone()
@@ -168,61 +213,95 @@ impl DvrState {
         source_id: &str,
         _channel_id: &str,
     ) -> anyhow::Result<bool> {
-        let playing = self.playing_stream.read().await;
-
-        // If not playing anything, no conflict
-        if !playing.is_playing {
+        // No conflict unless something is playing from this source
+        if self.viewer_connections(source_id) == 0 {
             return Ok(false);
         }
 
-        // Check if playing from the same source
-        if let Some(playing_source) = &playing.source_id {
-            if playing_source == source_id {
-                // Get max connections for this source
-                let max_connections = self.db.get_max_connections(source_id)?;
-
-                // If single connection (1) or unknown (None/0), it's a conflict
-                match max_connections {
-                    Some(1) | None | Some(0) => {
-                        return Ok(true);
-                    }
-                    Some(n) if n > 1 => {
-                        // Multiple connections allowed, check if we're already using one
-                        // For simplicity, assume watching uses 1 connection
-                        // TODO: Track actual connection usage
-                        return Ok(false);
-                    }
-                    _ => return Ok(true),
-                }
-            }
-        }
+        // Unknown (None/0) limits are treated as a single connection
+        let max_connections = match self.db.get_max_connections(source_id)? {
+            Some(n) if n > 0 => n as usize,
+            _ => 1,
+        };
 
-        Ok(false)
+        // A new recording needs one more connection than playback and recordings hold now
+        Ok(self.connections_in_use(source_id) + 1 > max_connections)
+    }
+}
+
+/// Filter used when debug logging is off: INFO and above, with SQLX quietened
+pub const DEFAULT_LOG_FILTER: &str = "info,sqlx=warn";
+
+/// Handle to swap the active log filter after startup
+static LOG_FILTER: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+/// Level for the `log` records the log plugin writes to the log file, matching a tracing filter.
+/// The plugin itself passes everything, so this global maximum is what filters the file.
+fn file_log_level(directives: &str) -> log::LevelFilter {
+    match directives {
+        "error" => log::LevelFilter::Error,
+        "warn" => log::LevelFilter::Warn,
+        "debug" => log::LevelFilter::Debug,
+        "trace" => log::LevelFilter::Trace,
+        _ if cfg!(debug_assertions) => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Info,
     }
 }
 
 /// Initialize logging for DVR operations
 ///
-/// When debug_logging is false, SQLX and other verbose logs are suppressed
+/// When debug_logging is false, SQLX and other verbose logs are suppressed.
+/// Also sets the level of the log file. Both can be changed later with `set_log_level`.
 pub fn init_logging(debug_logging: bool) {
-    use tracing_subscriber::{fmt, EnvFilter};
+    use tracing_subscriber::{fmt, prelude::*};
 
     let filter = if debug_logging {
         // Show all logs including DEBUG
         EnvFilter::new("debug")
     } else {
-        // Suppress sqlx and other verbose logs, only show INFO and above
-        EnvFilter::new("info,sqlx=warn")
+        EnvFilter::new(DEFAULT_LOG_FILTER)
+    };
+    let (filter_layer, handle) = reload::Layer::new(filter);
+
+    let subscriber = tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(
+            fmt::layer()
+                .with_target(true)
+                .with_level(true)
+                .with_thread_ids(true)
+                .with_file(true)
+                .with_line_number(true),
+        );
+
+    if tracing::subscriber::set_global_default(subscriber).is_ok() {
+        let _ = LOG_FILTER.set(handle);
+    }
+    log::set_max_level(file_log_level(if debug_logging { "debug" } else { DEFAULT_LOG_FILTER }));
+}
+
+/// Change the log level at runtime, for the console and the log file.
+///
+/// Accepts `error`, `warn`, `info`, `debug`, `trace`, or `default` / `info,sqlx=warn`
+/// for the startup filter. Returns the filter applied.
+pub fn set_log_level(level: &str) -> Result<&'static str, String> {
+    let level = level.trim().to_lowercase();
+    let directives = match level.as_str() {
+        "error" => "error",
+        "warn" => "warn",
+        "info" => "info",
+        "debug" => "debug",
+        "trace" => "trace",
+        "default" | DEFAULT_LOG_FILTER => DEFAULT_LOG_FILTER,
+        _ => return Err(format!("Unknown log level: {}", level)),
     };
 
-    let subscriber = fmt()
-        .with_target(true)
-        .with_level(true)
-        .with_thread_ids(true)
-        .with_file(true)
-        .with_line_number(true)
-        .with_env_filter(filter)
-        .finish();
+    let handle = LOG_FILTER.get().ok_or("Logging is not initialized")?;
+    handle
+        .reload(EnvFilter::new(directives))
+        .map_err(|e| format!("Failed to set log level: {}", e))?;
+    log::set_max_level(file_log_level(directives));
 
-    let _ = tracing::subscriber::set_global_default(subscriber);
+    info!("Log level set to '{}'", directives);
+    Ok(directives)
 }
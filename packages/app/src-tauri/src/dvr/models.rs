@@ -49,6 +49,8 @@ pub enum RecordingStatus {
     Completed,
     Failed,
     Partial,
+    /// The row's file is missing from disk
+    Orphaned,
 }
 
 impl RecordingStatus {
@@ -58,6 +60,7 @@ impl RecordingStatus {
             RecordingStatus::Completed => "completed",
             RecordingStatus::Failed => "failed",
             RecordingStatus::Partial => "partial",
+            RecordingStatus::Orphaned => "orphaned",
         }
     }
 }
@@ -71,6 +74,7 @@ impl std::str::FromStr for RecordingStatus {
             "completed" => Ok(RecordingStatus::Completed),
             "failed" => Ok(RecordingStatus::Failed),
             "partial" => Ok(RecordingStatus::Partial),
+            "orphaned" => Ok(RecordingStatus::Orphaned),
             _ => Err(format!("Unknown recording status: {}", s)),
         }
     }
@@ -95,6 +99,10 @@ pub struct Schedule {
     pub started_at: Option<i64>,
     /// Pre-resolved stream URL (optional, for sources that need URL regeneration)
     pub stream_url: Option<String>,
+    /// Maximum minutes to keep recording past the scheduled end if the program overruns
+    pub auto_extend_minutes: i32,
+    /// Higher wins when a connection-limited source has more due recordings than connections
+    pub priority: i32,
 }
 
 impl Schedule {
@@ -109,6 +117,32 @@ impl Schedule {
     }
 }
 
+#[cfg(test)]
+impl Schedule {
+    /// A one-hour schedule starting 2026-01-01T00:00:00Z with no padding, for tests
+    pub(crate) fn test_default() -> Self {
+        Schedule {
+            id: 1,
+            source_id: "source".to_string(),
+            channel_id: "channel".to_string(),
+            channel_name: "Channel".to_string(),
+            program_title: "Show".to_string(),
+            scheduled_start: 1_767_225_600,
+            scheduled_end: 1_767_229_200,
+            start_padding_sec: 0,
+            end_padding_sec: 0,
+            status: ScheduleStatus::Scheduled,
+            series_match_title: None,
+            recurrence: None,
+            created_at: 0,
+            started_at: None,
+            stream_url: None,
+            auto_extend_minutes: 0,
+            priority: 0,
+        }
+    }
+}
+
 /// A completed or in-progress recording file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Recording {
@@ -129,6 +163,78 @@ pub struct Recording {
     pub created_at: i64,
     /// Path to thumbnail image file
     pub thumbnail_path: Option<String>,
+    /// Number of times FFmpeg was restarted after a failed start
+    pub retry_count: i32,
+    /// Path to the scrub bar preview sprite (WebVTT index alongside with `.vtt`)
+    pub sprite_path: Option<String>,
+    /// For later parts of a split recording, the ID of the first part
+    pub part_of: Option<i64>,
+    /// 1-based part number of a split recording, None for unsplit recordings
+    pub part_number: Option<i32>,
+    /// Protected recordings are never removed by automatic cleanup
+    pub is_protected: bool,
+    /// Last playback position in seconds
+    pub watched_position: f64,
+    /// Played to (near) the end, or marked watched by the user
+    pub watched: bool,
+}
+
+/// Sort orders for the recordings library
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecordingSort {
+    #[default]
+    DateDesc,
+    DateAsc,
+    SizeDesc,
+    SizeAsc,
+    Channel,
+    Title,
+}
+
+impl RecordingSort {
+    /// ORDER BY clause for a query over `dvr_recordings`
+    pub fn order_by(self) -> &'static str {
+        match self {
+            RecordingSort::DateDesc => "COALESCE(actual_start, scheduled_start) DESC",
+            RecordingSort::DateAsc => "COALESCE(actual_start, scheduled_start) ASC",
+            RecordingSort::SizeDesc => "COALESCE(size_bytes, 0) DESC, COALESCE(actual_start, scheduled_start) DESC",
+            RecordingSort::SizeAsc => "COALESCE(size_bytes, 0) ASC, COALESCE(actual_start, scheduled_start) DESC",
+            RecordingSort::Channel => "channel_name COLLATE NOCASE ASC, COALESCE(actual_start, scheduled_start) DESC",
+            RecordingSort::Title => "program_title COLLATE NOCASE ASC, COALESCE(actual_start, scheduled_start) DESC",
+        }
+    }
+}
+
+impl std::str::FromStr for RecordingSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "date_desc" => Ok(RecordingSort::DateDesc),
+            "date_asc" => Ok(RecordingSort::DateAsc),
+            "size_desc" => Ok(RecordingSort::SizeDesc),
+            "size_asc" => Ok(RecordingSort::SizeAsc),
+            "channel" => Ok(RecordingSort::Channel),
+            "title" => Ok(RecordingSort::Title),
+            _ => Err(format!("Unknown recording sort: {}", s)),
+        }
+    }
+}
+
+/// One page of recordings plus the total number of matching rows
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingPage {
+    pub recordings: Vec<Recording>,
+    pub total: i64,
+}
+
+/// Scrub bar preview sprite for a recording
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingSprite {
+    /// Asset protocol URL of the JPEG sprite sheet
+    pub image_url: String,
+    /// WebVTT index mapping time ranges to `#xywh=` tiles in the sprite
+    pub index: String,
 }
 
 /// Settings for DVR operations
@@ -140,6 +246,34 @@ pub struct DvrSettings {
     pub default_start_padding_sec: i32,
     pub default_end_padding_sec: i32,
     pub keep_recordings_days: Option<i32>,
+    /// Container for new recordings: "ts", "mkv" or "mp4"
+    pub recording_format: String,
+    /// How many times to restart a recording that fails without writing any data
+    pub max_recording_retries: u32,
+    /// Base delay between retries in seconds (multiplied by the attempt number)
+    pub retry_backoff_sec: u64,
+    /// Recording filename template, e.g. `{date}_{channel}_{title}`; empty uses the default naming
+    pub filename_template: String,
+    /// Days to keep EPG programs after they have ended
+    pub epg_retention_days: u32,
+    /// Re-encode finished recordings: "none", "h264_720p" or "h265_1080p"
+    pub transcode_profile: String,
+    /// Split recordings into parts of at most this many minutes (0 = no limit)
+    pub segment_max_minutes: u32,
+    /// Split recordings into parts of roughly this many GB (0 = no limit)
+    pub segment_max_gb: f64,
+    /// Send a `dvr:notify` event when a recording completes or fails
+    pub notifications_enabled: bool,
+    /// User-Agent sent by FFmpeg for HTTP recordings (empty = FFmpeg's default)
+    pub recording_user_agent: String,
+    /// Reconnect HTTP recordings after network drops instead of failing
+    pub recording_reconnect: bool,
+    /// Seconds FFmpeg waits on a stalled connection or read before giving up
+    pub recording_timeout_sec: u32,
+    /// Where HLS recordings start: "live_edge" or "beginning" of the playlist
+    pub hls_start_mode: String,
+    /// IANA timezone for recurring schedules, e.g. "Europe/London" (empty = system zone)
+    pub timezone: String,
 }
 
 impl Default for DvrSettings {
@@ -151,6 +285,20 @@ impl Default for DvrSettings {
             default_start_padding_sec: 60,
             default_end_padding_sec: 300,
             keep_recordings_days: Some(30),
+            recording_format: "ts".to_string(),
+            max_recording_retries: 3,
+            retry_backoff_sec: 10,
+            filename_template: String::new(),
+            epg_retention_days: 7,
+            transcode_profile: "none".to_string(),
+            segment_max_minutes: 0,
+            notifications_enabled: true,
+            segment_max_gb: 0.0,
+            recording_user_agent: String::new(),
+            recording_reconnect: true,
+            recording_timeout_sec: 30,
+            hls_start_mode: "live_edge".to_string(),
+            timezone: String::new(),
         }
     }
 }
@@ -164,10 +312,11 @@ pub struct ScheduleRequest {
     pub program_title: String,
     pub scheduled_start: i64,
     pub scheduled_end: i64,
-    #[serde(default = "default_start_padding")]
-    pub start_padding_sec: i32,
-    #[serde(default = "default_end_padding")]
-    pub end_padding_sec: i32,
+    /// Falls back to the source's padding, then the global default, when omitted
+    #[serde(default)]
+    pub start_padding_sec: Option<i32>,
+    #[serde(default)]
+    pub end_padding_sec: Option<i32>,
     #[serde(default)]
     pub series_match_title: Option<String>,
     #[serde(default)]
@@ -175,13 +324,34 @@ pub struct ScheduleRequest {
     /// Optional pre-resolved stream URL for sources requiring URL regeneration
     #[serde(default)]
     pub stream_url: Option<String>,
+    /// Keep recording up to this many minutes past the end if the EPG shows an overrun (0 = off)
+    #[serde(default)]
+    pub auto_extend_minutes: i32,
+    /// Contention priority on connection-limited sources (higher wins, default 0)
+    #[serde(default)]
+    pub priority: i32,
 }
 
-fn default_start_padding() -> i32 {
-    60
+/// Padding override for one source (None = use the global default)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourcePadding {
+    pub source_id: String,
+    pub start_padding_sec: Option<i32>,
+    pub end_padding_sec: Option<i32>,
 }
-fn default_end_padding() -> i32 {
-    300
+
+/// A series recording rule, stored in `series_rules`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesRule {
+    #[serde(default)]
+    pub id: i64,
+    pub source_id: String,
+    pub channel_id: String,
+    pub channel_name: String,
+    pub series_title: String,
+    /// None = use the source's padding, then the global default
+    pub start_padding_sec: Option<i32>,
+    pub end_padding_sec: Option<i32>,
 }
 
 /// Conflict information when scheduling overlaps
@@ -190,6 +360,23 @@ pub struct ScheduleConflict {
     pub has_conflict: bool,
     pub conflicts: Vec<Schedule>,
     pub message: Option<String>,
+    /// Estimated size of the recording in bytes
+    pub estimated_bytes: u64,
+    /// Free bytes on the storage volume (None if it couldn't be determined)
+    pub free_bytes: Option<u64>,
+    /// True if the estimated size exceeds the free space
+    pub insufficient_space: bool,
+    /// Connections the source is using right now (playback and recordings)
+    pub connections_in_use: usize,
+    pub max_connections: i32,
+    /// IDs of the overlapping schedules
+    pub conflicting_schedule_ids: Vec<i64>,
+    /// Earliest start (same duration) at which the source has a free connection
+    pub suggested_start: Option<i64>,
+    /// Overlapping schedules with a lower priority, one of which would be preempted
+    pub preemptable_schedule_ids: Vec<i64>,
+    /// Lowest priority at which this recording would preempt an overlapping one
+    pub priority_to_preempt: Option<i32>,
 }
 
 /// Disk usage information
@@ -201,6 +388,88 @@ pub struct DiskInfo {
     pub usage_percent: f64,
 }
 
+/// Space taken by finished recordings, alongside the storage volume's totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingsDiskUsage {
+    /// Sum of `size_bytes` across completed and partial recordings
+    pub recordings_bytes: u64,
+    pub recording_count: i64,
+    /// Volume holding the DVR storage path
+    pub volume: DiskInfo,
+}
+
+/// A program entry for now/next channel tiles (times are Unix timestamps)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramSummary {
+    pub title: String,
+    pub description: Option<String>,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Currently airing and upcoming program on a channel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NowNext {
+    pub now: Option<ProgramSummary>,
+    pub next: Option<ProgramSummary>,
+}
+
+/// Saved playback position of a VOD item (from the frontend's watch history),
+/// for resume / continue watching
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchProgress {
+    pub media_id: String,
+    pub media_type: String,
+    pub position: f64,
+    pub duration: f64,
+    pub watched: bool,
+}
+
+/// Video stream details from `probe_stream`
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoStreamInfo {
+    pub codec: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+    pub bitrate: Option<u64>,
+}
+
+/// Audio track details from `probe_stream`
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioStreamInfo {
+    pub codec: String,
+    pub channels: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub language: Option<String>,
+    pub bitrate: Option<u64>,
+}
+
+/// What ffprobe reports about a live stream or file
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamProbe {
+    /// Container, e.g. "mpegts" or "hls"
+    pub format: Option<String>,
+    /// Overall bitrate in bits per second
+    pub bitrate: Option<u64>,
+    pub video: Option<VideoStreamInfo>,
+    pub audio: Vec<AudioStreamInfo>,
+}
+
+/// Outcome of a short test recording of a channel
+#[derive(Debug, Clone, Serialize)]
+pub struct TestRecordingResult {
+    pub success: bool,
+    /// Clip length that was requested
+    pub seconds: u32,
+    /// Bytes written before the clip was deleted
+    pub file_size: u64,
+    /// ffprobe details of the clip, if anything was recorded
+    pub probe: Option<StreamProbe>,
+    /// Last FFmpeg error line, or why nothing was recorded
+    pub error: Option<String>,
+}
+
 /// Event sent to frontend when recording starts/completes/fails
 #[derive(Debug, Clone, Serialize)]
 pub struct RecordingEvent {
@@ -4,8 +4,9 @@
 //! Handles process lifecycle, monitoring, and status updates.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -17,14 +18,34 @@ use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 use crate::dvr::database::DvrDatabase;
-use crate::dvr::models::{RecordingEvent, RecordingStatus, Schedule, ScheduleStatus};
-use crate::dvr::stream_resolver::resolve_stream_url;
-use crate::dvr::thumbnail::generate_thumbnail;
+use crate::dvr::models::{
+    AudioStreamInfo, DvrSettings, Recording, RecordingEvent, RecordingStatus, Schedule, ScheduleStatus,
+    StreamProbe, TestRecordingResult, VideoStreamInfo,
+};
+use crate::dvr::stream_resolver::{self, resolve_stream_url};
+use crate::dvr::thumbnail::{generate_thumbnail, generate_thumbnail_sprite};
+use crate::dvr::transcode;
 use rusqlite::OptionalExtension;
 use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
 
 use tokio::sync::watch;
 
+/// Number of frames in the scrub bar preview sprite
+const SPRITE_FRAME_COUNT: u32 = 20;
+
+/// Minimum time between `dvr:progress` events for one recording
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long FFmpeg gets to finalize its output after 'q' before it is killed
+const FFMPEG_STOP_GRACE: Duration = Duration::from_secs(10);
+
+/// FFmpeg output kept per recording for `get_recording_log` (characters)
+const RECORDING_LOG_MAX_CHARS: usize = 64 * 1024;
+
+/// Where HLS recordings start in the playlist: newest segment or the first one listed
+pub const HLS_START_MODES: &[&str] = &["live_edge", "beginning"];
+
 /// Active recording handle
 struct RecordingHandle {
     /// FFmpeg child process (wrapped in Option so we can take ownership)
@@ -35,16 +56,81 @@ struct RecordingHandle {
     schedule: Schedule,
     /// When recording started
     start_time: Instant,
+    /// Media duration FFmpeg reports as written (`time=`), which stops advancing if the stream stalls
+    recorded_seconds: f64,
+    /// Output size FFmpeg reports as written (`size=`)
+    size_bytes: u64,
+    /// Recorded seconds and bytes from FFmpeg runs before the last pause
+    recorded_before: f64,
+    size_before: u64,
     /// Cancellation signal sender (cloned for external use)
     cancel_tx: watch::Sender<bool>,
+    /// Pause signal: true stops FFmpeg until false resumes into a new part
+    pause_tx: watch::Sender<bool>,
+    /// Finish signal: true stops FFmpeg cleanly and keeps what was recorded as completed
+    finish_tx: watch::Sender<bool>,
+    /// Whether the recording is currently paused
+    paused: bool,
+}
+
+/// A schedule's hold on one of its source's connections
+struct SourceSlot {
+    priority: i32,
+    /// Cancellation signal for the recording holding the slot. The recording task uses it as
+    /// its own, so a schedule can be stopped before its `RecordingHandle` is registered.
+    cancel_tx: watch::Sender<bool>,
+}
+
+impl SourceSlot {
+    fn new(priority: i32) -> Self {
+        Self { priority, cancel_tx: watch::channel(false).0 }
+    }
+}
+
+/// How a single FFmpeg run ended without error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WaitOutcome {
+    /// FFmpeg finished or the scheduled end was reached
+    Finished,
+    /// FFmpeg was stopped because the recording was paused
+    Paused,
+}
+
+/// How a paused recording left the paused state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResumeOutcome {
+    Resumed,
+    /// The scheduled end passed while paused
+    Ended,
+    Cancelled,
+}
+
+/// Result of handing a due schedule to the recording manager
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartOutcome {
+    /// A connection slot was free and the recording task was spawned
+    Started,
+    /// The source is at its connection limit; the schedule waits for a free slot
+    Queued,
+    /// The schedule is already recording or queued
+    AlreadyPending,
 }
 
 /// Manages active recordings
 pub struct RecordingManager {
     /// Active recordings by schedule ID
     active_recordings: Arc<Mutex<HashMap<i64, RecordingHandle>>>,
+    /// Schedules holding one of their source's connections (by schedule ID), by source ID.
+    /// Reserved before the recording task starts, so schedules firing together can't overbook.
+    source_slots: Arc<Mutex<HashMap<String, HashMap<i64, SourceSlot>>>>,
+    /// Due schedules waiting for a connection on their source, in arrival order
+    queued_recordings: Arc<Mutex<Vec<Schedule>>>,
+    /// Next `source_slots` key for a test capture; negative so it can't clash with a schedule ID
+    next_test_slot: AtomicI64,
     /// Path to FFmpeg binary
     ffmpeg_path: PathBuf,
+    /// Path to ffprobe, used for duration and codec probing when available
+    ffprobe_path: Option<PathBuf>,
     /// Default storage directory
     default_storage: PathBuf,
     /// Database reference
@@ -76,6 +162,18 @@ impl RecordingManager {
             }
         };
 
+        // ffprobe is optional too; features that need it report a clear error without it
+        let ffprobe_path = match find_ffprobe(app_handle) {
+            Ok(path) => {
+                info!("Using ffprobe: {:?}", path);
+                Some(path)
+            }
+            Err(e) => {
+                warn!("ffprobe not found, falling back to FFmpeg for probing: {}", e);
+                None
+            }
+        };
+
         // Get default storage path
         let default_storage = get_default_storage_path()?;
         info!("Default storage: {:?}", default_storage);
@@ -92,7 +190,11 @@ impl RecordingManager {
 
         let manager = Self {
             active_recordings: Arc::new(Mutex::new(HashMap::new())),
+            source_slots: Arc::new(Mutex::new(HashMap::new())),
+            queued_recordings: Arc::new(Mutex::new(Vec::new())),
+            next_test_slot: AtomicI64::new(-1),
             ffmpeg_path,
+            ffprobe_path,
             default_storage,
             db,
             app_handle: app_handle.clone(),
@@ -112,70 +214,371 @@ impl RecordingManager {
         Ok(manager)
     }
 
-    /// Record a scheduled program
-    pub async fn record(&self, schedule: Schedule) -> Result<()> {
-        // Check if FFmpeg is available
-        if !self.ffmpeg_path.exists() && which::which(&self.ffmpeg_path).is_err() {
-            return Err(anyhow::anyhow!(
-                "FFmpeg is not available. Please install FFmpeg to use recording functionality."
-            ));
+    /// Concurrent recordings allowed for a source, from its max_connections (at least 1)
+    fn connection_limit(&self, source_id: &str) -> usize {
+        match self.db.get_max_connections(source_id) {
+            Ok(Some(n)) if n > 0 => n as usize,
+            Ok(_) => 1,
+            Err(e) => {
+                warn!("Failed to read max connections for {}, assuming 1: {}", source_id, e);
+                1
+            }
         }
+    }
 
-        // Check if this is a Stalker source that needs real-time URL resolution
-        // Stalker sources have stream_url containing .m3u8, or we need to check the channel's direct_url
-        let is_hls = schedule.stream_url.as_ref().map(|u| u.contains(".m3u8")).unwrap_or(false);
-
-        // Also check if the channel's direct_url indicates Stalker
-        let conn = self.db.get_conn()?;
-        let direct_url: Option<String> = conn.query_row(
-            "SELECT direct_url FROM channels WHERE stream_id = ?1",
-            [&schedule.channel_id],
-            |row| row.get(0)
-        ).optional()?;
+    /// Connections recordings currently hold on a source
+    pub fn connections_in_use(&self, source_id: &str) -> usize {
+        self.source_slots.lock().get(source_id).map_or(0, |held| held.len())
+    }
 
-        let is_stalker_channel = direct_url.map(|url| url.starts_with("stalker_")).unwrap_or(false);
+    /// Start a due schedule if its source has a free connection, otherwise queue it.
+    ///
+    /// When the source is full and the schedule outranks the lowest-priority recording
+    /// running there, that recording is stopped so the schedule takes its connection.
+    /// Queued schedules start automatically, highest priority first, as connections free up.
+    pub fn start_or_queue(self: &Arc<Self>, schedule: Schedule) -> Result<StartOutcome> {
+        let limit = self.connection_limit(&schedule.source_id);
+        let preempt;
 
-        let needs_url_resolution = is_hls || is_stalker_channel;
+        {
+            let mut slots = self.source_slots.lock();
+            let mut queue = self.queued_recordings.lock();
 
-        println!("[DVR Recorder] Channel {}: is_hls={}, is_stalker={}, needs_resolution={}",
-                 schedule.channel_id, is_hls, is_stalker_channel, needs_url_resolution);
+            let running = slots.values().any(|held| held.contains_key(&schedule.id));
+            if running || queue.iter().any(|s| s.id == schedule.id) {
+                return Ok(StartOutcome::AlreadyPending);
+            }
 
-        let stream_url = if needs_url_resolution {
-            // For Stalker/HLS streams, request fresh URL from frontend
-            println!("[DVR Recorder] Stalker/HLS stream detected, requesting fresh URL from frontend");
+            let held = slots.get(&schedule.source_id);
+            let used = held.map_or(0, |held| held.len());
+            if used < limit {
+                slots.entry(schedule.source_id.clone()).or_default().insert(schedule.id, SourceSlot::new(schedule.priority));
+                drop(queue);
+                drop(slots);
+                self.spawn_recording(schedule)?;
+                return Ok(StartOutcome::Started);
+            }
 
-            // Emit event to frontend to resolve URL
-            println!("[DVR Recorder] Emitting dvr:resolve_url_now event for schedule {}", schedule.id);
-            let emit_result = self.app_handle.emit("dvr:resolve_url_now", serde_json::json!({
+            // Only preempt if no equally urgent schedule is already waiting for the next free slot
+            let lowest_running = held
+                .and_then(|held| held.iter().min_by_key(|(_, slot)| slot.priority))
+                .map(|(id, slot)| (*id, slot.priority, slot.cancel_tx.clone()));
+            let outranked_by_queue = queue
+                .iter()
+                .any(|s| s.source_id == schedule.source_id && s.priority >= schedule.priority);
+            preempt = lowest_running
+                .filter(|(_, priority, _)| *priority < schedule.priority && !outranked_by_queue);
+
+            info!(
+                "Source {} is using {}/{} connections, queueing '{}' (schedule {}, priority {})",
+                schedule.source_id, used, limit, schedule.program_title, schedule.id, schedule.priority
+            );
+            let _ = self.app_handle.emit("dvr:queued", serde_json::json!({
                 "schedule_id": schedule.id,
-                "channel_id": schedule.channel_id,
                 "source_id": schedule.source_id,
+                "position": queue.iter().filter(|s| s.source_id == schedule.source_id && s.priority >= schedule.priority).count() + 1,
             }));
-            println!("[DVR Recorder] Emit result: {:?}", emit_result);
+            queue.push(schedule.clone());
+        }
 
-            // Wait for frontend to resolve and update the URL
-            // Stalker resolution takes ~300-500ms, so we wait 1.5s to be safe
-            println!("[DVR Recorder] Waiting 1.5s for frontend URL resolution...");
-            tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
+        if let Some((victim_id, victim_priority, victim_cancel)) = preempt {
+            warn!(
+                "Preempting schedule {} (priority {}) on {} for '{}' (schedule {}, priority {})",
+                victim_id, victim_priority, schedule.source_id, schedule.program_title, schedule.id, schedule.priority
+            );
+            let _ = self.app_handle.emit("dvr:preempted", serde_json::json!({
+                "schedule_id": victim_id,
+                "preempted_by": schedule.id,
+                "source_id": schedule.source_id,
+            }));
+            // Signal the slot itself: the victim may still be resolving or probing, with no
+            // `RecordingHandle` for stop_recording to find yet. Stopping it releases its slot,
+            // which hands it to the highest-priority queued schedule.
+            victim_cancel.send_replace(true);
+            let manager = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = manager.stop_recording(victim_id).await {
+                    error!("Failed to stop preempted recording {}: {}", victim_id, e);
+                }
+            });
+        }
 
-            // Re-fetch schedule to get updated URL
-            let updated_schedule = self.db.get_schedule(schedule.id)?
-                .ok_or_else(|| anyhow::anyhow!("Schedule disappeared"))?;
+        Ok(StartOutcome::Queued)
+    }
 
-            if let Some(ref url) = updated_schedule.stream_url {
-                println!("[DVR Recorder] Got updated URL from frontend: {}", url);
-                url.clone()
-            } else {
-                // Fall back to original URL if frontend didn't update
-                println!("[DVR Recorder] WARNING: Frontend didn't update URL, falling back to resolver");
-                resolve_stream_url(&schedule, &self.db).await?
+    /// Start a user-initiated schedule now instead of waiting for the next scheduler poll.
+    /// Connection limits still apply, so the schedule may be queued.
+    pub fn start_recording_immediate(self: &Arc<Self>, schedule_id: i64) -> Result<StartOutcome> {
+        let schedule = self.db.get_schedule(schedule_id)?
+            .ok_or_else(|| anyhow::anyhow!("Schedule {} not found", schedule_id))?;
+        if schedule.status != ScheduleStatus::Scheduled {
+            return Err(anyhow::anyhow!("Schedule {} is not waiting to record", schedule_id));
+        }
+
+        info!("Starting '{}' (schedule {}) immediately", schedule.program_title, schedule.id);
+        self.start_or_queue(schedule)
+    }
+
+    /// Mark the schedule as recording and run it on a background task.
+    /// The caller must already hold a connection slot for it.
+    fn spawn_recording(self: &Arc<Self>, schedule: Schedule) -> Result<()> {
+        if let Err(e) = self.db.update_schedule_status(schedule.id, ScheduleStatus::Recording) {
+            self.release_slot(&schedule.source_id, schedule.id);
+            return Err(e);
+        }
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            println!("[DVR Recorder] Recording task spawned for ID {}: {}", schedule.id, schedule.program_title);
+            if let Err(e) = manager.record(schedule.clone()).await {
+                error!("Recording failed for {}: {}", schedule.program_title, e);
+
+                if let Err(e) = manager.db.update_schedule_status(schedule.id, ScheduleStatus::Failed) {
+                    error!("Failed to update schedule status: {}", e);
+                }
             }
-        } else {
-            // For non-HLS streams, use normal resolution
-            println!("[DVR Recorder] Non-Stalker stream, using normal resolution");
-            resolve_stream_url(&schedule, &self.db).await?
+            manager.release_slot(&schedule.source_id, schedule.id);
+        });
+
+        Ok(())
+    }
+
+    /// Give back a schedule's connection and start queued recordings that now fit,
+    /// highest priority first (earliest queued among equals)
+    fn release_slot(self: &Arc<Self>, source_id: &str, schedule_id: i64) {
+        let limit = self.connection_limit(source_id);
+        let now = chrono::Utc::now().timestamp();
+        let mut to_start = Vec::new();
+
+        {
+            let mut slots = self.source_slots.lock();
+            let mut queue = self.queued_recordings.lock();
+
+            let held = slots.entry(source_id.to_string()).or_default();
+            held.remove(&schedule_id);
+
+            while held.len() < limit {
+                let next_pos = queue
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| s.source_id == source_id)
+                    .max_by_key(|(pos, s)| (s.priority, std::cmp::Reverse(*pos)))
+                    .map(|(pos, _)| pos);
+                let Some(pos) = next_pos else {
+                    break;
+                };
+                let next = queue.remove(pos);
+                if next.actual_end() <= now {
+                    warn!("Queued recording '{}' (schedule {}) ended before a connection freed up", next.program_title, next.id);
+                    let _ = self.db.update_schedule_status(next.id, ScheduleStatus::Failed);
+                    continue;
+                }
+                held.insert(next.id, SourceSlot::new(next.priority));
+                to_start.push(next);
+            }
+
+            if held.is_empty() {
+                slots.remove(source_id);
+            }
+        }
+
+        for next in to_start {
+            info!(
+                "Connection freed on {}, starting queued '{}' (schedule {}, priority {})",
+                next.source_id, next.program_title, next.id, next.priority
+            );
+            if let Err(e) = self.spawn_recording(next) {
+                error!("Failed to start queued recording: {}", e);
+            }
+        }
+    }
+
+    /// The cancellation signal of the source slot a schedule holds, if any
+    fn held_slot_cancel_tx(&self, schedule_id: i64) -> Option<watch::Sender<bool>> {
+        self.source_slots
+            .lock()
+            .values()
+            .find_map(|held| held.get(&schedule_id))
+            .map(|slot| slot.cancel_tx.clone())
+    }
+
+    /// Update the priority of a running or queued schedule so contention decisions use it
+    pub fn set_priority(&self, schedule_id: i64, priority: i32) {
+        let mut slots = self.source_slots.lock();
+        for held in slots.values_mut() {
+            if let Some(slot) = held.get_mut(&schedule_id) {
+                slot.priority = priority;
+            }
+        }
+        drop(slots);
+
+        if let Some(queued) = self.queued_recordings.lock().iter_mut().find(|s| s.id == schedule_id) {
+            queued.priority = priority;
+        }
+    }
+
+    /// Drop a schedule from the wait queue (e.g. when it is canceled). Returns whether it was queued.
+    pub fn dequeue(&self, schedule_id: i64) -> bool {
+        let mut queue = self.queued_recordings.lock();
+        let before = queue.len();
+        queue.retain(|s| s.id != schedule_id);
+        queue.len() != before
+    }
+
+    /// Schedules waiting for a free connection, in the order they will start per source
+    pub fn get_queued_recordings(&self) -> Vec<QueuedRecording> {
+        let mut queue = self.queued_recordings.lock().clone();
+        queue.sort_by_key(|s| std::cmp::Reverse(s.priority));
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        queue
+            .iter()
+            .map(|s| {
+                let position = positions.entry(s.source_id.as_str()).or_insert(0);
+                *position += 1;
+                QueuedRecording {
+                    schedule_id: s.id,
+                    source_id: s.source_id.clone(),
+                    channel_name: s.channel_name.clone(),
+                    program_title: s.program_title.clone(),
+                    scheduled_start: s.scheduled_start,
+                    scheduled_end: s.scheduled_end,
+                    priority: s.priority,
+                    position: *position,
+                }
+            })
+            .collect()
+    }
+
+    /// ffprobe found at startup, or an error explaining how to get it
+    fn ffprobe(&self) -> Result<&PathBuf> {
+        self.ffprobe_path.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("ffprobe not found. It ships with FFmpeg; place it next to ffmpeg or on your PATH.")
+        })
+    }
+
+    /// Describe the streams at `url` (codec, resolution, bitrate, audio tracks) with ffprobe
+    pub async fn probe_stream(&self, url: &str) -> Result<StreamProbe> {
+        probe_stream_info(self.ffprobe()?, url).await
+    }
+
+    /// Record a short clip of `stream_url` to a temp file, probe it, then delete it
+    ///
+    /// The capture holds one of `source_id`'s connections like a recording, so it is refused
+    /// when the source is at its limit, and a schedule that needs the connection stops it.
+    /// Failures to connect or record are reported in the result rather than as errors,
+    /// so the UI can show what went wrong.
+    pub async fn test_recording(self: &Arc<Self>, source_id: &str, stream_url: &str, seconds: u32) -> Result<TestRecordingResult> {
+        if !self.ffmpeg_available() {
+            return Err(anyhow::anyhow!(
+                "FFmpeg is not available. Please install FFmpeg to use recording functionality."
+            ));
+        }
+
+        let (slot_key, cancel_rx) = self.reserve_test_slot(source_id)?;
+        let result = self.capture_test_clip(stream_url, seconds, cancel_rx).await;
+        self.release_slot(source_id, slot_key);
+        result
+    }
+
+    /// Take a connection on `source_id` for a test capture at the lowest priority, returning its
+    /// `source_slots` key and cancellation signal. Fails if recordings are using every connection.
+    fn reserve_test_slot(&self, source_id: &str) -> Result<(i64, watch::Receiver<bool>)> {
+        let limit = self.connection_limit(source_id);
+        let mut slots = self.source_slots.lock();
+        let held = slots.entry(source_id.to_string()).or_default();
+        if held.len() >= limit {
+            return Err(anyhow::anyhow!(
+                "This source allows {} connection(s) and recordings are using all of them. Try again when one finishes.",
+                limit
+            ));
+        }
+
+        let key = self.next_test_slot.fetch_sub(1, Ordering::Relaxed);
+        let slot = SourceSlot::new(i32::MIN);
+        let cancel_rx = slot.cancel_tx.subscribe();
+        held.insert(key, slot);
+        Ok((key, cancel_rx))
+    }
+
+    /// Run the FFmpeg capture for `test_recording`, stopping early if the slot is cancelled
+    async fn capture_test_clip(&self, stream_url: &str, seconds: u32, mut cancel_rx: watch::Receiver<bool>) -> Result<TestRecordingResult> {
+        let seconds = seconds.clamp(1, 60);
+        let settings = self.db.get_settings()?;
+        let clip_path = std::env::temp_dir().join(format!(
+            "ynotv-test-recording-{}.ts",
+            chrono::Utc::now().timestamp_millis()
+        ));
+
+        let is_hls = stream_url.contains(".m3u8");
+        let mut cmd = Command::new(&self.ffmpeg_path);
+        add_input_args(&mut cmd, stream_url, is_hls, &settings);
+        cmd.arg("-i").arg(stream_url)
+            .arg("-c").arg("copy")
+            .arg("-t").arg(seconds.to_string())
+            .arg("-f").arg("mpegts")
+            .arg("-y").arg(&clip_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+
+        info!("Test recording {}s of {}", seconds, stream_url);
+        let limit = Duration::from_secs(seconds as u64 + settings.recording_timeout_sec.max(1) as u64 + 10);
+        // FFmpeg is killed on drop, so a cancelled capture doesn't keep the connection open
+        let error = tokio::select! {
+            run = tokio::time::timeout(limit, cmd.output()) => match run {
+                Err(_) => Some(format!("FFmpeg did not finish within {}s", limit.as_secs())),
+                Ok(Err(e)) => Some(format!("Failed to run FFmpeg: {}", e)),
+                Ok(Ok(output)) if !output.status.success() => {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    Some(stderr.lines().last().unwrap_or("unknown error").to_string())
+                }
+                Ok(Ok(_)) => None,
+            },
+            Ok(()) = cancel_rx.changed() => Some("Stopped to free the connection for a scheduled recording".to_string()),
         };
-        
+
+        let file_size = std::fs::metadata(&clip_path).map(|m| m.len()).unwrap_or(0);
+        let probe = match (&self.ffprobe_path, file_size > 0) {
+            (Some(ffprobe), true) => probe_stream_info(ffprobe, &clip_path.to_string_lossy()).await.ok(),
+            _ => None,
+        };
+
+        if clip_path.exists() {
+            if let Err(e) = std::fs::remove_file(&clip_path) {
+                warn!("Failed to delete test clip {:?}: {}", clip_path, e);
+            }
+        }
+
+        let error = error.or_else(|| (file_size == 0).then(|| "No data was recorded".to_string()));
+        Ok(TestRecordingResult {
+            success: error.is_none(),
+            seconds,
+            file_size,
+            probe,
+            error,
+        })
+    }
+
+    /// Whether the FFmpeg binary found at startup can be run
+    pub fn ffmpeg_available(&self) -> bool {
+        self.ffmpeg_path.exists() || which::which(&self.ffmpeg_path).is_ok()
+    }
+
+    /// Record a scheduled program
+    pub async fn record(&self, schedule: Schedule) -> Result<()> {
+        // Check if FFmpeg is available
+        if !self.ffmpeg_path.exists() && which::which(&self.ffmpeg_path).is_err() {
+            return Err(anyhow::anyhow!(
+                "FFmpeg is not available. Please install FFmpeg to use recording functionality."
+            ));
+        }
+
+        let mut stream_url = self.resolve_recording_url(&schedule).await?;
+
         // DEBUG: Log the URL being used for recording
         println!("[DVR Recorder] Recording '{}' using URL: {}", schedule.program_title, stream_url);
         println!("[DVR Recorder] Schedule ID: {}, Channel ID: {}", schedule.id, schedule.channel_id);
@@ -186,23 +589,57 @@ impl RecordingManager {
         // Get storage path from settings or use default
         let storage_path = self.get_storage_path().await?;
 
-        // Generate filename
-        let filename = generate_filename(&schedule);
+        // Pick the container format (mp4 can't hold every codec found in live streams) and the
+        // part length. One probe answers both, so only one extra connection is opened while
+        // the recording holds its slot on the source.
+        let settings = self.db.get_settings()?;
+        let mut recording_format = settings.recording_format.clone();
+        let probe = if recording_format == "mp4" || settings.segment_max_gb > 0.0 {
+            match tokio::time::timeout(START_PROBE_TIMEOUT, probe_stream(&self.ffmpeg_path, &stream_url)).await {
+                Ok(Some(probe)) => Some(probe),
+                _ => {
+                    debug!("Couldn't probe the stream for '{}' in time", schedule.program_title);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        if recording_format == "mp4" {
+            // An unknown stream keeps the requested format; only known codecs switch it
+            match probe.as_ref().and_then(|p| mp4_compatible(&p.codecs)) {
+                Some(false) => {
+                    warn!(
+                        "Stream for '{}' has codecs that mp4 can't hold, changing the container from mp4 to mkv",
+                        schedule.program_title
+                    );
+                    recording_format = "mkv".to_string();
+                }
+                Some(true) => {}
+                None => debug!("Stream codecs for '{}' unknown, keeping mp4", schedule.program_title),
+            }
+        }
+        let bitrate_kbps = probe.and_then(|p| p.bitrate_kbps);
+
+        // Generate filename; split recordings write numbered parts, registered under the first one
+        let base_filename = generate_filename(&schedule, &recording_format, &settings.filename_template);
+        let segment_secs = segment_duration(&settings, bitrate_kbps);
+        let (base_filename, recording_id) =
+            self.claim_filename(&schedule, &storage_path, &base_filename, segment_secs.is_some())?;
+        let (filename, ffmpeg_output) = match segment_secs {
+            Some(secs) => {
+                info!("Splitting '{}' into {}s parts", schedule.program_title, secs);
+                (
+                    segment_filename(&base_filename, "001"),
+                    storage_path.join(segment_filename(&base_filename, "%03d")),
+                )
+            }
+            None => (base_filename.clone(), storage_path.join(&base_filename)),
+        };
         let output_path = storage_path.join(&filename);
 
         // Calculate recording duration
-        let duration_secs = schedule.actual_end() - schedule.actual_start();
-
-        // Create recording entry in database
-        let recording_id = self.db.add_recording(
-            schedule.id,
-            output_path.to_str().unwrap(),
-            &filename,
-            &schedule.channel_name,
-            &schedule.program_title,
-            schedule.scheduled_start,
-            schedule.scheduled_end,
-        )?;
+        let mut duration_secs = schedule.actual_end() - schedule.actual_start();
 
         info!(
             "Recording #{}: {} ({} seconds)",
@@ -213,54 +650,192 @@ impl RecordingManager {
         let event = RecordingEvent::started(&schedule, recording_id);
         let _ = self.event_tx.send(event).await;
 
-        // Detect stream type for appropriate FFmpeg flags
-        let is_hls = stream_url.contains(".m3u8") || stream_url.contains("/mono.m3u8");
-        println!("[DVR Recorder] Stream type: {}", if is_hls { "HLS (m3u8)" } else { "Direct TS" });
-        
-        // Build FFmpeg command
-        let mut cmd = Command::new(&self.ffmpeg_path);
-        
-        // Input flags
-        if is_hls {
-            // HLS-specific flags
-            cmd.arg("-live_start_index").arg("-1");  // Start from live edge
-            cmd.arg("-http_persistent").arg("0");    // Don't reuse HTTP connections
-        }
-        
-        cmd.arg("-timeout").arg("30000000")  // 30 second read timeout (microseconds)
-            .arg("-i").arg(&stream_url)
-            .arg("-c").arg("copy")              // Zero transcoding
-            .arg("-t").arg(duration_secs.to_string())
-            .arg("-fflags").arg("+flush_packets")  // Flush packets immediately
-            .arg("-y")                           // Overwrite if exists
-            .arg(&output_path)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-
-        // Hide console window on Windows (CREATE_NO_WINDOW = 0x08000000)
-        #[cfg(windows)]
-        cmd.creation_flags(0x08000000);
+        let mut attempt: u32 = 0;
 
-        // Spawn FFmpeg process
-        let child = cmd.spawn()
-            .context("Failed to spawn FFmpeg")?;
+        // Pausing stops FFmpeg and resuming starts a new part, so one recording can span several files.
+        // Part 1 is `output_path`; later parts use the numbered part names.
+        let segmented = segment_secs.is_some();
+        let part_path = |number: u32| -> PathBuf {
+            if number == 1 {
+                output_path.clone()
+            } else {
+                storage_path.join(segment_filename(&base_filename, &format!("{:03}", number)))
+            }
+        };
+        let mut part_files: Vec<PathBuf> = Vec::new();
+        let mut next_part: u32 = 1;
 
-        // Create cancellation channel
-        let (cancel_tx, cancel_rx) = watch::channel(false);
+        // Create cancellation and pause channels, shared by every FFmpeg run of this recording.
+        // Cancellation comes from the source slot, which may already have been signalled.
+        let cancel_tx = self.held_slot_cancel_tx(schedule.id).unwrap_or_else(|| watch::channel(false).0);
+        let cancel_rx = cancel_tx.subscribe();
+        let (pause_tx, mut pause_rx) = watch::channel(false);
+        let (finish_tx, finish_rx) = watch::channel(false);
 
         // Track active recording
         let handle = RecordingHandle {
-            process: Some(child),
+            process: None,
             recording_id,
             schedule: schedule.clone(),
             start_time: Instant::now(),
-            cancel_tx,
+            recorded_seconds: 0.0,
+            size_bytes: 0,
+            recorded_before: 0.0,
+            size_before: 0,
+            cancel_tx: cancel_tx.clone(),
+            pause_tx,
+            finish_tx,
+            paused: false,
         };
-
         self.active_recordings.lock().insert(schedule.id, handle);
 
-        // Wait for completion
-        let result = self.wait_for_recording(schedule.id, recording_id, duration_secs, cancel_rx).await;
+        let result = loop {
+            // A cancel or stop can land while resolving a URL or waiting to retry
+            if *cancel_rx.borrow() {
+                break Err(anyhow::anyhow!("Recording cancelled by user"));
+            }
+            if *finish_rx.borrow() {
+                break if part_files.is_empty() {
+                    Err(anyhow::anyhow!("Recording stopped by user before any data was written"))
+                } else {
+                    Ok(())
+                };
+            }
+
+            // Spawn FFmpeg process
+            let (run_output, segment) = match segment_secs {
+                Some(secs) => (ffmpeg_output.clone(), Some((secs, next_part))),
+                None => (part_path(next_part), None),
+            };
+            let mut cmd = self.build_ffmpeg_command(&schedule, &stream_url, &run_output, duration_secs, &recording_format, segment, &settings);
+            let child = match cmd.spawn() {
+                Ok(child) => child,
+                Err(e) => {
+                    self.active_recordings.lock().remove(&schedule.id);
+                    return Err(e).context("Failed to spawn FFmpeg");
+                }
+            };
+
+            if let Some(handle) = self.active_recordings.lock().get_mut(&schedule.id) {
+                handle.process = Some(child);
+            }
+
+            // Wait for completion
+            let result = self.wait_for_recording(&schedule, recording_id, duration_secs, cancel_rx.clone(), pause_rx.clone(), finish_rx.clone()).await;
+
+            // Carry this run's progress over so it keeps counting up across parts
+            if let Some(handle) = self.active_recordings.lock().get_mut(&schedule.id) {
+                handle.recorded_before += handle.recorded_seconds;
+                handle.size_before += handle.size_bytes;
+                handle.recorded_seconds = 0.0;
+                handle.size_bytes = 0;
+            }
+
+            // Pick up the parts this run wrote (a segmented run can write several)
+            let parts_before = part_files.len();
+            loop {
+                let path = part_path(next_part);
+                if std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0) == 0 {
+                    break;
+                }
+                part_files.push(path);
+                next_part += 1;
+                if !segmented {
+                    break;
+                }
+            }
+
+            let cancelled = *cancel_tx.borrow();
+
+            let error = match result {
+                Ok(WaitOutcome::Finished) => break Ok(()),
+                Ok(WaitOutcome::Paused) => {
+                    let _ = self.app_handle.emit("dvr:paused", serde_json::json!({
+                        "schedule_id": schedule.id,
+                        "recording_id": recording_id,
+                        "paused": true,
+                    }));
+
+                    match self.wait_for_resume(&schedule, recording_id, &mut pause_rx, cancel_rx.clone(), finish_rx.clone()).await {
+                        ResumeOutcome::Resumed => {}
+                        ResumeOutcome::Ended => break Ok(()),
+                        ResumeOutcome::Cancelled => break Err(anyhow::anyhow!("Recording cancelled by user")),
+                    }
+
+                    let _ = self.app_handle.emit("dvr:paused", serde_json::json!({
+                        "schedule_id": schedule.id,
+                        "recording_id": recording_id,
+                        "paused": false,
+                    }));
+
+                    // The old URL (or its token) may have expired while paused
+                    match self.resolve_recording_url(&schedule).await {
+                        Ok(url) => stream_url = url,
+                        Err(e) => warn!("Failed to re-resolve URL for recording #{}, reusing previous: {}", recording_id, e),
+                    }
+
+                    duration_secs = schedule.actual_end() - chrono::Utc::now().timestamp();
+                    if duration_secs <= 0 {
+                        break Ok(());
+                    }
+                    continue;
+                }
+                Err(e) => e,
+            };
+
+            // Retry zero-byte failures (e.g. a transient stream error at start) while
+            // there is still time left in the scheduled window
+            let wrote_data = part_files.len() > parts_before;
+            let remaining = schedule.actual_end() - chrono::Utc::now().timestamp();
+            if cancelled || wrote_data || remaining <= 0 || attempt >= settings.max_recording_retries {
+                break Err(error);
+            }
+
+            attempt += 1;
+            let backoff_secs = settings.retry_backoff_sec * attempt as u64;
+            warn!(
+                "Recording #{} failed ({}), retrying in {}s (attempt {}/{})",
+                recording_id, error, backoff_secs, attempt, settings.max_recording_retries
+            );
+
+            if let Err(e) = self.db.update_recording_retry_count(recording_id, attempt as i32) {
+                warn!("Failed to update retry count for recording #{}: {}", recording_id, e);
+            }
+
+            let _ = self.app_handle.emit("dvr:retrying", serde_json::json!({
+                "schedule_id": schedule.id,
+                "recording_id": recording_id,
+                "attempt": attempt,
+                "max_retries": settings.max_recording_retries,
+                "retry_in_sec": backoff_secs,
+                "error": error.to_string(),
+            }));
+
+            // A rejected token must not be handed out again by the cache
+            if stream_resolver::is_auth_failure(&error.to_string()) {
+                stream_resolver::invalidate_stalker_tokens(&schedule.source_id);
+            }
+
+            // Wake early if the user cancels or stops during the backoff; the loop top handles it
+            let mut backoff_cancel = cancel_rx.clone();
+            let mut backoff_finish = finish_rx.clone();
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(backoff_secs)) => {}
+                Ok(()) = backoff_cancel.changed() => continue,
+                Ok(()) = backoff_finish.changed() => continue,
+            }
+
+            // Re-resolve the URL in case the old one (or its token) went stale
+            match self.resolve_recording_url(&schedule).await {
+                Ok(url) => stream_url = url,
+                Err(e) => warn!("Failed to re-resolve URL for recording #{}, reusing previous: {}", recording_id, e),
+            }
+
+            duration_secs = schedule.actual_end() - chrono::Utc::now().timestamp();
+            if duration_secs <= 0 {
+                break Err(error);
+            }
+        };
 
         // Remove from active recordings
         self.active_recordings.lock().remove(&schedule.id);
@@ -275,6 +850,19 @@ impl RecordingManager {
                     .map(|m| m.len() as i64)
                     .ok();
 
+                // Notify before any fallible bookkeeping so a DB or storage error can't swallow it
+                if settings.notifications_enabled {
+                    let size = recorded_size(&output_path, &part_files);
+                    self.notify_outcome(
+                        &schedule,
+                        recording_id,
+                        "completed",
+                        size,
+                        "Recording finished",
+                        &format!("{} ({:.1} MB)", schedule.program_title, size as f64 / 1_000_000.0),
+                    );
+                }
+
                 // Update recording status with file size
                 self.db.update_recording_status(
                     recording_id,
@@ -286,33 +874,20 @@ impl RecordingManager {
                 // Update schedule status to completed
                 self.db.update_schedule_status(schedule.id, ScheduleStatus::Completed)?;
 
-                // Get storage path for thumbnail generation
+                // Queue up the next occurrence for daily/weekly schedules
+                self.schedule_next_recurrence(&schedule);
+
+                // Generate thumbnail and preview sprite asynchronously, after re-encoding if enabled
                 let storage_path = self.get_storage_path().await?;
-
-                // Generate thumbnail asynchronously
-                let video_path = output_path.to_string_lossy().to_string();
-                let db = self.db.clone();
-                let recording_id_for_thumb = recording_id;
-                let storage_path_for_thumb = storage_path.to_string_lossy().to_string();
-
-                tokio::spawn(async move {
-                    match generate_thumbnail(&video_path, recording_id_for_thumb, &storage_path_for_thumb).await {
-                        Ok(Some(thumb_path)) => {
-                            if let Err(e) = db.update_recording_thumbnail(
-                                recording_id_for_thumb,
-                                thumb_path.to_str().unwrap_or(""),
-                            ) {
-                                error!("Failed to update thumbnail path in database: {}", e);
-                            }
-                        }
-                        Ok(None) => {
-                            warn!("Thumbnail generation returned None for recording {}", recording_id_for_thumb);
-                        }
-                        Err(e) => {
-                            error!("Thumbnail generation failed for recording {}: {}", recording_id_for_thumb, e);
-                        }
-                    }
-                });
+                let parts = self.register_parts(recording_id, &output_path, &part_files);
+                let part_duration = segment_secs.map(|secs| secs as i64).unwrap_or(schedule.actual_end() - schedule.actual_start());
+                for (part_id, part_path) in parts {
+                    if transcode::is_enabled(&settings.transcode_profile) {
+                        self.spawn_transcode(&part_path, part_id, &storage_path, &settings.transcode_profile, part_duration as f64);
+                    } else {
+                        self.spawn_thumbnail_generation(&part_path, part_id, &storage_path);
+                    }
+                }
 
                 // Emit completed event
                 let event = RecordingEvent::completed(&schedule, recording_id);
@@ -323,17 +898,28 @@ impl RecordingManager {
             Err(e) => {
                 error!("Recording #{} failed: {}", recording_id, e);
 
-                // Check if file was partially created
+                // Check if file was partially created; a later part may hold data even if the first is empty
                 let file_size = std::fs::metadata(&output_path)
                     .map(|m| m.len() as i64)
                     .unwrap_or(0);
+                let total_size = recorded_size(&output_path, &part_files);
 
-                let status = if file_size > 0 {
+                let status = if total_size > 0 {
                     RecordingStatus::Partial
                 } else {
                     RecordingStatus::Failed
                 };
 
+                // A user stop or a preemption also ends here but isn't a failure worth a popup
+                if settings.notifications_enabled && !*cancel_rx.borrow() {
+                    let (outcome, title) = if total_size > 0 {
+                        ("partial", "Recording incomplete")
+                    } else {
+                        ("failed", "Recording failed")
+                    };
+                    self.notify_outcome(&schedule, recording_id, outcome, total_size, title, &format!("{}: {}", schedule.program_title, e));
+                }
+
                 // Update database
                 self.db.update_recording_status(
                     recording_id,
@@ -343,33 +929,16 @@ impl RecordingManager {
                 )?;
 
                 // For partial recordings, also generate a thumbnail
-                if file_size > 0 {
+                if total_size > 0 {
                     let storage_path = self.get_storage_path().await?;
-                    let video_path = output_path.to_string_lossy().to_string();
-                    let db = self.db.clone();
-                    let recording_id_for_thumb = recording_id;
-                    let storage_path_for_thumb = storage_path.to_string_lossy().to_string();
-
-                    tokio::spawn(async move {
-                        match generate_thumbnail(&video_path, recording_id_for_thumb, &storage_path_for_thumb).await {
-                            Ok(Some(thumb_path)) => {
-                                if let Err(e) = db.update_recording_thumbnail(
-                                    recording_id_for_thumb,
-                                    thumb_path.to_str().unwrap_or(""),
-                                ) {
-                                    error!("Failed to update thumbnail path for partial recording: {}", e);
-                                }
-                            }
-                            Ok(None) => {
-                                warn!("Thumbnail generation returned None for partial recording {}", recording_id_for_thumb);
-                            }
-                            Err(e) => {
-                                error!("Thumbnail generation failed for partial recording {}: {}", recording_id_for_thumb, e);
-                            }
-                        }
-                    });
+                    for (part_id, part_path) in self.register_parts(recording_id, &output_path, &part_files) {
+                        self.spawn_thumbnail_generation(&part_path, part_id, &storage_path);
+                    }
                 }
 
+                // A single failed airing shouldn't end a recurring schedule
+                self.schedule_next_recurrence(&schedule);
+
                 // Emit failed event
                 let event = RecordingEvent::failed(&schedule, e.to_string());
                 let _ = self.event_tx.send(event).await;
@@ -379,18 +948,350 @@ impl RecordingManager {
         }
     }
 
+    /// Show a native desktop notification for a finished recording and mirror it
+    /// to the frontend as `dvr:notify` for in-app toasts
+    fn notify_outcome(&self, schedule: &Schedule, recording_id: i64, outcome: &str, size_bytes: i64, title: &str, body: &str) {
+        if let Err(e) = self.app_handle.notification().builder().title(title).body(body).show() {
+            warn!("Failed to show recording notification: {}", e);
+        }
+        let _ = self.app_handle.emit("dvr:notify", serde_json::json!({
+            "outcome": outcome,
+            "schedule_id": schedule.id,
+            "recording_id": recording_id,
+            "program_title": schedule.program_title,
+            "channel_name": schedule.channel_name,
+            "size_bytes": size_bytes,
+            "message": format!("{}: {}", title, body),
+        }));
+    }
+
+    /// Resolve the stream URL to record, asking the frontend for a fresh one
+    /// for Stalker/HLS streams whose tokens expire quickly
+    async fn resolve_recording_url(&self, schedule: &Schedule) -> Result<String> {
+        // Check if this is a Stalker source that needs real-time URL resolution
+        // Stalker sources have stream_url containing .m3u8, or we need to check the channel's direct_url
+        let is_hls = schedule.stream_url.as_ref().map(|u| u.contains(".m3u8")).unwrap_or(false);
+
+        // Also check if the channel's direct_url indicates Stalker
+        let conn = self.db.get_conn()?;
+        let direct_url: Option<String> = conn.query_row(
+            "SELECT direct_url FROM channels WHERE stream_id = ?1",
+            [&schedule.channel_id],
+            |row| row.get(0)
+        ).optional()?;
+
+        let is_stalker_channel = direct_url.map(|url| url.starts_with("stalker_")).unwrap_or(false);
+
+        let needs_url_resolution = is_hls || is_stalker_channel;
+
+        println!("[DVR Recorder] Channel {}: is_hls={}, is_stalker={}, needs_resolution={}",
+                 schedule.channel_id, is_hls, is_stalker_channel, needs_url_resolution);
+
+        let cached_url = if needs_url_resolution {
+            stream_resolver::cached_stalker_url(&schedule.source_id, &schedule.channel_id)
+        } else {
+            None
+        };
+
+        let stream_url = if let Some(url) = cached_url {
+            // A recent resolution for this channel is still fresh, skip the frontend round-trip
+            println!("[DVR Recorder] Reusing cached Stalker URL for channel {}", schedule.channel_id);
+            url
+        } else if needs_url_resolution {
+            // For Stalker/HLS streams, request fresh URL from frontend
+            println!("[DVR Recorder] Stalker/HLS stream detected, requesting fresh URL from frontend");
+
+            // Emit event to frontend to resolve URL
+            println!("[DVR Recorder] Emitting dvr:resolve_url_now event for schedule {}", schedule.id);
+            let emit_result = self.app_handle.emit("dvr:resolve_url_now", serde_json::json!({
+                "schedule_id": schedule.id,
+                "channel_id": schedule.channel_id,
+                "source_id": schedule.source_id,
+            }));
+            println!("[DVR Recorder] Emit result: {:?}", emit_result);
+
+            // Wait for frontend to resolve and update the URL
+            // Stalker resolution takes ~300-500ms, so we wait 1.5s to be safe
+            println!("[DVR Recorder] Waiting 1.5s for frontend URL resolution...");
+            tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
+
+            // Re-fetch schedule to get updated URL
+            let updated_schedule = self.db.get_schedule(schedule.id)?
+                .ok_or_else(|| anyhow::anyhow!("Schedule disappeared"))?;
+
+            if let Some(ref url) = updated_schedule.stream_url {
+                println!("[DVR Recorder] Got updated URL from frontend: {}", url);
+                url.clone()
+            } else {
+                // Fall back to original URL if frontend didn't update
+                println!("[DVR Recorder] WARNING: Frontend didn't update URL, falling back to resolver");
+                resolve_stream_url(schedule, &self.db).await?
+            }
+        } else {
+            // For non-HLS streams, use normal resolution
+            println!("[DVR Recorder] Non-Stalker stream, using normal resolution");
+            resolve_stream_url(schedule, &self.db).await?
+        };
+
+        Ok(stream_url)
+    }
+
+    /// Build the FFmpeg command for a recording
+    #[allow(clippy::too_many_arguments)]
+    fn build_ffmpeg_command(
+        &self,
+        schedule: &Schedule,
+        stream_url: &str,
+        output_path: &PathBuf,
+        duration_secs: i64,
+        recording_format: &str,
+        segment: Option<(u64, u32)>,
+        settings: &DvrSettings,
+    ) -> Command {
+        // Detect stream type for appropriate FFmpeg flags
+        let is_hls = stream_url.contains(".m3u8") || stream_url.contains("/mono.m3u8");
+        println!("[DVR Recorder] Stream type: {}", if is_hls { "HLS (m3u8)" } else { "Direct TS" });
+        
+        // Build FFmpeg command
+        let mut cmd = Command::new(&self.ffmpeg_path);
+        
+        // Input flags
+        add_input_args(&mut cmd, stream_url, is_hls, settings);
+        cmd.arg("-i").arg(stream_url)
+            .arg("-c").arg("copy")              // Zero transcoding
+            .arg("-fflags").arg("+flush_packets"); // Flush packets immediately
+
+        // Auto-extending recordings are stopped from wait_for_recording instead of -t
+        if schedule.auto_extend_minutes <= 0 {
+            cmd.arg("-t").arg(duration_secs.to_string());
+        }
+
+        // Container-specific output flags (still remuxing only)
+        if recording_format == "mp4" {
+            cmd.arg("-sn");                             // DVB/teletext subtitles can't go in mp4
+        }
+
+        if let Some((secs, start_number)) = segment {
+            // Numbered parts; each part starts at timestamp 0 so it plays on its own
+            let muxer = match recording_format {
+                "mp4" => "mp4",
+                "mkv" => "matroska",
+                _ => "mpegts",
+            };
+            cmd.arg("-f").arg("segment")
+                .arg("-segment_time").arg(secs.to_string())
+                .arg("-segment_start_number").arg(start_number.to_string())
+                .arg("-reset_timestamps").arg("1")
+                .arg("-segment_format").arg(muxer);
+            if recording_format == "mp4" {
+                cmd.arg("-segment_format_options").arg("movflags=+faststart");
+            }
+        } else {
+            match recording_format {
+                "mp4" => {
+                    cmd.arg("-movflags").arg("+faststart");  // Move index to front for playback
+                }
+                "mkv" => {
+                    cmd.arg("-f").arg("matroska");
+                }
+                _ => {}
+            }
+        }
+
+        cmd.arg("-y")                           // Overwrite if exists
+            .arg(output_path)
+            .stdin(Stdio::piped())               // Allows a graceful 'q' stop
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // Hide console window on Windows (CREATE_NO_WINDOW = 0x08000000)
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+
+        cmd
+    }
+
+    /// Pick a free filename for a recording and register it in the database
+    ///
+    /// Adds `_2`, `_3`, ... before the extension while the name is taken on disk or by
+    /// another recording. The database row is what reserves the name, so recordings
+    /// starting at the same moment get different files. Returns the base filename
+    /// and the new recording's ID.
+    fn claim_filename(
+        &self,
+        schedule: &Schedule,
+        storage_path: &std::path::Path,
+        filename: &str,
+        segmented: bool,
+    ) -> Result<(String, i64)> {
+        let (stem, extension) = filename.rsplit_once('.').unwrap_or((filename, ""));
+
+        for n in 1u32.. {
+            let candidate = match (n, extension) {
+                (1, _) => filename.to_string(),
+                (_, "") => format!("{}_{}", stem, n),
+                _ => format!("{}_{}.{}", stem, n, extension),
+            };
+            // A split recording's first file is `_part001`, so that name has to be free too
+            let whole = storage_path.join(&candidate);
+            let first_part = storage_path.join(segment_filename(&candidate, "001"));
+            if whole.exists() || first_part.exists() {
+                continue;
+            }
+
+            let (registered, alias) = if segmented { (&first_part, &whole) } else { (&whole, &first_part) };
+            let registered_name = registered.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if let Some(id) = self.db.claim_recording_path(
+                schedule,
+                &registered.to_string_lossy(),
+                &registered_name,
+                &alias.to_string_lossy(),
+            )? {
+                if n > 1 {
+                    info!("'{}' already exists, recording to '{}' instead", filename, candidate);
+                }
+                return Ok((candidate, id));
+            }
+        }
+        unreachable!("ran out of filename suffixes")
+    }
+
+    /// Register the later parts of a split or resumed recording and return (recording ID, path)
+    /// for every part. The first part is the recording created at start; a recording with
+    /// a single file returns just that.
+    fn register_parts(&self, recording_id: i64, output_path: &Path, part_files: &[PathBuf]) -> Vec<(i64, PathBuf)> {
+        let mut parts = vec![(recording_id, output_path.to_path_buf())];
+
+        for (index, path) in part_files.iter().enumerate().skip(1) {
+            let part_number = index as i32 + 1;
+            let filename = path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+            let size = std::fs::metadata(path).map(|m| m.len() as i64).unwrap_or(0);
+
+            match self.db.add_recording_part(recording_id, part_number, path.to_str().unwrap_or(""), &filename, size) {
+                Ok(part_id) => parts.push((part_id, path.clone())),
+                Err(e) => error!("Failed to register part {} of recording #{}: {}", part_number, recording_id, e),
+            }
+        }
+
+        parts
+    }
+
+    /// Generate the poster thumbnail and scrub bar sprite for a recording in the background
+    fn spawn_thumbnail_generation(&self, output_path: &Path, recording_id: i64, storage_path: &Path) {
+        let video_path = output_path.to_string_lossy().to_string();
+        let storage_path = storage_path.to_string_lossy().to_string();
+        let db = self.db.clone();
+
+        tokio::spawn(async move {
+            generate_previews(&db, &video_path, recording_id, &storage_path).await;
+        });
+    }
+
+    /// Re-encode a finished recording in the background, swap it in for the original,
+    /// then generate previews from whichever file was kept
+    fn spawn_transcode(
+        &self,
+        output_path: &Path,
+        recording_id: i64,
+        storage_path: &Path,
+        profile: &str,
+        expected_duration: f64,
+    ) {
+        let ffmpeg_path = self.ffmpeg_path.clone();
+        let ffprobe_path = self.ffprobe_path.clone();
+        let input = output_path.to_path_buf();
+        let storage_path = storage_path.to_string_lossy().to_string();
+        let profile = profile.to_string();
+        let db = self.db.clone();
+        let app_handle = self.app_handle.clone();
+
+        tokio::spawn(async move {
+            let transcoded = transcode::output_path(&input, &profile);
+            let mut last_emit: Option<Instant> = None;
+
+            // The scheduled length is only a guess if the recording was cut short or extended
+            let expected_duration = match &ffprobe_path {
+                Some(ffprobe) => probe_duration(ffprobe, &input.to_string_lossy()).await.unwrap_or(expected_duration),
+                None => expected_duration,
+            };
+
+            let result = transcode::transcode_recording(
+                &ffmpeg_path,
+                &input,
+                &transcoded,
+                &profile,
+                expected_duration,
+                |processed, total| {
+                    // FFmpeg reports a few times a second; once a second is plenty for a progress bar
+                    if last_emit.is_some_and(|at| at.elapsed() < Duration::from_secs(1)) {
+                        return;
+                    }
+                    last_emit = Some(Instant::now());
+                    let percent = if total > 0.0 { (processed / total * 100.0).min(100.0) } else { 0.0 };
+                    let _ = app_handle.emit("dvr:transcode_progress", serde_json::json!({
+                        "recording_id": recording_id,
+                        "profile": profile,
+                        "status": "running",
+                        "processed_sec": processed,
+                        "duration_sec": total,
+                        "percent": percent,
+                    }));
+                },
+            )
+            .await
+            .and_then(|()| replace_recording_file(&db, recording_id, &input, &transcoded));
+
+            let kept = match result {
+                Ok(()) => {
+                    let _ = app_handle.emit("dvr:transcode_progress", serde_json::json!({
+                        "recording_id": recording_id,
+                        "profile": profile,
+                        "status": "completed",
+                        "percent": 100.0,
+                    }));
+                    transcoded
+                }
+                Err(e) => {
+                    error!("Transcode failed for recording {}, keeping original: {}", recording_id, e);
+                    let _ = app_handle.emit("dvr:transcode_progress", serde_json::json!({
+                        "recording_id": recording_id,
+                        "profile": profile,
+                        "status": "failed",
+                        "error": e.to_string(),
+                    }));
+                    input
+                }
+            };
+
+            generate_previews(&db, &kept.to_string_lossy(), recording_id, &storage_path).await;
+        });
+    }
+
+    /// Create the next occurrence of a recurring schedule, logging any failure
+    fn schedule_next_recurrence(&self, schedule: &Schedule) {
+        if schedule.recurrence.is_none() {
+            return;
+        }
+
+        if let Err(e) = self.db.create_next_recurrence(schedule) {
+            error!("Failed to create next recurrence for schedule {}: {}", schedule.id, e);
+        }
+    }
+
     /// Wait for a recording to complete
     async fn wait_for_recording(
         &self,
-        schedule_id: i64,
+        schedule: &Schedule,
         recording_id: i64,
         expected_duration: i64,
         mut cancel_rx: watch::Receiver<bool>,
-    ) -> Result<()> {
+        mut pause_rx: watch::Receiver<bool>,
+        mut finish_rx: watch::Receiver<bool>,
+    ) -> Result<WaitOutcome> {
         // Take ownership of the process from the handle
         let mut child = {
             let mut recordings = self.active_recordings.lock();
-            let handle = recordings.get_mut(&schedule_id)
+            let handle = recordings.get_mut(&schedule.id)
                 .context("Recording handle not found")?;
             handle.process.take()
                 .context("Recording process already taken")?
@@ -400,15 +1301,77 @@ impl RecordingManager {
         let stderr = child.stderr.take()
             .context("Failed to take stderr")?;
 
+        let active_recordings = self.active_recordings.clone();
+        let app_handle = self.app_handle.clone();
+        let schedule_id = schedule.id;
+
+        // Shared with the stderr task so the log survives it being aborted on stop/cancel
+        let log = Arc::new(Mutex::new(String::new()));
+        let task_log = log.clone();
+        // Latest HTTP error status FFmpeg reported, to explain a failure
+        let http_error = Arc::new(Mutex::new(None::<u16>));
+        let task_http_error = http_error.clone();
+
         let stderr_task = tokio::spawn(async move {
-            let reader = BufReader::new(stderr);
-            let mut lines = reader.lines();
+            // Stats lines end in '\r' (FFmpeg rewrites them in place), everything else in '\n'
+            let mut reader = BufReader::new(stderr);
+            let mut buf = Vec::new();
             let mut output = String::new();
+            let mut last_emit: Option<Instant> = None;
+
+            while let Ok(read) = reader.read_until(b'\r', &mut buf).await {
+                if read == 0 {
+                    break;
+                }
+                for line in String::from_utf8_lossy(&buf).split(['\r', '\n']) {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+
+                    if let Some(recorded) = transcode::parse_stats_time(line) {
+                        let size = transcode::parse_stats_size(line);
+                        let progress = {
+                            let mut recordings = active_recordings.lock();
+                            recordings.get_mut(&schedule_id).map(|handle| {
+                                handle.recorded_seconds = recorded;
+                                if let Some(size) = size {
+                                    handle.size_bytes = size;
+                                }
+                                handle.progress()
+                            })
+                        };
+
+                        if let Some(progress) = progress {
+                            if last_emit.is_none_or(|at| at.elapsed() >= PROGRESS_EMIT_INTERVAL) {
+                                last_emit = Some(Instant::now());
+                                let _ = app_handle.emit("dvr:progress", &progress);
+                            }
+                        }
+                        // Stats lines arrive several times a second; keep them out of the log and error output
+                        continue;
+                    }
 
-            while let Ok(Some(line)) = lines.next_line().await {
-                println!("[FFmpeg #{}] {}", recording_id, line);
-                output.push_str(&line);
-                output.push('\n');
+                    println!("[FFmpeg #{}] {}", recording_id, line);
+                    if let Some(code) = crate::parse_http_error_code(&line.to_lowercase()) {
+                        let first = task_http_error.lock().replace(code).is_none();
+                        if first {
+                            let _ = app_handle.emit("dvr:http_error", serde_json::json!({
+                                "schedule_id": schedule_id,
+                                "recording_id": recording_id,
+                                "code": code,
+                                "message": crate::http_error_message(code),
+                            }));
+                        }
+                    }
+                    output.push_str(line);
+                    output.push('\n');
+
+                    let mut log = task_log.lock();
+                    log.push_str(line);
+                    log.push('\n');
+                    trim_log_front(&mut log, RECORDING_LOG_MAX_CHARS);
+                }
+                buf.clear();
             }
 
             output
@@ -417,218 +1380,1263 @@ impl RecordingManager {
         // Wrap in Option to handle timeout case
         let mut stderr_task_opt = Some(stderr_task);
 
+        // Auto-extend lets the recording run past its scheduled end, up to the cap
+        let extend_secs = (schedule.auto_extend_minutes.max(0) as i64) * 60;
+        let auto_extend = extend_secs > 0;
+        let started = tokio::time::Instant::now();
+        let mut stop_at = started + Duration::from_secs(expected_duration.max(0) as u64);
+        let mut current_end = schedule.actual_end();
+        let end_cap = schedule.actual_end() + extend_secs;
+
         // Wait for FFmpeg with timeout (duration + 5 minute buffer, min 10 minutes)
-        let timeout_secs = std::cmp::max(expected_duration + 300, 600);
-        let timeout = Duration::from_secs(timeout_secs as u64);
+        let timeout_secs = std::cmp::max(expected_duration + extend_secs + 300, 600);
+        let timeout_at = started + Duration::from_secs(timeout_secs as u64);
         info!("Recording #{} waiting with timeout: {}s", recording_id, timeout_secs);
 
         // Wait for completion, timeout, OR cancellation
-        let result = tokio::select! {
-            // Normal completion
-            status = child.wait() => {
-                // Get stderr output
-                let stderr_task = stderr_task_opt.take()
-                    .expect("stderr_task should exist");
-                let stderr_output = match tokio::time::timeout(
-                    Duration::from_secs(5),
-                    stderr_task
-                ).await {
-                    Ok(Ok(output)) => output,
-                    _ => "(stderr capture timed out or failed)".to_string(),
-                };
+        let result = loop {
+            tokio::select! {
+                // Normal completion
+                status = child.wait() => {
+                    // Get stderr output
+                    let stderr_task = stderr_task_opt.take()
+                        .expect("stderr_task should exist");
+                    let stderr_output = match tokio::time::timeout(
+                        Duration::from_secs(5),
+                        stderr_task
+                    ).await {
+                        Ok(Ok(output)) => output,
+                        _ => "(stderr capture timed out or failed)".to_string(),
+                    };
+
+                    break match status {
+                        Ok(s) if s.success() => Ok(WaitOutcome::Finished),
+                        Ok(s) => {
+                            let code = s.code().unwrap_or(-1);
+                            eprintln!("[DVR Recorder] FFmpeg stderr for recording #{}:\n{}", recording_id, stderr_output);
+                            Err(anyhow::anyhow!("FFmpeg exited with code {}: {}", code, stderr_output.lines().last().unwrap_or("unknown error")))
+                        }
+                        Err(e) => Err(anyhow::anyhow!("FFmpeg wait error: {}", e))
+                    };
+                }
 
-                match status {
-                    Ok(s) if s.success() => Ok(()),
-                    Ok(s) => {
-                        let code = s.code().unwrap_or(-1);
-                        eprintln!("[DVR Recorder] FFmpeg stderr for recording #{}:\n{}", recording_id, stderr_output);
-                        Err(anyhow::anyhow!("FFmpeg exited with code {}: {}", code, stderr_output.lines().last().unwrap_or("unknown error")))
+                // Cancelled by user
+                _ = cancel_rx.changed() => {
+                    info!("Recording #{} cancelled by user", recording_id);
+                    stop_ffmpeg_gracefully(&mut child).await;
+                    if let Some(task) = stderr_task_opt.take() {
+                        task.abort();
                     }
-                    Err(e) => Err(anyhow::anyhow!("FFmpeg wait error: {}", e))
+                    break Err(anyhow::anyhow!("Recording cancelled by user"));
                 }
-            }
 
-            // Cancelled by user
-            _ = cancel_rx.changed() => {
-                info!("Recording #{} cancelled by user", recording_id);
-                let _ = child.kill().await;
-                if let Some(task) = stderr_task_opt {
-                    task.abort();
+                // Stopped early by the user, keeping what was recorded
+                Ok(()) = finish_rx.changed() => {
+                    info!("Recording #{} stopped by user, finalizing file", recording_id);
+                    stop_ffmpeg_gracefully(&mut child).await;
+                    if let Some(task) = stderr_task_opt.take() {
+                        task.abort();
+                    }
+                    break Ok(WaitOutcome::Finished);
+                }
+
+                // Paused by user: stop cleanly so the part written so far stays playable
+                Ok(()) = pause_rx.changed() => {
+                    if !*pause_rx.borrow_and_update() {
+                        continue;
+                    }
+                    info!("Recording #{} paused, stopping FFmpeg", recording_id);
+                    stop_ffmpeg_gracefully(&mut child).await;
+                    if let Some(task) = stderr_task_opt.take() {
+                        task.abort();
+                    }
+                    break Ok(WaitOutcome::Paused);
+                }
+
+                // Scheduled end reached on an auto-extending recording
+                _ = tokio::time::sleep_until(stop_at), if auto_extend => {
+                    let epg_end = self.db
+                        .get_program_end(&schedule.source_id, &schedule.channel_id, &schedule.program_title, schedule.scheduled_start)
+                        .unwrap_or_else(|e| {
+                            warn!("Failed to check program end for recording #{}: {}", recording_id, e);
+                            None
+                        });
+
+                    let new_end = epg_end
+                        .map(|end| std::cmp::min(end + schedule.end_padding_sec as i64, end_cap))
+                        .unwrap_or(current_end);
+
+                    if new_end > current_end {
+                        let extended_by = new_end - current_end;
+                        info!("Recording #{} extended by {}s (program overrun)", recording_id, extended_by);
+                        stop_at += Duration::from_secs(extended_by as u64);
+                        current_end = new_end;
+                        let _ = self.app_handle.emit("dvr:extended", serde_json::json!({
+                            "schedule_id": schedule.id,
+                            "recording_id": recording_id,
+                            "new_end": new_end,
+                            "extended_by_sec": extended_by,
+                        }));
+                        continue;
+                    }
+
+                    info!("Recording #{} reached its end, stopping FFmpeg", recording_id);
+                    stop_ffmpeg_gracefully(&mut child).await;
+                    if let Some(task) = stderr_task_opt.take() {
+                        task.abort();
+                    }
+                    break Ok(WaitOutcome::Finished);
                 }
-                Err(anyhow::anyhow!("Recording cancelled by user"))
-            }
 
-            // Timeout
-            _ = tokio::time::sleep(timeout) => {
-                warn!("Recording #{} timed out, killing FFmpeg", recording_id);
-                let _ = child.kill().await;
-                if let Some(task) = stderr_task_opt {
-                    task.abort();
+                // Timeout
+                _ = tokio::time::sleep_until(timeout_at) => {
+                    warn!("Recording #{} timed out, killing FFmpeg", recording_id);
+                    let _ = child.kill().await;
+                    if let Some(task) = stderr_task_opt.take() {
+                        task.abort();
+                    }
+                    break Err(anyhow::anyhow!("Recording timed out"));
                 }
-                Err(anyhow::anyhow!("Recording timed out"))
             }
         };
 
+        // Report "Access Denied (403)" rather than FFmpeg's last line
+        let http_error = *http_error.lock();
+        let result = match (result, http_error) {
+            (Err(e), Some(code)) if !*cancel_rx.borrow() => {
+                warn!("Recording #{} failed with HTTP {}: {}", recording_id, code, e);
+                Err(anyhow::anyhow!(crate::http_error_message(code)))
+            }
+            (result, _) => result,
+        };
+
+        let mut log = std::mem::take(&mut *log.lock());
+        if let Err(e) = &result {
+            log.push_str(&format!("Recording failed: {}\n", e));
+        }
+        if !log.is_empty() {
+            if let Err(e) = self.db.append_recording_log(recording_id, &log, RECORDING_LOG_MAX_CHARS) {
+                warn!("Failed to save FFmpeg log for recording #{}: {}", recording_id, e);
+            }
+        }
+
         result
     }
 
-    /// Stop a specific recording by schedule ID
-    pub async fn stop_recording(&self, schedule_id: i64) -> Result<()> {
-        println!("[DVR Recorder] stop_recording called for schedule {}", schedule_id);
-        info!("Stopping recording for schedule {}", schedule_id);
+    /// Captured FFmpeg output for a recording, most recent last
+    pub fn get_recording_log(&self, recording_id: i64) -> Result<String> {
+        Ok(self.db.get_recording_log(recording_id)?.unwrap_or_default())
+    }
 
-        // Debug: print all active recordings
-        {
-            let recordings = self.active_recordings.lock();
-            println!("[DVR Recorder] Active recordings: {:?}", recordings.keys().collect::<Vec<_>>());
+    /// Wait while a recording is paused until it is resumed, cancelled, or its scheduled end passes
+    async fn wait_for_resume(
+        &self,
+        schedule: &Schedule,
+        recording_id: i64,
+        pause_rx: &mut watch::Receiver<bool>,
+        mut cancel_rx: watch::Receiver<bool>,
+        mut finish_rx: watch::Receiver<bool>,
+    ) -> ResumeOutcome {
+        let remaining = (schedule.actual_end() - chrono::Utc::now().timestamp()).max(0) as u64;
+        let end = tokio::time::Instant::now() + Duration::from_secs(remaining);
+
+        loop {
+            tokio::select! {
+                changed = pause_rx.changed() => {
+                    if changed.is_err() || !*pause_rx.borrow_and_update() {
+                        info!("Recording #{} resumed", recording_id);
+                        return ResumeOutcome::Resumed;
+                    }
+                }
+                _ = cancel_rx.changed() => {
+                    info!("Recording #{} cancelled while paused", recording_id);
+                    return ResumeOutcome::Cancelled;
+                }
+                Ok(()) = finish_rx.changed() => {
+                    info!("Recording #{} stopped by user while paused", recording_id);
+                    return ResumeOutcome::Ended;
+                }
+                _ = tokio::time::sleep_until(end) => {
+                    info!("Recording #{} reached its end while paused", recording_id);
+                    return ResumeOutcome::Ended;
+                }
+            }
         }
+    }
 
-        // Get the cancel_tx sender
-        let cancel_tx = {
-            let recordings = self.active_recordings.lock();
-            recordings.get(&schedule_id).map(|h| h.cancel_tx.clone())
-        };
+    /// Pause an in-progress recording.
+    ///
+    /// FFmpeg can't pause a live capture, so the current process is stopped and
+    /// `resume_recording` starts a new part, registered alongside the first.
+    pub fn pause_recording(&self, schedule_id: i64) -> Result<()> {
+        let mut recordings = self.active_recordings.lock();
+        let handle = recordings.get_mut(&schedule_id)
+            .ok_or_else(|| anyhow::anyhow!("No active recording for schedule {}", schedule_id))?;
+
+        if handle.paused {
+            return Err(anyhow::anyhow!("Recording is already paused"));
+        }
 
-        if let Some(cancel_tx) = cancel_tx {
-            println!("[DVR Recorder] Found recording, sending cancellation signal");
-            info!("Sending cancellation signal for schedule {}", schedule_id);
-            let _ = cancel_tx.send(true);
+        handle.paused = true;
+        let _ = handle.pause_tx.send(true);
+        info!("Pausing recording for schedule {}", schedule_id);
+        Ok(())
+    }
 
-            // Give the cancellation a moment to be processed, then kill directly
-            tokio::time::sleep(Duration::from_millis(100)).await;
+    /// Resume a paused recording into a new part
+    pub fn resume_recording(&self, schedule_id: i64) -> Result<()> {
+        let mut recordings = self.active_recordings.lock();
+        let handle = recordings.get_mut(&schedule_id)
+            .ok_or_else(|| anyhow::anyhow!("No active recording for schedule {}", schedule_id))?;
 
-            // Also try to kill the process directly
-            // Take the process out of the handle while the lock is held, then kill outside
-            let process_to_kill = {
-                let mut recordings = self.active_recordings.lock();
-                recordings.get_mut(&schedule_id).and_then(|h| h.process.take())
-            };
-            if let Some(mut process) = process_to_kill {
-                println!("[DVR Recorder] Killing FFmpeg process directly");
-                let _ = process.kill().await;
-                info!("Killed FFmpeg process for schedule {}", schedule_id);
-            } else {
-                println!("[DVR Recorder] Process already taken (likely already stopped)");
-            }
-        } else {
-            println!("[DVR Recorder] No active recording found for schedule {}", schedule_id);
-            info!("No active recording found for schedule {}", schedule_id);
+        if !handle.paused {
+            return Err(anyhow::anyhow!("Recording is not paused"));
         }
 
+        handle.paused = false;
+        let _ = handle.pause_tx.send(false);
+        info!("Resuming recording for schedule {}", schedule_id);
         Ok(())
     }
 
-    /// Stop all active recordings
-    pub async fn stop_all_recordings(&self) -> Result<()> {
-        let recordings: Vec<i64> = {
-            let guard = self.active_recordings.lock();
-            guard.keys().copied().collect()
-        };
+    /// Recompute a recording's size from its file and its duration with ffprobe.
+    ///
+    /// The end time is set to the start plus the probed duration, which fixes
+    /// recordings left at 0 bytes or with a wrong length after a crash.
+    pub async fn repair_recording_metadata(&self, recording_id: i64) -> Result<Recording> {
+        let recording = self.db.get_recording(recording_id)?
+            .ok_or_else(|| anyhow::anyhow!("Recording {} not found", recording_id))?;
+        if recording.status == RecordingStatus::Recording {
+            return Err(anyhow::anyhow!("Recording {} is still in progress", recording_id));
+        }
 
-        info!("Stopping {} active recordings", recordings.len());
+        let size = tokio::fs::metadata(&recording.file_path).await
+            .with_context(|| format!("Cannot read {}", recording.file_path))?
+            .len() as i64;
 
-        for schedule_id in recordings {
-            if let Some(mut handle) = self.active_recordings.lock().remove(&schedule_id) {
-                if let Some(mut process) = handle.process.take() {
-                    let _ = process.kill().await;
-                }
+        let duration = probe_duration(self.ffprobe()?, &recording.file_path).await?;
 
-                // Update status
-                let _ = self.db.update_schedule_status(schedule_id, ScheduleStatus::Canceled);
-            }
-        }
+        let actual_start = recording.actual_start.unwrap_or(recording.scheduled_start);
+        let actual_end = actual_start + duration.round() as i64;
+        self.db.update_recording_metadata(recording_id, size, actual_start, actual_end)?;
 
-        Ok(())
+        info!(
+            "Repaired recording {}: {} bytes, {:.0}s (was {:?} bytes)",
+            recording_id, size, duration, recording.size_bytes
+        );
+
+        self.db.get_recording(recording_id)?
+            .ok_or_else(|| anyhow::anyhow!("Recording {} disappeared", recording_id))
     }
 
-    /// Get storage path from settings
-    async fn get_storage_path(&self) -> Result<PathBuf> {
-        let settings = self.db.get_settings()?;
+    /// Join finished recordings into one file with FFmpeg's concat demuxer (`-c copy`).
+    ///
+    /// The parts are joined in the given order and must all have the same codecs.
+    /// On success a single recording replaces them and the originals are deleted.
+    /// Returns the new recording ID.
+    pub async fn concat_recordings(&self, recording_ids: &[i64]) -> Result<i64> {
+        if recording_ids.len() < 2 {
+            return Err(anyhow::anyhow!("Select at least two recordings to join"));
+        }
 
-        if settings.storage_path.is_empty() {
-            Ok(self.default_storage.clone())
-        } else {
-            let path = PathBuf::from(&settings.storage_path);
+        let mut parts = Vec::with_capacity(recording_ids.len());
+        for &id in recording_ids {
+            let recording = self.db.get_recording(id)?
+                .ok_or_else(|| anyhow::anyhow!("Recording {} not found", id))?;
+            if recording.status == RecordingStatus::Recording {
+                return Err(anyhow::anyhow!("Recording {} is still in progress", id));
+            }
+            if !PathBuf::from(&recording.file_path).exists() {
+                return Err(anyhow::anyhow!("File for recording {} is missing: {}", id, recording.file_path));
+            }
+            parts.push(recording);
+        }
+
+        // Stream copy only works if every part has the same streams
+        let mut expected_codecs: Option<Vec<String>> = None;
+        for part in &parts {
+            let codecs = match &self.ffprobe_path {
+                Some(ffprobe) => probe_file_codecs(ffprobe, &part.file_path).await,
+                None => probe_stream_codecs(&self.ffmpeg_path, &part.file_path).await,
+            }
+                .ok_or_else(|| anyhow::anyhow!("Could not read streams of {}", part.filename))?;
+            match &expected_codecs {
+                None => expected_codecs = Some(codecs),
+                Some(expected) if *expected != codecs => {
+                    return Err(anyhow::anyhow!(
+                        "{} has different codecs ({}) than {} ({})",
+                        part.filename, codecs.join(", "), parts[0].filename, expected.join(", ")
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let first = &parts[0];
+        let first_path = PathBuf::from(&first.file_path);
+        let storage_path = first_path.parent().map(PathBuf::from).unwrap_or_else(|| self.default_storage.clone());
+        let output_path = joined_output_path(&first_path);
+        let filename = output_path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+
+        // The concat demuxer reads its inputs from a list file; quotes are escaped as '\''
+        let list_path = storage_path.join(format!(".concat_{}.txt", first.id));
+        let list: String = parts
+            .iter()
+            .map(|part| format!("file '{}'\n", part.file_path.replace('\'', "'\\''")))
+            .collect();
+        std::fs::write(&list_path, list).context("Failed to write concat list")?;
+
+        info!("Joining {} recordings into {:?}", parts.len(), output_path);
+
+        let mut cmd = Command::new(&self.ffmpeg_path);
+        cmd.arg("-hide_banner")
+            .arg("-f").arg("concat")
+            .arg("-safe").arg("0")              // Absolute paths in the list
+            .arg("-i").arg(&list_path)
+            .arg("-c").arg("copy");
+        if output_path.extension().map(|e| e == "mp4").unwrap_or(false) {
+            cmd.arg("-movflags").arg("+faststart");
+        }
+        cmd.arg("-y")
+            .arg(&output_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+
+        let output = cmd.output().await.context("Failed to spawn FFmpeg")?;
+        let _ = std::fs::remove_file(&list_path);
+
+        let size = std::fs::metadata(&output_path).map(|m| m.len()).unwrap_or(0);
+        if !output.status.success() || size == 0 {
+            let _ = std::fs::remove_file(&output_path);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!(
+                "FFmpeg concat failed: {}",
+                stderr.lines().last().unwrap_or("unknown error")
+            ));
+        }
+
+        let actual_end = parts.iter().filter_map(|p| p.actual_end).max();
+        let new_id = self.db.add_joined_recording(
+            first.id,
+            output_path.to_str().unwrap_or(""),
+            &filename,
+            size as i64,
+            actual_end,
+        )?;
+
+        for part in &parts {
+            if let Err(e) = delete_recording_with_files(&self.db, part).await {
+                warn!("Failed to delete joined recording {}: {}", part.id, e);
+            }
+        }
+
+        self.spawn_thumbnail_generation(&output_path, new_id, &storage_path);
+
+        info!("Joined {} recordings into recording {}", parts.len(), new_id);
+        Ok(new_id)
+    }
+
+    /// Cut a finished recording down to `start_seconds..end_seconds` with `-c copy`.
+    ///
+    /// The start is moved back to the keyframe at or before `start_seconds`, since a
+    /// stream copy can't begin mid-GOP. With `keep_original` the trimmed file becomes a
+    /// new recording; otherwise it replaces the original. Returns the trimmed recording's ID.
+    pub async fn trim_recording(&self, id: i64, start_seconds: f64, end_seconds: f64, keep_original: bool) -> Result<i64> {
+        let recording = self.db.get_recording(id)?
+            .ok_or_else(|| anyhow::anyhow!("Recording {} not found", id))?;
+        if recording.status == RecordingStatus::Recording {
+            return Err(anyhow::anyhow!("Recording {} is still in progress", id));
+        }
+        let input = PathBuf::from(&recording.file_path);
+        if !input.is_file() {
+            return Err(anyhow::anyhow!("Recording file is missing: {}", recording.file_path));
+        }
+
+        let ffprobe = self.ffprobe()?;
+        let duration = probe_duration(ffprobe, &recording.file_path).await?;
+        let end = end_seconds.min(duration);
+        if !(start_seconds >= 0.0 && end > start_seconds) {
+            return Err(anyhow::anyhow!("Invalid trim range {:.1}s - {:.1}s (recording is {:.1}s)", start_seconds, end_seconds, duration));
+        }
+        let start = keyframe_before(ffprobe, &recording.file_path, start_seconds).await.unwrap_or(start_seconds);
+
+        let output = trimmed_output_path(&input);
+        info!("Trimming recording {} to {:.2}s - {:.2}s into {:?}", id, start, end, output);
+
+        let mut cmd = Command::new(&self.ffmpeg_path);
+        cmd.arg("-hide_banner")
+            .arg("-ss").arg(format!("{:.3}", start))
+            .arg("-i").arg(&input)
+            .arg("-t").arg(format!("{:.3}", end - start))
+            .arg("-map").arg("0")
+            .arg("-c").arg("copy")
+            .arg("-avoid_negative_ts").arg("make_zero");
+        if output.extension().map(|e| e == "mp4").unwrap_or(false) {
+            cmd.arg("-movflags").arg("+faststart");
+        }
+        cmd.arg("-y")
+            .arg(&output)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+
+        let result = cmd.output().await.context("Failed to spawn FFmpeg")?;
+        let size = std::fs::metadata(&output).map(|m| m.len()).unwrap_or(0);
+        if !result.status.success() || size == 0 {
+            let _ = std::fs::remove_file(&output);
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            return Err(anyhow::anyhow!(
+                "FFmpeg trim failed: {}",
+                stderr.lines().last().unwrap_or("unknown error")
+            ));
+        }
+
+        let trimmed_duration = probe_duration(ffprobe, &output.to_string_lossy()).await.unwrap_or(end - start);
+        let actual_start = recording.actual_start.unwrap_or(recording.scheduled_start) + start.round() as i64;
+        let actual_end = actual_start + trimmed_duration.round() as i64;
+
+        let trimmed_id = if keep_original {
+            let filename = output.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+            match self.db.add_trimmed_recording(id, &output.to_string_lossy(), &filename, size as i64, actual_start, actual_end) {
+                Ok(new_id) => new_id,
+                Err(e) => {
+                    let _ = std::fs::remove_file(&output);
+                    return Err(e);
+                }
+            }
+        } else {
+            replace_recording_file(&self.db, id, &input, &output)?;
+            self.db.update_recording_metadata(id, size as i64, actual_start, actual_end)?;
+
+            // The previews still show the untrimmed file; drop them so they're regenerated
+            if let Some(thumbnail_path) = &recording.thumbnail_path {
+                let _ = tokio::fs::remove_file(thumbnail_path).await;
+            }
+            if let Some(sprite_path) = &recording.sprite_path {
+                let sprite_path = PathBuf::from(sprite_path);
+                let _ = tokio::fs::remove_file(sprite_path.with_extension("vtt")).await;
+                let _ = tokio::fs::remove_file(sprite_path).await;
+            }
+            self.db.update_recording_paths(id, &output.to_string_lossy(), None, None)?;
+            id
+        };
+
+        let storage_path = input.parent().map(PathBuf::from).unwrap_or_else(|| self.default_storage.clone());
+        self.spawn_thumbnail_generation(&output, trimmed_id, &storage_path);
+
+        info!("Trimmed recording {} into recording {} ({:.0}s)", id, trimmed_id, trimmed_duration);
+        Ok(trimmed_id)
+    }
+
+    /// Stop a specific recording by schedule ID
+    pub async fn stop_recording(&self, schedule_id: i64) -> Result<()> {
+        println!("[DVR Recorder] stop_recording called for schedule {}", schedule_id);
+        info!("Stopping recording for schedule {}", schedule_id);
+
+        // Debug: print all active recordings
+        {
+            let recordings = self.active_recordings.lock();
+            println!("[DVR Recorder] Active recordings: {:?}", recordings.keys().collect::<Vec<_>>());
+        }
+
+        // Get the cancel_tx sender
+        let cancel_tx = {
+            let recordings = self.active_recordings.lock();
+            recordings.get(&schedule_id).map(|h| h.cancel_tx.clone())
+        };
+
+        if let Some(cancel_tx) = cancel_tx {
+            println!("[DVR Recorder] Found recording, sending cancellation signal");
+            info!("Sending cancellation signal for schedule {}", schedule_id);
+            let _ = cancel_tx.send(true);
+
+            // Give the cancellation a moment to be processed by the recording task
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            // If the task hasn't taken the process yet, stop it here
+            // Take the process out of the handle while the lock is held, then stop outside
+            let process_to_stop = {
+                let mut recordings = self.active_recordings.lock();
+                recordings.get_mut(&schedule_id).and_then(|h| h.process.take())
+            };
+            if let Some(mut process) = process_to_stop {
+                println!("[DVR Recorder] Stopping FFmpeg process directly");
+                stop_ffmpeg_gracefully(&mut process).await;
+                info!("Stopped FFmpeg process for schedule {}", schedule_id);
+            } else {
+                println!("[DVR Recorder] Process already taken (likely already stopped)");
+            }
+        } else if let Some(slot_cancel_tx) = self.held_slot_cancel_tx(schedule_id) {
+            // Holding a slot but not registered yet: the task stops before it spawns FFmpeg
+            info!("Schedule {} is still starting, signalling its source slot", schedule_id);
+            slot_cancel_tx.send_replace(true);
+        } else {
+            println!("[DVR Recorder] No active recording found for schedule {}", schedule_id);
+            info!("No active recording found for schedule {}", schedule_id);
+        }
+
+        Ok(())
+    }
+
+    /// Stop a schedule's recording if it is running, drop it from the queue and cancel it
+    pub async fn cancel_schedule(&self, schedule_id: i64) -> Result<()> {
+        if let Some(schedule) = self.db.get_schedule(schedule_id)? {
+            if matches!(schedule.status, ScheduleStatus::Recording) {
+                debug!("Schedule {} is recording, stopping FFmpeg process", schedule_id);
+                self.stop_recording(schedule_id).await?;
+            }
+        }
+
+        // A queued schedule must not start once a connection frees up
+        if self.dequeue(schedule_id) {
+            debug!("Removed schedule {} from the recording queue", schedule_id);
+        }
+
+        self.db.cancel_schedule(schedule_id)
+    }
+
+    /// Delete several recordings (every part of each) with their files, thumbnails and
+    /// sprites. In-progress recordings are stopped first and their tasks waited for, so
+    /// FFmpeg has released the files. All rows are removed in one transaction before any
+    /// file is touched; video files that can't be deleted afterwards are listed per file.
+    pub async fn bulk_delete_recordings(&self, ids: Vec<i64>) -> BulkRecordingDeletion {
+        let mut result = BulkRecordingDeletion::default();
+        let mut deletable: Vec<(i64, Vec<Recording>)> = Vec::new();
+
+        for id in ids {
+            match self.stop_recording_parts(id).await {
+                Ok(parts) => deletable.push((id, parts)),
+                Err(e) => {
+                    warn!("Failed to delete recording {}: {:#}", id, e);
+                    result.failed.push(RecordingDeleteFailure { id, error: format!("{:#}", e) });
+                }
+            }
+        }
+
+        let row_ids: Vec<i64> = deletable.iter().flat_map(|(_, parts)| parts.iter().map(|p| p.id)).collect();
+        if let Err(e) = self.db.delete_recordings(&row_ids) {
+            // Nothing was removed, so the files are all still in place
+            let error = format!("Failed to delete from database: {:#}", e);
+            result.failed.extend(deletable.into_iter().map(|(id, _)| RecordingDeleteFailure { id, error: error.clone() }));
+            return result;
+        }
+        result.deleted = deletable.len();
+
+        for (id, parts) in &deletable {
+            for part in parts {
+                if let Err(e) = delete_part_files(part).await {
+                    warn!("Deleted recording {} but not its file {}: {}", id, part.file_path, e);
+                    result.undeleted_files.push(RecordingFileFailure {
+                        id: *id,
+                        path: part.file_path.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        info!(
+            "Bulk deleted {} recordings ({} failed, {} files left behind)",
+            result.deleted, result.failed.len(), result.undeleted_files.len()
+        );
+        result
+    }
+
+    /// Stop a recording if needed and wait for its task to finish, returning all its parts
+    async fn stop_recording_parts(&self, id: i64) -> Result<Vec<Recording>> {
+        let parts = self.db.get_recording_parts(id)?;
+        if parts.is_empty() {
+            return Err(anyhow::anyhow!("Recording {} not found", id));
+        }
+
+        let recording_schedules: Vec<i64> = parts
+            .iter()
+            .filter(|part| part.status == RecordingStatus::Recording)
+            .filter_map(|part| part.schedule_id)
+            .collect();
+        if recording_schedules.is_empty() {
+            return Ok(parts);
+        }
+
+        for schedule_id in recording_schedules {
+            self.cancel_schedule(schedule_id).await?;
+            if !self.wait_for_recording_task(schedule_id).await {
+                return Err(anyhow::anyhow!("Recording {} is still being finalized, try again shortly", id));
+            }
+        }
+
+        // The task may have registered more parts while it finished
+        self.db.get_recording_parts(id)
+    }
+
+    /// Wait for a stopped schedule's recording task to end: its handle is removed once FFmpeg
+    /// has exited and its slot once the result is saved. False if it outlasts the stop grace period.
+    async fn wait_for_recording_task(&self, schedule_id: i64) -> bool {
+        let deadline = Instant::now() + FFMPEG_STOP_GRACE + Duration::from_secs(1);
+        while self.active_recordings.lock().contains_key(&schedule_id) || self.held_slot_cancel_tx(schedule_id).is_some() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        true
+    }
+
+    /// Save a copy of a finished recording outside the managed storage, optionally
+    /// remuxed (no re-encode) into `mp4` or `mkv`. `dest` may be a directory or a file path.
+    /// The parts of a split recording are joined into one file, and an existing file at the
+    /// destination is never overwritten.
+    /// Progress is emitted as `dvr:export_progress`; the original is never touched.
+    pub async fn export_recording(&self, id: i64, dest: &std::path::Path, remux_to: Option<&str>) -> Result<PathBuf> {
+        let recording = self.db.get_recording(id)?
+            .ok_or_else(|| anyhow::anyhow!("Recording {} not found", id))?;
+        if recording.status == RecordingStatus::Recording {
+            return Err(anyhow::anyhow!("Recording {} is still in progress", id));
+        }
+        // A split recording is exported as one file, joined in part order
+        let parts = self.db.get_recording_parts(id)?;
+        let inputs: Vec<PathBuf> = parts.iter().map(|part| PathBuf::from(&part.file_path)).collect();
+        if let Some(missing) = inputs.iter().find(|input| !input.is_file()) {
+            return Err(anyhow::anyhow!("Recording file is missing: {}", missing.display()));
+        }
+        let remux_to = remux_to.map(str::to_ascii_lowercase);
+        if let Some(format) = &remux_to {
+            if format != "mp4" && format != "mkv" {
+                return Err(anyhow::anyhow!("Unsupported export format: {}", format));
+            }
+        }
+
+        let filename = match parts.as_slice() {
+            [first, _, ..] => joined_filename(&first.filename),
+            _ => recording.filename.clone(),
+        };
+        let mut output = if dest.is_dir() { dest.join(&filename) } else { dest.to_path_buf() };
+        if let Some(format) = &remux_to {
+            output.set_extension(format);
+        }
+        let (parent, name) = match (output.parent(), output.file_name()) {
+            (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => (parent.to_path_buf(), name.to_owned()),
+            (_, Some(name)) => (PathBuf::from("."), name.to_owned()),
+            _ => return Err(anyhow::anyhow!("Invalid export destination: {:?}", output)),
+        };
+        tokio::fs::create_dir_all(&parent).await
+            .with_context(|| format!("Failed to create {:?}", parent))?;
+
+        // Compare resolved paths, so a relative path, `..`, a symlink or different case on
+        // Windows can't point the export back at a recording file
+        let resolved_output = std::fs::canonicalize(&output).unwrap_or_else(|_| {
+            std::fs::canonicalize(&parent).unwrap_or_else(|_| parent.clone()).join(&name)
+        });
+        if inputs.iter().any(|input| std::fs::canonicalize(input).is_ok_and(|input| input == resolved_output)) {
+            return Err(anyhow::anyhow!("Export destination is the recording itself"));
+        }
+        if output.exists() {
+            return Err(anyhow::anyhow!("Export destination already exists: {:?}", output));
+        }
+
+        // The parts run from the earliest start to the latest end
+        let expected = match (parts.iter().filter_map(|p| p.actual_start).min(), parts.iter().filter_map(|p| p.actual_end).max()) {
+            (Some(start), Some(end)) if end > start => (end - start) as f64,
+            _ => (recording.scheduled_end - recording.scheduled_start) as f64,
+        };
+
+        let app_handle = self.app_handle.clone();
+        let emit = |status: &str, percent: f64| {
+            let _ = app_handle.emit("dvr:export_progress", serde_json::json!({
+                "recording_id": id,
+                "status": status,
+                "percent": percent,
+                "path": output.to_string_lossy(),
+            }));
+        };
+
+        let mut last_emit: Option<Instant> = None;
+        let mut on_progress = |done: f64, total: f64| {
+            if last_emit.is_some_and(|at| at.elapsed() < Duration::from_secs(1)) {
+                return;
+            }
+            last_emit = Some(Instant::now());
+            emit("running", if total > 0.0 { (done / total * 100.0).min(100.0) } else { 0.0 });
+        };
+
+        // Several parts are joined by FFmpeg even when the container stays the same
+        let result = match inputs.as_slice() {
+            [input] if remux_to.is_none() => {
+                copy_with_progress(input, &output, |copied, total| on_progress(copied as f64, total as f64)).await
+            }
+            _ => transcode::remux_recording(&self.ffmpeg_path, &inputs, &output, expected, &mut on_progress).await,
+        };
+
+        match result {
+            Ok(()) => {
+                info!("Exported recording {} to {:?}", id, output);
+                emit("completed", 100.0);
+                Ok(output)
+            }
+            Err(e) => {
+                emit("failed", 0.0);
+                Err(e)
+            }
+        }
+    }
+
+    /// Stop a recording early but keep it: FFmpeg is asked to quit so the container is
+    /// finalized, and the recording is marked completed with what was written so far
+    pub fn stop_and_keep(&self, schedule_id: i64) -> Result<()> {
+        let recordings = self.active_recordings.lock();
+        let handle = recordings.get(&schedule_id)
+            .ok_or_else(|| anyhow::anyhow!("No active recording for schedule {}", schedule_id))?;
+
+        info!("Stopping recording for schedule {} and keeping the file", schedule_id);
+        let _ = handle.finish_tx.send(true);
+        Ok(())
+    }
+
+    /// Stop all active recordings
+    pub async fn stop_all_recordings(&self) -> Result<()> {
+        let recordings: Vec<i64> = {
+            let guard = self.active_recordings.lock();
+            guard.keys().copied().collect()
+        };
+
+        info!("Stopping {} active recordings", recordings.len());
+
+        // Each recording task asks its FFmpeg to quit on cancellation, so files get finalized
+        for schedule_id in &recordings {
+            if let Some(handle) = self.active_recordings.lock().get(schedule_id) {
+                let _ = handle.cancel_tx.send(true);
+            }
+        }
+
+        // Tasks remove their handle once FFmpeg has exited; give them the same grace period
+        let deadline = Instant::now() + FFMPEG_STOP_GRACE + Duration::from_secs(1);
+        while !self.active_recordings.lock().is_empty() && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        for schedule_id in recordings {
+            let handle = self.active_recordings.lock().remove(&schedule_id);
+            if let Some(mut handle) = handle {
+                if let Some(mut process) = handle.process.take() {
+                    stop_ffmpeg_gracefully(&mut process).await;
+                }
+
+                // Update status
+                let _ = self.db.update_schedule_status(schedule_id, ScheduleStatus::Canceled);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get storage path from settings
+    async fn get_storage_path(&self) -> Result<PathBuf> {
+        let settings = self.db.get_settings()?;
+
+        if settings.storage_path.is_empty() {
+            Ok(self.default_storage.clone())
+        } else {
+            let path = PathBuf::from(&settings.storage_path);
             std::fs::create_dir_all(&path)?;
             Ok(path)
         }
     }
 
-    /// Get active recordings with their current progress
-    pub fn get_active_recordings(&self) -> Vec<RecordingProgress> {
-        let recordings = self.active_recordings.lock();
-        recordings
-            .values()
-            .map(|handle| {
-                let elapsed = handle.start_time.elapsed().as_secs() as i64;
-                RecordingProgress {
-                    schedule_id: handle.schedule.id,
-                    recording_id: handle.recording_id,
-                    channel_name: handle.schedule.channel_name.clone(),
-                    program_title: handle.schedule.program_title.clone(),
-                    elapsed_seconds: elapsed,
-                    scheduled_duration: handle.schedule.scheduled_end - handle.schedule.scheduled_start,
+    /// Get active recordings with their current progress
+    pub fn get_active_recordings(&self) -> Vec<RecordingProgress> {
+        let recordings = self.active_recordings.lock();
+        recordings.values().map(RecordingHandle::progress).collect()
+    }
+
+    /// Status of one schedule: live progress while recording, or a placeholder
+    /// with `queued` set while it waits for a connection. None if neither.
+    pub fn get_recording_status(&self, schedule_id: i64) -> Option<RecordingProgress> {
+        if let Some(handle) = self.active_recordings.lock().get(&schedule_id) {
+            return Some(handle.progress());
+        }
+
+        let queue = self.queued_recordings.lock();
+        let schedule = queue.iter().find(|s| s.id == schedule_id)?;
+        Some(RecordingProgress {
+            schedule_id,
+            recording_id: 0, // No recording row exists until it starts
+            channel_name: schedule.channel_name.clone(),
+            program_title: schedule.program_title.clone(),
+            elapsed_seconds: 0,
+            recorded_seconds: 0,
+            size_bytes: 0,
+            paused: false,
+            queued: true,
+            scheduled_duration: schedule.scheduled_end - schedule.scheduled_start,
+        })
+    }
+}
+
+impl RecordingHandle {
+    fn progress(&self) -> RecordingProgress {
+        RecordingProgress {
+            schedule_id: self.schedule.id,
+            recording_id: self.recording_id,
+            channel_name: self.schedule.channel_name.clone(),
+            program_title: self.schedule.program_title.clone(),
+            elapsed_seconds: self.start_time.elapsed().as_secs() as i64,
+            recorded_seconds: (self.recorded_before + self.recorded_seconds) as i64,
+            size_bytes: self.size_before + self.size_bytes,
+            paused: self.paused,
+            queued: false,
+            scheduled_duration: self.schedule.scheduled_end - self.schedule.scheduled_start,
+        }
+    }
+}
+
+/// Progress information for an active recording
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecordingProgress {
+    pub schedule_id: i64,
+    pub recording_id: i64,
+    pub channel_name: String,
+    pub program_title: String,
+    /// Wall-clock time since FFmpeg started
+    pub elapsed_seconds: i64,
+    /// Media time actually written, as reported by FFmpeg
+    pub recorded_seconds: i64,
+    /// Output file size as reported by FFmpeg
+    pub size_bytes: u64,
+    pub paused: bool,
+    /// Waiting for a free connection on its source; nothing has been recorded yet
+    pub queued: bool,
+    pub scheduled_duration: i64,
+}
+
+/// A due recording waiting for its source to free a connection
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QueuedRecording {
+    pub schedule_id: i64,
+    pub source_id: String,
+    pub channel_name: String,
+    pub program_title: String,
+    pub scheduled_start: i64,
+    pub scheduled_end: i64,
+    pub priority: i32,
+    /// 1-based place in the source's queue
+    pub position: usize,
+}
+
+/// Delete a recording's database row along with its video file, thumbnail and preview sprite
+pub async fn delete_recording_with_files(db: &DvrDatabase, recording: &Recording) -> Result<()> {
+    // Get file path and thumbnail path first
+    let paths = db.delete_recording(recording.id)?;
+
+    // Delete the preview sprite and its index
+    if let Some(sprite_path) = &recording.sprite_path {
+        let sprite_path = PathBuf::from(sprite_path);
+        let _ = tokio::fs::remove_file(sprite_path.with_extension("vtt")).await;
+        let _ = tokio::fs::remove_file(sprite_path).await;
+    }
+
+    // Delete video file if it exists
+    if let Some((file_path, thumbnail_path)) = paths {
+        if std::path::Path::new(&file_path).exists() {
+            let _ = tokio::fs::remove_file(file_path).await;
+        }
+
+        // Delete thumbnail if it exists
+        if let Some(thumb_path) = thumbnail_path {
+            if std::path::Path::new(&thumb_path).exists() {
+                let _ = tokio::fs::remove_file(thumb_path).await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove a deleted recording part's video, thumbnail and sprite. Only a failure to remove
+/// the video is reported; a file that is already gone counts as removed.
+async fn delete_part_files(part: &Recording) -> std::io::Result<()> {
+    if let Some(thumb_path) = &part.thumbnail_path {
+        let _ = tokio::fs::remove_file(thumb_path).await;
+    }
+    if let Some(sprite_path) = &part.sprite_path {
+        let sprite_path = PathBuf::from(sprite_path);
+        let _ = tokio::fs::remove_file(sprite_path.with_extension("vtt")).await;
+        let _ = tokio::fs::remove_file(sprite_path).await;
+    }
+    match tokio::fs::remove_file(&part.file_path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// A recording `bulk_delete_recordings` could not delete
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecordingDeleteFailure {
+    pub id: i64,
+    pub error: String,
+}
+
+/// Outcome of deleting several recordings at once
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct BulkRecordingDeletion {
+    pub deleted: usize,
+    pub failed: Vec<RecordingDeleteFailure>,
+    /// Video files of deleted recordings that could not be removed
+    pub undeleted_files: Vec<RecordingFileFailure>,
+}
+
+/// A video file left on disk after its recording was deleted
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecordingFileFailure {
+    pub id: i64,
+    pub path: String,
+    pub error: String,
+}
+
+/// Outcome of moving recordings to a new storage directory
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct StorageMigration {
+    pub moved: usize,
+    pub failed: usize,
+    /// Video files in the old directory without a recording row, left where they were
+    pub skipped: Vec<String>,
+}
+
+/// Copy a file in chunks, reporting (bytes copied, total bytes). Fails rather than
+/// overwrite an existing `to`; a failed copy removes the partial output.
+async fn copy_with_progress<F>(from: &std::path::Path, to: &std::path::Path, mut on_progress: F) -> Result<()>
+where
+    F: FnMut(u64, u64),
+{
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut reader = tokio::fs::File::open(from).await
+        .with_context(|| format!("Failed to open {:?}", from))?;
+    let mut writer = tokio::fs::OpenOptions::new().write(true).create_new(true).open(to).await
+        .with_context(|| format!("Failed to create {:?}", to))?;
+
+    let result: Result<()> = async {
+        let total = reader.metadata().await?.len();
+
+        let mut buf = vec![0u8; 1024 * 1024];
+        let mut copied = 0u64;
+        loop {
+            let read = reader.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buf[..read]).await?;
+            copied += read as u64;
+            on_progress(copied, total);
+        }
+        writer.flush().await?;
+        Ok(())
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(to).await;
+    }
+    result
+}
+
+/// Move a file, falling back to copy + delete when `rename` can't cross volumes.
+/// Never replaces an existing `to`.
+async fn move_file(from: &std::path::Path, to: &std::path::Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    // rename silently replaces the destination on Unix
+    if tokio::fs::try_exists(to).await.unwrap_or(true) {
+        anyhow::bail!("{:?} already exists", to);
+    }
+    if tokio::fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+
+    copy_with_progress(from, to, |_, _| {})
+        .await
+        .with_context(|| format!("Failed to copy {:?} to {:?}", from, to))?;
+    tokio::fs::remove_file(from).await
+        .with_context(|| format!("Copied {:?} but could not remove the original", from))?;
+    Ok(())
+}
+
+/// Where a file from the old storage directory goes in the new one, keeping its
+/// place relative to the old directory (e.g. `.thumbnails/`). A name already taken
+/// in the new directory gets a `_1`, `_2`, ... suffix.
+fn migrated_path(path: &std::path::Path, old_dir: &std::path::Path, new_dir: &std::path::Path) -> PathBuf {
+    let target = match path.strip_prefix(old_dir) {
+        Ok(relative) => new_dir.join(relative),
+        Err(_) => new_dir.join(path.file_name().unwrap_or_default()),
+    };
+    if target == path || !target.exists() {
+        return target;
+    }
+
+    let stem = target.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = target.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| target.with_file_name(format!("{}_{}{}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(target)
+}
+
+/// Move one recording's video, thumbnail and preview sprite into `new_dir` and update its row
+async fn migrate_recording(
+    db: &DvrDatabase,
+    recording: &Recording,
+    old_dir: &std::path::Path,
+    new_dir: &std::path::Path,
+) -> Result<()> {
+    let file_path = PathBuf::from(&recording.file_path);
+    let new_file = migrated_path(&file_path, old_dir, new_dir);
+    if new_file != file_path {
+        move_file(&file_path, &new_file).await?;
+    }
+
+    // Thumbnails and sprites can be regenerated, so losing one isn't worth failing the move
+    let mut new_thumbnail = recording.thumbnail_path.clone();
+    if let Some(thumbnail) = recording.thumbnail_path.as_deref().map(PathBuf::from) {
+        let target = migrated_path(&thumbnail, old_dir, new_dir);
+        match move_file(&thumbnail, &target).await {
+            Ok(()) => new_thumbnail = Some(target.to_string_lossy().to_string()),
+            Err(e) => warn!("Failed to move thumbnail for recording {}: {}", recording.id, e),
+        }
+    }
+
+    let mut new_sprite = recording.sprite_path.clone();
+    if let Some(sprite) = recording.sprite_path.as_deref().map(PathBuf::from) {
+        let target = migrated_path(&sprite, old_dir, new_dir);
+        match move_file(&sprite, &target).await {
+            Ok(()) => {
+                let _ = move_file(&sprite.with_extension("vtt"), &target.with_extension("vtt")).await;
+                new_sprite = Some(target.to_string_lossy().to_string());
+            }
+            Err(e) => warn!("Failed to move preview sprite for recording {}: {}", recording.id, e),
+        }
+    }
+
+    db.update_recording_paths(
+        recording.id,
+        &new_file.to_string_lossy(),
+        new_thumbnail.as_deref(),
+        new_sprite.as_deref(),
+    )
+}
+
+/// Move every finished recording from `old_dir` into `new_dir`.
+///
+/// `on_progress` is called with (recordings done, total, filename) after each one.
+/// A recording that can't be moved is left where it was and counted as failed.
+pub async fn migrate_storage<F>(
+    db: &DvrDatabase,
+    old_dir: &std::path::Path,
+    new_dir: &std::path::Path,
+    mut on_progress: F,
+) -> Result<StorageMigration>
+where
+    F: FnMut(usize, usize, &str),
+{
+    let recordings = db.get_completed_recordings()?;
+    let total = recordings.len();
+    let mut result = StorageMigration::default();
+
+    info!("Moving {} recordings from {:?} to {:?}", total, old_dir, new_dir);
+
+    for (index, recording) in recordings.iter().enumerate() {
+        if !std::path::Path::new(&recording.file_path).exists() {
+            result.failed += 1;
+            warn!("Recording {} file is missing, not moving it: {}", recording.id, recording.file_path);
+        } else {
+            match migrate_recording(db, recording, old_dir, new_dir).await {
+                Ok(()) => result.moved += 1,
+                Err(e) => {
+                    result.failed += 1;
+                    warn!("Failed to move recording {}: {:#}", recording.id, e);
                 }
-            })
-            .collect()
+            }
+        }
+        on_progress(index + 1, total, &recording.filename);
+    }
+
+    result.skipped = unregistered_videos(db, old_dir)?;
+    if !result.skipped.is_empty() {
+        warn!("Left {} video files without a recording in {:?}", result.skipped.len(), old_dir);
     }
+
+    info!("Storage migration finished: {} moved, {} failed", result.moved, result.failed);
+    Ok(result)
 }
 
-/// Progress information for an active recording
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct RecordingProgress {
-    pub schedule_id: i64,
-    pub recording_id: i64,
-    pub channel_name: String,
-    pub program_title: String,
-    pub elapsed_seconds: i64,
-    pub scheduled_duration: i64,
+/// Video files directly in `dir` that no recording row points at
+fn unregistered_videos(db: &DvrDatabase, dir: &std::path::Path) -> Result<Vec<String>> {
+    let known: std::collections::HashSet<PathBuf> = db
+        .get_recording_file_paths()?
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    let Ok(entries) = std::fs::read_dir(dir) else { return Ok(Vec::new()) };
+
+    let mut videos: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let is_video = path
+                .extension()
+                .is_some_and(|ext| crate::dvr::cleanup::IMPORTABLE_EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)));
+            let hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            is_video && !hidden && path.is_file() && !known.contains(path)
+        })
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    videos.sort();
+    Ok(videos)
+}
+
+/// The first part's file name split into its stem without the `_partNNN` suffix and its extension
+fn joined_name_parts(first_part: &std::path::Path) -> (String, String) {
+    let stem = first_part.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = first_part.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_else(|| "ts".to_string());
+
+    let base = match stem.rsplit_once("_part") {
+        Some((base, number)) if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) => base.to_string(),
+        _ => stem,
+    };
+    (base, extension)
+}
+
+/// File name for the parts of a split recording joined into one file
+fn joined_filename(first_part: &str) -> String {
+    let (base, extension) = joined_name_parts(std::path::Path::new(first_part));
+    format!("{}.{}", base, extension)
+}
+
+/// Path for a joined recording: the first part's name without its `_partNNN` suffix,
+/// or with `_joined` added if that file already exists
+fn joined_output_path(first_part: &Path) -> PathBuf {
+    let (base, extension) = joined_name_parts(first_part);
+
+    let candidate = first_part.with_file_name(format!("{}.{}", base, extension));
+    if candidate.exists() {
+        first_part.with_file_name(format!("{}_joined.{}", base, extension))
+    } else {
+        candidate
+    }
+}
+
+/// Generate the poster thumbnail and scrub bar sprite for a recording and store their paths
+async fn generate_previews(db: &DvrDatabase, video_path: &str, recording_id: i64, storage_path: &str) {
+    match generate_thumbnail(video_path, recording_id, storage_path).await {
+        Ok(Some(thumb_path)) => {
+            if let Err(e) = db.update_recording_thumbnail(
+                recording_id,
+                thumb_path.to_str().unwrap_or(""),
+            ) {
+                error!("Failed to update thumbnail path in database: {}", e);
+            }
+        }
+        Ok(None) => {
+            warn!("Thumbnail generation returned None for recording {}", recording_id);
+        }
+        Err(e) => {
+            error!("Thumbnail generation failed for recording {}: {}", recording_id, e);
+        }
+    }
+
+    match generate_thumbnail_sprite(video_path, recording_id, SPRITE_FRAME_COUNT, storage_path).await {
+        Ok(Some(sprite_path)) => {
+            if let Err(e) = db.update_recording_sprite(
+                recording_id,
+                sprite_path.to_str().unwrap_or(""),
+            ) {
+                error!("Failed to update sprite path in database: {}", e);
+            }
+        }
+        Ok(None) => {
+            warn!("Sprite generation returned None for recording {}", recording_id);
+        }
+        Err(e) => {
+            error!("Sprite generation failed for recording {}: {}", recording_id, e);
+        }
+    }
+}
+
+/// Point a recording at its transcoded file and delete the original.
+/// On failure the original is kept and the transcoded file removed.
+fn replace_recording_file(db: &DvrDatabase, recording_id: i64, original: &PathBuf, transcoded: &PathBuf) -> Result<()> {
+    let size = std::fs::metadata(transcoded).map(|m| m.len()).unwrap_or(0);
+    let filename = transcoded.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+
+    let updated = if size == 0 {
+        Err(anyhow::anyhow!("Transcoded file is empty"))
+    } else {
+        db.update_recording_file(recording_id, &transcoded.to_string_lossy(), &filename, size as i64)
+    };
+    if let Err(e) = updated {
+        let _ = std::fs::remove_file(transcoded);
+        return Err(e);
+    }
+
+    if let Err(e) = std::fs::remove_file(original) {
+        warn!("Failed to remove original recording {:?} after transcode: {}", original, e);
+    }
+    Ok(())
 }
 
 /// Find FFmpeg binary
 fn find_ffmpeg(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    find_bundled_binary(app_handle, "ffmpeg")
+}
+
+/// Find ffprobe, which ships alongside FFmpeg
+pub fn find_ffprobe(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
+    find_bundled_binary(app_handle, "ffprobe")
+}
+
+/// Locate a bundled tool (`ffmpeg`, `ffprobe`): sidecar, resources, dev path, then PATH
+fn find_bundled_binary(app_handle: &tauri::AppHandle, name: &str) -> Result<PathBuf> {
     use tauri::Manager;
 
+    let exe_name = if cfg!(windows) { format!("{}.exe", name) } else { name.to_string() };
+
+    // Tauri bundles sidecars with the target triple appended
+    #[cfg(target_os = "windows")]
+    let platform_name = format!("{}-x86_64-pc-windows-msvc.exe", name);
+    #[cfg(target_os = "macos")]
+    let platform_name = format!("{}-x86_64-apple-darwin", name);
+    #[cfg(target_os = "linux")]
+    let platform_name = format!("{}-x86_64-unknown-linux-gnu", name);
+
     // First try to resolve as a sidecar (bundled external binary)
     // Sidecars are placed in the same directory as the main executable
     if let Ok(exe_dir) = std::env::current_exe() {
         if let Some(dir) = exe_dir.parent() {
-            // Sidecar naming: ffmpeg.exe on Windows, ffmpeg on Unix
-            let sidecar_name = if cfg!(windows) { "ffmpeg.exe" } else { "ffmpeg" };
-            let sidecar_path = dir.join(&sidecar_name);
+            let sidecar_path = dir.join(&exe_name);
             if sidecar_path.exists() {
-                println!("[FFmpeg] Found sidecar at: {:?}", sidecar_path);
+                println!("[{}] Found sidecar at: {:?}", name, sidecar_path);
                 return Ok(sidecar_path);
             }
 
-            // Also check for platform-specific names (tauri bundles with target triple)
-            #[cfg(target_os = "windows")]
-            let platform_ffmpeg = dir.join("ffmpeg-x86_64-pc-windows-msvc.exe");
-            #[cfg(target_os = "macos")]
-            let platform_ffmpeg = dir.join("ffmpeg-x86_64-apple-darwin");
-            #[cfg(target_os = "linux")]
-            let platform_ffmpeg = dir.join("ffmpeg-x86_64-unknown-linux-gnu");
-
-            if platform_ffmpeg.exists() {
-                println!("[FFmpeg] Found platform-specific binary at: {:?}", platform_ffmpeg);
-                return Ok(platform_ffmpeg);
+            let platform_path = dir.join(&platform_name);
+            if platform_path.exists() {
+                println!("[{}] Found platform-specific binary at: {:?}", name, platform_path);
+                return Ok(platform_path);
             }
         }
     }
 
-    // Try bundled FFmpeg in resources (legacy path)
+    // Try bundled binary in resources (legacy path)
     if let Ok(resource_dir) = app_handle.path().resource_dir() {
-        #[cfg(target_os = "windows")]
-        let bundled = resource_dir.join("bin").join("ffmpeg-x86_64-pc-windows-msvc.exe");
-
-        #[cfg(target_os = "macos")]
-        let bundled = resource_dir.join("bin").join("ffmpeg-x86_64-apple-darwin");
-
-        #[cfg(target_os = "linux")]
-        let bundled = resource_dir.join("bin").join("ffmpeg-x86_64-unknown-linux-gnu");
-
+        let bundled = resource_dir.join("bin").join(&platform_name);
         if bundled.exists() {
-            println!("[FFmpeg] Found in resources: {:?}", bundled);
+            println!("[{}] Found in resources: {:?}", name, bundled);
             return Ok(bundled);
         }
     }
@@ -638,61 +2646,836 @@ fn find_ffmpeg(app_handle: &tauri::AppHandle) -> Result<PathBuf> {
     {
         let dev_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join("bin")
-            .join(if cfg!(windows) { "ffmpeg.exe" } else { "ffmpeg" });
+            .join(&exe_name);
 
         if dev_path.exists() {
-            println!("[FFmpeg] Found in dev path: {:?}", dev_path);
+            println!("[{}] Found in dev path: {:?}", name, dev_path);
             return Ok(dev_path);
         }
     }
 
-    // Fallback to system FFmpeg
-    let ffmpeg = if cfg!(windows) { "ffmpeg.exe" } else { "ffmpeg" };
-
-    // Check PATH
-    if let Ok(path) = which::which(ffmpeg) {
-        println!("[FFmpeg] Found in PATH: {:?}", path);
+    // Fallback to the system binary on PATH
+    if let Ok(path) = which::which(&exe_name) {
+        println!("[{}] Found in PATH: {:?}", name, path);
         return Ok(path);
     }
 
     Err(anyhow::anyhow!(
-        "FFmpeg not found. Please install FFmpeg or ensure it's bundled with the app."
+        "{} not found. Please install FFmpeg or ensure it's bundled with the app.",
+        name
     ))
 }
 
 /// Get default storage path
-fn get_default_storage_path() -> Result<PathBuf> {
+pub fn get_default_storage_path() -> Result<PathBuf> {
     let home = dirs::home_dir().context("Failed to get home directory")?;
     let path = home.join("Videos").join("IPTV-Recordings");
     Ok(path)
 }
 
+/// Resolve the configured storage path, falling back to the default
+pub fn resolve_storage_path(settings: &DvrSettings) -> Result<PathBuf> {
+    if settings.storage_path.is_empty() {
+        get_default_storage_path()
+    } else {
+        Ok(PathBuf::from(&settings.storage_path))
+    }
+}
+
+/// Check a file can be written to `path`, or, if it doesn't exist yet, that it could be
+/// created in its nearest existing parent. Only a short-lived probe file is written.
+pub fn check_storage_writable(path: &std::path::Path) -> Result<()> {
+    let existing = path
+        .ancestors()
+        .find(|dir| dir.exists())
+        .ok_or_else(|| anyhow::anyhow!("Storage directory {:?} is on a drive that isn't connected", path))?;
+    if !existing.is_dir() {
+        return Err(anyhow::anyhow!("Storage path {:?} is not a directory", existing));
+    }
+
+    let probe = existing.join(".ynotv_write_test");
+    std::fs::write(&probe, b"ok")
+        .with_context(|| format!("Storage directory {:?} is not writable", existing))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Ask FFmpeg to finish by sending 'q' on stdin, killing it if it doesn't exit in time
+async fn stop_ffmpeg_gracefully(child: &mut Child) {
+    use tokio::io::AsyncWriteExt;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(b"q").await;
+        let _ = stdin.flush().await;
+    }
+
+    if tokio::time::timeout(FFMPEG_STOP_GRACE, child.wait()).await.is_err() {
+        warn!("FFmpeg did not exit after 'q', killing it");
+        let _ = child.kill().await;
+    }
+}
+
+/// Codecs that can be stream-copied into an mp4 container
+const MP4_COMPATIBLE_CODECS: &[&str] = &[
+    "h264", "hevc", "mpeg4", "mpeg2video", "av1", "vp9",
+    "aac", "mp3", "mp2", "ac3", "eac3", "opus",
+];
+
+/// What FFmpeg reports when opening a stream or file
+struct OpenedStream {
+    /// Video/audio codecs in stream order
+    codecs: Vec<String>,
+    /// Overall bitrate in kb/s, if known
+    bitrate_kbps: Option<u64>,
+}
+
+/// Probe a stream or file with FFmpeg and list its video/audio codecs in stream order.
+/// Returns None if the probe fails.
+async fn probe_stream_codecs(ffmpeg_path: &PathBuf, input: &str) -> Option<Vec<String>> {
+    probe_stream(ffmpeg_path, input).await.map(|probe| probe.codecs)
+}
+
+/// Open a stream or file with FFmpeg and read its codecs and bitrate. Returns None if the probe fails.
+async fn probe_stream(ffmpeg_path: &PathBuf, input: &str) -> Option<OpenedStream> {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner");
+    if input.contains("://") {
+        cmd.arg("-timeout").arg("15000000");
+    }
+    cmd.arg("-i").arg(input)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+
+    // ffmpeg without an output prints the stream info and exits with an error
+    let output = match tokio::time::timeout(Duration::from_secs(20), cmd.output()).await {
+        Ok(Ok(output)) => output,
+        _ => return None,
+    };
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut codecs = Vec::new();
+
+    // "Duration: N/A, start: 1234.5, bitrate: 5200 kb/s"; live streams often report N/A
+    let bitrate_kbps = stderr
+        .lines()
+        .filter_map(|line| line.split("bitrate:").nth(1))
+        .filter_map(|rest| rest.trim().strip_suffix("kb/s"))
+        .find_map(|kbps| kbps.trim().parse().ok())
+        .filter(|kbps: &u64| *kbps > 0);
+
+    for line in stderr.lines() {
+        let line = line.trim();
+        if !line.starts_with("Stream #") {
+            continue;
+        }
+
+        for kind in ["Video: ", "Audio: "] {
+            if let Some(pos) = line.find(kind) {
+                let codec = line[pos + kind.len()..]
+                    .split([' ', ','])
+                    .next()
+                    .unwrap_or("");
+                codecs.push(codec.to_string());
+            }
+        }
+    }
+
+    Some(OpenedStream { codecs, bitrate_kbps })
+}
+
+/// Timestamp of the last keyframe at or before `at` seconds, read with ffprobe.
+/// Both are relative to the container's start time, like FFmpeg's `-ss`.
+async fn keyframe_before(ffprobe_path: &PathBuf, input: &str, at: f64) -> Option<f64> {
+    if at <= 0.0 {
+        return Some(0.0);
+    }
+
+    // Frame timestamps are absolute; MPEG-TS rarely starts at zero
+    let start_time = probe_start_time(ffprobe_path, input).await;
+    let from = (start_time + at - 15.0).max(start_time);
+
+    // Only decode packet headers in a window before the cut; GOPs are rarely over 10s
+    let mut cmd = Command::new(ffprobe_path);
+    cmd.arg("-v").arg("error")
+        .arg("-select_streams").arg("v:0")
+        .arg("-skip_frame").arg("nokey")
+        .arg("-show_entries").arg("frame=pts_time")
+        .arg("-of").arg("csv=p=0")
+        .arg("-read_intervals").arg(format!("{:.3}%{:.3}", from, start_time + at + 1.0))
+        .arg(input)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+
+    let output = tokio::time::timeout(Duration::from_secs(60), cmd.output()).await.ok()?.ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().trim_end_matches(',').parse::<f64>().ok())
+        .map(|t| t - start_time)
+        .filter(|t| *t <= at)
+        .reduce(f64::max)
+        .map(|t| t.max(0.0))
+}
+
+/// Container start time of a media file in seconds, read with ffprobe (0 if unknown)
+async fn probe_start_time(ffprobe_path: &PathBuf, input: &str) -> f64 {
+    let mut cmd = Command::new(ffprobe_path);
+    cmd.arg("-v").arg("error")
+        .arg("-show_entries").arg("format=start_time")
+        .arg("-of").arg("default=noprint_wrappers=1:nokey=1")
+        .arg(input)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+
+    match tokio::time::timeout(Duration::from_secs(60), cmd.output()).await {
+        Ok(Ok(output)) => String::from_utf8_lossy(&output.stdout).trim().parse::<f64>().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+/// Path for the trimmed copy of a recording, e.g. `Show.ts` -> `Show.trimmed.ts`
+fn trimmed_output_path(input: &Path) -> PathBuf {
+    let stem = input.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = input.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_else(|| "ts".to_string());
+
+    let mut candidate = input.with_file_name(format!("{}.trimmed.{}", stem, extension));
+    let mut n = 2;
+    while candidate.exists() {
+        candidate = input.with_file_name(format!("{}.trimmed{}.{}", stem, n, extension));
+        n += 1;
+    }
+    candidate
+}
+
+/// Container duration of a media file in seconds, read with ffprobe
+async fn probe_duration(ffprobe_path: &PathBuf, input: &str) -> Result<f64> {
+    let mut cmd = Command::new(ffprobe_path);
+    cmd.arg("-v").arg("error")
+        .arg("-show_entries").arg("format=duration")
+        .arg("-of").arg("default=noprint_wrappers=1:nokey=1")
+        .arg(input)
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped());
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+
+    let output = tokio::time::timeout(Duration::from_secs(60), cmd.output())
+        .await
+        .context("ffprobe timed out")?
+        .context("Failed to run ffprobe")?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.trim().parse::<f64>().map_err(|_| {
+        anyhow::anyhow!(
+            "ffprobe could not read a duration: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    })
+}
+
+/// How long ffprobe may spend on a stream before it is treated as dead
+const STREAM_PROBE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Parse a `num/den` frame rate as reported by ffprobe ("30000/1001")
+fn parse_frame_rate(value: &str) -> Option<f64> {
+    let (num, den) = value.split_once('/')?;
+    let num: f64 = num.parse().ok()?;
+    let den: f64 = den.parse().ok()?;
+    (den > 0.0 && num > 0.0).then(|| num / den)
+}
+
+/// Drop whole lines from the start of `log` until it fits in `max` bytes
+fn trim_log_front(log: &mut String, max: usize) {
+    if log.len() <= max {
+        return;
+    }
+    let cut = (log.len() - max..log.len()).find(|&i| log.is_char_boundary(i)).unwrap_or(log.len());
+    // Already at the start of a line if the byte before is a newline
+    let cut = if log.as_bytes()[cut - 1] == b'\n' {
+        cut
+    } else {
+        log[cut..].find('\n').map(|i| cut + i + 1).unwrap_or(log.len())
+    };
+    log.drain(..cut);
+}
+
+/// FFmpeg input options for a live stream: HLS start position, HTTP user agent,
+/// reconnects and timeouts from the DVR settings
+fn add_input_args(cmd: &mut Command, stream_url: &str, is_hls: bool, settings: &DvrSettings) {
+    if is_hls {
+        // Live edge skips the segments already in the playlist; some providers only
+        // list the program's segments and need the whole playlist captured
+        let start_index = if settings.hls_start_mode == "beginning" { "0" } else { "-1" };
+        cmd.arg("-live_start_index").arg(start_index);
+        cmd.arg("-http_persistent").arg("0");    // Don't reuse HTTP connections
+    }
+
+    if stream_url.starts_with("http://") || stream_url.starts_with("https://") {
+        // Some providers reject FFmpeg's own User-Agent with a 403
+        if !settings.recording_user_agent.is_empty() {
+            cmd.arg("-user_agent").arg(&settings.recording_user_agent);
+        }
+        // Ride out network blips instead of ending the recording
+        if settings.recording_reconnect {
+            cmd.arg("-reconnect").arg("1")
+                .arg("-reconnect_streamed").arg("1")
+                .arg("-reconnect_delay_max").arg("5");
+        }
+    }
+
+    let timeout_us = settings.recording_timeout_sec.max(1) as u64 * 1_000_000;
+    if is_hls {
+        // -timeout only covers the playlist request; this also bounds segment reads
+        cmd.arg("-rw_timeout").arg(timeout_us.to_string());
+    }
+    cmd.arg("-timeout").arg(timeout_us.to_string());  // Connection/read timeout (microseconds)
+}
+
+/// Run ffprobe on a stream and collect its format, video and audio details
+async fn probe_stream_info(ffprobe_path: &PathBuf, url: &str) -> Result<StreamProbe> {
+    let mut cmd = Command::new(ffprobe_path);
+    cmd.arg("-v").arg("error")
+        .arg("-print_format").arg("json")
+        .arg("-show_format")
+        .arg("-show_streams");
+    if url.contains("://") {
+        // Fail the open instead of waiting forever on a dead server (microseconds)
+        cmd.arg("-rw_timeout").arg("15000000");
+    }
+    cmd.arg(url)
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+
+    let output = tokio::time::timeout(STREAM_PROBE_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| anyhow::anyhow!("Stream did not respond within {}s", STREAM_PROBE_TIMEOUT.as_secs()))?
+        .context("Failed to run ffprobe")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("ffprobe failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).context("Invalid ffprobe output")?;
+
+    // ffprobe reports most numbers as strings
+    let number = |value: &serde_json::Value, key: &str| -> Option<u64> {
+        match &value[key] {
+            serde_json::Value::String(s) => s.parse().ok(),
+            v => v.as_u64(),
+        }
+    };
+    let text = |value: &serde_json::Value, key: &str| value[key].as_str().map(str::to_string);
+
+    let mut probe = StreamProbe {
+        format: text(&json["format"], "format_name"),
+        bitrate: number(&json["format"], "bit_rate"),
+        video: None,
+        audio: Vec::new(),
+    };
+
+    for stream in json["streams"].as_array().into_iter().flatten() {
+        let codec = text(stream, "codec_name").unwrap_or_else(|| "unknown".to_string());
+        match stream["codec_type"].as_str() {
+            Some("video") if probe.video.is_none() => {
+                probe.video = Some(VideoStreamInfo {
+                    codec,
+                    width: number(stream, "width").map(|w| w as u32),
+                    height: number(stream, "height").map(|h| h as u32),
+                    frame_rate: stream["avg_frame_rate"].as_str().and_then(parse_frame_rate)
+                        .or_else(|| stream["r_frame_rate"].as_str().and_then(parse_frame_rate)),
+                    bitrate: number(stream, "bit_rate"),
+                });
+            }
+            Some("audio") => probe.audio.push(AudioStreamInfo {
+                codec,
+                channels: number(stream, "channels").map(|c| c as u32),
+                sample_rate: number(stream, "sample_rate").map(|r| r as u32),
+                language: text(&stream["tags"], "language"),
+                bitrate: number(stream, "bit_rate"),
+            }),
+            _ => {}
+        }
+    }
+
+    Ok(probe)
+}
+
+/// Codec names of a file's video and audio streams, in stream order, read with ffprobe
+async fn probe_file_codecs(ffprobe_path: &PathBuf, input: &str) -> Option<Vec<String>> {
+    let mut cmd = Command::new(ffprobe_path);
+    cmd.arg("-v").arg("error")
+        .arg("-show_entries").arg("stream=codec_type,codec_name")
+        .arg("-of").arg("csv=p=0")
+        .arg(input)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+
+    let output = match tokio::time::timeout(Duration::from_secs(20), cmd.output()).await {
+        Ok(Ok(output)) if output.status.success() => output,
+        _ => return None,
+    };
+
+    // Lines look like "h264,video"; subtitle and data streams are skipped to match the FFmpeg probe
+    let codecs = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().split_once(','))
+        .filter(|(_, kind)| *kind == "video" || *kind == "audio")
+        .map(|(codec, _)| codec.to_string())
+        .collect();
+    Some(codecs)
+}
+
+/// Whether probed audio/video codecs all fit in mp4; None if no codecs were found
+fn mp4_compatible(codecs: &[String]) -> Option<bool> {
+    if codecs.is_empty() {
+        return None;
+    }
+
+    if let Some(codec) = codecs.iter().find(|c| !MP4_COMPATIBLE_CODECS.contains(&c.as_str())) {
+        debug!("Codec '{}' is not mp4 compatible", codec);
+        return Some(false);
+    }
+
+    Some(true)
+}
+
+/// Bitrate assumed when sizing parts for a stream that doesn't report one (typical HD IPTV)
+const ASSUMED_BITRATE_KBPS: u64 = 8000;
+
+/// Shortest part a size limit can produce, so a tiny limit doesn't create thousands of files
+const MIN_SEGMENT_SECS: u64 = 60;
+
+/// How long each probe may hold up the start of a recording; the programme is already airing
+const START_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Length of each part for split recordings, from the settings' duration and size limits
+/// and the stream's bitrate. None when recordings aren't split.
+fn segment_duration(settings: &DvrSettings, bitrate_kbps: Option<u64>) -> Option<u64> {
+    let by_time = (settings.segment_max_minutes > 0).then(|| settings.segment_max_minutes as u64 * 60);
+
+    // The segment muxer only splits on time, so turn the size limit into a duration at the stream's bitrate
+    let by_size = if settings.segment_max_gb > 0.0 {
+        let kbps = bitrate_kbps.unwrap_or_else(|| {
+            warn!("Stream bitrate unknown, sizing parts for {} kb/s", ASSUMED_BITRATE_KBPS);
+            ASSUMED_BITRATE_KBPS
+        });
+        let max_bits = settings.segment_max_gb * 1024.0 * 1024.0 * 1024.0 * 8.0;
+        Some(((max_bits / (kbps as f64 * 1000.0)) as u64).max(MIN_SEGMENT_SECS))
+    } else {
+        None
+    };
+
+    match (by_time, by_size) {
+        (Some(time), Some(size)) => Some(time.min(size)),
+        (time, size) => time.or(size),
+    }
+}
+
+/// Bytes written across every part of a recording (just `output_path` if it wasn't split)
+fn recorded_size(output_path: &PathBuf, part_files: &[PathBuf]) -> i64 {
+    let size = |path: &PathBuf| std::fs::metadata(path).map(|m| m.len() as i64).unwrap_or(0);
+    if part_files.is_empty() {
+        size(output_path)
+    } else {
+        part_files.iter().map(size).sum()
+    }
+}
+
+/// Filename of one part of a split recording, e.g. `Show.ts` -> `Show_part001.ts`
+fn segment_filename(filename: &str, part: &str) -> String {
+    match filename.rsplit_once('.') {
+        Some((stem, extension)) => format!("{}_part{}.{}", stem, part, extension),
+        None => format!("{}_part{}", filename, part),
+    }
+}
+
 /// Generate filename for recording
-fn generate_filename(schedule: &Schedule) -> String {
+fn generate_filename(schedule: &Schedule, extension: &str, template: &str) -> String {
+    if !template.trim().is_empty() {
+        match expand_filename_template(template, schedule) {
+            Some(name) => return finalize_filename(&name, extension),
+            None => warn!(
+                "Filename template '{}' produced no usable name for '{}', using default naming",
+                template, schedule.program_title
+            ),
+        }
+    }
+
     let timestamp = chrono::DateTime::from_timestamp(schedule.scheduled_start, 0)
         .map(|dt| dt.format("%Y-%m-%dT%H-%M-%S").to_string())
         .unwrap_or_else(|| "unknown".to_string());
 
-    // Sanitize for Windows
-    let sanitized_title: String = schedule
-        .program_title
-        .chars()
-        .map(|c| match c {
-            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
-            c => c,
-        })
-        .take(50)
-        .collect();
+    let sanitized_title = sanitize_filename_part(&schedule.program_title, 50);
+    let sanitized_channel = sanitize_filename_part(&schedule.channel_name, 30);
 
-    let sanitized_channel: String = schedule
-        .channel_name
+    finalize_filename(&format!("{}_{}_{}", timestamp, sanitized_channel, sanitized_title), extension)
+}
+
+/// Longest recording filename in bytes, extension included. Filesystems allow 255;
+/// the rest leaves room for `_part001` suffixes and temp names.
+const MAX_FILENAME_BYTES: usize = 200;
+
+/// Names Windows reserves for devices, with or without an extension
+const RESERVED_DEVICE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Replace characters that aren't allowed in filenames (Windows is the strictest)
+/// and turn tabs/newlines into spaces
+fn replace_reserved_chars(value: &str) -> String {
+    value
         .chars()
         .map(|c| match c {
             '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_whitespace() => ' ',
+            c if c.is_control() => '_',
             c => c,
         })
-        .take(30)
-        .collect();
+        .collect()
+}
+
+/// Collapse runs of spaces and trim spaces and dots from both ends
+/// (leading dots hide files on Unix, trailing ones are dropped by Windows)
+fn tidy_filename(value: &str) -> String {
+    value
+        .split(' ')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c| c == '.' || c == ' ')
+        .to_string()
+}
+
+/// Sanitize a title or channel name for use in a filename and cap the length
+fn sanitize_filename_part(value: &str, max_chars: usize) -> String {
+    let tidy = tidy_filename(&replace_reserved_chars(value));
+    tidy_filename(&tidy.chars().take(max_chars).collect::<String>())
+}
+
+/// Build the final `stem.extension`, safe on every platform: tidied, not a reserved
+/// device name, and at most MAX_FILENAME_BYTES long
+fn finalize_filename(stem: &str, extension: &str) -> String {
+    let mut stem = tidy_filename(&replace_reserved_chars(stem));
+
+    // "CON.ts" or "nul.backup.ts" can't be created on Windows
+    let base = stem.split('.').next().unwrap_or_default().trim_end();
+    if RESERVED_DEVICE_NAMES.iter().any(|name| name.eq_ignore_ascii_case(base)) {
+        stem.insert(0, '_');
+    }
+
+    let max_stem = MAX_FILENAME_BYTES.saturating_sub(extension.len() + 1);
+    if stem.len() > max_stem {
+        let mut cut = max_stem;
+        while !stem.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        stem = tidy_filename(&stem[..cut]);
+    }
+
+    if stem.is_empty() {
+        stem = "recording".to_string();
+    }
+    format!("{}.{}", stem, extension)
+}
+
+/// Tokens accepted in the `filename_template` setting
+const FILENAME_TEMPLATE_TOKENS: &[&str] = &["date", "time", "channel", "title", "season", "episode"];
+
+/// Check that a filename template only uses known tokens and can't expand to an empty name
+pub fn validate_filename_template(template: &str) -> std::result::Result<(), String> {
+    if template.trim().is_empty() {
+        // Empty means "use the default naming"
+        return Ok(());
+    }
+
+    let mut has_content = false;
+    let mut rest = template;
+    while let Some(open) = rest.find(['{', '}']) {
+        if rest[..open].trim().chars().any(|c| c.is_alphanumeric()) {
+            has_content = true;
+        }
+        if rest.as_bytes()[open] == b'}' {
+            return Err("unmatched '}'".to_string());
+        }
+        let after = &rest[open + 1..];
+        let close = after.find('}').ok_or_else(|| "unmatched '{'".to_string())?;
+        let token = &after[..close];
+        if !FILENAME_TEMPLATE_TOKENS.contains(&token) {
+            return Err(format!("unknown token '{{{}}}'", token));
+        }
+        // Date, time and channel are always present; the rest may expand to nothing
+        if matches!(token, "date" | "time" | "channel") {
+            has_content = true;
+        }
+        rest = &after[close + 1..];
+    }
+    if rest.chars().any(|c| c.is_alphanumeric()) {
+        has_content = true;
+    }
+
+    if !has_content {
+        return Err("template would produce an empty filename".to_string());
+    }
+    Ok(())
+}
+
+/// Expand a filename template for a schedule, or `None` if it's invalid or expands to nothing
+fn expand_filename_template(template: &str, schedule: &Schedule) -> Option<String> {
+    validate_filename_template(template).ok()?;
+
+    // UTC like the default naming, so names don't depend on the machine's zone
+    let start = chrono::DateTime::from_timestamp(schedule.scheduled_start, 0)?;
+    let (season, episode) = parse_season_episode(&schedule.program_title)
+        .map(|(s, e)| (format!("{:02}", s), format!("{:02}", e)))
+        .unwrap_or_default();
+
+    let mut name = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        name.push_str(&replace_reserved_chars(&rest[..open]));
+        let after = &rest[open + 1..];
+        let close = after.find('}')?;
+        let value = match &after[..close] {
+            "date" => start.format("%Y-%m-%d").to_string(),
+            "time" => start.format("%H-%M-%S").to_string(),
+            "channel" => sanitize_filename_part(&schedule.channel_name, 30),
+            "title" => sanitize_filename_part(&schedule.program_title, 50),
+            "season" => season.clone(),
+            "episode" => episode.clone(),
+            _ => return None,
+        };
+        name.push_str(&value);
+        rest = &after[close + 1..];
+    }
+    name.push_str(&replace_reserved_chars(rest));
+
+    // Tidying and the length cap happen in finalize_filename
+    if name.chars().any(|c| c.is_alphanumeric()) {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// Pull season/episode numbers out of titles like "Show S02E05" or "Show 2x05"
+fn parse_season_episode(title: &str) -> Option<(u32, u32)> {
+    let upper = title.to_uppercase();
+    let bytes = upper.as_bytes();
+
+    for i in 0..bytes.len() {
+        let at_word_start = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        if !at_word_start {
+            continue;
+        }
+
+        let (season_start, separator) = if bytes[i] == b'S' {
+            (i + 1, b'E')
+        } else if bytes[i].is_ascii_digit() {
+            (i, b'X')
+        } else {
+            continue;
+        };
+
+        let season_end = season_start
+            + bytes[season_start..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if season_end == season_start || season_end >= bytes.len() || bytes[season_end] != separator {
+            continue;
+        }
+        let episode_start = season_end + 1;
+        let episode_end = episode_start
+            + bytes[episode_start..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if episode_end == episode_start {
+            continue;
+        }
+
+        let season = upper[season_start..season_end].parse().ok()?;
+        let episode = upper[episode_start..episode_end].parse().ok()?;
+        return Some((season, episode));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(title: &str, channel: &str) -> Schedule {
+        Schedule {
+            channel_name: channel.to_string(),
+            program_title: title.to_string(),
+            ..Schedule::test_default()
+        }
+    }
+
+    #[test]
+    fn test_slot_cancel_reaches_task_subscribing_later() {
+        // Preemption can signal a slot before its recording task subscribes
+        let slot = SourceSlot::new(0);
+        slot.cancel_tx.send_replace(true);
+        let cancel_rx = slot.cancel_tx.subscribe();
+        assert!(*cancel_rx.borrow());
+    }
+
+    #[test]
+    fn test_segment_duration_from_time_and_size_limits() {
+        let mut settings = DvrSettings::default();
+        assert_eq!(segment_duration(&settings, Some(8_000)), None);
 
-    format!("{}_{}_{}.ts", timestamp, sanitized_channel, sanitized_title)
+        settings.segment_max_minutes = 30;
+        assert_eq!(segment_duration(&settings, None), Some(1_800));
+
+        // 1 GiB at 8 Mb/s is about 18 minutes, shorter than the time limit
+        settings.segment_max_gb = 1.0;
+        assert_eq!(segment_duration(&settings, Some(8_000)), Some(1_073));
+        // Unknown bitrate sizes parts for the assumed one
+        assert_eq!(segment_duration(&settings, None), Some(1_073));
+
+        settings.segment_max_gb = 0.001;
+        assert_eq!(segment_duration(&settings, Some(8_000)), Some(MIN_SEGMENT_SECS));
+    }
+
+    #[test]
+    fn test_mp4_compatible_unknown_without_codecs() {
+        let codecs = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(mp4_compatible(&codecs(&["h264", "aac"])), Some(true));
+        assert_eq!(mp4_compatible(&codecs(&["h264", "pcm_s16be"])), Some(false));
+        // A probe that found nothing says nothing about the stream
+        assert_eq!(mp4_compatible(&[]), None);
+    }
+
+    #[test]
+    fn test_trim_log_front_keeps_whole_lines() {
+        let mut log = "line1\nline2\nline3\n".to_string();
+        trim_log_front(&mut log, 100);
+        assert_eq!(log, "line1\nline2\nline3\n");
+
+        trim_log_front(&mut log, 8);
+        assert_eq!(log, "line3\n");
+
+        // A single line longer than the limit goes entirely
+        let mut log = "abcdef".to_string();
+        trim_log_front(&mut log, 3);
+        assert_eq!(log, "");
+    }
+
+    #[test]
+    fn test_trim_log_front_cuts_on_char_boundary() {
+        // The byte limit lands inside a two-byte "é"
+        let mut log = "ééé\nabc\n".to_string();
+        trim_log_front(&mut log, 10);
+        assert_eq!(log, "abc\n");
+
+        let mut log = "a\n日本語\n".to_string();
+        trim_log_front(&mut log, 10);
+        assert_eq!(log, "日本語\n");
+    }
+
+    #[test]
+    fn test_sanitize_slashes_and_emoji() {
+        assert_eq!(sanitize_filename_part("AC/DC: Live \u{1F3B8}", 50), "AC_DC_ Live \u{1F3B8}");
+        assert_eq!(sanitize_filename_part("News\\Weather | 24/7?", 50), "News_Weather _ 24_7_");
+    }
+
+    #[test]
+    fn test_sanitize_trims_dots_and_collapses_whitespace() {
+        assert_eq!(sanitize_filename_part("..hidden show..", 50), "hidden show");
+        assert_eq!(sanitize_filename_part("  The \t Late\n\nShow  ", 50), "The Late Show");
+        // Cutting at the cap mustn't leave a trailing space or dot
+        assert_eq!(sanitize_filename_part("Ends here. More", 10), "Ends here");
+    }
+
+    #[test]
+    fn test_finalize_avoids_reserved_device_names() {
+        assert_eq!(finalize_filename("CON", "ts"), "_CON.ts");
+        assert_eq!(finalize_filename("nul.backup", "mkv"), "_nul.backup.mkv");
+        assert_eq!(finalize_filename("Console", "ts"), "Console.ts");
+        assert_eq!(finalize_filename("...", "ts"), "recording.ts");
+    }
+
+    #[test]
+    fn test_finalize_caps_length_on_char_boundary() {
+        let stem = "\u{1F4FA}".repeat(100); // 4 bytes each
+        let name = finalize_filename(&stem, "mp4");
+        assert!(name.len() <= MAX_FILENAME_BYTES);
+        assert!(name.ends_with(".mp4"));
+        assert!(name.trim_end_matches(".mp4").chars().all(|c| c == '\u{1F4FA}'));
+    }
+
+    #[test]
+    fn test_generate_filename_with_tricky_title() {
+        let s = schedule(".Bonus/Extras: \u{1F389}  Special ", "CH 4 / HD");
+        let name = generate_filename(&s, "ts", "");
+        assert_eq!(name, "2026-01-01T00-00-00_CH 4 _ HD_Bonus_Extras_ \u{1F389} Special.ts");
+        assert!(!name.contains('/'));
+
+        let templated = generate_filename(&s, "mkv", "{title} - {channel}");
+        assert_eq!(templated, "Bonus_Extras_ \u{1F389} Special - CH 4 _ HD.mkv");
+
+        // Dates and times are UTC, matching the default naming
+        let dated = generate_filename(&s, "ts", "{date} {time} {channel}");
+        assert_eq!(dated, "2026-01-01 00-00-00 CH 4 _ HD.ts");
+    }
+
+    #[test]
+    fn test_joined_filename_drops_part_suffix() {
+        assert_eq!(joined_filename("Show_part001.ts"), "Show.ts");
+        assert_eq!(joined_filename("Show_party.mkv"), "Show_party.mkv");
+    }
+
+    #[tokio::test]
+    async fn test_copy_with_progress_refuses_existing_destination() {
+        let dir = std::env::temp_dir().join(format!("ynotv-export-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (from, to) = (dir.join("recording.ts"), dir.join("export.ts"));
+        std::fs::write(&from, b"recording").unwrap();
+        std::fs::write(&to, b"keep me").unwrap();
+
+        assert!(copy_with_progress(&from, &to, |_, _| {}).await.is_err());
+        assert_eq!(std::fs::read(&to).unwrap(), b"keep me");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_migration_never_overwrites_a_taken_name() {
+        let dir = std::env::temp_dir().join(format!("ynotv-migrate-{}", std::process::id()));
+        let (old_dir, new_dir) = (dir.join("old"), dir.join("new"));
+        std::fs::create_dir_all(&old_dir).unwrap();
+        std::fs::create_dir_all(&new_dir).unwrap();
+        let from = old_dir.join("News.ts");
+        std::fs::write(&from, b"recording").unwrap();
+        std::fs::write(new_dir.join("News.ts"), b"keep me").unwrap();
+        std::fs::write(new_dir.join("News_1.ts"), b"keep me too").unwrap();
+
+        assert!(move_file(&from, &new_dir.join("News.ts")).await.is_err());
+        assert!(from.exists());
+
+        let target = migrated_path(&from, &old_dir, &new_dir);
+        assert_eq!(target, new_dir.join("News_2.ts"));
+        move_file(&from, &target).await.unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"recording");
+        assert_eq!(std::fs::read(new_dir.join("News.ts")).unwrap(), b"keep me");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
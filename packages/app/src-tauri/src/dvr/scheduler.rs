@@ -4,12 +4,14 @@
 //! Uses tokio-cron-scheduler for efficient job scheduling.
 
 use std::sync::Arc;
+use chrono::{Duration, TimeZone};
+use tauri::Emitter;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{error, info, warn};
 
 use crate::dvr::database::DvrDatabase;
-use crate::dvr::models::{Schedule, ScheduleStatus};
-use crate::dvr::recorder::RecordingManager;
+use crate::dvr::models::{Schedule, ScheduleRequest, SeriesRule};
+use crate::dvr::recorder::{RecordingManager, StartOutcome};
 
 /// Window in seconds to look ahead for recordings
 const SCHEDULING_WINDOW_SECONDS: i64 = 60;
@@ -20,8 +22,12 @@ const MISSED_RECORDING_GRACE_SECONDS: i64 = 300;
 /// Poll interval in seconds
 const POLL_INTERVAL_SECONDS: u32 = 30;
 
+/// Interval for scanning the EPG for new series airings (15 minutes)
+const SERIES_SCAN_INTERVAL_SECONDS: u64 = 900;
+
 /// Manages the recording schedule
 pub struct Scheduler {
+    app_handle: tauri::AppHandle,
     db: Arc<DvrDatabase>,
     recorder: Arc<RecordingManager>,
     scheduler: Option<JobScheduler>,
@@ -36,8 +42,9 @@ unsafe impl Sync for Scheduler {}
 
 impl Scheduler {
     /// Create a new scheduler
-    pub fn new(db: Arc<DvrDatabase>, recorder: Arc<RecordingManager>) -> Self {
+    pub fn new(app_handle: &tauri::AppHandle, db: Arc<DvrDatabase>, recorder: Arc<RecordingManager>) -> Self {
         Self {
+            app_handle: app_handle.clone(),
             db,
             recorder,
             scheduler: None,
@@ -58,16 +65,18 @@ impl Scheduler {
         let sched = JobScheduler::new().await?;
 
         // Add polling job
+        let app_handle = self.app_handle.clone();
         let db = self.db.clone();
         let recorder = self.recorder.clone();
 
         let job = Job::new_repeated_async(
             std::time::Duration::from_secs(POLL_INTERVAL_SECONDS as u64),
             move |_uuid, _l| {
+                let app_handle = app_handle.clone();
                 let db = db.clone();
                 let recorder = recorder.clone();
                 Box::pin(async move {
-                    if let Err(e) = poll_schedules(&db, &recorder).await {
+                    if let Err(e) = poll_schedules(&app_handle, &db, &recorder).await {
                         error!("Error polling schedules: {}", e);
                     }
                 })
@@ -76,8 +85,30 @@ impl Scheduler {
 
         sched.add(job).await?;
 
+        // Add series scan job
+        let db = self.db.clone();
+
+        let series_job = Job::new_repeated_async(
+            std::time::Duration::from_secs(SERIES_SCAN_INTERVAL_SECONDS),
+            move |_uuid, _l| {
+                let db = db.clone();
+                Box::pin(async move {
+                    if let Err(e) = scan_series_rules(&db) {
+                        error!("Error scanning series rules: {}", e);
+                    }
+                })
+            },
+        )?;
+
+        sched.add(series_job).await?;
+
+        // Pick up new series airings before the first poll
+        if let Err(e) = scan_series_rules(&self.db) {
+            error!("Error in initial series scan: {}", e);
+        }
+
         // Run initial poll immediately
-        if let Err(e) = poll_schedules(&self.db, &self.recorder).await {
+        if let Err(e) = poll_schedules(&self.app_handle, &self.db, &self.recorder).await {
             error!("Error in initial poll: {}", e);
         }
 
@@ -110,8 +141,116 @@ impl Scheduler {
     }
 }
 
+/// Scan all series rules and schedule any new airings found in the EPG
+pub fn scan_series_rules(db: &Arc<DvrDatabase>) -> anyhow::Result<usize> {
+    let rules = db.get_series_rules()?;
+    let mut created = 0;
+
+    for rule in &rules {
+        match schedule_series_airings(db, rule) {
+            Ok(ids) => created += ids.len(),
+            Err(e) => warn!("Series scan failed for '{}': {}", rule.series_title, e),
+        }
+    }
+
+    if created > 0 {
+        info!("Series scan created {} new schedule(s)", created);
+    }
+
+    Ok(created)
+}
+
+/// Create schedules for upcoming airings matching a series rule
+///
+/// Airings that are already scheduled are skipped, as are airings that would
+/// exceed the source's connection limit. Returns the IDs of new schedules.
+pub fn schedule_series_airings(db: &Arc<DvrDatabase>, rule: &SeriesRule) -> anyhow::Result<Vec<i64>> {
+    let now = chrono::Utc::now().timestamp();
+    let airings = db.find_series_airings(&rule.source_id, &rule.channel_id, &rule.series_title, now)?;
+
+    let mut created = Vec::new();
+    for (title, start, end) in airings {
+        if db.schedule_exists(&rule.source_id, &rule.channel_id, start)? {
+            continue;
+        }
+
+        let request = ScheduleRequest {
+            source_id: rule.source_id.clone(),
+            channel_id: rule.channel_id.clone(),
+            channel_name: rule.channel_name.clone(),
+            program_title: title,
+            scheduled_start: start,
+            scheduled_end: end,
+            start_padding_sec: rule.start_padding_sec,
+            end_padding_sec: rule.end_padding_sec,
+            series_match_title: Some(rule.series_title.clone()),
+            recurrence: None,
+            stream_url: None,
+            auto_extend_minutes: 0,
+            priority: 0,
+        };
+        if let Some(max_conn) = db.connection_limit_reached(&request)? {
+            warn!(
+                "Skipping series airing '{}' at {}: connection limit ({} max) reached",
+                request.program_title, start, max_conn
+            );
+            continue;
+        }
+        created.push(db.add_schedule(&request)?);
+    }
+
+    Ok(created)
+}
+
+/// Start and end of the next occurrence of a recurring schedule
+///
+/// Steps the wall-clock time in `timezone` (IANA name, empty = system zone) so a
+/// daily 20:00 recording stays at 20:00 local across DST changes. Returns None
+/// if the schedule doesn't recur or the recurrence is unknown.
+pub fn next_recurrence(schedule: &Schedule, timezone: &str) -> Option<(i64, i64)> {
+    let days = match schedule.recurrence.as_deref().map(str::to_lowercase).as_deref() {
+        Some("daily") => 1,
+        Some("weekly") => 7,
+        Some(other) => {
+            warn!("Unknown recurrence '{}' on schedule {}", other, schedule.id);
+            return None;
+        }
+        None => return None,
+    };
+
+    let next_start = if timezone.is_empty() {
+        add_local_days(&chrono::Local, schedule.scheduled_start, days)?
+    } else {
+        match timezone.parse::<chrono_tz::Tz>() {
+            Ok(tz) => add_local_days(&tz, schedule.scheduled_start, days)?,
+            Err(_) => {
+                warn!("Unknown timezone '{}', using the system zone", timezone);
+                add_local_days(&chrono::Local, schedule.scheduled_start, days)?
+            }
+        }
+    };
+
+    let length = schedule.scheduled_end - schedule.scheduled_start;
+    Some((next_start, next_start + length))
+}
+
+/// Add whole days to a timestamp in local time of `tz`
+///
+/// A local time skipped by a spring-forward jump is moved past the gap; an
+/// ambiguous one (fall-back) resolves to the earlier instant.
+fn add_local_days<Tz: TimeZone>(tz: &Tz, timestamp: i64, days: i64) -> Option<i64> {
+    let local = tz.timestamp_opt(timestamp, 0).single()?.naive_local();
+    let next = local.checked_add_signed(Duration::days(days))?;
+
+    tz.from_local_datetime(&next)
+        .earliest()
+        .or_else(|| tz.from_local_datetime(&(next + Duration::hours(1))).earliest())
+        .map(|dt| dt.timestamp())
+}
+
 /// Poll for schedules that should start recording
 async fn poll_schedules(
+    app_handle: &tauri::AppHandle,
     db: &Arc<DvrDatabase>,
     recorder: &Arc<RecordingManager>,
 ) -> anyhow::Result<()> {
@@ -122,6 +261,14 @@ async fn poll_schedules(
     let count = db.count_scheduled()?;
     println!("[DVR Scheduler] Found {} scheduled recordings", count);
 
+    // Once per poll, so the UI can tell the scheduler is alive and show the next start
+    let next_start = if count > 0 { db.next_scheduled_start(now)? } else { None };
+    let _ = app_handle.emit("dvr:heartbeat", serde_json::json!({
+        "timestamp": now,
+        "scheduled_count": count,
+        "next_start": next_start,
+    }));
+
     if count == 0 {
         return Ok(());
     }
@@ -154,23 +301,23 @@ async fn poll_schedules(
         schedules.len()
     );
 
-    // Start each recording
+    // Start each recording (or queue it behind the source's connection limit),
+    // highest priority first so it gets the free connections
+    let mut schedules = schedules;
+    schedules.sort_by_key(|s| std::cmp::Reverse(s.priority));
     for schedule in schedules {
         println!("[DVR Scheduler] About to start recording ID {}: {}", schedule.id, schedule.program_title);
-        if let Err(e) = start_recording(db, recorder, schedule).await {
+        if let Err(e) = start_recording(recorder, schedule) {
             error!("Failed to start recording: {}", e);
             println!("[DVR Scheduler] ERROR: Failed to start recording: {}", e);
-        } else {
-            println!("[DVR Scheduler] Recording started successfully");
         }
     }
 
     Ok(())
 }
 
-/// Start a single recording
-async fn start_recording(
-    db: &Arc<DvrDatabase>,
+/// Hand a single due schedule to the recorder
+fn start_recording(
     recorder: &Arc<RecordingManager>,
     schedule: Schedule,
 ) -> anyhow::Result<()> {
@@ -180,29 +327,146 @@ async fn start_recording(
         schedule.program_title, schedule.channel_name, schedule.channel_id
     );
 
-    // Update status to recording
-    println!("[DVR Scheduler] Updating schedule status to Recording...");
-    db.update_schedule_status(schedule.id, ScheduleStatus::Recording)?;
-    println!("[DVR Scheduler] Status updated, spawning recording task...");
-
-    // Spawn recording task
-    let db = db.clone();
-    let recorder = recorder.clone();
+    match recorder.start_or_queue(schedule)? {
+        StartOutcome::Started => println!("[DVR Scheduler] Recording started successfully"),
+        StartOutcome::Queued => println!("[DVR Scheduler] Source at its connection limit, recording queued"),
+        StartOutcome::AlreadyPending => {}
+    }
 
-    tokio::spawn(async move {
-        println!("[DVR Scheduler] Recording task spawned for ID {}: {}", schedule.id, schedule.program_title);
-        if let Err(e) = recorder.record(schedule.clone()).await {
-            error!("Recording failed for {}: {}", schedule.program_title, e);
-            println!("[DVR Scheduler] ERROR: Recording failed for {}: {}", schedule.program_title, e);
+    Ok(())
+}
 
-            // Update status to failed
-            if let Err(e) = db.update_schedule_status(schedule.id, ScheduleStatus::Failed) {
-                error!("Failed to update schedule status: {}", e);
-            }
-        } else {
-            println!("[DVR Scheduler] Recording completed successfully for ID {}", schedule.id);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::New_York;
+
+    fn schedule(start: i64, end: i64, recurrence: &str) -> Schedule {
+        Schedule {
+            scheduled_start: start,
+            scheduled_end: end,
+            recurrence: Some(recurrence.to_string()),
+            ..Schedule::test_default()
         }
-    });
+    }
 
-    Ok(())
+    fn new_york(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+        New_York.with_ymd_and_hms(y, m, d, h, min, 0).unwrap().timestamp()
+    }
+
+    #[test]
+    fn test_daily_keeps_local_time_across_spring_forward() {
+        // DST starts 2026-03-08 02:00 in New York, so that day is 23 hours long
+        let start = new_york(2026, 3, 7, 20, 0);
+        let s = schedule(start, start + 3600, "daily");
+
+        let (next_start, next_end) = next_recurrence(&s, "America/New_York").unwrap();
+        assert_eq!(next_start, new_york(2026, 3, 8, 20, 0));
+        assert_eq!(next_start - start, 23 * 3600);
+        assert_eq!(next_end - next_start, 3600);
+    }
+
+    #[test]
+    fn test_daily_skips_past_spring_forward_gap() {
+        // 02:30 doesn't exist on 2026-03-08; the recording moves to 03:30 EDT
+        let start = new_york(2026, 3, 7, 2, 30);
+        let s = schedule(start, start + 1800, "daily");
+
+        let (next_start, _) = next_recurrence(&s, "America/New_York").unwrap();
+        assert_eq!(next_start, new_york(2026, 3, 8, 3, 30));
+    }
+
+    #[test]
+    fn test_weekly_and_unknown_recurrence() {
+        let start = new_york(2026, 3, 4, 21, 0);
+        let s = schedule(start, start + 3600, "weekly");
+        let (next_start, _) = next_recurrence(&s, "America/New_York").unwrap();
+        assert_eq!(next_start, new_york(2026, 3, 11, 21, 0));
+
+        assert!(next_recurrence(&schedule(start, start + 3600, "hourly"), "UTC").is_none());
+    }
+
+    /// Database with the frontend tables the series scheduler reads, and one airing of
+    /// "Evening News" on channel 101 tomorrow at 20:00 UTC
+    fn series_database() -> (Arc<DvrDatabase>, SeriesRule, i64) {
+        let db = crate::dvr::database::test_database();
+        let start = (chrono::Utc::now().date_naive() + Duration::days(1))
+            .and_hms_opt(20, 0, 0)
+            .unwrap()
+            .and_utc();
+        db.get_conn()
+            .unwrap()
+            .execute_batch(&format!(
+                "CREATE TABLE sourcesMeta (source_id TEXT PRIMARY KEY, max_connections TEXT);
+                 CREATE TABLE channels (stream_id TEXT PRIMARY KEY);
+                 CREATE TABLE programs (source_id TEXT, stream_id TEXT, title TEXT, start TEXT, end TEXT);
+                 INSERT INTO sourcesMeta (source_id) VALUES ('source');
+                 INSERT INTO channels VALUES ('101');
+                 INSERT INTO programs VALUES ('source', '101', 'Evening News', '{}', '{}');",
+                start.to_rfc3339(),
+                (start + Duration::hours(1)).to_rfc3339()
+            ))
+            .unwrap();
+
+        let rule = SeriesRule {
+            id: 0,
+            source_id: "source".to_string(),
+            channel_id: "101".to_string(),
+            channel_name: "News One".to_string(),
+            series_title: "Evening News".to_string(),
+            start_padding_sec: None,
+            end_padding_sec: None,
+        };
+        (Arc::new(db), rule, start.timestamp())
+    }
+
+    #[test]
+    fn test_series_airings_use_source_padding() {
+        let (db, rule, _) = series_database();
+        db.save_setting("default_start_padding_sec", "60").unwrap();
+        db.save_setting("default_end_padding_sec", "300").unwrap();
+        db.set_source_padding("source", Some(120), Some(900)).unwrap();
+
+        let ids = schedule_series_airings(&db, &rule).unwrap();
+        assert_eq!(ids.len(), 1);
+        let schedule = db.get_schedule(ids[0]).unwrap().unwrap();
+        assert_eq!((schedule.start_padding_sec, schedule.end_padding_sec), (120, 900));
+    }
+
+    #[test]
+    fn test_series_airings_treat_zero_max_connections_as_one() {
+        let (db, rule, _) = series_database();
+        db.get_conn().unwrap().execute("UPDATE sourcesMeta SET max_connections = '0'", []).unwrap();
+
+        assert_eq!(schedule_series_airings(&db, &rule).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_series_airings_conflict_on_padded_window() {
+        let (db, rule, start) = series_database();
+        db.get_conn().unwrap().execute("UPDATE sourcesMeta SET max_connections = '1'", []).unwrap();
+
+        // Ends two minutes before the airing, but its 5 minute end padding runs into it
+        let earlier = ScheduleRequest {
+            source_id: "source".to_string(),
+            channel_id: "101".to_string(),
+            channel_name: "News One".to_string(),
+            program_title: "Quiz".to_string(),
+            scheduled_start: start - 3720,
+            scheduled_end: start - 120,
+            start_padding_sec: Some(0),
+            end_padding_sec: Some(300),
+            series_match_title: None,
+            recurrence: None,
+            stream_url: None,
+            auto_extend_minutes: 0,
+            priority: 0,
+        };
+        db.add_schedule(&earlier).unwrap();
+        assert!(schedule_series_airings(&db, &rule).unwrap().is_empty());
+
+        db.get_conn().unwrap().execute("UPDATE dvr_schedules SET end_padding_sec = 0", []).unwrap();
+        let rule = SeriesRule { start_padding_sec: Some(0), ..rule };
+        assert_eq!(schedule_series_airings(&db, &rule).unwrap().len(), 1);
+    }
 }
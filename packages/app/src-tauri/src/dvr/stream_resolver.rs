@@ -4,6 +4,10 @@
 //! to handle token expiration. Uses stored direct_url as fallback for M3U sources.
 
 use anyhow::Result;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 use rusqlite::{Row, OptionalExtension};
 
@@ -20,6 +24,61 @@ struct SourceConfig {
     mac: Option<String>,
 }
 
+/// How long a Stalker URL resolved by the frontend (and the portal token it carries) is reused
+const STALKER_TOKEN_TTL: Duration = Duration::from_secs(300);
+
+/// A resolved Stalker play URL and when it stops being trusted
+struct CachedStalkerUrl {
+    url: String,
+    expires_at: Instant,
+}
+
+/// Resolved Stalker URLs keyed by (source_id, channel_id).
+/// The portal token is per source, so invalidation drops every entry of a source.
+static STALKER_TOKEN_CACHE: Lazy<Mutex<HashMap<(String, String), CachedStalkerUrl>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Get a still-valid resolved Stalker URL for a channel, if one is cached
+pub fn cached_stalker_url(source_id: &str, channel_id: &str) -> Option<String> {
+    let mut cache = STALKER_TOKEN_CACHE.lock();
+    let key = (source_id.to_string(), channel_id.to_string());
+    match cache.get(&key) {
+        Some(entry) if entry.expires_at > Instant::now() => Some(entry.url.clone()),
+        Some(_) => {
+            cache.remove(&key);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Remember a freshly resolved Stalker URL for `STALKER_TOKEN_TTL`
+pub fn cache_stalker_url(source_id: &str, channel_id: &str, url: &str) {
+    STALKER_TOKEN_CACHE.lock().insert(
+        (source_id.to_string(), channel_id.to_string()),
+        CachedStalkerUrl {
+            url: url.to_string(),
+            expires_at: Instant::now() + STALKER_TOKEN_TTL,
+        },
+    );
+}
+
+/// Forget every cached URL of a source, e.g. after the portal rejected its token
+pub fn invalidate_stalker_tokens(source_id: &str) {
+    let mut cache = STALKER_TOKEN_CACHE.lock();
+    let before = cache.len();
+    cache.retain(|(source, _), _| source != source_id);
+    if cache.len() != before {
+        info!("Invalidated {} cached Stalker URL(s) for source {}", before - cache.len(), source_id);
+    }
+}
+
+/// Whether an FFmpeg/HTTP error message indicates the stream token was rejected
+pub fn is_auth_failure(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("401 unauthorized") || message.contains("403 forbidden")
+}
+
 /// Resolve a stream URL for a schedule
 /// 
 /// If the schedule has a pre-resolved stream_url (e.g., from frontend for Stalker sources),
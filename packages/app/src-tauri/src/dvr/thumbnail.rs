@@ -128,6 +128,177 @@ pub async fn generate_thumbnail(
     }
 }
 
+/// Width of each frame in a thumbnail sprite
+const SPRITE_TILE_WIDTH: u32 = 160;
+/// Height of each frame in a thumbnail sprite
+const SPRITE_TILE_HEIGHT: u32 = 90;
+/// Maximum number of frames per sprite row
+const SPRITE_MAX_COLUMNS: u32 = 5;
+
+/// Generate a filmstrip sprite sheet for scrub bar previews
+///
+/// Extracts `count` evenly-spaced frames from the video, tiles them into a
+/// single JPEG and writes a WebVTT index (`.vtt` next to the sprite) that maps
+/// each time range to its tile using `#xywh=` fragments.
+///
+/// # Returns
+/// * `Ok(Some(PathBuf))` - Path to the generated sprite image
+/// * `Ok(None)` - Sprite generation failed but not critically
+/// * `Err` - Critical error occurred
+pub async fn generate_thumbnail_sprite(
+    video_path: &str,
+    recording_id: i64,
+    count: u32,
+    storage_path: &str,
+) -> Result<Option<PathBuf>> {
+    let video_path = Path::new(video_path);
+
+    if count == 0 || !video_path.exists() {
+        warn!("Cannot generate sprite - video file not found: {:?}", video_path);
+        return Ok(None);
+    }
+
+    let ffmpeg_path = find_ffmpeg().await?;
+
+    let duration = match probe_duration(&ffmpeg_path, video_path).await {
+        Some(d) if d > 0.0 => d,
+        _ => {
+            warn!("Cannot generate sprite - unknown duration for {:?}", video_path);
+            return Ok(None);
+        }
+    };
+
+    let thumbnails_dir = Path::new(storage_path).join(".thumbnails");
+    tokio::fs::create_dir_all(&thumbnails_dir)
+        .await
+        .context("Failed to create thumbnails directory")?;
+
+    let sprite_path = thumbnails_dir.join(format!("{}_sprite.jpg", recording_id));
+    let vtt_path = sprite_path.with_extension("vtt");
+
+    let columns = count.min(SPRITE_MAX_COLUMNS);
+    let rows = count.div_ceil(columns);
+    let interval = duration / count as f64;
+
+    info!(
+        "Generating {}-frame sprite for recording {} ({:.0}s, every {:.1}s)",
+        count, recording_id, duration, interval
+    );
+
+    // Only decode keyframes - much faster on long recordings and good enough for previews
+    let filter = format!(
+        "fps=1/{:.3},scale={}:{},tile={}x{}",
+        interval, SPRITE_TILE_WIDTH, SPRITE_TILE_HEIGHT, columns, rows
+    );
+
+    let mut cmd = Command::new(&ffmpeg_path);
+    cmd.arg("-skip_frame")
+        .arg("nokey")
+        .arg("-i")
+        .arg(video_path)
+        .arg("-vf")
+        .arg(&filter)
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-q:v")
+        .arg("4")
+        .arg("-y")
+        .arg(&sprite_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Hide console window on Windows (CREATE_NO_WINDOW = 0x08000000)
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+
+    let output = timeout(Duration::from_secs(300), cmd.output())
+        .await
+        .context("Sprite generation timed out")?;
+
+    match output {
+        Ok(result) if result.status.success() && sprite_path.exists() => {}
+        Ok(result) => {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            error!("FFmpeg failed to generate sprite: {}", stderr);
+            return Ok(None);
+        }
+        Err(e) => {
+            error!("Failed to execute FFmpeg for sprite: {}", e);
+            return Ok(None);
+        }
+    }
+
+    // Write the WebVTT index
+    let sprite_name = sprite_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut vtt = String::from("WEBVTT\n");
+    for i in 0..count {
+        let start = i as f64 * interval;
+        let end = start + interval;
+        let x = (i % columns) * SPRITE_TILE_WIDTH;
+        let y = (i / columns) * SPRITE_TILE_HEIGHT;
+        vtt.push_str(&format!(
+            "\n{} --> {}\n{}#xywh={},{},{},{}\n",
+            format_vtt_time(start),
+            format_vtt_time(end),
+            sprite_name,
+            x,
+            y,
+            SPRITE_TILE_WIDTH,
+            SPRITE_TILE_HEIGHT
+        ));
+    }
+    tokio::fs::write(&vtt_path, vtt)
+        .await
+        .context("Failed to write sprite index")?;
+
+    info!("Sprite generated successfully: {:?}", sprite_path);
+    Ok(Some(sprite_path))
+}
+
+/// Read a video's duration in seconds from FFmpeg's input summary
+async fn probe_duration(ffmpeg_path: &Path, video_path: &Path) -> Option<f64> {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner")
+        .arg("-i")
+        .arg(video_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+
+    // ffmpeg without an output exits with an error after printing the summary
+    let output = timeout(Duration::from_secs(30), cmd.output()).await.ok()?.ok()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    // "  Duration: 01:02:03.45, start: ..."
+    let line = stderr.lines().find(|l| l.trim_start().starts_with("Duration:"))?;
+    let value = line.trim_start().trim_start_matches("Duration:").trim();
+    let value = value.split(',').next()?;
+
+    let mut parts = value.split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Format seconds as a WebVTT timestamp (HH:MM:SS.mmm)
+fn format_vtt_time(seconds: f64) -> String {
+    let total_ms = (seconds * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        total_ms / 3_600_000,
+        (total_ms / 60_000) % 60,
+        (total_ms / 1000) % 60,
+        total_ms % 1000
+    )
+}
+
 /// Find FFmpeg binary
 ///
 /// Searches for FFmpeg in the following order:
@@ -0,0 +1,250 @@
+//! Post-recording transcoding
+//!
+//! Recordings are captured with `-c copy`; when a transcode profile is set in
+//! the DVR settings, a second FFmpeg pass re-encodes the finished file to save space.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tracing::{debug, info};
+
+/// Transcode profiles accepted by the `transcode_profile` setting
+pub const TRANSCODE_PROFILES: &[&str] = &["none", "h264_720p", "h265_1080p"];
+
+/// Encodes are CPU heavy, so recordings finishing together are transcoded one at a time
+static TRANSCODE_SLOTS: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(1));
+
+/// Video encoder arguments and maximum output height for a profile, None for "none"/unknown
+fn profile_video_args(profile: &str) -> Option<(&'static [&'static str], u32)> {
+    match profile {
+        "h264_720p" => Some((&["-c:v", "libx264", "-preset", "veryfast", "-crf", "23"], 720)),
+        "h265_1080p" => Some((&["-c:v", "libx265", "-preset", "fast", "-crf", "26"], 1080)),
+        _ => None,
+    }
+}
+
+/// Whether `profile` re-encodes recordings
+pub fn is_enabled(profile: &str) -> bool {
+    profile_video_args(profile).is_some()
+}
+
+/// Parse an FFmpeg `HH:MM:SS.xx` timestamp into seconds
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut parts = value.trim().splitn(3, ':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Value of a `key=value` token on an FFmpeg stats line, e.g. `time=00:01:02.50`.
+/// FFmpeg pads values with spaces (`size=    1024kB`), so leading whitespace is skipped.
+fn stats_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let start = line.find(&format!("{}=", key))? + key.len() + 1;
+    let rest = line[start..].trim_start();
+    Some(rest.split_whitespace().next().unwrap_or(""))
+}
+
+/// Seconds of media processed so far, from the `time=` token of an FFmpeg stats line
+pub fn parse_stats_time(line: &str) -> Option<f64> {
+    parse_timestamp(stats_value(line, "time")?)
+}
+
+/// Bytes written so far, from the `size=` token of an FFmpeg stats line (`1024kB`, `1024KiB`, `N/A`)
+pub fn parse_stats_size(line: &str) -> Option<u64> {
+    let value = stats_value(line, "size")?;
+    let digits_end = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let amount: u64 = value[..digits_end].parse().ok()?;
+    let multiplier = match value[digits_end..].to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" | "kib" => 1024,
+        "mb" | "mib" => 1024 * 1024,
+        "gb" | "gib" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    Some(amount * multiplier)
+}
+
+/// Input duration from the `Duration: HH:MM:SS.xx, start: ...` line FFmpeg prints when opening a file
+fn parse_input_duration(line: &str) -> Option<f64> {
+    let rest = line.trim_start().strip_prefix("Duration:")?;
+    parse_timestamp(rest.split(',').next()?)
+}
+
+/// Path for the transcoded copy of `input`, e.g. `Show.ts` -> `Show.h264_720p.ts`
+pub fn output_path(input: &Path, profile: &str) -> PathBuf {
+    let stem = input.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = input.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_else(|| "mkv".to_string());
+    input.with_file_name(format!("{}.{}.{}", stem, profile, extension))
+}
+
+/// Re-encode `input` into `output` with the given profile.
+///
+/// `on_progress` is called with (processed seconds, total seconds) as FFmpeg reports
+/// progress; the total comes from the input's header, falling back to `expected_duration`.
+/// A failed encode removes the partial output.
+pub async fn transcode_recording<F>(
+    ffmpeg_path: &Path,
+    input: &Path,
+    output: &Path,
+    profile: &str,
+    expected_duration: f64,
+    on_progress: F,
+) -> Result<()>
+where
+    F: FnMut(f64, f64),
+{
+    let (video_args, max_height) = profile_video_args(profile)
+        .ok_or_else(|| anyhow!("Unknown transcode profile: {}", profile))?;
+    let is_mp4 = output.extension().map(|e| e.eq_ignore_ascii_case("mp4")).unwrap_or(false);
+
+    let _permit = TRANSCODE_SLOTS.acquire().await.context("Transcode queue closed")?;
+    info!("Transcoding {:?} with profile {}", input, profile);
+
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner")
+        .arg("-i").arg(input)
+        .arg("-map").arg("0:v:0")
+        .arg("-map").arg("0:a?")
+        .args(video_args)
+        // Only ever scale down, keeping the aspect ratio with an even width
+        .arg("-vf").arg(format!("scale=-2:'min({},ih)'", max_height))
+        .arg("-c:a").arg("aac")
+        .arg("-b:a").arg("160k");
+
+    if is_mp4 {
+        cmd.arg("-movflags").arg("+faststart");
+        if profile.starts_with("h265") {
+            cmd.arg("-tag:v").arg("hvc1"); // Needed for Apple players to accept HEVC in mp4
+        }
+    }
+
+    cmd.arg("-y").arg(output);
+
+    run_with_progress(cmd, output, expected_duration, on_progress, "transcode").await?;
+    info!("Transcoded {:?} -> {:?}", input, output);
+    Ok(())
+}
+
+/// Rewrap `inputs` into the container implied by `output`'s extension (mp4/mkv)
+/// without re-encoding. Several inputs (the parts of a split recording) are joined in
+/// order with the concat demuxer. An existing `output` is never overwritten.
+/// Progress is reported like `transcode_recording`.
+pub async fn remux_recording<F>(
+    ffmpeg_path: &Path,
+    inputs: &[PathBuf],
+    output: &Path,
+    expected_duration: f64,
+    on_progress: F,
+) -> Result<()>
+where
+    F: FnMut(f64, f64),
+{
+    let is_mp4 = output.extension().map(|e| e.eq_ignore_ascii_case("mp4")).unwrap_or(false);
+    info!("Remuxing {:?} -> {:?}", inputs, output);
+
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner");
+    // The concat demuxer reads its inputs from a list file; quotes are escaped as '\''
+    let list_path = match inputs {
+        [input] => {
+            cmd.arg("-i").arg(input);
+            None
+        }
+        _ => {
+            let name = output.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let list_path = output.with_file_name(format!(".{}.concat.txt", name));
+            let list: String = inputs
+                .iter()
+                .map(|input| format!("file '{}'\n", input.to_string_lossy().replace('\'', "'\\''")))
+                .collect();
+            std::fs::write(&list_path, list).context("Failed to write concat list")?;
+            cmd.arg("-f").arg("concat")
+                .arg("-safe").arg("0")          // Absolute paths in the list
+                .arg("-i").arg(&list_path);
+            Some(list_path)
+        }
+    };
+    cmd.arg("-map").arg("0:v?")
+        .arg("-map").arg("0:a?")
+        .arg("-c").arg("copy");
+    if is_mp4 {
+        // MPEG-TS AAC carries ADTS headers that mp4 doesn't allow
+        cmd.arg("-bsf:a").arg("aac_adtstoasc").arg("-movflags").arg("+faststart");
+    }
+    cmd.arg("-n").arg(output);
+
+    let result = run_with_progress(cmd, output, expected_duration, on_progress, "remux").await;
+    if let Some(list_path) = list_path {
+        let _ = std::fs::remove_file(list_path);
+    }
+    result
+}
+
+/// Run an FFmpeg command, feeding its stats lines to `on_progress`.
+/// A failed run removes the partial output.
+async fn run_with_progress<F>(
+    mut cmd: Command,
+    output: &Path,
+    expected_duration: f64,
+    mut on_progress: F,
+    label: &str,
+) -> Result<()>
+where
+    F: FnMut(f64, f64),
+{
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    // Hide console window on Windows (CREATE_NO_WINDOW = 0x08000000)
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+
+    let mut child = cmd.spawn().with_context(|| format!("Failed to spawn FFmpeg for {}", label))?;
+    let stderr = child.stderr.take().context("Failed to take stderr")?;
+
+    // Stats lines are terminated by '\r' while FFmpeg updates them in place, so split on both
+    let mut reader = BufReader::new(stderr);
+    let mut buf = Vec::new();
+    let mut total = expected_duration;
+    let mut last_line = String::new();
+
+    loop {
+        buf.clear();
+        let read = reader.read_until(b'\r', &mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        for line in String::from_utf8_lossy(&buf).split(['\r', '\n']) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(duration) = parse_input_duration(line) {
+                total = duration;
+            } else if let Some(processed) = parse_stats_time(line) {
+                on_progress(processed, total);
+            }
+            debug!("[FFmpeg {}] {}", label, line);
+            last_line = line.to_string();
+        }
+    }
+
+    let status = child.wait().await?;
+    if !status.success() {
+        let _ = tokio::fs::remove_file(output).await;
+        return Err(anyhow!(
+            "FFmpeg {} exited with code {}: {}",
+            label,
+            status.code().unwrap_or(-1),
+            last_line
+        ));
+    }
+
+    Ok(())
+}
@@ -150,6 +150,81 @@ pub struct EpgProgram {
     pub description: Option<String>,
     pub start: String,  // ISO 8601 format
     pub stop: String,   // ISO 8601 format
+    pub season: Option<i32>,
+    pub episode: Option<i32>,
+    pub category: Option<String>,  // Comma-separated when a programme has several
+    pub rating: Option<String>,
+    pub icon: Option<String>,  // First http(s) <icon src="..."> of the programme
+}
+
+/// Store the `src` of a programme `<icon>` unless an earlier icon already provided a valid URL
+fn capture_program_icon(
+    program: &mut EpgProgram,
+    element: &quick_xml::events::BytesStart,
+    reader: &Reader<&[u8]>,
+) {
+    if program.icon.is_some() {
+        return;
+    }
+
+    let src = element
+        .try_get_attribute("src")
+        .ok()
+        .flatten()
+        .and_then(|a| a.decode_and_unescape_value(reader.decoder()).ok().map(|v| v.trim().to_string()));
+
+    if let Some(src) = src {
+        let lower = src.to_lowercase();
+        if lower.starts_with("http://") || lower.starts_with("https://") {
+            program.icon = Some(src);
+        }
+    }
+}
+
+/// Parse an XMLTV `<episode-num>` value into (season, episode), both 1-based
+///
+/// Supports the `xmltv_ns` system (`season.episode.part`, 0-based, each part
+/// optionally `n/total`, e.g. `0.2.0/1`) and `onscreen` strings like `S01E03`,
+/// `S1 E3` or `1x03`.
+fn parse_episode_num(system: &str, value: &str) -> (Option<i32>, Option<i32>) {
+    let value = value.trim();
+
+    if system.eq_ignore_ascii_case("xmltv_ns") {
+        let mut parts = value.split('.');
+        let parse_part = |part: Option<&str>| -> Option<i32> {
+            let number = part?.split('/').next()?.trim();
+            number.parse::<i32>().ok().filter(|n| *n >= 0).map(|n| n + 1)
+        };
+        let season = parse_part(parts.next());
+        let episode = parse_part(parts.next());
+        return (season, episode);
+    }
+
+    if system.eq_ignore_ascii_case("onscreen") {
+        let upper = value.to_uppercase();
+        let digits_after = |s: &str| -> Option<i32> {
+            let digits: String = s.trim_start().chars().take_while(|c| c.is_ascii_digit()).collect();
+            digits.parse().ok()
+        };
+
+        // S01E03 / S1 E3 / S01 - E03
+        if let Some(e_pos) = upper.find('E') {
+            let episode = digits_after(&upper[e_pos + 1..]);
+            let season = upper[..e_pos].rfind('S').and_then(|s_pos| digits_after(&upper[s_pos + 1..]));
+            if episode.is_some() {
+                return (season, episode);
+            }
+        }
+
+        // 1x03
+        if let Some((season, episode)) = upper.split_once('X') {
+            let season_digits: String = season.trim().chars().rev().take_while(|c| c.is_ascii_digit()).collect();
+            let season = season_digits.chars().rev().collect::<String>().parse().ok();
+            return (season, digits_after(episode));
+        }
+    }
+
+    (None, None)
 }
 
 /// Channel mapping from EPG channel ID to stream_id(s)
@@ -304,9 +379,6 @@ pub async fn stream_parse_epg<R: tauri::Runtime>(
 
     info!("Channel lookup has {} entries", channel_lookup.len());
 
-    // Check if URL is gzipped
-    let is_gzipped = epg_url.ends_with(".gz");
-
     // Create HTTP client with optimized settings and TLS configuration
     // Using native-tls to handle various certificate types including self-signed
     let client = reqwest::Client::builder()
@@ -358,20 +430,6 @@ pub async fn stream_parse_epg<R: tauri::Runtime>(
     let total_bytes = response.content_length();
     info!("EPG download started, total size: {:?} bytes", total_bytes);
 
-    // Check if response is actually gzipped (server may return gzip even if URL doesn't end with .gz)
-    let is_response_gzipped = response.headers()
-        .get("content-encoding")
-        .and_then(|v| v.to_str().ok())
-        .map(|v| v.to_lowercase().contains("gzip"))
-        .unwrap_or(false);
-    let should_decompress = is_gzipped || is_response_gzipped;
-    if should_decompress {
-        info!("[EPG] Will decompress response (URL gzipped: {}, Content-Encoding: {})",
-            is_gzipped,
-            response.headers().get("content-encoding").and_then(|v| v.to_str().ok()).unwrap_or("none")
-        );
-    }
-
     // SQLite old programs deletion is now deferred to parse_download_stream 
     // to ensure download succeeds first
 
@@ -395,7 +453,6 @@ pub async fn stream_parse_epg<R: tauri::Runtime>(
             app_handle_clone,
             source_id_clone,
             total_bytes,
-            is_gzipped,
             advanced_epg_matching,
             db_clone,
             src_ctx_clone,
@@ -455,7 +512,7 @@ struct StreamingParserResult {
 }
 
 /// Parse EPG by downloading chunks and parsing incrementally
-/// Handles both plain XML and gzipped XML (.xml.gz)
+/// Handles both plain XML and gzipped XML, told apart by the gzip magic bytes
 async fn parse_download_stream<R: tauri::Runtime>(
     response: reqwest::Response,
     channel_lookup: HashMap<String, Vec<String>>,
@@ -463,7 +520,6 @@ async fn parse_download_stream<R: tauri::Runtime>(
     app_handle: tauri::AppHandle<R>,
     source_id: String,
     total_bytes: Option<u64>,
-    is_gzipped: bool,
     advanced_epg_matching: bool,
     db: crate::dvr::database::DvrDatabase,
     src_ctx: String,
@@ -471,13 +527,12 @@ async fn parse_download_stream<R: tauri::Runtime>(
 ) -> Result<StreamingParserResult> {
     let start_time = std::time::Instant::now();
 
-    // Check if response is actually gzipped BEFORE consuming response body
+    // Read the header BEFORE consuming response body; only used for logging
     let is_response_gzipped = response.headers()
         .get("content-encoding")
         .and_then(|v| v.to_str().ok())
         .map(|v| v.to_lowercase().contains("gzip"))
         .unwrap_or(false);
-    let should_decompress = is_gzipped || is_response_gzipped;
 
     // Download chunks into a buffer
     let mut chunks: Vec<bytes::Bytes> = Vec::new();
@@ -508,20 +563,14 @@ async fn parse_download_stream<R: tauri::Runtime>(
         }
     }
 
-    // Defer SQLite deletion until we know the EPG was completely downloaded into memory!
-    info!("[EPG] EPG Download verified successful. Safe to delete old programs!");
-    info!("[EPG] Deleting old programs for source {}", src_ctx);
-    let deleted_count = delete_programs_for_source(&db, &source_id)?;
-    info!("[EPG] Deleted {} old programs for source {}", deleted_count, src_ctx);
-
     let download_ms = start_time.elapsed().as_millis() as u64;
 
     info!(
-        "[EPG] Downloaded {} bytes in {} chunks in {}ms (gzipped: {})",
+        "[EPG] Downloaded {} bytes in {} chunks in {}ms (Content-Encoding gzip: {})",
         total_bytes_downloaded,
         chunks.len(),
         download_ms,
-        should_decompress
+        is_response_gzipped
     );
 
     // Combine chunks for parsing (pre-allocate for speed)
@@ -538,27 +587,17 @@ async fn parse_download_stream<R: tauri::Runtime>(
             compressed_data[0], compressed_data[1], compressed_data[2], compressed_data[3]);
     }
 
-    // Check for gzip magic bytes (1f 8b) as fallback detection
-    let has_gzip_magic = compressed_data.len() >= 2 && compressed_data[0] == 0x1f && compressed_data[1] == 0x8b;
-    if !should_decompress && has_gzip_magic {
-        info!("[EPG] Detected gzip magic bytes, will decompress");
+    // reqwest may already have decoded a Content-Encoding: gzip body
+    if is_response_gzipped && !has_gzip_magic(&compressed_data) {
+        info!("[EPG] Response was flagged as gzip but is already plain XML");
     }
-    let should_decompress = should_decompress || has_gzip_magic;
-
-    // Decompress if gzipped (either by URL extension, Content-Encoding header, or magic bytes)
-    let xml_data: Vec<u8> = if should_decompress {
-        use flate2::read::GzDecoder;
-        use std::io::Read;
-
-        let mut decoder = GzDecoder::new(&compressed_data[..]);
-        let mut decompressed = Vec::new();
-        decoder.read_to_end(&mut decompressed)
-            .context("Failed to decompress gzipped EPG")?;
-        info!("[EPG] Decompressed {} bytes to {} bytes", compressed_data.len(), decompressed.len());
-        decompressed
-    } else {
-        compressed_data
-    };
+    let xml_data = decode_epg_data(compressed_data, &src_ctx)?;
+
+    // Defer SQLite deletion until we know the EPG was completely downloaded and decoded
+    info!("[EPG] EPG Download verified successful. Safe to delete old programs!");
+    info!("[EPG] Deleting old programs for source {}", src_ctx);
+    let deleted_count = delete_programs_for_source(&db, &source_id)?;
+    info!("[EPG] Deleted {} old programs for source {}", deleted_count, src_ctx);
 
     let combine_ms = combine_start.elapsed().as_millis() as u64;
 
@@ -569,7 +608,6 @@ async fn parse_download_stream<R: tauri::Runtime>(
         batch_tx,
         app_handle,
         source_id,
-        total_bytes,
         total_bytes_downloaded,
         start_time,
         advanced_epg_matching,
@@ -585,6 +623,67 @@ async fn parse_download_stream<R: tauri::Runtime>(
     Ok(parse_result)
 }
 
+/// Check for the gzip magic bytes (1f 8b)
+fn has_gzip_magic(data: &[u8]) -> bool {
+    data.len() >= 2 && data[0] == 0x1f && data[1] == 0x8b
+}
+
+/// XMLTV bytes from a download or file, decompressed if they are gzipped
+///
+/// Decided by the gzip magic bytes alone: a `.gz` name can hold plain XML and
+/// gzipped guides are often served without the extension.
+fn decode_epg_data(data: Vec<u8>, src_ctx: &str) -> Result<Vec<u8>> {
+    if has_gzip_magic(&data) {
+        info!("[EPG] Detected gzip magic bytes, will decompress");
+        decompress_gzip_epg(&data, src_ctx)
+    } else {
+        Ok(data)
+    }
+}
+
+/// Most a gzip header's size hint may pre-allocate. The hint comes from the file, so a
+/// bogus one must not be able to reserve gigabytes up front; the buffer still grows past it.
+const GZIP_PREALLOC_MAX: usize = 256 * 1024 * 1024;
+
+/// XMLTV rarely compresses better than this, so larger size hints are treated as bogus
+const GZIP_MAX_RATIO: usize = 20;
+
+/// Bytes to reserve for decompressing `compressed`
+///
+/// Gzip stores the uncompressed size (mod 4GB) in the last 4 bytes; it is only used as a
+/// hint, bounded by the compressed size and `GZIP_PREALLOC_MAX`.
+fn gzip_prealloc(compressed: &[u8]) -> usize {
+    let size_hint = compressed
+        .len()
+        .checked_sub(4)
+        .map(|i| u32::from_le_bytes([compressed[i], compressed[i + 1], compressed[i + 2], compressed[i + 3]]) as usize)
+        .unwrap_or(0);
+
+    size_hint
+        .min(compressed.len().saturating_mul(GZIP_MAX_RATIO))
+        .min(GZIP_PREALLOC_MAX)
+}
+
+/// Decompress a gzipped XMLTV document
+fn decompress_gzip_epg(compressed: &[u8], src_ctx: &str) -> Result<Vec<u8>> {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let mut decoder = GzDecoder::new(compressed);
+    let mut decompressed = Vec::with_capacity(gzip_prealloc(compressed));
+    decoder.read_to_end(&mut decompressed).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to decompress gzipped EPG for {} ({} bytes): {}. The file may be truncated or not valid gzip",
+            src_ctx,
+            compressed.len(),
+            e
+        )
+    })?;
+
+    info!("[EPG] Decompressed {} bytes to {} bytes", compressed.len(), decompressed.len());
+    Ok(decompressed)
+}
+
 /// Build a mapping from display names to channel IDs by parsing <channel> elements
 /// This allows matching M3U channel names like "US: BET" to EPG channel id "bet.us"
 fn build_display_name_mapping(xml_data: &[u8]) -> HashMap<String, String> {
@@ -695,7 +794,6 @@ async fn parse_and_stream_batches<R: tauri::Runtime>(
     batch_tx: mpsc::Sender<Vec<EpgProgram>>,
     app_handle: tauri::AppHandle<R>,
     source_id: String,
-    total_bytes: Option<u64>,
     bytes_downloaded: u64,
     start_time: std::time::Instant,
     advanced_epg_matching: bool,
@@ -720,6 +818,8 @@ async fn parse_and_stream_batches<R: tauri::Runtime>(
     let mut current_program: Option<EpgProgram> = None;
     let mut current_element: Option<String> = None;
     let mut current_text = String::new();
+    let mut current_episode_system = String::new();
+    let mut in_rating = false;
 
     let mut total_programs = 0usize;
     let mut matched_programs = 0usize;
@@ -727,6 +827,9 @@ async fn parse_and_stream_batches<R: tauri::Runtime>(
     let mut batch = Vec::with_capacity(BATCH_SIZE);
     let mut last_progress_update = std::time::Instant::now();
 
+    // Parsing progress is reported against the (possibly decompressed) XML size
+    let xml_total_bytes = Some(xml_data.len() as u64);
+
     // Emit parsing progress
     emit_progress(
         &app_handle,
@@ -734,8 +837,8 @@ async fn parse_and_stream_batches<R: tauri::Runtime>(
         EpgParseProgress {
             source_id: source_id.to_string(),
             phase: "parsing".to_string(),
-            bytes_downloaded,
-            total_bytes,
+            bytes_downloaded: 0,
+            total_bytes: xml_total_bytes,
             programs_parsed: 0,
             programs_matched: 0,
             programs_inserted: 0,
@@ -776,13 +879,39 @@ async fn parse_and_stream_batches<R: tauri::Runtime>(
 
                         current_program = Some(program);
                     }
-                    "title" | "desc" => {
+                    "title" | "desc" | "category" => {
+                        current_element = Some(name);
+                        current_text.clear();
+                    }
+                    "episode-num" => {
+                        current_episode_system = e
+                            .try_get_attribute("system")
+                            .ok()
+                            .flatten()
+                            .and_then(|a| a.decode_and_unescape_value(reader.decoder()).ok().map(|v| v.to_string()))
+                            .unwrap_or_else(|| "onscreen".to_string());
+                        current_element = Some(name);
+                        current_text.clear();
+                    }
+                    "rating" => in_rating = true,
+                    "icon" if !in_rating => {
+                        if let Some(ref mut program) = current_program {
+                            capture_program_icon(program, &e, &reader);
+                        }
+                    }
+                    "value" if in_rating => {
                         current_element = Some(name);
                         current_text.clear();
                     }
                     _ => {}
                 }
             }
+            // <icon src="..."/> is almost always self-closing; skip icons nested in <rating>
+            Ok(Event::Empty(e)) if e.name().as_ref() == b"icon" && !in_rating => {
+                if let Some(ref mut program) = current_program {
+                    capture_program_icon(program, &e, &reader);
+                }
+            }
             Ok(Event::Text(e)) => {
                 if let Some(ref _element) = current_element {
                     if let Ok(text) = e.unescape() {
@@ -840,20 +969,21 @@ async fn parse_and_stream_batches<R: tauri::Runtime>(
                             // Progress updates
                             if total_programs % (BATCH_SIZE * PROGRESS_INTERVAL) == 0 {
                                 if last_progress_update.elapsed().as_millis() > 100 {
+                                    let bytes_parsed = reader.buffer_position();
                                     emit_progress(
                                         &app_handle,
                                         &source_id,
                                         EpgParseProgress {
                                             source_id: source_id.to_string(),
                                             phase: "parsing".to_string(),
-                                            bytes_downloaded,
-                                            total_bytes,
+                                            bytes_downloaded: bytes_parsed,
+                                            total_bytes: xml_total_bytes,
                                             programs_parsed: total_programs,
                                             programs_matched: matched_programs,
                                             programs_inserted: 0,
                                             estimated_remaining_seconds: estimate_remaining(
-                                                bytes_downloaded,
-                                                total_bytes,
+                                                bytes_parsed,
+                                                xml_total_bytes,
                                                 start_time.elapsed().as_secs(),
                                             ),
                                         },
@@ -876,6 +1006,44 @@ async fn parse_and_stream_batches<R: tauri::Runtime>(
                         }
                         current_element = None;
                     }
+                    "episode-num" => {
+                        if let Some(ref mut program) = current_program {
+                            let (season, episode) = parse_episode_num(&current_episode_system, &current_text);
+                            // xmltv_ns is the most precise system, so it wins over onscreen
+                            if episode.is_some() && (program.episode.is_none() || current_episode_system == "xmltv_ns") {
+                                program.season = season.or(program.season);
+                                program.episode = episode;
+                            }
+                        }
+                        current_element = None;
+                    }
+                    "category" => {
+                        if let Some(ref mut program) = current_program {
+                            let category = current_text.trim();
+                            if !category.is_empty() {
+                                match program.category {
+                                    Some(ref mut existing) => {
+                                        if !existing.split(", ").any(|c| c == category) {
+                                            existing.push_str(", ");
+                                            existing.push_str(category);
+                                        }
+                                    }
+                                    None => program.category = Some(category.to_string()),
+                                }
+                            }
+                        }
+                        current_element = None;
+                    }
+                    "value" if in_rating => {
+                        if let Some(ref mut program) = current_program {
+                            let rating = current_text.trim();
+                            if program.rating.is_none() && !rating.is_empty() {
+                                program.rating = Some(rating.to_string());
+                            }
+                        }
+                        current_element = None;
+                    }
+                    "rating" => in_rating = false,
                     _ => {}
                 }
             }
@@ -1024,13 +1192,19 @@ async fn insert_programs_batch_inner(
 
     let mut stmt = tx.prepare(
         "INSERT INTO programs (
-            id, stream_id, title, description, start, end, source_id
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            id, stream_id, title, description, start, end, source_id,
+            season, episode, category, rating, icon
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
         ON CONFLICT(id) DO UPDATE SET
             title = excluded.title,
             description = excluded.description,
             start = excluded.start,
-            end = excluded.end",
+            end = excluded.end,
+            season = excluded.season,
+            episode = excluded.episode,
+            category = excluded.category,
+            rating = excluded.rating,
+            icon = excluded.icon",
     )?;
 
     let mut inserted = 0;
@@ -1047,6 +1221,11 @@ async fn insert_programs_batch_inner(
             program.start,
             program.stop,
             source_id,
+            program.season,
+            program.episode,
+            program.category,
+            program.rating,
+            program.icon,
         ]) {
             Ok(_) => inserted += 1,
             Err(e) => {
@@ -1133,10 +1312,13 @@ pub async fn parse_epg_file<R: tauri::Runtime>(
     let total_bytes = metadata.len();
 
     // Read entire file into memory (for local files this is acceptable)
-    let mut xml_data = Vec::with_capacity(total_bytes as usize);
-    file.read_to_end(&mut xml_data).await
+    let mut file_data = Vec::with_capacity(total_bytes as usize);
+    file.read_to_end(&mut file_data).await
         .context("Failed to read EPG file")?;
 
+    let xml_data = decode_epg_data(file_data, &file_path)?;
+    let xml_bytes = xml_data.len() as u64;
+
     // Build channel lookup map (supports multiple stream_ids per epg_channel_id)
     let channel_lookup = build_channel_lookup(channel_mappings);
 
@@ -1160,8 +1342,7 @@ pub async fn parse_epg_file<R: tauri::Runtime>(
             batch_tx,
             app_handle_clone,
             source_id_clone,
-            Some(total_bytes),
-            total_bytes,
+            xml_bytes,
             start_time,
             advanced_epg_matching,
             timeshift_hours,
@@ -1174,7 +1355,7 @@ pub async fn parse_epg_file<R: tauri::Runtime>(
         batch_rx,
         &source_id,
         app_handle.clone(),
-        Some(total_bytes),
+        Some(xml_bytes),
         start_time,
     ).await;
 
@@ -1194,3 +1375,76 @@ pub async fn parse_epg_file<R: tauri::Runtime>(
         bytes_processed: total_bytes,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const XML: &[u8] = b"<?xml version=\"1.0\"?><tv><channel id=\"bbc.one\"/></tv>";
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_parse_episode_num() {
+        let cases = [
+            // xmltv_ns is zero-based and may carry totals after a slash
+            ("xmltv_ns", "0.4.", (Some(1), Some(5))),
+            ("xmltv_ns", "1.2/10.0/1", (Some(2), Some(3))),
+            ("xmltv_ns", " 2 . 9 . ", (Some(3), Some(10))),
+            ("xmltv_ns", ".4.", (None, Some(5))),
+            ("xmltv_ns", "0..", (Some(1), None)),
+            ("xmltv_ns", "", (None, None)),
+            ("xmltv_ns", "a.b.c", (None, None)),
+            ("onscreen", "S01E05", (Some(1), Some(5))),
+            ("onscreen", "s2 e10", (Some(2), Some(10))),
+            ("onscreen", "S01 - E03", (Some(1), Some(3))),
+            ("onscreen", "E12", (None, Some(12))),
+            ("onscreen", "1x03", (Some(1), Some(3))),
+            ("onscreen", "Episode five", (None, None)),
+            ("dd_progid", "EP01234567.0001", (None, None)),
+        ];
+
+        for (system, value, expected) in cases {
+            assert_eq!(parse_episode_num(system, value), expected, "{system} {value:?}");
+        }
+    }
+
+    #[test]
+    fn test_decode_epg_data_by_magic_bytes() {
+        assert_eq!(decode_epg_data(gzip(XML), "guide.xml").unwrap(), XML);
+        // A mislabelled guide.xml.gz holding plain XML is parsed as is
+        assert_eq!(decode_epg_data(XML.to_vec(), "guide.xml.gz").unwrap(), XML);
+    }
+
+    #[test]
+    fn test_oversized_size_footer_is_bounded() {
+        let mut compressed = gzip(XML);
+        let len = compressed.len();
+        compressed[len - 4..].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert_eq!(gzip_prealloc(&compressed), len * GZIP_MAX_RATIO);
+        // The footer no longer matches the data, which the decoder reports
+        assert!(decompress_gzip_epg(&compressed, "test").is_err());
+    }
+
+    #[test]
+    fn test_highly_compressed_gzip_still_decodes() {
+        let xml = XML.repeat(20_000);
+        let compressed = gzip(&xml);
+
+        assert!(gzip_prealloc(&compressed) < xml.len());
+        assert_eq!(decompress_gzip_epg(&compressed, "test").unwrap(), xml);
+    }
+
+    #[test]
+    fn test_truncated_gzip_is_an_error() {
+        let compressed = gzip(XML);
+        assert!(decompress_gzip_epg(&compressed[..compressed.len() / 2], "test").is_err());
+        assert!(decode_epg_data(vec![0x1f, 0x8b], "test").is_err());
+    }
+}
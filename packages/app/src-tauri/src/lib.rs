@@ -14,21 +14,46 @@ mod mpv_macos;
 mod mpv_windows;
 #[cfg(target_os = "windows")]
 mod mpv_secondary;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+#[path = "mpv_secondary_unix.rs"]
+mod mpv_secondary;
+
+mod mpv_cleanup;
+#[cfg(any(target_os = "windows", test))]
+mod mpv_watch;
 
 // Re-export the MPV state and functions based on platform
 #[cfg(target_os = "macos")]
 use mpv_macos::MpvState;
 #[cfg(target_os = "windows")]
 use mpv_windows::MpvState;
-#[cfg(target_os = "windows")]
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
 use mpv_secondary::SecondaryMpvState;
 
+/// Linux has no main MPV player yet (only multiview slot windows), so the
+/// main player commands report this instead
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const MPV_UNSUPPORTED: &str = "MPV playback is not supported on this platform";
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+struct MpvState;
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+impl MpvState {
+    fn new() -> Self {
+        MpvState
+    }
+}
+
 // DVR Module (Rust native implementation)
 mod dvr;
 use dvr::{DvrState, models::*};
 
 // Bulk database operations module
 mod db_bulk_ops;
+mod db_maintenance;
+mod db_queries;
+mod m3u;
 mod sync_provider;
 
 // Streaming EPG parser module
@@ -42,15 +67,6 @@ mod tvmaze;
 use tmdb_cache::{TmdbCache, MatchResult, CacheStats};
 
 
-// Bulk insert structures
-#[derive(Debug, Deserialize)]
-struct BulkInsertRequest {
-    table: String,
-    columns: Vec<String>,
-    rows: Vec<Vec<serde_json::Value>>,
-    operation: String, // "insert" or "replace"
-}
-
 // MPV Status structure (used by both platforms)
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct MpvStatus {
@@ -81,6 +97,20 @@ fn read_store_setting<R: Runtime>(app: &AppHandle<R>, key: &str) -> Option<serde
     store.get(key)
 }
 
+/// Helper to write a single setting into the store's nested `settings` object (frontend format)
+fn write_store_setting<R: Runtime>(app: &AppHandle<R>, key: &str, value: serde_json::Value) -> Result<(), String> {
+    use tauri_plugin_store::StoreExt;
+
+    let store = app.store(".settings.dat").map_err(|e| format!("Failed to open store: {}", e))?;
+    let mut settings = store
+        .get("settings")
+        .and_then(|v| v.as_object().cloned())
+        .unwrap_or_default();
+    settings.insert(key.to_string(), value);
+    store.set("settings", serde_json::Value::Object(settings));
+    store.save().map_err(|e| format!("Failed to save store: {}", e))
+}
+
 /// Get custom MPV parameters from settings store.
 /// Supports both nested `settings` object (frontend format) and root-level keys (legacy).
 async fn get_mpv_params_from_store<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
@@ -192,15 +222,28 @@ const BLOCKED_MPV_KEYS: &[&str] = &[
     "sub-file", "audio-file", "external-file",
 ];
 
+/// Options the app sets itself to embed and control MPV; a user copy would
+/// break window embedding or IPC, so these are dropped even with the whitelist off
+const RESERVED_MPV_KEYS: &[&str] = &[
+    "wid", "input-ipc-server", "input-ipc-client", "title", "geometry", "idle",
+];
+
+/// Option name of an `--key=value` / `--key` argument
+fn mpv_arg_key(arg: &str) -> &str {
+    arg.trim_start_matches("--").split('=').next().unwrap_or("")
+}
+
 fn sanitize_mpv_args(args: Vec<String>, allow_all: bool) -> Vec<String> {
     // If user disabled the whitelist, accept all well-formed arguments
     if allow_all {
         let mut valid_args = Vec::new();
         for arg in args {
-            if arg.starts_with("--") {
-                valid_args.push(arg);
-            } else {
+            if !arg.starts_with("--") {
                 log::warn!("SECURITY ALERT: Dropped malformed MPV argument (must start with --): {}", arg);
+            } else if RESERVED_MPV_KEYS.contains(&mpv_arg_key(&arg)) {
+                log::warn!("[MPV] Dropped argument reserved for embedding/IPC: {}", arg);
+            } else {
+                valid_args.push(arg);
             }
         }
         return valid_args;
@@ -237,6 +280,11 @@ fn sanitize_mpv_args(args: Vec<String>, allow_all: bool) -> Vec<String> {
             continue;
         }
         
+        if RESERVED_MPV_KEYS.contains(&key) {
+            log::warn!("[MPV] Dropped argument reserved for embedding/IPC: {}", arg);
+            continue;
+        }
+
         if BLOCKED_MPV_KEYS.contains(&key) {
             log::warn!("SECURITY ALERT: Blocked blacklisted MPV argument: {}", key);
             continue;
@@ -395,6 +443,9 @@ async fn init_mpv<R: Runtime>(app: AppHandle<R>, args: Vec<String>) -> Result<()
         log::warn!("[MPV] SECURITY: User has disabled the MPV parameter whitelist. All parameters will be accepted.");
     }
 
+    // Options from the user's mpv.conf go through the same firewall as typed arguments
+    custom_params.extend(mpv_config_args(&app));
+
     // Merge frontend-provided args (for timeshift settings from loaded state)
     // Frontend args are added after store params so they take precedence
     if !args.is_empty() {
@@ -425,18 +476,299 @@ async fn init_mpv<R: Runtime>(app: AppHandle<R>, args: Vec<String>) -> Result<()
         let state = app.state::<MpvState>();
         mpv_windows::init_mpv_with_params(app.clone(), state, safe_custom_params).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, args);
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
+/// Load a URL. For VOD pass `vod_media_id`/`vod_media_type` (its watch history entry),
+/// for a DVR recording `recording_id`, to save watch progress while playing; `resume`
+/// continues from the saved position. Tracking needs the Windows player: macOS refuses
+/// it, and the frontend saves VOD progress itself there.
 #[tauri::command]
-async fn mpv_load<R: Runtime>(app: AppHandle<R>, url: String) -> Result<(), String> {
+async fn mpv_load<R: Runtime>(
+    app: AppHandle<R>,
+    url: String,
+    vod_media_id: Option<String>,
+    vod_media_type: Option<String>,
+    recording_id: Option<i64>,
+    resume: Option<bool>,
+) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        mpv_macos::load_file(&app, url).await
+        if vod_media_id.is_some() || recording_id.is_some() || resume.unwrap_or(false) {
+            return Err("Watch progress tracking is not supported by the macOS player".to_string());
+        }
+        let _ = vod_media_type;
+        mpv_macos::load_file(&app, url).await?;
     }
     #[cfg(target_os = "windows")]
     {
-        mpv_windows::load_file(&app, url).await
+        use mpv_watch::WatchTarget;
+
+        let target = match (vod_media_id, vod_media_type, recording_id) {
+            (Some(media_id), Some(media_type), _) => Some(WatchTarget::Vod { media_id, media_type }),
+            (_, _, Some(id)) => Some(WatchTarget::Recording(id)),
+            _ => None,
+        };
+        let resume_at = match (&target, app.try_state::<DvrState>()) {
+            (Some(target), Some(dvr)) if resume.unwrap_or(false) => mpv_watch::saved_resume_position(&dvr.db, target),
+            _ => None,
+        };
+
+        // A manual load replaces whatever was queued to follow the previous file
+        mpv_windows::set_next_on_eof(&app, None);
+        mpv_windows::set_watch_target(&app, &url, target, resume_at);
+        mpv_windows::load_file(&app, url).await?;
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (url, vod_media_id, vod_media_type, recording_id, resume);
+        Err::<(), _>(MPV_UNSUPPORTED.to_string())?;
+    }
+
+    // Reapply the user's picture settings so a per-file profile or failed probe doesn't stick.
+    // Embedding on macOS needs `hwdec=no`, so a saved hwdec choice is left alone there.
+    for (key, property) in [("mpvHwdec", "hwdec"), ("mpvDeinterlace", "deinterlace")] {
+        if cfg!(target_os = "macos") && property == "hwdec" {
+            continue;
+        }
+        let Some(value) = read_store_setting(&app, key).and_then(|v| v.as_str().map(str::to_string)) else { continue };
+        if let Err(e) = mpv_set_property(app.clone(), property.to_string(), serde_json::Value::String(value)).await {
+            warn!("[MPV] Failed to reapply {} after load: {}", property, e);
+        }
+    }
+    Ok(())
+}
+
+/// Accepted values for the `hwdec` property
+const HWDEC_MODES: &[&str] = &[
+    "no", "auto", "auto-safe", "auto-copy", "yes",
+    "nvdec", "nvdec-copy", "d3d11va", "d3d11va-copy", "dxva2", "dxva2-copy",
+    "videotoolbox", "videotoolbox-copy", "vaapi", "vaapi-copy", "vulkan", "vulkan-copy",
+];
+
+/// Normalise a deinterlace setting: `true`/`false` or `"yes"`/`"no"`/`"auto"`
+fn deinterlace_mode(value: &serde_json::Value) -> Option<&'static str> {
+    match value {
+        serde_json::Value::Bool(true) => Some("yes"),
+        serde_json::Value::Bool(false) => Some("no"),
+        serde_json::Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "yes" => Some("yes"),
+            "no" => Some("no"),
+            "auto" => Some("auto"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Turn deinterlacing on, off or `"auto"` (only interlaced frames) and remember it
+#[tauri::command]
+async fn mpv_set_deinterlace<R: Runtime>(app: AppHandle<R>, enabled: serde_json::Value) -> Result<(), String> {
+    let mode = deinterlace_mode(&enabled).ok_or_else(|| format!("Invalid deinterlace value: {}", enabled))?;
+
+    write_store_setting(&app, "mpvDeinterlace", serde_json::Value::String(mode.to_string()))?;
+    mpv_set_property(app, "deinterlace".to_string(), serde_json::Value::String(mode.to_string())).await
+}
+
+/// Get the saved deinterlace mode (`"yes"`, `"no"` or `"auto"`)
+#[tauri::command]
+async fn mpv_get_deinterlace<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    Ok(read_store_setting(&app, "mpvDeinterlace")
+        .as_ref()
+        .and_then(deinterlace_mode)
+        .unwrap_or("no")
+        .to_string())
+}
+
+/// Aspect ratio and pan-and-scan forced for one channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AspectOverride {
+    aspect: String,
+    pan_scan: f64,
+}
+
+impl Default for AspectOverride {
+    fn default() -> Self {
+        Self { aspect: "-1".to_string(), pan_scan: 0.0 }
+    }
+}
+
+/// Whether `ratio` is a valid `video-aspect-override`: `-1` (auto), `W:H` or a positive number
+fn is_valid_aspect(ratio: &str) -> bool {
+    if ratio == "-1" {
+        return true;
     }
+    let positive = |s: &str| s.trim().parse::<f64>().map(|v| v > 0.0 && v.is_finite()).unwrap_or(false);
+    match ratio.split_once(':') {
+        Some((w, h)) => positive(w) && positive(h),
+        None => positive(ratio),
+    }
+}
+
+fn read_aspect_overrides<R: Runtime>(app: &AppHandle<R>) -> std::collections::HashMap<String, AspectOverride> {
+    read_store_setting(app, "mpvAspectOverrides")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Update (or with `None`, forget) the saved override for a channel
+fn save_aspect_override<R: Runtime>(
+    app: &AppHandle<R>,
+    channel_id: &str,
+    update: Option<&dyn Fn(&mut AspectOverride)>,
+) -> Result<(), String> {
+    let mut overrides = read_aspect_overrides(app);
+    match update {
+        Some(update) => update(overrides.entry(channel_id.to_string()).or_default()),
+        None => {
+            overrides.remove(channel_id);
+        }
+    }
+    let value = serde_json::to_value(&overrides).map_err(|e| format!("Failed to serialize aspect overrides: {}", e))?;
+    write_store_setting(app, "mpvAspectOverrides", value)
+}
+
+async fn apply_aspect_override<R: Runtime>(app: &AppHandle<R>, settings: &AspectOverride) -> Result<(), String> {
+    mpv_set_property(app.clone(), "video-aspect-override".to_string(), serde_json::Value::String(settings.aspect.clone())).await?;
+    mpv_set_property(app.clone(), "panscan".to_string(), serde_json::json!(settings.pan_scan)).await
+}
+
+/// Force the aspect ratio (`"16:9"`, `"4:3"`, `"-1"` for auto), remembered for `channel_id` if given
+#[tauri::command]
+async fn mpv_set_aspect<R: Runtime>(app: AppHandle<R>, ratio: String, channel_id: Option<String>) -> Result<(), String> {
+    let ratio = ratio.trim().to_string();
+    if !is_valid_aspect(&ratio) {
+        return Err(format!("Invalid aspect ratio: {}", ratio));
+    }
+
+    mpv_set_property(app.clone(), "video-aspect-override".to_string(), serde_json::Value::String(ratio.clone())).await?;
+    if let Some(channel_id) = channel_id {
+        save_aspect_override(&app, &channel_id, Some(&|o: &mut AspectOverride| o.aspect = ratio.clone()))?;
+    }
+    Ok(())
+}
+
+/// Zoom in to crop black bars (0.0 = off, 1.0 = fill), remembered for `channel_id` if given
+#[tauri::command]
+async fn mpv_set_pan_scan<R: Runtime>(app: AppHandle<R>, value: f64, channel_id: Option<String>) -> Result<(), String> {
+    if !value.is_finite() {
+        return Err(format!("Invalid pan-scan value: {}", value));
+    }
+    let value = value.clamp(0.0, 1.0);
+
+    mpv_set_property(app.clone(), "panscan".to_string(), serde_json::json!(value)).await?;
+    if let Some(channel_id) = channel_id {
+        save_aspect_override(&app, &channel_id, Some(&|o: &mut AspectOverride| o.pan_scan = value))?;
+    }
+    Ok(())
+}
+
+/// Back to the stream's own aspect ratio with no cropping, forgetting any saved override
+#[tauri::command]
+async fn mpv_reset_aspect<R: Runtime>(app: AppHandle<R>, channel_id: Option<String>) -> Result<(), String> {
+    apply_aspect_override(&app, &AspectOverride::default()).await?;
+    if let Some(channel_id) = channel_id {
+        save_aspect_override(&app, &channel_id, None)?;
+    }
+    Ok(())
+}
+
+/// Apply a channel's saved aspect override (or auto if it has none) after it starts playing
+#[tauri::command]
+async fn mpv_apply_channel_aspect<R: Runtime>(app: AppHandle<R>, channel_id: String) -> Result<AspectOverride, String> {
+    let settings = read_aspect_overrides(&app).remove(&channel_id).unwrap_or_default();
+    apply_aspect_override(&app, &settings).await?;
+    Ok(settings)
+}
+
+/// MPV output lines that mean the hardware decoder failed, lowercased
+#[cfg(any(target_os = "windows", test))]
+const DECODE_ERROR_MARKERS: &[&str] = &[
+    "hwaccel initialisation returned error",
+    "failed setup for format",
+    "could not create device",
+    "failed to create d3d11",
+    "error while decoding frame",
+    "hardware decoding failed",
+];
+
+/// Whether a (lowercased) MPV log line reports a hardware decode failure
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn is_decode_error(lower: &str) -> bool {
+    DECODE_ERROR_MARKERS.iter().any(|marker| lower.contains(marker))
+}
+
+/// Decode errors within `DECODE_ERROR_WINDOW` before the UI suggests software decoding.
+/// Live streams log the odd "error while decoding frame" for a corrupt packet.
+#[cfg(any(target_os = "windows", test))]
+const DECODE_ERROR_THRESHOLD: usize = 5;
+#[cfg(any(target_os = "windows", test))]
+const DECODE_ERROR_WINDOW: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Recent decode errors from one MPV, so only a sustained run of them is reported
+#[cfg(any(target_os = "windows", test))]
+#[derive(Debug, Default)]
+pub(crate) struct DecodeErrorCounter {
+    recent: std::collections::VecDeque<std::time::Instant>,
+}
+
+#[cfg(any(target_os = "windows", test))]
+impl DecodeErrorCounter {
+    /// Record a decode error; true when enough have piled up in the window to report
+    pub(crate) fn record(&mut self, now: std::time::Instant) -> bool {
+        while self.recent.front().is_some_and(|at| now.duration_since(*at) > DECODE_ERROR_WINDOW) {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(now);
+        if self.recent.len() < DECODE_ERROR_THRESHOLD {
+            return false;
+        }
+        self.recent.clear();
+        true
+    }
+}
+
+/// HTTP status (400-599) from a (lowercased) FFmpeg/MPV log line, e.g.
+/// "http error 403 forbidden" or "server returned 404 not found"
+pub(crate) fn parse_http_error_code(lower: &str) -> Option<u16> {
+    ["http error", "server returned"].iter().find_map(|marker| {
+        let pos = lower.find(marker)?;
+        lower[pos + marker.len()..]
+            .split_whitespace()
+            .find_map(|part| {
+                let clean = part.trim_matches(':').trim_matches(',');
+                clean.parse::<u16>().ok().filter(|&c| (400..600).contains(&c))
+            })
+    })
+}
+
+/// User-facing message for an HTTP error status from a stream
+pub(crate) fn http_error_message(code: u16) -> String {
+    match code {
+        401 => "Access Denied (401): Authentication required".to_string(),
+        403 => "Access Denied (403): Stream blocked by server".to_string(),
+        404 => "Stream Not Found (404)".to_string(),
+        _ => format!("HTTP Error ({}): Unable to load stream", code),
+    }
+}
+
+/// Switch hardware decoding at runtime and remember the choice for later loads
+#[tauri::command]
+async fn mpv_set_hwdec<R: Runtime>(app: AppHandle<R>, mode: String) -> Result<(), String> {
+    if cfg!(target_os = "macos") {
+        return Err("Hardware decoding is always off in the embedded macOS player".to_string());
+    }
+    let mode = mode.trim().to_lowercase();
+    if !HWDEC_MODES.contains(&mode.as_str()) {
+        return Err(format!("Unsupported hwdec mode: {}", mode));
+    }
+
+    write_store_setting(&app, "mpvHwdec", serde_json::Value::String(mode.clone()))?;
+    mpv_set_property(app, "hwdec".to_string(), serde_json::Value::String(mode)).await
 }
 
 #[tauri::command]
@@ -449,6 +781,11 @@ async fn mpv_play<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     {
         mpv_windows::play(&app).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
 #[tauri::command]
@@ -461,6 +798,11 @@ async fn mpv_pause<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     {
         mpv_windows::pause(&app).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
 #[tauri::command]
@@ -473,6 +815,11 @@ async fn mpv_resume<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     {
         mpv_windows::resume(&app).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
 #[tauri::command]
@@ -485,10 +832,19 @@ async fn mpv_stop<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     {
         mpv_windows::stop(&app).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
+/// Highest volume MPV is allowed to reach (`--volume-max`); above 100 is software gain
+pub(crate) const MAX_VOLUME: f64 = 150.0;
+
 #[tauri::command]
 async fn mpv_set_volume<R: Runtime>(app: AppHandle<R>, volume: f64) -> Result<(), String> {
+    let volume = if volume.is_finite() { volume.clamp(0.0, MAX_VOLUME) } else { 100.0 };
     #[cfg(target_os = "macos")]
     {
         mpv_macos::set_volume(&app, volume).await
@@ -497,6 +853,11 @@ async fn mpv_set_volume<R: Runtime>(app: AppHandle<R>, volume: f64) -> Result<()
     {
         mpv_windows::set_volume(&app, volume).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, volume);
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
 #[tauri::command]
@@ -509,6 +870,11 @@ async fn mpv_seek<R: Runtime>(app: AppHandle<R>, seconds: f64) -> Result<(), Str
     {
         mpv_windows::seek(&app, seconds).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, seconds);
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
 #[tauri::command]
@@ -521,6 +887,11 @@ async fn mpv_toggle_mute<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     {
         mpv_windows::toggle_mute(&app).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
 #[tauri::command]
@@ -533,6 +904,11 @@ async fn mpv_cycle_audio<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     {
         mpv_windows::cycle_audio(&app).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
 #[tauri::command]
@@ -545,6 +921,11 @@ async fn mpv_cycle_sub<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     {
         mpv_windows::cycle_sub(&app).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
 #[tauri::command]
@@ -557,136 +938,569 @@ async fn mpv_get_track_list<R: Runtime>(app: AppHandle<R>) -> Result<serde_json:
     {
         mpv_windows::get_track_list(&app).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
+/// Save how far a recording has been played (for players that don't go through MPV)
 #[tauri::command]
-async fn mpv_set_audio<R: Runtime>(app: AppHandle<R>, id: i64) -> Result<(), String> {
+async fn update_recording_watch_position(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+    position: f64,
+    duration: Option<f64>,
+) -> Result<(), String> {
+    state.db.update_recording_watch_position(id, position, duration)
+        .map_err(|e| format!("Failed to update recording position: {}", e))
+}
+
+/// Mark a recording watched or unwatched
+#[tauri::command]
+async fn set_recording_watched(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+    watched: bool,
+) -> Result<(), String> {
+    state.db.set_recording_watched(id, watched)
+        .map_err(|e| format!("Failed to update recording watched state: {}", e))
+}
+
+/// Get the saved playback position of a VOD item, if it has been played
+#[tauri::command]
+async fn get_watch_progress(
+    state: tauri::State<'_, DvrState>,
+    media_id: String,
+    media_type: String,
+) -> Result<Option<WatchProgress>, String> {
+    state.db.get_watch_progress(&media_id, &media_type)
+        .map_err(|e| format!("Failed to get watch progress: {}", e))
+}
+
+/// Queue the next episode to load when the current file ends cleanly (not on error).
+/// Call after loading the current episode; pass `None` to cancel.
+#[tauri::command]
+async fn set_next_on_eof<R: Runtime>(app: AppHandle<R>, url: Option<String>) -> Result<(), String> {
+    let url = url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
     #[cfg(target_os = "macos")]
     {
-        mpv_macos::set_audio_track(&app, id).await
+        let _ = (&app, url);
+        Err("Auto-advance is not supported on macOS yet".to_string())
     }
     #[cfg(target_os = "windows")]
     {
-        mpv_windows::set_audio_track(&app, id).await
+        mpv_windows::set_next_on_eof(&app, url);
+        Ok(())
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, url);
+        Err(MPV_UNSUPPORTED.to_string())
     }
 }
 
 #[tauri::command]
-async fn mpv_set_subtitle<R: Runtime>(app: AppHandle<R>, id: i64) -> Result<(), String> {
+async fn mpv_playlist_append<R: Runtime>(app: AppHandle<R>, url: String) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        mpv_macos::set_subtitle_track(&app, id).await
+        mpv_macos::playlist_append(&app, &url).await
     }
     #[cfg(target_os = "windows")]
     {
-        mpv_windows::set_subtitle_track(&app, id).await
+        mpv_windows::playlist_append(&app, &url).await
     }
-}
-
-#[tauri::command]
-async fn mpv_set_properties<R: Runtime>(
-    app: AppHandle<R>,
-    properties: Vec<(String, serde_json::Value)>,
-) -> Result<(), String> {
-    for (name, value) in properties {
-        #[cfg(target_os = "macos")]
-        {
-            mpv_macos::set_property(&app, name, value).await?;
-        }
-        #[cfg(target_os = "windows")]
-        {
-            mpv_windows::set_property(&app, name, value).await?;
-        }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, url);
+        Err(MPV_UNSUPPORTED.to_string())
     }
-    Ok(())
 }
 
 #[tauri::command]
-async fn mpv_set_property<R: Runtime>(
-    app: AppHandle<R>,
-    name: String,
-    value: serde_json::Value,
-) -> Result<(), String> {
+async fn mpv_playlist_clear<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        mpv_macos::set_property(&app, name, value).await
+        mpv_macos::playlist_clear(&app).await
     }
     #[cfg(target_os = "windows")]
     {
-        mpv_windows::set_property(&app, name, value).await
+        mpv_windows::playlist_clear(&app).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
     }
 }
 
 #[tauri::command]
-async fn mpv_get_property<R: Runtime>(app: AppHandle<R>, name: String) -> Result<serde_json::Value, String> {
+async fn mpv_playlist_next<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        mpv_macos::get_property(&app, &name).await
+        mpv_macos::playlist_next(&app).await
     }
     #[cfg(target_os = "windows")]
     {
-        mpv_windows::get_property(&app, name).await
+        mpv_windows::playlist_next(&app).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
     }
 }
 
 #[tauri::command]
-async fn mpv_sync_window<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
-    let window = app.get_webview_window("main").ok_or("Main window not found")?;
-    let pos = window.outer_position().map_err(|e| e.to_string())?;
-    let size = window.outer_size().map_err(|e| e.to_string())?;
-    
+async fn mpv_playlist_prev<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        mpv_macos::sync_window(&app, pos.x, pos.y, size.width, size.height).await
+        mpv_macos::playlist_prev(&app).await
     }
     #[cfg(target_os = "windows")]
     {
-        mpv_windows::sync_window(&app, pos.x, pos.y, size.width, size.height).await
+        mpv_windows::playlist_prev(&app).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
     }
 }
 
+/// MPV's `playlist` property: entries with `filename`, `title`, `current` and `playing`
 #[tauri::command]
-async fn mpv_kill<R: Runtime>(app: AppHandle<R>) {
+async fn mpv_get_playlist<R: Runtime>(app: AppHandle<R>) -> Result<serde_json::Value, String> {
     #[cfg(target_os = "macos")]
     {
-        mpv_macos::kill_mpv(&app).await;
+        mpv_macos::get_playlist(&app).await
     }
     #[cfg(target_os = "windows")]
     {
-        mpv_windows::kill_mpv(&app).await;
+        mpv_windows::get_playlist(&app).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
     }
 }
 
-/// Debug command to get cache-related MPV properties
-#[tauri::command]
-async fn mpv_get_cache_debug<R: Runtime>(app: AppHandle<R>) -> Result<serde_json::Value, String> {
-    use serde_json::json;
-
-    let mut result = serde_json::Map::new();
+/// One entry of MPV's `chapter-list`
+#[derive(Debug, Serialize)]
+struct MpvChapter {
+    index: usize,
+    title: Option<String>,
+    time: f64,
+}
 
-    // Get demuxer-max-back-bytes (the cache size setting)
-    let max_bytes = mpv_get_property(app.clone(), "demuxer-max-back-bytes".to_string()).await;
-    result.insert("demuxer-max-back-bytes".to_string(), max_bytes.unwrap_or(json!(null)));
+/// Chapters of the current file and the one being played
+#[derive(Debug, Serialize)]
+struct MpvChapters {
+    chapters: Vec<MpvChapter>,
+    current: Option<i64>,
+}
 
-    // Get demuxer-max-bytes
-    let max_bytes_fwd = mpv_get_property(app.clone(), "demuxer-max-bytes".to_string()).await;
-    result.insert("demuxer-max-bytes".to_string(), max_bytes_fwd.unwrap_or(json!(null)));
+#[tauri::command]
+async fn mpv_get_chapters<R: Runtime>(app: AppHandle<R>) -> Result<MpvChapters, String> {
+    #[cfg(target_os = "macos")]
+    let (list, current) = (mpv_macos::get_chapter_list(&app).await?, mpv_macos::get_chapter(&app).await);
+    #[cfg(target_os = "windows")]
+    let (list, current) = (mpv_windows::get_chapter_list(&app).await?, mpv_windows::get_chapter(&app).await);
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let (list, current): (serde_json::Value, Result<serde_json::Value, String>) = {
+        let _ = app;
+        (serde_json::Value::Null, Err(MPV_UNSUPPORTED.to_string()))
+    };
 
-    // Get cache property
-    let cache_enabled = mpv_get_property(app.clone(), "cache".to_string()).await;
-    result.insert("cache".to_string(), cache_enabled.unwrap_or(json!(null)));
+    let chapters = list
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .enumerate()
+                .map(|(index, entry)| MpvChapter {
+                    index,
+                    title: entry.get("title").and_then(|t| t.as_str()).map(str::to_string),
+                    time: entry.get("time").and_then(|t| t.as_f64()).unwrap_or(0.0),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // "chapter" is unavailable (an error) when the file has no chapters
+    let current = current.ok().and_then(|c| c.as_i64()).filter(|c| *c >= 0);
 
-    // Get demuxer-cache-state
-    let cache_state = mpv_get_property(app.clone(), "demuxer-cache-state".to_string()).await;
-    result.insert("demuxer-cache-state".to_string(), cache_state.unwrap_or(json!(null)));
+    Ok(MpvChapters { chapters, current })
+}
 
-    debug!("[MPV Debug] Cache settings: {:?}", result);
-    Ok(serde_json::Value::Object(result))
+#[tauri::command]
+async fn mpv_chapter_next<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        mpv_macos::add_chapter(&app, 1).await
+    }
+    #[cfg(target_os = "windows")]
+    {
+        mpv_windows::add_chapter(&app, 1).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
-/// Debug command to get the custom MPV parameters loaded from store
 #[tauri::command]
-async fn mpv_get_params_debug<R: Runtime>(app: AppHandle<R>) -> Result<serde_json::Value, String> {
+async fn mpv_chapter_prev<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        mpv_macos::add_chapter(&app, -1).await
+    }
+    #[cfg(target_os = "windows")]
+    {
+        mpv_windows::add_chapter(&app, -1).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
+}
+
+#[tauri::command]
+async fn mpv_set_chapter<R: Runtime>(app: AppHandle<R>, index: i64) -> Result<(), String> {
+    if index < 0 {
+        return Err(format!("Invalid chapter index: {}", index));
+    }
+    #[cfg(target_os = "macos")]
+    {
+        mpv_macos::set_chapter(&app, index).await
+    }
+    #[cfg(target_os = "windows")]
+    {
+        mpv_windows::set_chapter(&app, index).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, index);
+        Err(MPV_UNSUPPORTED.to_string())
+    }
+}
+
+#[tauri::command]
+async fn mpv_set_audio<R: Runtime>(app: AppHandle<R>, id: i64) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        mpv_macos::set_audio_track(&app, id).await
+    }
+    #[cfg(target_os = "windows")]
+    {
+        mpv_windows::set_audio_track(&app, id).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, id);
+        Err(MPV_UNSUPPORTED.to_string())
+    }
+}
+
+#[tauri::command]
+async fn mpv_set_subtitle<R: Runtime>(app: AppHandle<R>, id: i64) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        mpv_macos::set_subtitle_track(&app, id).await
+    }
+    #[cfg(target_os = "windows")]
+    {
+        mpv_windows::set_subtitle_track(&app, id).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, id);
+        Err(MPV_UNSUPPORTED.to_string())
+    }
+}
+
+#[tauri::command]
+async fn mpv_set_properties<R: Runtime>(
+    app: AppHandle<R>,
+    properties: Vec<(String, serde_json::Value)>,
+) -> Result<(), String> {
+    for (name, value) in properties {
+        #[cfg(target_os = "macos")]
+        {
+            mpv_macos::set_property(&app, name, value).await?;
+        }
+        #[cfg(target_os = "windows")]
+        {
+            mpv_windows::set_property(&app, name, value).await?;
+        }
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        {
+            let _ = (&app, name, value);
+            Err::<(), _>(MPV_UNSUPPORTED.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn mpv_set_property<R: Runtime>(
+    app: AppHandle<R>,
+    name: String,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        mpv_macos::set_property(&app, name, value).await
+    }
+    #[cfg(target_os = "windows")]
+    {
+        mpv_windows::set_property(&app, name, value).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, name, value);
+        Err(MPV_UNSUPPORTED.to_string())
+    }
+}
+
+#[tauri::command]
+async fn mpv_get_property<R: Runtime>(app: AppHandle<R>, name: String) -> Result<serde_json::Value, String> {
+    #[cfg(target_os = "macos")]
+    {
+        mpv_macos::get_property(&app, &name).await
+    }
+    #[cfg(target_os = "windows")]
+    {
+        mpv_windows::get_property(&app, name).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, name);
+        Err(MPV_UNSUPPORTED.to_string())
+    }
+}
+
+/// Whether MPV is running, connected over IPC and has a file loaded
+#[derive(Debug, Default, Serialize)]
+struct MpvReadyState {
+    running: bool,
+    connected: bool,
+    file_loaded: bool,
+}
+
+/// Query MPV readiness directly, for when the one-shot `mpv-ready` event was missed
+#[tauri::command]
+async fn mpv_is_ready<R: Runtime>(app: AppHandle<R>) -> Result<MpvReadyState, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let state = app.state::<MpvState>();
+        let running = state.process.lock().unwrap().is_some();
+        let connected = state.socket.lock().unwrap().is_some();
+        let file_loaded = connected && state.current_url.lock().unwrap().is_some();
+        Ok(MpvReadyState { running, connected, file_loaded })
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let running = mpv_windows::is_running(&app);
+        let connected = *app.state::<MpvState>().socket_connected.lock().unwrap();
+        let file_loaded = connected && mpv_windows::is_file_loaded(&app).await;
+        Ok(MpvReadyState { running, connected, file_loaded })
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Ok(MpvReadyState::default())
+    }
+}
+
+#[tauri::command]
+async fn mpv_sync_window<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("Main window not found")?;
+    let pos = window.outer_position().map_err(|e| e.to_string())?;
+    let size = window.outer_size().map_err(|e| e.to_string())?;
+    
+    #[cfg(target_os = "macos")]
+    {
+        mpv_macos::sync_window(&app, pos.x, pos.y, size.width, size.height).await?;
+        // Multiview slots are placed in screen coordinates, so they follow the window too
+        mpv_secondary::sync_slots(&app).await
+    }
+    #[cfg(target_os = "windows")]
+    {
+        mpv_windows::sync_window(&app, pos.x, pos.y, size.width, size.height).await
+    }
+    #[cfg(target_os = "linux")]
+    {
+        // No main player to move, but the slot windows still follow the app
+        let _ = (pos, size);
+        mpv_secondary::sync_slots(&app).await
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (app, pos, size);
+        Ok(())
+    }
+}
+
+#[tauri::command]
+async fn mpv_kill<R: Runtime>(app: AppHandle<R>) {
+    #[cfg(target_os = "macos")]
+    {
+        mpv_macos::kill_mpv(&app).await;
+    }
+    #[cfg(target_os = "windows")]
+    {
+        mpv_windows::kill_mpv(&app).await;
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+    }
+}
+
+/// Debug command to get cache-related MPV properties
+#[tauri::command]
+async fn mpv_get_cache_debug<R: Runtime>(app: AppHandle<R>) -> Result<serde_json::Value, String> {
+    use serde_json::json;
+
+    let mut result = serde_json::Map::new();
+
+    // Get demuxer-max-back-bytes (the cache size setting)
+    let max_bytes = mpv_get_property(app.clone(), "demuxer-max-back-bytes".to_string()).await;
+    result.insert("demuxer-max-back-bytes".to_string(), max_bytes.unwrap_or(json!(null)));
+
+    // Get demuxer-max-bytes
+    let max_bytes_fwd = mpv_get_property(app.clone(), "demuxer-max-bytes".to_string()).await;
+    result.insert("demuxer-max-bytes".to_string(), max_bytes_fwd.unwrap_or(json!(null)));
+
+    // Get cache property
+    let cache_enabled = mpv_get_property(app.clone(), "cache".to_string()).await;
+    result.insert("cache".to_string(), cache_enabled.unwrap_or(json!(null)));
+
+    // Get demuxer-cache-state
+    let cache_state = mpv_get_property(app.clone(), "demuxer-cache-state".to_string()).await;
+    result.insert("demuxer-cache-state".to_string(), cache_state.unwrap_or(json!(null)));
+
+    debug!("[MPV Debug] Cache settings: {:?}", result);
+    Ok(serde_json::Value::Object(result))
+}
+
+/// Options from the user's mpv.conf as `--key=value` arguments, so they can be sanitized
+/// like typed arguments rather than handed to MPV with `--include`
+fn mpv_config_args<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let Some(path) = read_store_setting(app, "mpvConfigPath")
+        .and_then(|v| v.as_str().map(|p| p.trim().to_string()))
+        .filter(|p| !p.is_empty())
+    else {
+        return Vec::new();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => parse_mpv_conf(&contents),
+        Err(e) => {
+            warn!("[MPV] Failed to read mpv.conf {}, ignoring: {}", path, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Turn mpv.conf lines (`key=value`, `key`, `# comment`) into `--key=value` arguments.
+/// Options inside `[profile]` sections only apply when that profile is used, so they are skipped.
+fn parse_mpv_conf(contents: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut in_profile = false;
+
+    for line in contents.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_profile = !line.eq_ignore_ascii_case("[default]");
+            continue;
+        }
+        if in_profile {
+            continue;
+        }
+
+        let option = line.trim_start_matches("--");
+        let arg = match option.split_once('=') {
+            Some((key, value)) => {
+                let value = value.trim();
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .unwrap_or(value);
+                format!("--{}={}", key.trim(), value)
+            }
+            None => format!("--{}", option),
+        };
+        args.push(arg);
+    }
+
+    args
+}
+
+/// User-supplied MPV options: extra arguments plus an optional mpv.conf to include
+#[derive(Debug, Serialize, Deserialize)]
+struct MpvExtraArgs {
+    args: Vec<String>,
+    config_path: Option<String>,
+}
+
+/// Get the extra MPV arguments and mpv.conf path from settings
+#[tauri::command]
+async fn get_mpv_extra_args<R: Runtime>(app: AppHandle<R>) -> Result<MpvExtraArgs, String> {
+    let args = read_store_setting(&app, "mpvParams")
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    let config_path = read_store_setting(&app, "mpvConfigPath")
+        .and_then(|v| v.as_str().map(str::to_string))
+        .filter(|p| !p.trim().is_empty());
+
+    Ok(MpvExtraArgs { args, config_path })
+}
+
+/// Save extra MPV arguments and an optional mpv.conf path (applied on the next MPV start).
+/// Arguments reserved for embedding/IPC are rejected here so the user sees why.
+#[tauri::command]
+async fn set_mpv_extra_args<R: Runtime>(
+    app: AppHandle<R>,
+    args: Vec<String>,
+    config_path: Option<String>,
+) -> Result<(), String> {
+    let args: Vec<String> = args.into_iter()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect();
+    if let Some(bad) = args.iter().find(|a| !a.starts_with("--")) {
+        return Err(format!("MPV arguments must start with --: {}", bad));
+    }
+    if let Some(reserved) = args.iter().find(|a| RESERVED_MPV_KEYS.contains(&mpv_arg_key(a))) {
+        return Err(format!("{} is managed by the app and can't be overridden", reserved));
+    }
+
+    let config_path = config_path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(path) = &config_path {
+        if !std::path::Path::new(path).is_file() {
+            return Err(format!("mpv.conf not found: {}", path));
+        }
+    }
+
+    write_store_setting(&app, "mpvParams", serde_json::Value::String(args.join("\n")))?;
+    write_store_setting(&app, "mpvConfigPath", config_path.map(serde_json::Value::String).unwrap_or(serde_json::Value::Null))
+}
+
+/// Debug command to get the custom MPV parameters loaded from store
+#[tauri::command]
+async fn mpv_get_params_debug<R: Runtime>(app: AppHandle<R>) -> Result<serde_json::Value, String> {
     use serde_json::json;
 
     let raw_params = get_mpv_params_from_store(&app).await;
@@ -762,6 +1576,11 @@ async fn mpv_toggle_stats<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
         use serde_json::json;
         mpv_windows::send_command(&app, "script-binding", vec![json!("stats/display-stats-toggle")]).await?;
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err::<(), _>(MPV_UNSUPPORTED.to_string())?;
+    }
     Ok(())
 }
 
@@ -783,6 +1602,11 @@ async fn mpv_set_geometry<R: Runtime>(
     {
         mpv_windows::mpv_set_geometry(&app, x, y, width, height).await
     }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (app, x, y, width, height);
+        Err(MPV_UNSUPPORTED.to_string())
+    }
 }
 
 // ============================================================================
@@ -799,9 +1623,9 @@ async fn multiview_load_slot<R: Runtime>(
     width: u32,
     height: u32,
 ) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
     { mpv_secondary::load_slot(&app, slot_id, url, x, y, width, height).await }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     { let _ = (slot_id, url, x, y, width, height); Ok(()) }
 }
 
@@ -810,9 +1634,9 @@ async fn multiview_stop_slot<R: Runtime>(
     app: AppHandle<R>,
     slot_id: u8,
 ) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
     { mpv_secondary::stop_slot(&app, slot_id).await }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     { let _ = slot_id; Ok(()) }
 }
 
@@ -823,9 +1647,9 @@ async fn multiview_set_property_slot<R: Runtime>(
     property: String,
     value: serde_json::Value,
 ) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
     { mpv_secondary::set_property_slot(&app, slot_id, &property, value).await }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     { let _ = (slot_id, property, value); Ok(()) }
 }
 
@@ -838,9 +1662,9 @@ async fn multiview_reposition_slot<R: Runtime>(
     width: u32,
     height: u32,
 ) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
     { mpv_secondary::reposition_slot(&app, slot_id, x, y, width, height).await }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     { let _ = (slot_id, x, y, width, height); Ok(()) }
 }
 
@@ -849,9 +1673,21 @@ async fn multiview_kill_slot<R: Runtime>(
     app: AppHandle<R>,
     slot_id: u8,
 ) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    { mpv_secondary::kill_slot(&app, slot_id).await; Ok(()) }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    { mpv_secondary::close_slot(&app, slot_id).await; Ok(()) }
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    { let _ = slot_id; Ok(()) }
+}
+
+/// Play audio from one multiview slot (1 = main player) and mute the rest
+#[tauri::command]
+async fn multiview_set_audio_focus<R: Runtime>(
+    app: AppHandle<R>,
+    slot_id: u8,
+) -> Result<(), String> {
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    { mpv_secondary::set_audio_focus(&app, slot_id).await }
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     { let _ = slot_id; Ok(()) }
 }
 
@@ -859,9 +1695,9 @@ async fn multiview_kill_slot<R: Runtime>(
 async fn multiview_kill_all<R: Runtime>(
     app: AppHandle<R>,
 ) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
     { mpv_secondary::kill_all(&app).await; Ok(()) }
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     { Ok(()) }
 }
 
@@ -897,6 +1733,22 @@ async fn schedule_recording(
     debug!("[DVR Command]   source_id: {}, channel_id: {}", request.source_id, request.channel_id);
     debug!("[DVR Command]   scheduled_start: {}, scheduled_end: {}", request.scheduled_start, request.scheduled_end);
 
+    // Make sure the recording will fit on the storage volume
+    let (start_padding, end_padding) = state.db.resolve_padding(&request)
+        .map_err(|e| format!("Failed to resolve padding: {}", e))?;
+    let duration = (request.scheduled_end + end_padding as i64)
+        - (request.scheduled_start - start_padding as i64);
+    let (estimated_bytes, free_bytes) = dvr::cleanup::check_space_for_recording(&state.db, duration);
+    if let Some(free) = free_bytes {
+        if estimated_bytes > free {
+            return Err(format!(
+                "Not enough disk space: recording needs about {} MB but only {} MB is free",
+                estimated_bytes / 1_000_000,
+                free / 1_000_000
+            ));
+        }
+    }
+
     // NOTE: For Stalker sources, we should NOT pre-resolve the URL because tokens expire quickly.
     // The URL will be resolved at recording time via resolve_dvr_stream_url command.
     // If a pre-resolved URL is provided for non-Stalker sources, it will be stored.
@@ -911,6 +1763,128 @@ async fn schedule_recording(
     Ok(id)
 }
 
+/// Record the currently playing channel from now for `duration_minutes`
+///
+/// The recording is started right away rather than on the next scheduler poll; the
+/// stream URL is resolved at start like any other schedule, since Stalker tokens expire.
+#[tauri::command]
+async fn record_now(
+    state: tauri::State<'_, DvrState>,
+    duration_minutes: u32,
+) -> Result<i64, String> {
+    if duration_minutes == 0 {
+        return Err("Duration must be at least one minute".to_string());
+    }
+
+    let playing = state.get_playing_stream().await;
+    let (source_id, channel_id) = match (playing.is_playing, playing.source_id, playing.channel_id) {
+        (true, Some(source_id), Some(channel_id)) => (source_id, channel_id),
+        _ => return Err("Nothing is playing".to_string()),
+    };
+    let channel_name = playing.channel_name.unwrap_or_else(|| channel_id.clone());
+
+    let now = chrono::Utc::now().timestamp();
+    let end = now + duration_minutes as i64 * 60;
+
+    let (conflicts, max_connections) = state.db.check_conflicts(&source_id, now, end)
+        .map_err(|e| format!("Failed to check conflicts: {}", e))?;
+    let max_conn = max_connections.filter(|n| *n > 0).unwrap_or(1);
+    if conflicts.len() as i32 >= max_conn {
+        return Err(format!(
+            "Source is at its connection limit ({} max) with {} overlapping recording(s)",
+            max_conn,
+            conflicts.len()
+        ));
+    }
+
+    let program_title = state.db.get_now_next(&source_id, &channel_id, now)
+        .ok()
+        .and_then(|now_next| now_next.now)
+        .map(|program| program.title)
+        .unwrap_or_else(|| format!("{} (Manual)", channel_name));
+
+    let request = ScheduleRequest {
+        source_id,
+        channel_id,
+        channel_name,
+        program_title,
+        scheduled_start: now,
+        scheduled_end: end,
+        start_padding_sec: Some(0),
+        end_padding_sec: Some(0),
+        series_match_title: None,
+        recurrence: None,
+        stream_url: None,
+        auto_extend_minutes: 0,
+        priority: 0,
+    };
+
+    let id = state.db.add_schedule(&request)
+        .map_err(|e| format!("Failed to schedule recording: {}", e))?;
+
+    info!("[DVR Command] Record now: '{}' on {} for {} min (schedule {})",
+        request.program_title, request.channel_name, duration_minutes, id);
+
+    // If this fails the schedule is still in its window, so the scheduler starts it on the next poll
+    if let Err(e) = state.recorder.start_recording_immediate(id) {
+        warn!("[DVR Command] Immediate start failed for schedule {}, leaving it to the scheduler: {}", id, e);
+    }
+    Ok(id)
+}
+
+/// Schedule every upcoming airing of a series on a channel
+///
+/// The series is saved as a rule, so the scheduler keeps picking up new
+/// airings as the EPG is refreshed.
+#[tauri::command]
+async fn schedule_series(
+    state: tauri::State<'_, DvrState>,
+    source_id: String,
+    channel_id: String,
+    series_title: String,
+) -> Result<Vec<i64>, String> {
+    debug!("[DVR Command] schedule_series called: {} on {}", series_title, channel_id);
+
+    let channel_name = state.db.get_channel_by_id(&channel_id)
+        .map_err(|e| format!("Failed to look up channel: {}", e))?
+        .map(|c| c.name)
+        .unwrap_or_else(|| channel_id.clone());
+
+    let mut rule = SeriesRule {
+        id: 0,
+        source_id,
+        channel_id,
+        channel_name,
+        series_title,
+        start_padding_sec: None,
+        end_padding_sec: None,
+    };
+
+    rule.id = state.db.add_series_rule(&rule)
+        .map_err(|e| format!("Failed to save series rule: {}", e))?;
+
+    let ids = dvr::scheduler::schedule_series_airings(&state.db, &rule)
+        .map_err(|e| format!("Failed to schedule series: {}", e))?;
+
+    debug!("[DVR Command] schedule_series created {} schedule(s)", ids.len());
+    Ok(ids)
+}
+
+/// Get all series recording rules
+#[tauri::command]
+async fn get_series_rules(state: tauri::State<'_, DvrState>) -> Result<Vec<SeriesRule>, String> {
+    state.db.get_series_rules()
+        .map_err(|e| format!("Failed to get series rules: {}", e))
+}
+
+/// Delete a series recording rule (already scheduled airings are kept)
+#[tauri::command]
+async fn delete_series_rule(state: tauri::State<'_, DvrState>, id: i64) -> Result<(), String> {
+    debug!("[DVR Command] delete_series_rule called: {}", id);
+    state.db.delete_series_rule(id)
+        .map_err(|e| format!("Failed to delete series rule: {}", e))
+}
+
 /// Update the stream URL for a schedule (used by frontend to provide resolved Stalker URLs)
 #[tauri::command]
 async fn update_dvr_stream_url(
@@ -924,6 +1898,11 @@ async fn update_dvr_stream_url(
     state.db.update_schedule_stream_url(schedule_id, &stream_url)
         .map_err(|e| format!("Failed to update stream URL: {}", e))?;
 
+    // Let back-to-back recordings of the channel reuse this resolution for a while
+    if let Ok(Some(schedule)) = state.db.get_schedule(schedule_id) {
+        dvr::stream_resolver::cache_stalker_url(&schedule.source_id, &schedule.channel_id, &stream_url);
+    }
+
     debug!("[DVR Command] Stream URL updated successfully for schedule {}", schedule_id);
     Ok(())
 }
@@ -949,53 +1928,193 @@ async fn cancel_recording(
 ) -> Result<(), String> {
     debug!("[DVR Command] cancel_recording called for schedule {}", id);
 
-    // First check if this is currently recording - if so, stop it
-    let schedule = state.db.get_schedule(id)
-        .map_err(|e| format!("Failed to get schedule: {}", e))?;
-
-    if let Some(ref s) = schedule {
-        if matches!(s.status, crate::dvr::models::ScheduleStatus::Recording) {
-            debug!("[DVR Command] Recording is active, stopping FFmpeg process...");
-            state.recorder.stop_recording(id).await
-                .map_err(|e| format!("Failed to stop recording: {}", e))?;
-        }
-    }
-
-    // Cancel the schedule
-    state.db.cancel_schedule(id)
+    // Stops the recording if it is active, and takes it out of the queue
+    state.recorder.cancel_schedule(id).await
         .map_err(|e| format!("Failed to cancel recording: {}", e))?;
 
     debug!("[DVR Command] Recording {} canceled successfully", id);
     Ok(())
 }
 
-/// Delete a recording (file + thumbnail + database)
+/// Pause an in-progress recording; resuming continues into a new part
+#[tauri::command]
+async fn pause_recording(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+) -> Result<(), String> {
+    state.recorder.pause_recording(id)
+        .map_err(|e| format!("Failed to pause recording: {}", e))
+}
+
+/// Resume a paused recording
+#[tauri::command]
+async fn resume_recording(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+) -> Result<(), String> {
+    state.recorder.resume_recording(id)
+        .map_err(|e| format!("Failed to resume recording: {}", e))
+}
+
+/// Delete a recording (file + thumbnail + database), including every part of a split recording
 #[tauri::command]
 async fn delete_recording(
     state: tauri::State<'_, DvrState>,
     id: i64,
 ) -> Result<(), String> {
-    // Get file path and thumbnail path first
-    let paths = state.db.delete_recording(id)
-        .map_err(|e| format!("Failed to delete recording: {}", e))?;
-
-    // Delete video file if it exists
-    if let Some((file_path, thumbnail_path)) = paths {
-        if std::path::Path::new(&file_path).exists() {
-            let _ = tokio::fs::remove_file(file_path).await;
-        }
+    let parts = state.db.get_recording_parts(id)
+        .map_err(|e| format!("Failed to get recording: {}", e))?;
 
-        // Delete thumbnail if it exists
-        if let Some(thumb_path) = thumbnail_path {
-            if std::path::Path::new(&thumb_path).exists() {
-                let _ = tokio::fs::remove_file(thumb_path).await;
-            }
-        }
+    for part in parts {
+        dvr::recorder::delete_recording_with_files(&state.db, &part).await
+            .map_err(|e| format!("Failed to delete recording: {}", e))?;
     }
 
     Ok(())
 }
 
+/// Delete several recordings at once, stopping any that are still recording
+#[tauri::command]
+async fn bulk_delete_recordings(
+    state: tauri::State<'_, DvrState>,
+    ids: Vec<i64>,
+) -> Result<dvr::recorder::BulkRecordingDeletion, String> {
+    Ok(state.recorder.bulk_delete_recordings(ids).await)
+}
+
+/// Save a copy of a recording elsewhere (e.g. a USB drive), optionally remuxed to mp4/mkv
+#[tauri::command]
+async fn export_recording(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+    dest_path: String,
+    remux_to: Option<String>,
+) -> Result<String, String> {
+    state.recorder.export_recording(id, std::path::Path::new(&dest_path), remux_to.as_deref()).await
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| format!("Failed to export recording: {:#}", e))
+}
+
+/// Resolve a channel's stream URL the same way recordings resolve it
+async fn resolve_channel_url(
+    db: &dvr::database::DvrDatabase,
+    source_id: String,
+    channel_id: String,
+) -> Result<String, String> {
+    if let Some(url) = dvr::stream_resolver::cached_stalker_url(&source_id, &channel_id) {
+        return Ok(url);
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let lookup = Schedule {
+        id: 0,
+        source_id,
+        channel_id: channel_id.clone(),
+        channel_name: channel_id,
+        program_title: String::new(),
+        scheduled_start: now,
+        scheduled_end: now,
+        start_padding_sec: 0,
+        end_padding_sec: 0,
+        status: ScheduleStatus::Scheduled,
+        series_match_title: None,
+        recurrence: None,
+        created_at: now,
+        started_at: None,
+        stream_url: None,
+        auto_extend_minutes: 0,
+        priority: 0,
+    };
+    dvr::stream_resolver::resolve_stream_url(&lookup, db).await
+        .map_err(|e| format!("Failed to resolve stream URL: {}", e))
+}
+
+/// Probe a stream with ffprobe for codec, resolution, bitrate and audio tracks.
+///
+/// Pass `url` to probe it directly, or `source_id` + `channel_id` to probe a channel;
+/// channel URLs (and `stalker_` placeholders) are resolved the same way recordings resolve them.
+#[tauri::command]
+async fn probe_stream(
+    state: tauri::State<'_, DvrState>,
+    url: Option<String>,
+    source_id: Option<String>,
+    channel_id: Option<String>,
+) -> Result<dvr::models::StreamProbe, String> {
+    let direct = url.filter(|u| !u.is_empty() && !u.starts_with("stalker_"));
+
+    let url = match (direct, source_id, channel_id) {
+        (Some(url), _, _) => url,
+        (None, Some(source_id), Some(channel_id)) => {
+            resolve_channel_url(&state.db, source_id, channel_id).await?
+        }
+        _ => return Err("Provide a stream URL or a source and channel".to_string()),
+    };
+
+    state.recorder.probe_stream(&url).await
+        .map_err(|e| format!("Failed to probe stream: {:#}", e))
+}
+
+/// Record a short test clip of a channel to check it can be recorded.
+///
+/// The clip is probed for codecs and deleted; `seconds` defaults to 10 (max 60).
+/// It uses one of the source's connections, so it fails while recordings use them all.
+#[tauri::command]
+async fn test_recording(
+    state: tauri::State<'_, DvrState>,
+    source_id: String,
+    channel_id: String,
+    seconds: Option<u32>,
+) -> Result<dvr::models::TestRecordingResult, String> {
+    let url = resolve_channel_url(&state.db, source_id.clone(), channel_id).await?;
+    state.recorder.test_recording(&source_id, &url, seconds.unwrap_or(10)).await
+        .map_err(|e| format!("Failed to test recording: {:#}", e))
+}
+
+/// Get the FFmpeg output captured while a recording ran (empty if none)
+#[tauri::command]
+async fn get_recording_log(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+) -> Result<String, String> {
+    state.recorder.get_recording_log(id)
+        .map_err(|e| format!("Failed to get recording log: {}", e))
+}
+
+/// Recompute a recording's file size and duration (size from disk, duration from ffprobe)
+#[tauri::command]
+async fn repair_recording_metadata(
+    state: tauri::State<'_, DvrState>,
+    recording_id: i64,
+) -> Result<Recording, String> {
+    state.recorder.repair_recording_metadata(recording_id).await
+        .map_err(|e| format!("Failed to repair recording: {:#}", e))
+}
+
+/// Join several recordings (e.g. the parts of a split or paused recording) into one file.
+/// Returns the ID of the joined recording.
+#[tauri::command]
+async fn concat_recording(
+    state: tauri::State<'_, DvrState>,
+    recording_ids: Vec<i64>,
+) -> Result<i64, String> {
+    state.recorder.concat_recordings(&recording_ids).await
+        .map_err(|e| format!("Failed to join recordings: {}", e))
+}
+
+/// Cut the padding off a recording. With `keep_original` the trimmed copy is added as a
+/// new recording; otherwise it replaces the original. Returns the trimmed recording's ID.
+#[tauri::command]
+async fn trim_recording(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+    start_seconds: f64,
+    end_seconds: f64,
+    keep_original: Option<bool>,
+) -> Result<i64, String> {
+    state.recorder.trim_recording(id, start_seconds, end_seconds, keep_original.unwrap_or(false)).await
+        .map_err(|e| format!("Failed to trim recording: {:#}", e))
+}
+
 /// Get all completed recordings
 #[tauri::command]
 async fn get_completed_recordings(
@@ -1007,6 +2126,69 @@ async fn get_completed_recordings(
     Ok(recordings)
 }
 
+/// Total size of finished recordings and the storage volume's capacity, for a usage bar
+#[tauri::command]
+async fn get_recordings_disk_usage(
+    state: tauri::State<'_, DvrState>,
+) -> Result<RecordingsDiskUsage, String> {
+    dvr::cleanup::get_recordings_disk_usage(&state.db)
+        .map_err(|e| format!("Failed to get disk usage: {}", e))
+}
+
+/// Page through recordings with optional status/channel/date filters
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn query_recordings(
+    state: tauri::State<'_, DvrState>,
+    status: Option<String>,
+    channel_name: Option<String>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+    sort: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<RecordingPage, String> {
+    let status = status
+        .map(|s| s.parse::<RecordingStatus>())
+        .transpose()?;
+    let sort = match sort {
+        Some(sort) => sort.parse::<RecordingSort>()?,
+        None => RecordingSort::default(),
+    };
+
+    state.db.query_recordings(
+        status,
+        channel_name.as_deref(),
+        from_ts,
+        to_ts,
+        sort,
+        limit.unwrap_or(100),
+        offset.unwrap_or(0),
+    )
+    .map_err(|e| format!("Failed to query recordings: {}", e))
+}
+
+/// Protect a recording from automatic cleanup (or remove the protection)
+#[tauri::command]
+async fn set_recording_protected(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+    protected: bool,
+) -> Result<(), String> {
+    state.db.set_recording_protected(id, protected)
+        .map_err(|e| format!("Failed to update recording protection: {}", e))
+}
+
+/// Stop an in-progress recording early and keep it as a completed recording
+#[tauri::command]
+async fn stop_and_keep_recording(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+) -> Result<(), String> {
+    state.recorder.stop_and_keep(id)
+        .map_err(|e| format!("Failed to stop recording: {}", e))
+}
+
 /// Get active recordings with live progress
 #[tauri::command]
 async fn get_active_recordings(
@@ -1016,6 +2198,23 @@ async fn get_active_recordings(
     Ok(progress)
 }
 
+/// Get live status for a single schedule, None if it is neither recording nor queued
+#[tauri::command]
+async fn get_recording_status(
+    state: tauri::State<'_, DvrState>,
+    schedule_id: i64,
+) -> Result<Option<dvr::recorder::RecordingProgress>, String> {
+    Ok(state.recorder.get_recording_status(schedule_id))
+}
+
+/// Get due recordings waiting for their source to free a connection
+#[tauri::command]
+async fn get_queued_recordings(
+    state: tauri::State<'_, DvrState>,
+) -> Result<Vec<dvr::recorder::QueuedRecording>, String> {
+    Ok(state.recorder.get_queued_recordings())
+}
+
 /// Get thumbnail image for a recording
 #[tauri::command]
 async fn get_recording_thumbnail(
@@ -1047,6 +2246,42 @@ async fn get_recording_thumbnail(
     }
 }
 
+/// Get the scrub bar preview sprite and its WebVTT index for a recording.
+/// The sprite is served through the asset protocol rather than copied over IPC.
+#[tauri::command]
+async fn get_recording_sprite(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DvrState>,
+    recording_id: i64,
+) -> Result<Option<RecordingSprite>, String> {
+    let recording = state.db.get_recording(recording_id)
+        .map_err(|e| format!("Failed to get recording: {}", e))?
+        .ok_or("Recording not found")?;
+
+    let Some(sprite_path) = recording.sprite_path else {
+        return Ok(None);
+    };
+
+    let sprite_path = std::path::PathBuf::from(sprite_path);
+    let index = match tokio::fs::read_to_string(sprite_path.with_extension("vtt")).await {
+        Ok(index) if sprite_path.is_file() => index,
+        Ok(_) => {
+            warn!("[DVR] Sprite image not found: {:?}", sprite_path);
+            return Ok(None);
+        }
+        Err(e) => {
+            warn!("[DVR] Sprite index not found or unreadable: {:?} - {}", sprite_path, e);
+            return Ok(None);
+        }
+    };
+
+    // Recordings can live anywhere, so each sprite is let into the asset scope as it's asked for
+    app.asset_protocol_scope()
+        .allow_file(&sprite_path)
+        .map_err(|e| format!("Failed to allow sprite: {}", e))?;
+    Ok(Some(RecordingSprite { image_url: asset_url(&sprite_path), index }))
+}
+
 /// Update schedule padding times
 #[tauri::command]
 async fn update_schedule_paddings(
@@ -1064,7 +2299,43 @@ async fn update_schedule_paddings(
     Ok(())
 }
 
-/// Check for schedule conflicts including connection limits
+/// Set a schedule's priority for connection contention (higher wins)
+#[tauri::command]
+async fn set_schedule_priority(
+    state: tauri::State<'_, DvrState>,
+    id: i64,
+    priority: i32,
+) -> Result<(), String> {
+    state.db.update_schedule_priority(id, priority)
+        .map_err(|e| format!("Failed to update schedule priority: {}", e))?;
+    state.recorder.set_priority(id, priority);
+    Ok(())
+}
+
+/// Get the padding override for a source (None fields use the global default)
+#[tauri::command]
+async fn get_source_padding(
+    state: tauri::State<'_, DvrState>,
+    source_id: String,
+) -> Result<SourcePadding, String> {
+    state.db.get_source_padding(&source_id)
+        .map_err(|e| format!("Failed to get source padding: {}", e))
+}
+
+/// Set or clear the padding override for a source
+#[tauri::command]
+async fn set_source_padding(
+    state: tauri::State<'_, DvrState>,
+    source_id: String,
+    start_padding_sec: Option<i32>,
+    end_padding_sec: Option<i32>,
+) -> Result<(), String> {
+    state.db.set_source_padding(&source_id, start_padding_sec, end_padding_sec)
+        .map_err(|e| format!("Failed to set source padding: {}", e))
+}
+
+/// Check for schedule conflicts including connection limits, with ways out when there is one:
+/// a later free start, or the priority that would preempt an overlapping recording
 #[tauri::command]
 async fn check_schedule_conflicts(
     state: tauri::State<'_, DvrState>,
@@ -1072,19 +2343,27 @@ async fn check_schedule_conflicts(
     channel_id: String,
     start: i64,
     end: i64,
+    priority: Option<i32>,
 ) -> Result<ScheduleConflict, String> {
     let (conflicts, max_connections) = state.db.check_conflicts(&source_id, start, end)
         .map_err(|e| format!("Failed to check conflicts: {}", e))?;
 
-    // Check if max connections would be exceeded
-    let max_conn = max_connections.unwrap_or(1);
-    let would_exceed_limit = conflicts.len() as i32 >= max_conn;
+    // Check if max connections would be exceeded; playback only counts if the window has started
+    let max_conn = max_connections.filter(|n| *n > 0).unwrap_or(1);
+    let now = chrono::Utc::now().timestamp();
+    let viewers = if start <= now && end > now { state.viewer_connections(&source_id) } else { 0 };
+    let would_exceed_limit = (conflicts.len() + viewers) as i32 >= max_conn;
+    let connections_in_use = state.connections_in_use(&source_id);
     
     // Check if user is currently watching this source
     let viewing_conflict = state.check_viewing_conflict(&source_id, &channel_id).await
         .map_err(|e| format!("Failed to check viewing conflict: {}", e))?;
 
-    let has_conflict = !conflicts.is_empty() || would_exceed_limit || viewing_conflict;
+    // Check if the recording would fit on the storage volume
+    let (estimated_bytes, free_bytes) = dvr::cleanup::check_space_for_recording(&state.db, end - start);
+    let insufficient_space = free_bytes.map(|free| estimated_bytes > free).unwrap_or(false);
+
+    let has_conflict = !conflicts.is_empty() || would_exceed_limit || viewing_conflict || insufficient_space;
     
     let message = if has_conflict {
         let mut parts = Vec::new();
@@ -1097,18 +2376,67 @@ async fn check_schedule_conflicts(
         if viewing_conflict {
             parts.push("you are currently watching this source".to_string());
         }
+        if insufficient_space {
+            parts.push(format!(
+                "not enough disk space (~{} MB needed, {} MB free)",
+                estimated_bytes / 1_000_000,
+                free_bytes.unwrap_or(0) / 1_000_000
+            ));
+        }
         Some(format!("Conflict: {}", parts.join(", ")))
     } else {
         None
     };
 
+    let (suggested_start, preemptable_schedule_ids, priority_to_preempt) = if would_exceed_limit {
+        let priority = priority.unwrap_or(0);
+        (
+            state.db.suggest_free_start(&source_id, start, end, max_conn, state.viewer_connections(&source_id), &conflicts),
+            conflicts.iter().filter(|s| s.priority < priority).map(|s| s.id).collect(),
+            conflicts.iter().map(|s| s.priority).min().map(|lowest| lowest + 1),
+        )
+    } else {
+        (None, Vec::new(), None)
+    };
+
     Ok(ScheduleConflict {
         has_conflict,
+        conflicting_schedule_ids: conflicts.iter().map(|s| s.id).collect(),
         conflicts,
         message,
+        estimated_bytes,
+        free_bytes,
+        insufficient_space,
+        connections_in_use,
+        max_connections: max_conn,
+        suggested_start,
+        preemptable_schedule_ids,
+        priority_to_preempt,
     })
 }
 
+/// Get free bytes on the DVR storage volume
+#[tauri::command]
+async fn get_storage_free_space(
+    state: tauri::State<'_, DvrState>,
+) -> Result<u64, String> {
+    dvr::cleanup::get_storage_free_space(&state.db)
+        .map_err(|e| format!("Failed to get free space: {}", e))
+}
+
+/// Get the current and next program for a channel
+#[tauri::command]
+async fn get_now_next(
+    state: tauri::State<'_, DvrState>,
+    source_id: String,
+    stream_id: String,
+    now_ts: Option<i64>,
+) -> Result<NowNext, String> {
+    let now = now_ts.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    state.db.get_now_next(&source_id, &stream_id, now)
+        .map_err(|e| format!("Failed to get now/next: {}", e))
+}
+
 /// Update currently playing stream information
 #[tauri::command]
 async fn update_playing_stream(
@@ -1141,20 +2469,142 @@ async fn get_dvr_settings(
     let settings = state.db.get_settings()
         .map_err(|e| format!("Failed to get settings: {}", e))?;
 
-    Ok(settings)
+    Ok(settings)
+}
+
+/// Save DVR setting
+#[tauri::command]
+async fn save_dvr_setting(
+    state: tauri::State<'_, DvrState>,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    if key == "filename_template" {
+        dvr::recorder::validate_filename_template(&value)
+            .map_err(|e| format!("Invalid filename template: {}", e))?;
+    }
+    if key == "transcode_profile" && !dvr::transcode::TRANSCODE_PROFILES.contains(&value.as_str()) {
+        return Err(format!("Unknown transcode profile: {}", value));
+    }
+    if key == "storage_path" {
+        validate_storage_path(&value)?;
+    }
+    if key == "hls_start_mode" && !dvr::recorder::HLS_START_MODES.contains(&value.as_str()) {
+        return Err(format!("Unknown HLS start mode: {}", value));
+    }
+    if key == "timezone" && !value.is_empty() && value.parse::<chrono_tz::Tz>().is_err() {
+        return Err(format!("Unknown timezone: {}", value));
+    }
+
+    state.db.save_setting(&key, &value)
+        .map_err(|e| format!("Failed to save setting: {}", e))?;
+
+    Ok(())
+}
+
+/// Result of validating a DVR storage directory
+#[derive(Debug, Serialize)]
+struct StoragePathInfo {
+    path: String,
+    /// Free bytes on the volume, None if it couldn't be determined
+    free_bytes: Option<u64>,
+}
+
+/// Make sure recordings can be written to `path` (empty = default location)
+fn validate_storage_path(path: &str) -> Result<StoragePathInfo, String> {
+    let dir = if path.is_empty() {
+        dvr::recorder::get_default_storage_path().map_err(|e| e.to_string())?
+    } else {
+        std::path::PathBuf::from(path)
+    };
+
+    // Choosing a location creates it; the check itself only looks
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Cannot create storage directory {:?}: {}", dir, e))?;
+    dvr::recorder::check_storage_writable(&dir).map_err(|e| format!("{:#}", e))?;
+
+    let free_bytes = dvr::cleanup::get_disk_info(&dir)
+        .map(|info| info.available_bytes)
+        .ok();
+
+    Ok(StoragePathInfo {
+        path: dir.to_string_lossy().to_string(),
+        free_bytes,
+    })
+}
+
+/// Set the DVR storage directory after checking it exists (or can be created) and is writable
+#[tauri::command]
+async fn set_storage_path(
+    state: tauri::State<'_, DvrState>,
+    path: String,
+) -> Result<StoragePathInfo, String> {
+    let info = validate_storage_path(&path)?;
+
+    state.db.save_setting("storage_path", &path)
+        .map_err(|e| format!("Failed to save setting: {}", e))?;
+
+    info!("[DVR] Storage path set to {} ({:?} bytes free)", info.path, info.free_bytes);
+    Ok(info)
+}
+
+/// Switch the DVR storage directory, optionally moving finished recordings there.
+///
+/// The new path is validated and saved right away; files are moved on a background
+/// task that reports through `dvr:storage_migration` events.
+#[tauri::command]
+async fn migrate_storage(
+    app: AppHandle,
+    state: tauri::State<'_, DvrState>,
+    new_path: String,
+    move_files: bool,
+) -> Result<StoragePathInfo, String> {
+    let old_dir = state.db.get_settings()
+        .and_then(|settings| dvr::recorder::resolve_storage_path(&settings))
+        .map_err(|e| format!("Failed to read current storage path: {}", e))?;
+
+    let info = set_storage_path(state.clone(), new_path).await?;
+
+    let new_dir = std::path::PathBuf::from(&info.path);
+    if !move_files || new_dir == old_dir {
+        return Ok(info);
+    }
+
+    let db = state.db.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = dvr::recorder::migrate_storage(&db, &old_dir, &new_dir, |current, total, filename| {
+            let _ = app.emit("dvr:storage_migration", serde_json::json!({
+                "status": "running",
+                "current": current,
+                "total": total,
+                "filename": filename,
+            }));
+        }).await;
+
+        let payload = match result {
+            Ok(summary) => serde_json::json!({
+                "status": "completed",
+                "moved": summary.moved,
+                "failed": summary.failed,
+                "skipped": summary.skipped,
+            }),
+            Err(e) => {
+                error!("[DVR] Storage migration failed: {}", e);
+                serde_json::json!({ "status": "failed", "error": e.to_string() })
+            }
+        };
+        let _ = app.emit("dvr:storage_migration", payload);
+    });
+
+    Ok(info)
 }
 
-/// Save DVR setting
+/// Change the log level without restarting (`error`..`trace`, or `default`).
+/// The level is saved and applied again at the next startup.
 #[tauri::command]
-async fn save_dvr_setting(
-    state: tauri::State<'_, DvrState>,
-    key: String,
-    value: String,
-) -> Result<(), String> {
-    state.db.save_setting(&key, &value)
-        .map_err(|e| format!("Failed to save setting: {}", e))?;
-
-    Ok(())
+async fn set_log_level<R: Runtime>(app: AppHandle<R>, level: String) -> Result<(), String> {
+    let directives = dvr::set_log_level(&level)?;
+    write_store_setting(&app, "logLevel", serde_json::Value::String(directives.to_string()))
 }
 
 /// Open log folder in system file explorer
@@ -1252,24 +2702,161 @@ async fn run_cleanup_now(
     Ok(())
 }
 
+/// Delete recordings until disk usage is under the configured limit (or just list them with dry_run)
+#[tauri::command]
+async fn enforce_disk_quota(
+    state: tauri::State<'_, DvrState>,
+    dry_run: Option<bool>,
+) -> Result<dvr::cleanup::QuotaCleanupReport, String> {
+    state.cleanup.enforce_quota(dry_run.unwrap_or(false)).await
+        .map_err(|e| format!("Quota cleanup failed: {}", e))
+}
+
+/// Compact the database file and report how much space was reclaimed
+#[tauri::command]
+async fn vacuum_database(
+    state: tauri::State<'_, DvrState>,
+) -> Result<db_maintenance::VacuumResult, String> {
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || db_maintenance::vacuum_database(&db))
+        .await
+        .map_err(|e| format!("Vacuum task failed: {}", e))?
+        .map_err(|e| format!("Vacuum failed: {}", e))
+}
+
+/// Cross-check recordings against the storage directory, marking rows with missing
+/// files as orphaned and, if asked, importing loose video files found there
+#[tauri::command]
+async fn scan_recordings(
+    state: tauri::State<'_, DvrState>,
+    import_loose: bool,
+) -> Result<dvr::cleanup::RecordingScanReport, String> {
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || dvr::cleanup::scan_recordings(&db, import_loose))
+        .await
+        .map_err(|e| format!("Scan task failed: {}", e))?
+        .map_err(|e| format!("Failed to scan recordings: {}", e))
+}
+
+/// Run SQLite's integrity check; returns "ok" when the database is healthy
+#[tauri::command]
+async fn integrity_check(
+    state: tauri::State<'_, DvrState>,
+) -> Result<String, String> {
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || db_maintenance::integrity_check(&db))
+        .await
+        .map_err(|e| format!("Integrity check task failed: {}", e))?
+        .map_err(|e| format!("Integrity check failed: {}", e))
+}
+
+/// Back up the database to `dest_path` while the app keeps running; returns the backup size
+#[tauri::command]
+async fn backup_database(
+    state: tauri::State<'_, DvrState>,
+    dest_path: String,
+) -> Result<u64, String> {
+    let db = state.db.clone();
+    tokio::task::spawn_blocking(move || db_maintenance::backup_database(&db, std::path::Path::new(&dest_path)))
+        .await
+        .map_err(|e| format!("Backup task failed: {}", e))?
+        .map_err(|e| format!("Backup failed: {}", e))
+}
+
+/// Validate a backup and stage it to replace the database on next launch; returns its size
+#[tauri::command]
+async fn restore_database(
+    app: AppHandle,
+    src_path: String,
+) -> Result<u64, String> {
+    let db_path = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join("ynotv.db");
+    tokio::task::spawn_blocking(move || db_maintenance::restore_database(&db_path, std::path::Path::new(&src_path)))
+        .await
+        .map_err(|e| format!("Restore task failed: {}", e))?
+        .map_err(|e| format!("Restore failed: {}", e))
+}
+
 // =============================================================================
 // Optimized Bulk Sync Commands
 // =============================================================================
 
+/// Generic bulk insert/replace/upsert/delete on one table, in a single transaction
+#[tauri::command]
+async fn bulk_insert(
+    state: tauri::State<'_, DvrState>,
+    request: db_bulk_ops::BulkInsertRequest,
+) -> Result<db_bulk_ops::BulkResult, String> {
+    debug!("[bulk_insert] {} {} rows into {}", request.operation, request.rows.len(), request.table);
+    db_bulk_ops::bulk_insert(&state.db, &request)
+        .map_err(|e| {
+            error!("[bulk_insert] ERROR: {}", e);
+            format!("Bulk {} failed: {}", request.operation, e)
+        })
+}
+
 /// Bulk upsert channels - optimized for sync operations
 #[tauri::command]
 async fn bulk_upsert_channels(
     state: tauri::State<'_, DvrState>,
     channels: Vec<db_bulk_ops::BulkChannel>,
+    return_ids: Option<bool>,
 ) -> Result<db_bulk_ops::BulkResult, String> {
     debug!("[bulk_upsert_channels] Called with {} channels", channels.len());
-    db_bulk_ops::bulk_upsert_channels(&state.db, channels)
+    db_bulk_ops::bulk_upsert_channels(&state.db, channels, return_ids.unwrap_or(false))
         .map_err(|e| {
             error!("[bulk_upsert_channels] ERROR: {}", e);
             format!("Bulk upsert channels failed: {}", e)
         })
 }
 
+/// Full-text search over channel names, optionally limited to some sources
+#[tauri::command]
+async fn search_channels(
+    state: tauri::State<'_, DvrState>,
+    query: String,
+    source_ids: Option<Vec<String>>,
+    sort: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let sort = db_queries::ChannelSort::parse(sort.as_deref().unwrap_or("relevance"))
+        .map_err(|e| e.to_string())?;
+    db_queries::search_channels(&state.db, &query, source_ids.as_deref(), sort, limit.unwrap_or(100), offset.unwrap_or(0))
+        .map_err(|e| format!("Channel search failed: {}", e))
+}
+
+/// Page through channels, optionally within one category
+#[tauri::command]
+async fn query_channels(
+    state: tauri::State<'_, DvrState>,
+    category_id: Option<String>,
+    sort: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<db_queries::QueryPage, String> {
+    let sort = db_queries::ChannelSort::parse(sort.as_deref().unwrap_or("alpha_asc"))
+        .map_err(|e| e.to_string())?;
+    db_queries::query_channels(&state.db, category_id.as_deref(), sort, limit.unwrap_or(200), offset.unwrap_or(0))
+        .map_err(|e| format!("Channel query failed: {}", e))
+}
+
+/// Page through VOD movies, optionally within one category
+#[tauri::command]
+async fn query_movies(
+    state: tauri::State<'_, DvrState>,
+    category_id: Option<String>,
+    sort: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<db_queries::QueryPage, String> {
+    let sort = db_queries::VodSort::parse(sort.as_deref().unwrap_or("alpha_asc"))
+        .map_err(|e| e.to_string())?;
+    db_queries::query_movies(&state.db, category_id.as_deref(), sort, limit.unwrap_or(200), offset.unwrap_or(0))
+        .map_err(|e| format!("Movie query failed: {}", e))
+}
+
 /// Bulk upsert categories - optimized for sync operations
 #[tauri::command]
 async fn bulk_upsert_categories(
@@ -1281,14 +2868,35 @@ async fn bulk_upsert_categories(
 }
 
 /// Bulk replace EPG programs for a source
+///
+/// `mode` is "replace" (default, clears the source first) or "merge" (upserts and
+/// only prunes programs past the retention window).
 #[tauri::command]
 async fn bulk_replace_programs(
     state: tauri::State<'_, DvrState>,
     source_id: String,
     programs: Vec<db_bulk_ops::BulkProgram>,
+    mode: Option<String>,
 ) -> Result<db_bulk_ops::BulkResult, String> {
-    db_bulk_ops::bulk_replace_programs(&state.db, &source_id, programs)
-        .map_err(|e| format!("Bulk replace programs failed: {}", e))
+    let result = match mode.as_deref().unwrap_or("replace") {
+        "replace" => db_bulk_ops::bulk_replace_programs(&state.db, &source_id, programs)
+            .map_err(|e| format!("Bulk replace programs failed: {}", e))?,
+        "merge" => {
+            let retention_days = state.db.get_settings()
+                .map(|s| s.epg_retention_days as i64)
+                .unwrap_or(db_bulk_ops::DEFAULT_PROGRAM_RETENTION_DAYS);
+            db_bulk_ops::bulk_merge_programs(&state.db, &source_id, programs, retention_days)
+                .map_err(|e| format!("Bulk merge programs failed: {}", e))?
+        }
+        other => return Err(format!("Unknown program sync mode '{}', expected 'replace' or 'merge'", other)),
+    };
+
+    // New EPG data may contain new airings for saved series rules
+    if let Err(e) = dvr::scheduler::scan_series_rules(&state.db) {
+        warn!("[DVR] Series scan after EPG sync failed: {}", e);
+    }
+
+    Ok(result)
 }
 
 /// Bulk upsert VOD movies
@@ -1311,6 +2919,17 @@ async fn bulk_upsert_series(
         .map_err(|e| format!("Bulk upsert series failed: {}", e))
 }
 
+/// Compare a provider's channel list with the stored rows for a source
+#[tauri::command]
+async fn diff_channels(
+    state: tauri::State<'_, DvrState>,
+    source_id: String,
+    incoming: Vec<db_bulk_ops::BulkChannel>,
+) -> Result<db_bulk_ops::ChannelDiff, String> {
+    db_bulk_ops::diff_channels(&state.db, &source_id, incoming)
+        .map_err(|e| format!("Channel diff failed: {}", e))
+}
+
 /// Bulk delete channels
 #[tauri::command]
 async fn bulk_delete_channels(
@@ -1345,11 +2964,183 @@ async fn update_source_meta(
         })
 }
 
+/// Webview URL for a file under the asset protocol, matching the UI's `convertFileSrc`
+fn asset_url(path: &std::path::Path) -> String {
+    let encoded: String = path
+        .to_string_lossy()
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    if cfg!(target_os = "windows") {
+        format!("http://asset.localhost/{}", encoded)
+    } else {
+        format!("asset://localhost/{}", encoded)
+    }
+}
+
+/// Grab the playing frame as a fallback logo for a channel without one.
+/// Only runs when invoked; waits `delay_ms` (default 2s) so the picture has started.
+/// Returns the new logo URL, or None if the channel already had a logo or the
+/// stream changed while waiting.
+#[tauri::command]
+async fn capture_channel_logo<R: Runtime>(
+    app: AppHandle<R>,
+    state: tauri::State<'_, DvrState>,
+    stream_id: String,
+    delay_ms: Option<u64>,
+) -> Result<Option<String>, String> {
+    let missing = db_bulk_ops::channel_missing_icon(&state.db, &stream_id)
+        .map_err(|e| format!("Failed to look up channel: {}", e))?;
+    if !missing {
+        return Ok(None);
+    }
+
+    // Zapping while we wait would save another channel's picture as this one's logo
+    let playing = mpv_get_property(app.clone(), "path".to_string()).await?;
+    tokio::time::sleep(std::time::Duration::from_millis(delay_ms.unwrap_or(2000))).await;
+    if mpv_get_property(app.clone(), "path".to_string()).await? != playing {
+        info!("[Channels] Stream changed before the logo capture for {}, skipping", stream_id);
+        return Ok(None);
+    }
+
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to get cache dir: {}", e))?
+        .join("channel-logos");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create logo directory: {}", e))?;
+    let file_name: String = stream_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let path = dir.join(format!("{}.jpg", file_name));
+
+    #[cfg(target_os = "macos")]
+    mpv_macos::screenshot_to_file(&app, &path.to_string_lossy()).await?;
+    #[cfg(target_os = "windows")]
+    mpv_windows::screenshot_to_file(&app, &path.to_string_lossy()).await?;
+
+    if !path.is_file() {
+        return Err("MPV did not write a screenshot".to_string());
+    }
+    if mpv_get_property(app.clone(), "path".to_string()).await? != playing {
+        let _ = std::fs::remove_file(&path);
+        return Ok(None);
+    }
+
+    // Kept apart from stream_icon, which the next provider sync overwrites
+    let icon = asset_url(&path);
+    let updated = db_bulk_ops::set_captured_icon_if_missing(&state.db, &stream_id, &icon)
+        .map_err(|e| format!("Failed to save channel logo: {}", e))?;
+    if updated {
+        info!("[Channels] Captured fallback logo for {}", stream_id);
+        Ok(Some(icon))
+    } else {
+        let _ = std::fs::remove_file(&path);
+        Ok(None)
+    }
+}
+
+/// Whether the DVR system started, and why not if it didn't.
+/// Works even when DVR commands fail because `DvrState` was never managed.
+#[tauri::command]
+async fn get_dvr_init_status(
+    status: tauri::State<'_, dvr::DvrInitStatus>,
+) -> Result<dvr::DvrInitStatus, String> {
+    Ok(status.inner().clone())
+}
+
+/// State of one backend component in the health check
+#[derive(Debug, Serialize)]
+struct ComponentHealth {
+    ok: bool,
+    message: String,
+}
+
+impl ComponentHealth {
+    fn from_result(result: Result<String, String>) -> Self {
+        match result {
+            Ok(message) => Self { ok: true, message },
+            Err(message) => Self { ok: false, message },
+        }
+    }
+}
+
+/// Result of `health_check`
+#[derive(Debug, Serialize)]
+struct HealthStatus {
+    /// Database, FFmpeg and storage are all usable (MPV only connects once playback starts)
+    healthy: bool,
+    database: ComponentHealth,
+    ffmpeg: ComponentHealth,
+    storage: ComponentHealth,
+    mpv: ComponentHealth,
+}
+
+/// Whether the MPV IPC connection is up
+fn mpv_connected<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    #[cfg(target_os = "macos")]
+    let connected = app.state::<MpvState>().socket.lock().map(|s| s.is_some()).unwrap_or(false);
+    #[cfg(target_os = "windows")]
+    let connected = app.state::<MpvState>().socket_connected.lock().map(|c| *c).unwrap_or(false);
+
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    if connected {
+        Ok("IPC connected".to_string())
+    } else {
+        Err("IPC not connected (MPV may not have been started yet)".to_string())
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = app;
+        Err(MPV_UNSUPPORTED.to_string())
+    }
+}
+
 /// Health check - verifies backend systems are ready
 #[tauri::command]
-async fn health_check(_state: tauri::State<'_, DvrState>) -> Result<bool, String> {
-    debug!("[health_check] DVR state is active");
-    Ok(true)
+async fn health_check(
+    app: AppHandle,
+    state: tauri::State<'_, DvrState>,
+) -> Result<HealthStatus, String> {
+    let database = ComponentHealth::from_result(
+        state.db.get_conn()
+            .map(|_| "Connection pool available".to_string())
+            .map_err(|e| e.to_string()),
+    );
+
+    let ffmpeg = ComponentHealth::from_result(if state.recorder.ffmpeg_available() {
+        Ok("FFmpeg found".to_string())
+    } else {
+        Err("FFmpeg not found; recording is unavailable".to_string())
+    });
+
+    let storage = ComponentHealth::from_result(
+        state.db.get_settings()
+            .and_then(|settings| dvr::recorder::resolve_storage_path(&settings))
+            .and_then(|path| {
+                dvr::recorder::check_storage_writable(&path)?;
+                Ok(if path.is_dir() {
+                    format!("{} is writable", path.display())
+                } else {
+                    format!("{} will be created on the first recording", path.display())
+                })
+            })
+            .map_err(|e| format!("{:#}", e)),
+    );
+
+    let mpv = ComponentHealth::from_result(mpv_connected(&app));
+
+    let healthy = database.ok && ffmpeg.ok && storage.ok;
+    debug!("[health_check] healthy={}", healthy);
+
+    Ok(HealthStatus { healthy, database, ffmpeg, storage, mpv })
 }
 
 /// Stream and parse EPG from URL with progress updates
@@ -1395,11 +3186,97 @@ pub struct TmdbCacheState(pub tokio::sync::Mutex<TmdbCache>);
 
 impl TmdbCacheState {
     /// Create with the given cache directory.
-    pub fn new(cache_dir: std::path::PathBuf) -> Self {
-        Self(tokio::sync::Mutex::new(TmdbCache::new(cache_dir)))
+    /// Download/index progress is emitted to the frontend as `tmdb:cache_progress`.
+    /// Custom export URLs are read from the `tmdbMoviesExportUrl`/`tmdbSeriesExportUrl` store settings.
+    pub fn new(cache_dir: std::path::PathBuf, app_handle: tauri::AppHandle) -> Self {
+        let read_url = |key: &str| {
+            read_store_setting(&app_handle, key).and_then(|v| v.as_str().map(str::to_string))
+        };
+        let movies_url = read_url("tmdbMoviesExportUrl");
+        let series_url = read_url("tmdbSeriesExportUrl");
+
+        let cache = TmdbCache::new(cache_dir)
+            .with_export_urls(movies_url, series_url)
+            .with_progress_callback(std::sync::Arc::new(
+                move |progress: tmdb_cache::TmdbCacheProgress| {
+                    let _ = app_handle.emit("tmdb:cache_progress", progress);
+                },
+            ));
+        Self(tokio::sync::Mutex::new(cache))
     }
 }
 
+/// Default time between background checks for stale TMDB caches
+const TMDB_AUTO_REFRESH_DEFAULT_HOURS: u64 = 6;
+
+/// Periodically refresh TMDB caches that have outlived their TTL.
+///
+/// Controlled by the `tmdbAutoRefresh` (default on) and `tmdbAutoRefreshIntervalHours`
+/// store settings, re-read every cycle. Downloads run on a detached cache so lookups
+/// aren't blocked; the fresh indexes are swapped in afterwards and
+/// `tmdb:cache_refreshed` is emitted.
+fn spawn_tmdb_auto_refresh(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let interval_hours = read_store_setting(&app_handle, "tmdbAutoRefreshIntervalHours")
+                .and_then(|v| v.as_u64())
+                .filter(|h| *h > 0)
+                .unwrap_or(TMDB_AUTO_REFRESH_DEFAULT_HOURS);
+            tokio::time::sleep(std::time::Duration::from_secs(interval_hours * 3600)).await;
+
+            let enabled = read_store_setting(&app_handle, "tmdbAutoRefresh")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            if !enabled {
+                continue;
+            }
+
+            let Some(state) = app_handle.try_state::<TmdbCacheState>() else {
+                continue;
+            };
+
+            // Only hold the lock long enough to read stats and make a detached copy
+            let (stats, mut fresh) = {
+                let cache = state.0.lock().await;
+                match cache.get_stats().await {
+                    Ok(stats) => (stats, cache.detached()),
+                    Err(e) => {
+                        warn!("[TMDB] Auto-refresh could not read cache stats: {}", e);
+                        continue;
+                    }
+                }
+            };
+            let ttl_hours = fresh.ttl_hours();
+
+            // Caches that were never built are left to be fetched on first use
+            let mut movies = None;
+            let mut series = None;
+            if stats.movies_cached && stats.movies_age_hours >= ttl_hours {
+                info!("[TMDB] Movies cache is {}h old, refreshing in background", stats.movies_age_hours);
+                match fresh.update_movies_cache().await {
+                    Ok(count) => movies = Some(count),
+                    Err(e) => warn!("[TMDB] Background movies refresh failed: {}", e),
+                }
+            }
+            if stats.series_cached && stats.series_age_hours >= ttl_hours {
+                info!("[TMDB] Series cache is {}h old, refreshing in background", stats.series_age_hours);
+                match fresh.update_series_cache().await {
+                    Ok(count) => series = Some(count),
+                    Err(e) => warn!("[TMDB] Background series refresh failed: {}", e),
+                }
+            }
+
+            if movies.is_some() || series.is_some() {
+                state.0.lock().await.absorb(fresh);
+                let _ = app_handle.emit("tmdb:cache_refreshed", serde_json::json!({
+                    "movies": movies,
+                    "series": series,
+                }));
+            }
+        }
+    });
+}
+
 // =============================================================================
 // TMDB Cache Commands
 // =============================================================================
@@ -1435,25 +3312,75 @@ async fn update_tmdb_series_cache(
 }
 
 /// Find movies by title
+/// `min_score` defaults to `tmdb_cache::DEFAULT_MIN_MATCH_SCORE` (0.8)
 #[tauri::command]
 async fn find_tmdb_movies(
     state: tauri::State<'_, TmdbCacheState>,
     title: String,
+    year: Option<u32>,
+    min_score: Option<f32>,
 ) -> Result<Vec<MatchResult>, String> {
+    let min_score = min_score.unwrap_or(tmdb_cache::DEFAULT_MIN_MATCH_SCORE);
+    // Only hold the cache lock while loading; matching runs on the shared index
+    let index = state.0.lock().await.movies_index().await
+        .map_err(|e| format!("Failed to find movies: {}", e))?;
+    Ok(index.find(&title, year, min_score))
+}
+
+/// Find a movie by IMDB id
+#[tauri::command]
+async fn find_tmdb_movie_by_imdb(
+    state: tauri::State<'_, TmdbCacheState>,
+    imdb_id: String,
+) -> Result<Option<MatchResult>, String> {
     let mut cache = state.0.lock().await;
-    cache.find_movies(&title).await
-        .map_err(|e| format!("Failed to find movies: {}", e))
+    cache.find_movie_by_imdb(&imdb_id).await
+        .map_err(|e| format!("Failed to find movie by IMDB id: {}", e))
 }
 
 /// Find series by title
+/// `min_score` defaults to `tmdb_cache::DEFAULT_MIN_MATCH_SCORE` (0.8)
 #[tauri::command]
 async fn find_tmdb_series(
     state: tauri::State<'_, TmdbCacheState>,
     title: String,
+    year: Option<u32>,
+    min_score: Option<f32>,
 ) -> Result<Vec<MatchResult>, String> {
+    let min_score = min_score.unwrap_or(tmdb_cache::DEFAULT_MIN_MATCH_SCORE);
+    // Only hold the cache lock while loading; matching runs on the shared index
+    let index = state.0.lock().await.series_index().await
+        .map_err(|e| format!("Failed to find series: {}", e))?;
+    Ok(index.find(&title, year, min_score))
+}
+
+/// Set custom TMDB export URLs (empty or null restores the default mirror)
+#[tauri::command]
+async fn set_tmdb_export_urls(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, TmdbCacheState>,
+    movies_url: Option<String>,
+    series_url: Option<String>,
+) -> Result<(), String> {
+    let normalize = |url: Option<String>| -> Result<Option<String>, String> {
+        match url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty()) {
+            Some(u) if u.starts_with("http://") || u.starts_with("https://") => Ok(Some(u)),
+            Some(u) => Err(format!("Export URL must start with http:// or https://: {}", u)),
+            None => Ok(None),
+        }
+    };
+    let movies_url = normalize(movies_url)?;
+    let series_url = normalize(series_url)?;
+
+    // Persist alongside the frontend settings so the URLs survive restarts
+    write_store_setting(&app, "tmdbMoviesExportUrl", serde_json::json!(movies_url))?;
+    write_store_setting(&app, "tmdbSeriesExportUrl", serde_json::json!(series_url))?;
+
     let mut cache = state.0.lock().await;
-    cache.find_series(&title).await
-        .map_err(|e| format!("Failed to find series: {}", e))
+    cache.set_export_urls(movies_url, series_url);
+    let (movies, series) = cache.export_urls();
+    info!("[TMDB] Export URLs set: movies={}, series={}", movies, series);
+    Ok(())
 }
 
 /// Clear TMDB cache
@@ -1891,8 +3818,8 @@ fn window_state_path(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
 
 fn save_window_state(app: &tauri::AppHandle) {
     if let Some(window) = app.get_webview_window("main") {
-        // Don't save fullscreen state — restore to last windowed geometry instead
-        if window.is_fullscreen().unwrap_or(false) {
+        // Don't save fullscreen/maximized state — restore to last windowed geometry instead
+        if window.is_fullscreen().unwrap_or(false) || window.is_maximized().unwrap_or(false) {
             return;
         }
         // Get inner_size (physical pixels) and convert to logical pixels
@@ -2046,47 +3973,202 @@ fn fallback_update_store_file(app: &tauri::AppHandle, width: u32, height: u32) {
     }
 }
 
-fn restore_window_state(app: &tauri::AppHandle) {
-    if let Some(path) = window_state_path(app) {
-        if let Ok(json) = std::fs::read_to_string(&path) {
-            if let Ok(state) = serde_json::from_str::<WindowState>(&json) {
-                if let Some(window) = app.get_webview_window("main") {
-                    // Apply size as logical size (DPI-independent)
-                    // This ensures the window opens at the correct logical size regardless of monitor scaling
-                    let _ = window.set_size(tauri::Size::Logical(
-                        tauri::LogicalSize { width: state.width as f64, height: state.height as f64 }
-                    ));
-                    // Apply position (only if non-zero — avoids placing off-screen on first run)
-                    if state.x != 0 || state.y != 0 {
-                        let _ = window.set_position(tauri::Position::Physical(
-                            tauri::PhysicalPosition { x: state.x, y: state.y }
-                        ));
+/// Shrink and move a window (physical pixels) so it lies entirely inside a monitor's work area
+fn clamp_to_work_area(
+    size: tauri::PhysicalSize<u32>,
+    position: tauri::PhysicalPosition<i32>,
+    work_area: &tauri::PhysicalRect<i32, u32>,
+) -> (tauri::PhysicalSize<u32>, tauri::PhysicalPosition<i32>) {
+    let width = size.width.min(work_area.size.width);
+    let height = size.height.min(work_area.size.height);
+    let max_x = work_area.position.x + (work_area.size.width - width) as i32;
+    let max_y = work_area.position.y + (work_area.size.height - height) as i32;
+
+    (
+        tauri::PhysicalSize::new(width, height),
+        tauri::PhysicalPosition::new(
+            position.x.clamp(work_area.position.x, max_x),
+            position.y.clamp(work_area.position.y, max_y),
+        ),
+    )
+}
+
+/// The monitor whose full area contains a physical point
+fn monitor_at(monitors: &[tauri::Monitor], x: i32, y: i32) -> Option<tauri::Monitor> {
+    monitors
+        .iter()
+        .find(|m| {
+            let pos = m.position();
+            let size = m.size();
+            x >= pos.x && x < pos.x + size.width as i32 && y >= pos.y && y < pos.y + size.height as i32
+        })
+        .cloned()
+}
+
+/// Apply a logical size and physical position to the main window, clamped to the work area
+/// of the monitor it belongs on so it can never open larger than or off the visible screen.
+///
+/// The monitor is the one containing the middle of the requested window. If no connected
+/// monitor contains it (e.g. the TV it was on is unplugged), the window is centered on the
+/// primary monitor instead. Sizes are converted with that monitor's DPI scale.
+fn apply_clamped_geometry(
+    window: &tauri::WebviewWindow,
+    logical_size: Option<tauri::LogicalSize<f64>>,
+    position: Option<tauri::PhysicalPosition<i32>>,
+) {
+    let monitors = window.available_monitors().unwrap_or_default();
+    let primary = window.primary_monitor().ok().flatten();
+    let current = window.current_monitor().ok().flatten();
+
+    // Physical size on a given monitor: the requested logical size at its scale, else the current size
+    let size_on = |monitor: &tauri::Monitor| -> Option<tauri::PhysicalSize<u32>> {
+        match logical_size {
+            Some(size) => Some(size.to_physical::<u32>(monitor.scale_factor())),
+            None => window.outer_size().ok(),
+        }
+    };
+
+    let (monitor, position) = match position {
+        Some(position) => {
+            let guess = current.as_ref().or(primary.as_ref()).and_then(&size_on);
+            let (half_w, half_h) = guess.map(|s| (s.width as i32 / 2, s.height as i32 / 2)).unwrap_or((0, 0));
+            match monitor_at(&monitors, position.x + half_w, position.y + half_h) {
+                Some(monitor) => (monitor, Some(position)),
+                None => {
+                    info!("[WindowState] Saved position ({}, {}) is not on any connected monitor, centering on primary",
+                        position.x, position.y);
+                    match primary.or(current) {
+                        Some(monitor) => (monitor, None),
+                        None => return,
                     }
-                    debug!("[WindowState] Restored: {}x{} logical at ({}, {})",
-                        state.width, state.height, state.x, state.y);
                 }
             }
         }
-    }
+        None => match current.or(primary) {
+            Some(monitor) => {
+                let position = window.outer_position().ok();
+                (monitor, position)
+            }
+            None => {
+                warn!("[WindowState] No monitor found, leaving window geometry unchanged");
+                return;
+            }
+        },
+    };
+
+    let Some(size) = size_on(&monitor) else {
+        return;
+    };
+    let work_area = monitor.work_area();
+
+    // Without a usable position, center on the chosen monitor
+    let position = position.unwrap_or_else(|| {
+        let width = size.width.min(work_area.size.width);
+        let height = size.height.min(work_area.size.height);
+        tauri::PhysicalPosition::new(
+            work_area.position.x + ((work_area.size.width - width) / 2) as i32,
+            work_area.position.y + ((work_area.size.height - height) / 2) as i32,
+        )
+    });
+
+    let (size, position) = clamp_to_work_area(size, position, work_area);
+    // Move first so a mixed-DPI setup applies the size on the target monitor
+    let _ = window.set_position(tauri::Position::Physical(position));
+    let _ = window.set_size(tauri::Size::Physical(size));
+    debug!("[WindowState] Applied {}x{} at ({}, {}) on monitor {:?} (scale {})",
+        size.width, size.height, position.x, position.y, monitor.name(), monitor.scale_factor());
 }
 
-/// Restore only window position (not size) - used when UI controls the startup size
-fn restore_window_position(app: &tauri::AppHandle) {
-    if let Some(path) = window_state_path(app) {
-        if let Ok(json) = std::fs::read_to_string(&path) {
-            if let Ok(state) = serde_json::from_str::<WindowState>(&json) {
-                if let Some(window) = app.get_webview_window("main") {
-                    // Apply position only (only if non-zero — avoids placing off-screen on first run)
-                    if state.x != 0 || state.y != 0 {
-                        let _ = window.set_position(tauri::Position::Physical(
-                            tauri::PhysicalPosition { x: state.x, y: state.y }
-                        ));
-                        debug!("[WindowState] Restored position: ({}, {})", state.x, state.y);
-                    }
-                }
-            }
+/// Restore the saved window size and position, clamped to the visible screen
+fn restore_window_state(app: &tauri::AppHandle) {
+    let Some(path) = window_state_path(app) else {
+        return;
+    };
+    let Ok(json) = std::fs::read_to_string(&path) else {
+        return;
+    };
+    let Ok(state) = serde_json::from_str::<WindowState>(&json) else {
+        return;
+    };
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    // A zero size means only the position was saved ("don't save window size" setting)
+    let logical_size = (state.width > 0 && state.height > 0)
+        .then(|| tauri::LogicalSize::new(state.width as f64, state.height as f64));
+    // Only apply a non-zero position — avoids placing off-screen on first run
+    let position = (state.x != 0 || state.y != 0)
+        .then(|| tauri::PhysicalPosition::new(state.x, state.y));
+
+    apply_clamped_geometry(&window, logical_size, position);
+    debug!("[WindowState] Restored: {}x{} logical at ({}, {})",
+        state.width, state.height, state.x, state.y);
+}
+
+/// Bumped on every move/resize event; a scheduled save only runs if no event came after it
+static WINDOW_SAVE_GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Save the window geometry shortly after the last move/resize event
+fn schedule_window_state_save(app: &tauri::AppHandle) {
+    use std::sync::atomic::Ordering;
+
+    let generation = WINDOW_SAVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(750)).await;
+        if WINDOW_SAVE_GENERATION.load(Ordering::SeqCst) == generation {
+            save_window_state(&app);
         }
+    });
+}
+
+/// Resize the main window to the startup size from settings, clamped to the visible screen
+#[tauri::command]
+async fn apply_startup_window_size(app: tauri::AppHandle, width: f64, height: f64) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("Main window not found")?;
+    apply_clamped_geometry(&window, Some(tauri::LogicalSize::new(width, height)), None);
+    Ok(())
+}
+
+/// Keep the main window above other apps (e.g. for the mini player); remembered across restarts
+#[tauri::command]
+async fn set_always_on_top(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("Main window not found")?;
+    window.set_always_on_top(enabled)
+        .map_err(|e| format!("Failed to set always on top: {}", e))?;
+    write_store_setting(&app, "alwaysOnTop", serde_json::json!(enabled))?;
+    Ok(())
+}
+
+/// Whether the main window is currently kept above other apps
+#[tauri::command]
+async fn get_always_on_top(app: tauri::AppHandle) -> Result<bool, String> {
+    let window = app.get_webview_window("main").ok_or("Main window not found")?;
+    window.is_always_on_top()
+        .map_err(|e| format!("Failed to get always on top: {}", e))
+}
+
+/// Forget the saved window geometry and put the main window back at a safe default size, centered
+#[tauri::command]
+async fn reset_window_geometry(app: tauri::AppHandle) -> Result<(), String> {
+    const DEFAULT_WIDTH: u32 = 1280;
+    const DEFAULT_HEIGHT: u32 = 720;
+
+    if let Some(path) = window_state_path(&app) {
+        let _ = std::fs::remove_file(path);
     }
+    update_startup_size_in_store(&app, DEFAULT_WIDTH, DEFAULT_HEIGHT);
+
+    let window = app.get_webview_window("main").ok_or("Main window not found")?;
+    let _ = window.unmaximize();
+    let _ = window.set_fullscreen(false);
+    apply_clamped_geometry(&window, Some(tauri::LogicalSize::new(DEFAULT_WIDTH as f64, DEFAULT_HEIGHT as f64)), None);
+    window.center().map_err(|e| format!("Failed to center window: {}", e))?;
+
+    info!("[WindowState] Window geometry reset to {}x{}", DEFAULT_WIDTH, DEFAULT_HEIGHT);
+    Ok(())
 }
 
 // =============================================================================
@@ -2104,11 +4186,8 @@ pub fn run() {
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_log::Builder::new()
-            .level(if cfg!(debug_assertions) {
-                log::LevelFilter::Debug
-            } else {
-                log::LevelFilter::Info
-            })
+            // Everything reaches the plugin; the level set by dvr::init_logging / set_log_level filters it
+            .level(log::LevelFilter::Trace)
             .timezone_strategy(tauri_plugin_log::TimezoneStrategy::UseLocal)
             .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepAll)
             .target(tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir {
@@ -2117,11 +4196,20 @@ pub fn run() {
             .build())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_notification::init())
         // Manage platform-specific MPV state
         .manage(MpvState::new())
         .setup(|app| {
-            // Register secondary MPV state (Windows only)
+            // Kill MPV sidecars and sockets left behind by a crashed previous run
+            mpv_cleanup::cleanup_stale_mpv();
+
+            // Register secondary MPV state (multiview slots)
             #[cfg(target_os = "windows")]
+            {
+                app.manage(SecondaryMpvState::new());
+                mpv_secondary::start_health_monitor(app.handle().clone());
+            }
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
             app.manage(SecondaryMpvState::new());
 
             // Configure macOS window for proper dragging with transparent titlebar
@@ -2178,8 +4266,23 @@ pub fn run() {
                 }
             });
 
-            // For now, disable verbose logging by default (sqlx logs are too noisy)
-            dvr::init_logging(false);
+            // Verbose logging (sqlx is noisy) only when enabled in the Debug settings
+            let debug_logging = read_store_setting(app.handle(), "debugLoggingEnabled")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            dvr::init_logging(debug_logging);
+            if let Some(level) = read_store_setting(app.handle(), "logLevel").and_then(|v| v.as_str().map(str::to_string)) {
+                if let Err(e) = dvr::set_log_level(&level) {
+                    warn!("[Logging] Ignoring saved log level: {}", e);
+                }
+            }
+
+            // Swap in a database staged by restore_database before anything opens it
+            if let Ok(data_dir) = app.path().app_data_dir() {
+                if let Err(e) = db_maintenance::apply_pending_restore(&data_dir.join("ynotv.db")) {
+                    error!("[DVR Setup] Failed to apply staged database restore: {}", e);
+                }
+            }
 
             match tauri::async_runtime::block_on(async move {
                 info!("[DVR Setup] Starting DVR initialization...");
@@ -2188,12 +4291,14 @@ pub fn run() {
                 Ok(dvr_state) => {
                     info!("[DVR Setup] System initialized successfully, managing state...");
                     app.manage(dvr_state);
+                    app.manage(dvr::DvrInitStatus { available: true, error: None });
                     info!("[DVR Setup] State managed successfully");
                 }
                 Err(e) => {
                     error!("[DVR Setup] WARNING: Failed to initialize full DVR: {}", e);
                     error!("[DVR Setup] DVR features (recording) will be unavailable.");
                     error!("[DVR Setup] Bulk sync operations may also be affected.");
+                    app.manage(dvr::DvrInitStatus { available: false, error: Some(format!("{:#}", e)) });
                 }
             }
 
@@ -2201,8 +4306,9 @@ pub fn run() {
             // across all TMDB commands instead of being re-created each call.
             match app.path().app_cache_dir() {
                 Ok(cache_dir) => {
-                    app.manage(TmdbCacheState::new(cache_dir));
+                    app.manage(TmdbCacheState::new(cache_dir, app.handle().clone()));
                     info!("[TMDB] Cache state initialized");
+                    spawn_tmdb_auto_refresh(app.handle().clone());
                 }
                 Err(e) => {
                     error!("[TMDB] Failed to get cache dir for TmdbCacheState: {}", e);
@@ -2222,22 +4328,38 @@ pub fn run() {
                 });
             }
 
-            // Restore saved window position only (not size - size is controlled by UI settings)
-            // Position is restored so the window opens in the same place it was closed
-            restore_window_position(app.handle());
+            // Restore saved window size and position, clamped to the monitor so it stays on screen
+            restore_window_state(app.handle());
 
-            // Note: Window size is applied by the frontend after settings are loaded
-            // to ensure the user-defined startupWidth/startupHeight from Settings -> UI is respected
+            if read_store_setting(app.handle(), "alwaysOnTop").and_then(|v| v.as_bool()) == Some(true) {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.set_always_on_top(true);
+                }
+            }
+
+            // Note: The frontend re-applies startupWidth/startupHeight from Settings -> UI after
+            // settings are loaded, through apply_startup_window_size so it is clamped the same way
 
             Ok(())
         })
-        // Save window size/position when the window is about to close
+        // Save window size/position when the main window moves, resizes or is about to close
         .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                save_window_state(&window.app_handle());
+            match event {
+                tauri::WindowEvent::CloseRequested { .. } => {
+                    save_window_state(window.app_handle());
+                }
+                tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) if window.label() == "main" => {
+                    schedule_window_state_save(window.app_handle());
+                }
+                _ => {}
             }
         })
         .invoke_handler(tauri::generate_handler![
+            // Window commands
+            apply_startup_window_size,
+            reset_window_geometry,
+            set_always_on_top,
+            get_always_on_top,
             // MPV commands
             init_mpv,
             mpv_load,
@@ -2256,6 +4378,29 @@ pub fn run() {
             mpv_set_audio,
             mpv_set_subtitle,
             mpv_set_property,
+            mpv_is_ready,
+            mpv_set_hwdec,
+            mpv_set_deinterlace,
+            mpv_get_deinterlace,
+            mpv_set_aspect,
+            mpv_set_pan_scan,
+            mpv_reset_aspect,
+            mpv_apply_channel_aspect,
+            mpv_get_chapters,
+            set_next_on_eof,
+            get_watch_progress,
+            update_recording_watch_position,
+            set_recording_watched,
+            mpv_playlist_append,
+            mpv_playlist_clear,
+            mpv_playlist_next,
+            mpv_playlist_prev,
+            mpv_get_playlist,
+            mpv_chapter_next,
+            mpv_chapter_prev,
+            mpv_set_chapter,
+            get_mpv_extra_args,
+            set_mpv_extra_args,
             mpv_set_properties,
             mpv_get_property,
             mpv_sync_window,
@@ -2270,46 +4415,93 @@ pub fn run() {
             multiview_reposition_slot,
             multiview_kill_slot,
             multiview_kill_all,
+            multiview_set_audio_focus,
             // Optimized bulk sync commands
             sync_provider::sync_m3u_source,
+            sync_provider::import_m3u,
             sync_provider::sync_xtream_source,
             sync_provider::sync_xtream_vod_movies,
             sync_provider::sync_xtream_vod_series,
+            sync_provider::sync_xtream,
+            bulk_insert,
             bulk_upsert_channels,
+            search_channels,
+            query_channels,
+            query_movies,
             bulk_upsert_categories,
             bulk_replace_programs,
             bulk_upsert_movies,
             bulk_upsert_series,
+            diff_channels,
             bulk_delete_channels,
             bulk_delete_categories,
             update_source_meta,
+            capture_channel_logo,
             health_check,
+            get_dvr_init_status,
             // Streaming EPG commands
             stream_parse_epg,
             parse_epg_file,
             // DVR commands
             init_dvr,
             schedule_recording,
+            record_now,
+            schedule_series,
+            get_series_rules,
+            delete_series_rule,
             get_scheduled_recordings,
             cancel_recording,
+            pause_recording,
+            stop_and_keep_recording,
+            resume_recording,
             delete_recording,
+            concat_recording,
+            repair_recording_metadata,
+            probe_stream,
+            test_recording,
+            get_recording_log,
             get_completed_recordings,
+            query_recordings,
+            set_recording_protected,
+            bulk_delete_recordings,
+            export_recording,
+            trim_recording,
+            get_recordings_disk_usage,
             get_active_recordings,
+            get_recording_status,
+            get_queued_recordings,
             get_recording_thumbnail,
+            get_recording_sprite,
             update_schedule_paddings,
+            set_schedule_priority,
+            get_source_padding,
+            set_source_padding,
             check_schedule_conflicts,
+            get_storage_free_space,
+            get_now_next,
             update_playing_stream,
             update_dvr_stream_url,
             get_dvr_settings,
             save_dvr_setting,
+            set_storage_path,
+            migrate_storage,
             open_file_location,
             open_log_folder,
+            set_log_level,
             run_cleanup_now,
+            enforce_disk_quota,
+            vacuum_database,
+            scan_recordings,
+            integrity_check,
+            backup_database,
+            restore_database,
             // TMDB cache commands
             get_tmdb_cache_stats,
             update_tmdb_movies_cache,
             update_tmdb_series_cache,
             find_tmdb_movies,
+            find_tmdb_movie_by_imdb,
+            set_tmdb_export_urls,
             find_tmdb_series,
             clear_tmdb_cache,
             // TVMaze / TV Calendar commands
@@ -2333,3 +4525,43 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_http_error_code() {
+        assert_eq!(parse_http_error_code("[ffmpeg] http: http error 403 forbidden"), Some(403));
+        assert_eq!(parse_http_error_code("[ffmpeg/demuxer] server returned 404 not found"), Some(404));
+        assert_eq!(parse_http_error_code("http error: 401, unauthorized"), Some(401));
+        // Only client and server errors count
+        assert_eq!(parse_http_error_code("http error 302 found"), None);
+        assert_eq!(parse_http_error_code("server returned 600 whatever"), None);
+        // A marker with no code, or a code with no marker
+        assert_eq!(parse_http_error_code("http error while reading"), None);
+        assert_eq!(parse_http_error_code("stream 0: 404 packets dropped"), None);
+    }
+
+    #[test]
+    fn test_decode_errors_reported_only_in_bursts() {
+        assert!(is_decode_error("[ffmpeg/video] h264: error while decoding frame!"));
+        assert!(!is_decode_error("[ffmpeg] http: http error 404 not found"));
+
+        let mut counter = DecodeErrorCounter::default();
+        let start = std::time::Instant::now();
+
+        // A corrupt frame every few seconds on a live stream never adds up
+        for i in 0..10 {
+            assert!(!counter.record(start + std::time::Duration::from_secs(i * 3)));
+        }
+
+        let burst = start + std::time::Duration::from_secs(60);
+        for i in 0..DECODE_ERROR_THRESHOLD as u64 - 1 {
+            assert!(!counter.record(burst + std::time::Duration::from_millis(i * 100)));
+        }
+        assert!(counter.record(burst + std::time::Duration::from_secs(1)));
+        // Reported once, then counting starts over
+        assert!(!counter.record(burst + std::time::Duration::from_secs(2)));
+    }
+}
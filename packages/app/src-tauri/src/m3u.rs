@@ -0,0 +1,363 @@
+//! M3U playlist parsing
+//!
+//! Turns a raw `#EXTM3U` body into channel and category rows ready for the
+//! bulk upsert functions. Stream and category ids reproduce the JS local
+//! adapter's (`m3u-parser.ts`) exactly, so switching parsers keeps favorites attached.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::db_bulk_ops::{BulkCategory, BulkChannel};
+
+/// Channels and categories parsed from one playlist
+#[derive(Debug, Default)]
+pub struct ParsedM3u {
+    pub channels: Vec<BulkChannel>,
+    pub categories: Vec<BulkCategory>,
+    /// EPG URL from the header's `url-tvg` / `x-tvg-url` attribute
+    pub epg_url: Option<String>,
+}
+
+/// DJB2 hash in base36, matching the JS local adapter's `stableHash`
+///
+/// JS keeps the running hash as a float: only `hash << 5` is truncated to 32 bits,
+/// and the input is read as UTF-16 code units.
+fn stable_hash(s: &str) -> String {
+    let mut hash: f64 = 5381.0;
+    for unit in s.encode_utf16() {
+        // ToInt32 (the value is always integral), then a wrapping 32-bit shift
+        let int32 = hash.rem_euclid(4_294_967_296.0) as u32 as i32;
+        hash = (int32 << 5) as f64 + hash + unit as f64;
+    }
+
+    let mut n = hash.abs() as u64;
+    if n == 0 {
+        return "0".to_string();
+    }
+    let chars = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut digits = Vec::new();
+    while n > 0 {
+        digits.push(chars[(n % 36) as usize] as char);
+        n /= 36;
+    }
+    // Most significant digits first, like `toString(36).substring(0, 8)`
+    digits.iter().rev().take(8).collect()
+}
+
+/// Category id for a group title: `/[^a-z0-9]+/g` runs become one `-`, trimmed at the ends
+fn category_slug(group_title: &str) -> String {
+    let mut slug = String::new();
+    for c in group_title.to_lowercase().chars() {
+        if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+fn generate_stable_stream_id(source_id: &str, tvg_id: &str, url: &str, seen_ids: &mut HashSet<String>) -> String {
+    // JS replaces per UTF-16 unit, so characters outside the BMP become two underscores
+    let sanitized_tvg_id: String = tvg_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c.to_string()
+            } else {
+                "_".repeat(c.len_utf16())
+            }
+        })
+        .collect();
+
+    if !sanitized_tvg_id.is_empty() {
+        let base_id = format!("{}_{}", source_id, sanitized_tvg_id);
+        if !seen_ids.contains(&base_id) {
+            seen_ids.insert(base_id.clone());
+            return base_id;
+        }
+
+        let url_hash = stable_hash(url);
+        let unique_id = format!("{}_{}", base_id, url_hash);
+        seen_ids.insert(unique_id.clone());
+        return unique_id;
+    }
+
+    let url_hash = stable_hash(url);
+    let fallback_id = format!("{}_url_{}", source_id, url_hash);
+
+    if !seen_ids.contains(&fallback_id) {
+        seen_ids.insert(fallback_id.clone());
+        return fallback_id;
+    }
+
+    let mut counter = 1;
+    loop {
+        let final_id = format!("{}_{}", fallback_id, counter);
+        if !seen_ids.contains(&final_id) {
+            seen_ids.insert(final_id.clone());
+            return final_id;
+        }
+        counter += 1;
+    }
+}
+
+/// Split a directive line (without its `#EXTINF:` / `#EXTM3U` prefix) into its
+/// `key=value` attributes and the trailing title after the first unquoted comma.
+///
+/// Values may be double-quoted, single-quoted or bare; keys are lowercased.
+fn parse_directive(rest: &str) -> (HashMap<String, String>, Option<String>) {
+    let mut attrs = HashMap::new();
+    let chars: Vec<char> = rest.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == ',' {
+            let title: String = chars[i + 1..].iter().collect();
+            return (attrs, Some(title.trim().to_string()));
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        // Read a bare token: either a key (followed by '=') or the duration
+        let token_start = i;
+        while i < chars.len() && chars[i] != '=' && chars[i] != ',' && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        let token: String = chars[token_start..i].iter().collect();
+        if i >= chars.len() || chars[i] != '=' {
+            continue;
+        }
+        i += 1; // skip '='
+
+        let value = match chars.get(i) {
+            Some(&quote) if quote == '"' || quote == '\'' => {
+                i += 1;
+                let value_start = i;
+                while i < chars.len() && chars[i] != quote {
+                    i += 1;
+                }
+                let value: String = chars[value_start..i].iter().collect();
+                i += 1; // skip closing quote (or run past the end if it was missing)
+                value
+            }
+            _ => {
+                let value_start = i;
+                while i < chars.len() && chars[i] != ',' && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                chars[value_start..i].iter().collect()
+            }
+        };
+
+        attrs.insert(token.to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    (attrs, None)
+}
+
+/// Parse a playlist body into channel and category rows for `source_id`.
+///
+/// Lines other than `#EXTM3U`, `#EXTINF` and `#EXTGRP` are treated as comments.
+/// Entries whose URL line never arrives are dropped.
+pub fn parse_m3u(source_id: &str, content: &str) -> ParsedM3u {
+    let mut parsed = ParsedM3u::default();
+    let mut seen_categories = HashSet::new();
+    let mut seen_ids = HashSet::new();
+
+    let mut current_extinf: Option<(HashMap<String, String>, Option<String>)> = None;
+    let mut current_group: Option<String> = None;
+    let mut channel_counter = 0;
+
+    for line in content.lines().map(|l| l.trim_start_matches('\u{feff}').trim()) {
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("#EXTM3U") {
+            let (attrs, _) = parse_directive(rest);
+            parsed.epg_url = attrs
+                .get("url-tvg")
+                .or_else(|| attrs.get("x-tvg-url"))
+                .filter(|url| !url.is_empty())
+                .cloned();
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("#EXTINF:") {
+            current_extinf = Some(parse_directive(rest));
+            current_group = None;
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("#EXTGRP:") {
+            current_group = Some(rest.trim().to_string());
+            continue;
+        }
+
+        if line.starts_with('#') || !line.contains("://") {
+            continue;
+        }
+
+        let Some((attrs, title)) = current_extinf.take() else {
+            continue;
+        };
+        channel_counter += 1;
+
+        let attr = |key: &str| attrs.get(key).cloned().unwrap_or_default();
+        let tvg_id = attr("tvg-id");
+        let tvg_name = attr("tvg-name");
+        let tvg_logo = attr("tvg-logo");
+        let group_title = attrs
+            .get("group-title")
+            .filter(|g| !g.is_empty())
+            .cloned()
+            .or_else(|| current_group.take())
+            .unwrap_or_default();
+        let tvg_chno = attr("tvg-chno").parse::<i32>().ok();
+        let tv_archive = if attr("catchup").is_empty() && attr("catchup-source").is_empty() { 0 } else { 1 };
+
+        let name = title
+            .filter(|t| !t.is_empty())
+            .or_else(|| Some(tvg_name).filter(|n| !n.is_empty()))
+            .unwrap_or_else(|| format!("Channel {}", channel_counter));
+
+        let stream_id = generate_stable_stream_id(source_id, &tvg_id, line, &mut seen_ids);
+
+        let category_ids = if group_title.is_empty() {
+            "[]".to_string()
+        } else {
+            let category_id = format!("{}_{}", source_id, category_slug(&group_title));
+
+            if seen_categories.insert(category_id.clone()) {
+                parsed.categories.push(BulkCategory {
+                    category_id: category_id.clone(),
+                    category_name: group_title.clone(),
+                    source_id: source_id.to_string(),
+                    parent_id: None,
+                    enabled: None,
+                    display_order: None,
+                    channel_count: None,
+                    filter_words: None,
+                });
+            }
+            format!("[\"{}\"]", category_id)
+        };
+
+        parsed.channels.push(BulkChannel {
+            stream_id,
+            source_id: source_id.to_string(),
+            category_ids: Some(category_ids),
+            name,
+            channel_num: tvg_chno,
+            is_favorite: None,
+            enabled: None,
+            stream_type: Some("live".to_string()),
+            stream_icon: Some(tvg_logo),
+            epg_channel_id: Some(tvg_id),
+            added: None,
+            custom_sid: None,
+            tv_archive: Some(tv_archive),
+            direct_source: None,
+            direct_url: Some(line.to_string()),
+            xmltv_id: None,
+            series_no: None,
+            live: Some(1),
+        });
+    }
+
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoted_attribute_with_comma() {
+        let parsed = parse_m3u(
+            "src",
+            "#EXTM3U\n#EXTINF:-1 tvg-id=\"bbc.one\" tvg-logo='http://logo/bbc.png' group-title=\"News, Weather\",BBC One, HD\nhttp://host/bbc\n",
+        );
+        assert_eq!(parsed.channels.len(), 1);
+        let channel = &parsed.channels[0];
+        assert_eq!(channel.name, "BBC One, HD");
+        assert_eq!(channel.stream_id, "src_bbc.one");
+        assert_eq!(channel.stream_icon.as_deref(), Some("http://logo/bbc.png"));
+        assert_eq!(parsed.categories[0].category_name, "News, Weather");
+        assert_eq!(channel.category_ids.as_deref(), Some("[\"src_news-weather\"]"));
+    }
+
+    #[test]
+    fn test_missing_tvg_attributes() {
+        let parsed = parse_m3u("src", "#EXTM3U\n#EXTINF:-1,Plain\nhttp://host/plain\n#EXTINF:-1,\nhttp://host/untitled\n");
+        assert_eq!(parsed.channels.len(), 2);
+
+        let plain = &parsed.channels[0];
+        assert_eq!(plain.name, "Plain");
+        assert_eq!(plain.stream_id, "src_url_pnoj7m");
+        assert_eq!(plain.epg_channel_id.as_deref(), Some(""));
+        assert_eq!(plain.category_ids.as_deref(), Some("[]"));
+        assert_eq!(plain.channel_num, None);
+
+        // No title or tvg-name falls back to the position in the playlist
+        assert_eq!(parsed.channels[1].name, "Channel 2");
+        assert!(parsed.categories.is_empty());
+    }
+
+    /// Expected values come from the JS `stableHash` / `createCategoryId` in m3u-parser.ts
+    #[test]
+    fn test_ids_match_js_adapter() {
+        assert_eq!(stable_hash("http://example.com/live/user/pass/12345.ts"), "382kgd0");
+        assert_eq!(stable_hash("http://host/ü/📺"), "nwy0k");
+        assert_eq!(stable_hash(""), "45h");
+        assert_eq!(stable_hash(&"a".repeat(3000)), "oyqk69v");
+
+        assert_eq!(category_slug("News & Sports"), "news-sports");
+        assert_eq!(category_slug(" -Ünï Côde- "), "n-c-de");
+
+        let parsed = parse_m3u("src", "#EXTM3U\n#EXTINF:-1 tvg-id=\"📺x!\",TV\nhttp://host/tv\n");
+        assert_eq!(parsed.channels[0].stream_id, "src___x_");
+    }
+
+    #[test]
+    fn test_crlf_and_bom() {
+        let parsed = parse_m3u(
+            "src",
+            "\u{feff}#EXTM3U url-tvg=\"http://epg/guide.xml\"\r\n#EXTINF:-1 tvg-chno=\"7\",Seven\r\nhttp://host/seven\r\n",
+        );
+        assert_eq!(parsed.epg_url.as_deref(), Some("http://epg/guide.xml"));
+        assert_eq!(parsed.channels.len(), 1);
+        assert_eq!(parsed.channels[0].name, "Seven");
+        assert_eq!(parsed.channels[0].channel_num, Some(7));
+        assert_eq!(parsed.channels[0].direct_url.as_deref(), Some("http://host/seven"));
+    }
+
+    #[test]
+    fn test_extgrp_group() {
+        let parsed = parse_m3u(
+            "src",
+            "#EXTM3U\n#EXTINF:-1,Sports One\n#EXTGRP:Sports\nhttp://host/s1\n#EXTINF:-1 group-title=\"Movies\",Film\n#EXTGRP:Sports\nhttp://host/f\n#EXTINF:-1,Loose\nhttp://host/loose\n",
+        );
+        assert_eq!(parsed.channels.len(), 3);
+        assert_eq!(parsed.channels[0].category_ids.as_deref(), Some("[\"src_sports\"]"));
+        // group-title wins over #EXTGRP
+        assert_eq!(parsed.channels[1].category_ids.as_deref(), Some("[\"src_movies\"]"));
+        // A group only applies to the entry it follows
+        assert_eq!(parsed.channels[2].category_ids.as_deref(), Some("[]"));
+        assert_eq!(parsed.categories.len(), 2);
+    }
+
+    #[test]
+    fn test_entry_without_url_line_is_dropped() {
+        let parsed = parse_m3u(
+            "src",
+            "#EXTM3U\n#EXTINF:-1,No Url\n#EXTINF:-1,Has Url\n# a comment\nhttp://host/ok\n#EXTINF:-1,Trailing\n",
+        );
+        assert_eq!(parsed.channels.len(), 1);
+        assert_eq!(parsed.channels[0].name, "Has Url");
+        assert_eq!(parsed.channels[0].direct_url.as_deref(), Some("http://host/ok"));
+    }
+}
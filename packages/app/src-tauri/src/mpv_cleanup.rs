@@ -0,0 +1,114 @@
+//! Startup cleanup of MPV leftovers from previous runs
+//!
+//! A crashed or force-killed app can leave its MPV sidecars running (holding
+//! the player window or IPC socket) and, on Unix, socket files in /tmp. Both
+//! show up as "MPV won't start" or a black screen on the next launch.
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tracing::{info, warn};
+
+/// Command-line markers that identify an MPV sidecar spawned by this app
+const MPV_ARG_MARKERS: &[&str] = &[
+    "--title=YNOTV_MPV_",
+    "--input-ipc-server=/tmp/ynotv-mpv",
+    r"--input-ipc-server=\\.\pipe\mpv-socket-",
+    r"--input-ipc-server=\\.\pipe\mpv-secondary-",
+];
+
+/// Main player socket used on macOS and Linux; unlike slot sockets it has no owner PID
+#[cfg(unix)]
+const MAIN_SOCKET: &str = "/tmp/ynotv-mpv.sock";
+
+fn is_app_mpv(args: &[std::ffi::OsString]) -> bool {
+    args.iter()
+        .map(|arg| arg.to_string_lossy())
+        .any(|arg| MPV_ARG_MARKERS.iter().any(|marker| arg.starts_with(marker)))
+}
+
+/// Whether a process is an instance of this app
+fn is_app_process(process: &sysinfo::Process) -> bool {
+    process.name().to_string_lossy().to_lowercase().contains("ynotv")
+}
+
+/// Kill MPV sidecars left behind by a previous run and remove stale sockets.
+///
+/// A player counts as orphaned when its parent is gone, is init (PID 1, which
+/// adopts orphans on Unix) or is no longer this app (its PID was reused), so
+/// players owned by another running instance are left alone. Returns the number
+/// of processes killed.
+pub fn cleanup_stale_mpv() -> usize {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        ProcessRefreshKind::new().with_cmd(UpdateKind::Always),
+    );
+
+    let mut killed = 0;
+    for (pid, process) in system.processes() {
+        let name = process.name().to_string_lossy().to_lowercase();
+        if !name.starts_with("mpv") || !is_app_mpv(process.cmd()) {
+            continue;
+        }
+
+        let orphaned = match process.parent() {
+            None => true,
+            Some(parent) if parent.as_u32() == 1 => true,
+            Some(parent) => !system.process(parent).is_some_and(is_app_process),
+        };
+        if !orphaned {
+            continue;
+        }
+
+        if process.kill() {
+            info!("[MPV] Killed orphaned MPV process {} from a previous run", pid);
+            killed += 1;
+        } else {
+            warn!("[MPV] Failed to kill orphaned MPV process {}", pid);
+        }
+    }
+
+    #[cfg(unix)]
+    remove_stale_sockets(&system);
+
+    killed
+}
+
+/// Remove `/tmp/ynotv-mpv-slot-<slot>-<pid>.sock` files whose owning app process is dead,
+/// and the main player socket when no other instance of the app is running
+#[cfg(unix)]
+fn remove_stale_sockets(system: &System) {
+    let own_pid = std::process::id();
+    let other_instance = system
+        .processes()
+        .iter()
+        .any(|(pid, process)| pid.as_u32() != own_pid && is_app_process(process));
+    let main_socket = std::path::Path::new(MAIN_SOCKET);
+    if !other_instance && main_socket.exists() {
+        match std::fs::remove_file(main_socket) {
+            Ok(()) => info!("[MPV] Removed stale socket {:?}", main_socket),
+            Err(e) => warn!("[MPV] Failed to remove stale socket {:?}: {}", main_socket, e),
+        }
+    }
+
+    let Ok(entries) = std::fs::read_dir("/tmp") else { return };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(owner) = socket_owner_pid(&file_name) else { continue };
+        if owner == own_pid || system.process(sysinfo::Pid::from_u32(owner)).is_some_and(is_app_process) {
+            continue;
+        }
+
+        match std::fs::remove_file(entry.path()) {
+            Ok(()) => info!("[MPV] Removed stale socket {:?}", entry.path()),
+            Err(e) => warn!("[MPV] Failed to remove stale socket {:?}: {}", entry.path(), e),
+        }
+    }
+}
+
+/// Parse the owning PID out of a slot socket file name
+#[cfg(unix)]
+fn socket_owner_pid(file_name: &std::ffi::OsStr) -> Option<u32> {
+    let name = file_name.to_str()?;
+    let rest = name.strip_prefix("ynotv-mpv-slot-")?.strip_suffix(".sock")?;
+    rest.rsplit('-').next()?.parse().ok()
+}
@@ -63,6 +63,7 @@ pub async fn launch_mpv<R: Runtime>(
         format!("--input-ipc-server={}", IPC_SOCKET),
         "--vo=libmpv".to_string(),
         "--hwdec=no".to_string(),
+        format!("--volume-max={}", crate::MAX_VOLUME),
     ];
 
     // Add custom parameters from settings
@@ -103,30 +104,12 @@ pub async fn launch_mpv<R: Runtime>(
     tauri::async_runtime::spawn(async move {
         let mut parse_and_emit = |line_str: &str, app_handle: &tauri::AppHandle<R>| {
             let lower = line_str.to_lowercase();
-            let http_error_code: Option<u16> = if lower.contains("http error") || lower.contains("http error ") {
-                lower.find("http error")
-                    .and_then(|pos| {
-                        let after = &lower[pos + "http error".len()..];
-                        after.split_whitespace()
-                            .find_map(|part| {
-                                let clean = part.trim_matches(':').trim_matches(',');
-                                clean.parse::<u16>().ok().filter(|&c| c >= 400 && c < 600)
-                            })
-                    })
-            } else {
-                None
-            };
-
-            if let Some(code) = http_error_code {
-                let error_msg = match code {
-                    401 => "Access Denied (401): Authentication required".to_string(),
-                    403 => "Access Denied (403): Stream blocked by server".to_string(),
-                    404 => "Stream Not Found (404)".to_string(),
-                    _ => format!("HTTP Error ({}): Unable to load stream", code),
-                };
+            if let Some(code) = crate::parse_http_error_code(&lower) {
+                let error_msg = crate::http_error_message(code);
                 println!("[MPV] Emitting HTTP error: {}", error_msg);
                 let _ = app_handle.emit("mpv-http-error", error_msg);
             }
+            // No decode error prompt: the embedded player always decodes in software
         };
 
         while let Some(event) = rx.recv().await {
@@ -203,7 +186,7 @@ fn start_status_monitor<R: Runtime>(app: AppHandle<R>) {
                 let result = get_property_internal(&app, prop).await;
                 match (*prop, result) {
                     ("pause", Ok(Value::Bool(p))) => last_status.playing = !p,
-                    ("volume", Ok(Value::Number(v))) => last_status.volume = v.as_f64().unwrap_or(100.0),
+                    ("volume", Ok(Value::Number(v))) => last_status.volume = v.as_f64().unwrap_or(100.0).clamp(0.0, crate::MAX_VOLUME),
                     ("mute", Ok(Value::Bool(m))) => last_status.muted = m,
                     ("time-pos", Ok(Value::Number(t))) => last_status.position = t.as_f64().unwrap_or(0.0),
                     ("duration", Ok(Value::Number(d))) => last_status.duration = d.as_f64().unwrap_or(0.0),
@@ -335,6 +318,53 @@ pub async fn cycle_sub<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
     Ok(())
 }
 
+pub async fn playlist_append<R: Runtime>(app: &AppHandle<R>, url: &str) -> Result<(), String> {
+    send_command(app, json!({ "command": ["loadfile", url, "append-play"] })).await?;
+    Ok(())
+}
+
+pub async fn playlist_clear<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    send_command(app, json!({ "command": ["playlist-clear"] })).await?;
+    Ok(())
+}
+
+pub async fn playlist_next<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    send_command(app, json!({ "command": ["playlist-next", "weak"] })).await?;
+    Ok(())
+}
+
+pub async fn playlist_prev<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    send_command(app, json!({ "command": ["playlist-prev", "weak"] })).await?;
+    Ok(())
+}
+
+pub async fn get_playlist<R: Runtime>(app: &AppHandle<R>) -> Result<Value, String> {
+    get_property_internal(app, "playlist").await
+}
+
+pub async fn screenshot_to_file<R: Runtime>(app: &AppHandle<R>, path: &str) -> Result<(), String> {
+    send_command(app, json!({ "command": ["screenshot-to-file", path, "video"] })).await?;
+    Ok(())
+}
+
+pub async fn get_chapter_list<R: Runtime>(app: &AppHandle<R>) -> Result<Value, String> {
+    get_property_internal(app, "chapter-list").await
+}
+
+pub async fn get_chapter<R: Runtime>(app: &AppHandle<R>) -> Result<Value, String> {
+    get_property_internal(app, "chapter").await
+}
+
+pub async fn add_chapter<R: Runtime>(app: &AppHandle<R>, delta: i64) -> Result<(), String> {
+    send_command(app, json!({ "command": ["add", "chapter", delta] })).await?;
+    Ok(())
+}
+
+pub async fn set_chapter<R: Runtime>(app: &AppHandle<R>, index: i64) -> Result<(), String> {
+    send_command(app, json!({ "command": ["set_property", "chapter", index] })).await?;
+    Ok(())
+}
+
 pub async fn get_track_list<R: Runtime>(app: &AppHandle<R>) -> Result<Value, String> {
     send_command(app, json!({ "command": ["get_property", "track-list"] })).await
 }
@@ -1,41 +1,95 @@
-//! Secondary MPV instances for multiview slots 2, 3, and 4.
+//! Secondary MPV instances for multiview slots 2 through 9.
 //! Each slot gets its own MPV process embedded in the main HWND,
-//! resized to its quadrant via SetWindowPos.
+//! resized to its grid cell via SetWindowPos. Slot 1 is the main MPV.
 
 use std::collections::HashMap;
 use std::sync::Mutex;
 use std::time::Duration;
-use tauri::{AppHandle, Runtime, Manager};
+use tauri::{AppHandle, Emitter, Runtime, Manager};
 use tauri_plugin_shell::ShellExt;
 use tauri_plugin_shell::process::CommandEvent;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::windows::named_pipe::ClientOptions;
 use serde_json::{json, Value};
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 
 // ─── State ───────────────────────────────────────────────────────────────────
 
+/// Highest slot id, enough for a 3x3 grid (slot 1 is the main MPV)
+const MAX_SLOT_ID: u8 = 9;
+
+/// How often slot processes are checked for crashes
+const SLOT_HEALTH_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Respawn attempts for a crashed slot before giving up; the delay doubles from
+/// `SLOT_RESPAWN_BASE_DELAY` after each failure
+const MAX_SLOT_RESPAWNS: u32 = 5;
+const SLOT_RESPAWN_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// A slot that crashes again within this long of a respawn keeps its attempt count
+const SLOT_STABLE_AFTER: Duration = Duration::from_secs(60);
+
 struct SlotInstance {
     pid: u32,
     /// Raw HWND value stored as isize so it's Send
     hwnd: isize,
     ipc_tx: Option<tokio::sync::mpsc::Sender<String>>,
+    /// URL most recently loaded (None once stopped), used to respawn a crashed slot
+    last_url: Option<String>,
+    /// Last position as (x, y, width, height)
+    rect: (i32, i32, u32, u32),
+    /// Set while the health monitor is bringing a crashed slot back; `pid` is the dead process
+    respawning: bool,
+    /// Respawns so far in the current crash streak, and when the last one happened
+    respawns: u32,
+    respawned_at: Option<std::time::Instant>,
+}
+
+impl SlotInstance {
+    fn new(pid: u32, hwnd: isize, ipc_tx: Option<tokio::sync::mpsc::Sender<String>>, rect: (i32, i32, u32, u32)) -> Self {
+        SlotInstance { pid, hwnd, ipc_tx, last_url: None, rect, respawning: false, respawns: 0, respawned_at: None }
+    }
 }
 
 pub struct SecondaryMpvState {
+    /// Bumped whenever a slot is killed or (re)spawned, so a spawn that was
+    /// overtaken by a newer one can tell before registering its process.
+    /// Always locked before `slots` when both are needed.
+    generations: Mutex<HashMap<u8, u64>>,
     slots: Mutex<HashMap<u8, SlotInstance>>,
+    /// Slot whose audio is playing (1 = main MPV); every other slot is muted
+    audio_focus: Mutex<u8>,
 }
 
 impl SecondaryMpvState {
     pub fn new() -> Self {
         SecondaryMpvState {
+            generations: Mutex::new(HashMap::new()),
             slots: Mutex::new(HashMap::new()),
+            audio_focus: Mutex::new(1),
         }
     }
 }
 
 // ─── Helpers ──────────────────────────────────────────────────────────────────
 
+fn check_slot_id(slot_id: u8) -> Result<(), String> {
+    if (2..=MAX_SLOT_ID).contains(&slot_id) {
+        Ok(())
+    } else {
+        Err(format!("Invalid secondary slot {} (expected 2-{})", slot_id, MAX_SLOT_ID))
+    }
+}
+
+fn terminate_process(pid: u32) {
+    use windows::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
+    unsafe {
+        if let Ok(ph) = OpenProcess(PROCESS_TERMINATE, false, pid) {
+            let _ = TerminateProcess(ph, 0);
+        }
+    }
+}
+
 fn slot_socket_path(slot_id: u8) -> String {
     format!(r"\\.\pipe\mpv-secondary-{}-{}", slot_id, std::process::id())
 }
@@ -80,7 +134,24 @@ async fn send_ipc(tx: &tokio::sync::mpsc::Sender<String>, command: &str, args: V
     let _ = tx.send(msg).await;
 }
 
-async fn connect_ipc(socket_path: &str) -> Result<tokio::sync::mpsc::Sender<String>, String> {
+/// Playback state reported for a slot via `mpv-slot-status`
+#[derive(Clone, serde::Serialize)]
+struct SlotStatus {
+    slot_id: u8,
+    paused: bool,
+    /// True while MPV isn't advancing playback (paused, buffering or stalled)
+    idle: bool,
+    position: f64,
+}
+
+/// Minimum gap between status events that only carry a new position
+const SLOT_STATUS_POSITION_INTERVAL: Duration = Duration::from_secs(1);
+
+async fn connect_ipc<R: Runtime>(
+    app: &AppHandle<R>,
+    slot_id: u8,
+    socket_path: &str,
+) -> Result<tokio::sync::mpsc::Sender<String>, String> {
     let stream = {
         let mut retries = 15;
         loop {
@@ -99,19 +170,45 @@ async fn connect_ipc(socket_path: &str) -> Result<tokio::sync::mpsc::Sender<Stri
         }
     }?;
 
-    let (mut reader, mut writer) = tokio::io::split(stream);
+    let (reader, mut writer) = tokio::io::split(stream);
     let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(16);
 
-    // Spawn a dummy reader to continuously drain the named pipe OS buffer.
-    // MPV writes JSON events continuously. If we do not read from the buffer,
-    // the OS pipe fills up and blocks MPV's event loop, deadlocking the window.
+    // The reader must keep draining the named pipe: MPV writes JSON events
+    // continuously, and a full OS pipe buffer blocks MPV's event loop.
+    // Besides draining, it turns the observed properties into `mpv-slot-status`
+    // events (position updates are throttled to keep per-slot overhead low).
+    let app_handle = app.clone();
     tauri::async_runtime::spawn(async move {
-        use tokio::io::AsyncReadExt;
-        let mut buf = [0u8; 4096];
-        loop {
-            match reader.read(&mut buf).await {
-                Ok(0) | Err(_) => break, // Pipe closed or error
-                Ok(_) => {} // Discard all read bytes
+        let mut lines = BufReader::new(reader).lines();
+        let mut status = SlotStatus { slot_id, paused: false, idle: true, position: 0.0 };
+        let mut last_emit: Option<std::time::Instant> = None;
+
+        while let Ok(Some(line)) = lines.next_line().await {
+            let Ok(msg) = serde_json::from_str::<Value>(&line) else { continue };
+            if msg.get("event").and_then(Value::as_str) != Some("property-change") {
+                continue;
+            }
+            let data = msg.get("data");
+            let changed = match msg.get("name").and_then(Value::as_str) {
+                Some("pause") => {
+                    let paused = data.and_then(Value::as_bool).unwrap_or(false);
+                    std::mem::replace(&mut status.paused, paused) != paused
+                }
+                Some("core-idle") => {
+                    let idle = data.and_then(Value::as_bool).unwrap_or(true);
+                    std::mem::replace(&mut status.idle, idle) != idle
+                }
+                Some("time-pos") => {
+                    status.position = data.and_then(Value::as_f64).unwrap_or(0.0);
+                    false
+                }
+                _ => continue,
+            };
+
+            let due = !matches!(last_emit, Some(t) if t.elapsed() < SLOT_STATUS_POSITION_INTERVAL);
+            if changed || due {
+                last_emit = Some(std::time::Instant::now());
+                let _ = app_handle.emit("mpv-slot-status", status.clone());
             }
         }
     });
@@ -124,6 +221,10 @@ async fn connect_ipc(socket_path: &str) -> Result<tokio::sync::mpsc::Sender<Stri
         }
     });
 
+    for (id, property) in ["pause", "core-idle", "time-pos"].iter().enumerate() {
+        send_ipc(&tx, "observe_property", vec![json!(id + 1), json!(property)]).await;
+    }
+
     Ok(tx)
 }
 
@@ -133,29 +234,85 @@ async fn connect_ipc(socket_path: &str) -> Result<tokio::sync::mpsc::Sender<Stri
 pub async fn kill_slot<R: Runtime>(app: &AppHandle<R>, slot_id: u8) {
     let state = app.state::<SecondaryMpvState>();
     let maybe_pid = {
+        let mut generations = state.generations.lock().unwrap();
+        *generations.entry(slot_id).or_default() += 1;
         let mut slots = state.slots.lock().unwrap();
-        if let Some(slot) = slots.remove(&slot_id) {
-            drop(slot.ipc_tx); // close IPC channel
-            Some(slot.pid)
-        } else {
-            None
+        match slots.remove(&slot_id) {
+            // A respawning slot's process is already gone (and its pid may be reused)
+            Some(slot) if !slot.respawning => {
+                drop(slot.ipc_tx); // close IPC channel
+                Some(slot.pid)
+            }
+            _ => None,
         }
     };
     if let Some(pid) = maybe_pid {
-        use windows::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
-        unsafe {
-            if let Ok(ph) = OpenProcess(PROCESS_TERMINATE, false, pid) {
-                let _ = TerminateProcess(ph, 0);
-            }
+        terminate_process(pid);
+    }
+}
+
+/// Close a slot the user is done with; if it had the audio, the main MPV gets it back
+pub async fn close_slot<R: Runtime>(app: &AppHandle<R>, slot_id: u8) {
+    kill_slot(app, slot_id).await;
+
+    let state = app.state::<SecondaryMpvState>();
+    let had_focus = {
+        let mut focus = state.audio_focus.lock().unwrap();
+        let had_focus = *focus == slot_id;
+        if had_focus {
+            *focus = 1;
         }
+        had_focus
+    };
+    if had_focus {
+        let _ = crate::mpv_windows::set_property(app, "mute".to_string(), json!(false)).await;
     }
 }
 
-/// Kill all secondary slots
+/// Kill every tracked secondary slot
 pub async fn kill_all<R: Runtime>(app: &AppHandle<R>) {
-    kill_slot(app, 2).await;
-    kill_slot(app, 3).await;
-    kill_slot(app, 4).await;
+    let state = app.state::<SecondaryMpvState>();
+    let drained: Vec<SlotInstance> = {
+        let mut generations = state.generations.lock().unwrap();
+        let mut slots = state.slots.lock().unwrap();
+        for id in slots.keys() {
+            *generations.entry(*id).or_default() += 1;
+        }
+        slots.drain().map(|(_, slot)| slot).collect()
+    };
+    for slot in drained.into_iter().filter(|slot| !slot.respawning) {
+        drop(slot.ipc_tx); // close IPC channel
+        terminate_process(slot.pid);
+    }
+
+    // With the secondaries gone, audio belongs to the main MPV again
+    let previous_focus = std::mem::replace(&mut *state.audio_focus.lock().unwrap(), 1);
+    if previous_focus != 1 {
+        let _ = crate::mpv_windows::set_property(app, "mute".to_string(), json!(false)).await;
+    }
+}
+
+/// Unmute one slot (1 = main MPV) and mute all the others
+pub async fn set_audio_focus<R: Runtime>(app: &AppHandle<R>, slot_id: u8) -> Result<(), String> {
+    if slot_id != 1 {
+        check_slot_id(slot_id)?;
+    }
+
+    let state = app.state::<SecondaryMpvState>();
+    *state.audio_focus.lock().unwrap() = slot_id;
+    let slot_txs: Vec<(u8, tokio::sync::mpsc::Sender<String>)> = {
+        let slots = state.slots.lock().unwrap();
+        slots.iter()
+            .filter_map(|(id, slot)| slot.ipc_tx.clone().map(|tx| (*id, tx)))
+            .collect()
+    };
+
+    // Mute every player even if the main one fails, so two tiles are never heard at once
+    let main_result = crate::mpv_windows::set_property(app, "mute".to_string(), json!(slot_id != 1)).await;
+    for (id, tx) in slot_txs {
+        send_ipc(&tx, "set_property", vec![json!("mute"), json!(id != slot_id)]).await;
+    }
+    main_result
 }
 
 /// Spawn a secondary MPV for the given slot, positioned at (x, y, w, h)
@@ -167,12 +324,34 @@ pub async fn spawn_slot<R: Runtime>(
     width: u32,
     height: u32,
 ) -> Result<(), String> {
+    check_slot_id(slot_id)?;
+
     // Kill any existing instance
     kill_slot(app, slot_id).await;
 
+    let generation = current_generation(app, slot_id);
+    start_slot_process(app, slot_id, (x, y, width, height), generation).await
+}
+
+fn current_generation<R: Runtime>(app: &AppHandle<R>, slot_id: u8) -> u64 {
+    let state = app.state::<SecondaryMpvState>();
+    let generations = state.generations.lock().unwrap();
+    generations.get(&slot_id).copied().unwrap_or(0)
+}
+
+/// Start an MPV process for a slot and register it, unless the slot's generation
+/// moved on while it was starting (killed or spawned again), in which case the new
+/// process is terminated and an error returned. Replaces any respawn marker.
+async fn start_slot_process<R: Runtime>(
+    app: &AppHandle<R>,
+    slot_id: u8,
+    (x, y, width, height): (i32, i32, u32, u32),
+    generation: u64,
+) -> Result<(), String> {
     // Get parent HWND before any awaits
     let parent_hwnd_raw = get_parent_hwnd(app)?;
     let socket_path = slot_socket_path(slot_id);
+    let focused = *app.state::<SecondaryMpvState>().audio_focus.lock().unwrap() == slot_id;
 
     // Secondary slots use default args only - custom params only apply to main MPV
     let args = vec![
@@ -190,7 +369,8 @@ pub async fn spawn_slot<R: Runtime>(
         "--cursor-autohide=no".into(),
         "--no-terminal".into(),
         "--volume=80".into(),
-        "--mute=yes".into(),
+        format!("--volume-max={}", crate::MAX_VOLUME),
+        format!("--mute={}", if focused { "no" } else { "yes" }),
     ];
 
     let sidecar = app.shell().sidecar("mpv")
@@ -220,19 +400,26 @@ pub async fn spawn_slot<R: Runtime>(
 
     // Find the MPV child HWND by exact title and position it
     let target_title = format!("YNOTV_MPV_SLOT_{}", slot_id);
-    if let Some(hwnd_raw) = crate::mpv_windows::find_mpv_hwnd_by_title(parent_hwnd_raw, &target_title) {
+    let hwnd_raw = crate::mpv_windows::find_mpv_hwnd_by_title(parent_hwnd_raw, &target_title);
+    if let Some(hwnd_raw) = hwnd_raw {
         let _ = set_hwnd_rect(hwnd_raw, x, y, width, height, true);
-        // Store the discovered HWND so we don't need to search again
-        let ipc_tx = connect_ipc(&socket_path).await.ok();
-        let state = app.state::<SecondaryMpvState>();
-        let mut slots = state.slots.lock().unwrap();
-        slots.insert(slot_id, SlotInstance { pid, hwnd: hwnd_raw, ipc_tx });
-    } else {
-        let ipc_tx = connect_ipc(&socket_path).await.ok();
-        let state = app.state::<SecondaryMpvState>();
-        let mut slots = state.slots.lock().unwrap();
-        slots.insert(slot_id, SlotInstance { pid, hwnd: 0, ipc_tx });
     }
+    let ipc_tx = connect_ipc(app, slot_id, &socket_path).await.ok();
+
+    let state = app.state::<SecondaryMpvState>();
+    let generations = state.generations.lock().unwrap();
+    if generations.get(&slot_id).copied().unwrap_or(0) != generation {
+        terminate_process(pid);
+        return Err(format!("Slot {} was stopped or restarted while starting", slot_id));
+    }
+    // Store the discovered HWND so we don't need to search again
+    let mut slots = state.slots.lock().unwrap();
+    let mut instance = SlotInstance::new(pid, hwnd_raw.unwrap_or(0), ipc_tx, (x, y, width, height));
+    if let Some(marker) = slots.get(&slot_id).filter(|slot| slot.respawning) {
+        instance.last_url = marker.last_url.clone();
+        instance.respawns = marker.respawns;
+    }
+    slots.insert(slot_id, instance);
 
     Ok(())
 }
@@ -272,6 +459,11 @@ pub async fn load_slot<R: Runtime>(
     if let Some(tx) = tx {
         send_ipc(&tx, "loadfile", vec![json!(url.clone())]).await;
     }
+
+    let state = app.state::<SecondaryMpvState>();
+    if let Some(slot) = state.slots.lock().unwrap().get_mut(&slot_id) {
+        slot.last_url = Some(url);
+    }
     Ok(())
 }
 
@@ -279,8 +471,11 @@ pub async fn load_slot<R: Runtime>(
 pub async fn stop_slot<R: Runtime>(app: &AppHandle<R>, slot_id: u8) -> Result<(), String> {
     let tx = {
         let state = app.state::<SecondaryMpvState>();
-        let slots = state.slots.lock().unwrap();
-        slots.get(&slot_id).and_then(|s| s.ipc_tx.clone())
+        let mut slots = state.slots.lock().unwrap();
+        slots.get_mut(&slot_id).and_then(|s| {
+            s.last_url = None;
+            s.ipc_tx.clone()
+        })
     };
     if let Some(tx) = tx {
         send_ipc(&tx, "stop", vec![]).await;
@@ -317,8 +512,11 @@ pub async fn reposition_slot<R: Runtime>(
 ) -> Result<(), String> {
     let slot_entry = {
         let state = app.state::<SecondaryMpvState>();
-        let slots = state.slots.lock().unwrap();
-        slots.get(&slot_id).map(|s| (s.hwnd, s.pid))
+        let mut slots = state.slots.lock().unwrap();
+        slots.get_mut(&slot_id).map(|s| {
+            s.rect = (x, y, width, height);
+            (s.hwnd, s.pid)
+        })
     };
 
     if let Some((hwnd, _pid)) = slot_entry {
@@ -349,3 +547,107 @@ pub async fn reposition_slot<R: Runtime>(
 
     Ok(())
 }
+
+/// Watch slot processes and respawn any that died, reloading their last URL.
+/// Emits `mpv-slot-recovered` for each recovered slot, or `mpv-slot-failed`
+/// once a slot has used up its respawn attempts.
+pub fn start_health_monitor<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SLOT_HEALTH_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let dead: Vec<(u8, u64)> = {
+                let state = app.state::<SecondaryMpvState>();
+                let generations = state.generations.lock().unwrap();
+                let mut slots = state.slots.lock().unwrap();
+                let ids: Vec<u8> = slots.iter()
+                    .filter(|(_, slot)| !slot.respawning && !crate::mpv_windows::is_process_alive(slot.pid))
+                    .map(|(id, _)| *id)
+                    .collect();
+
+                let mut dead = Vec::new();
+                for id in ids {
+                    let slot = slots.get_mut(&id).unwrap();
+                    if slot.last_url.is_none() {
+                        eprintln!("[MPV-{}] Process exited while idle, not respawning", id);
+                        slots.remove(&id);
+                        continue;
+                    }
+                    // Leave a marker so commands and later scans see the slot is being handled
+                    slot.respawning = true;
+                    slot.ipc_tx = None;
+                    if !matches!(slot.respawned_at, Some(t) if t.elapsed() < SLOT_STABLE_AFTER) {
+                        slot.respawns = 0;
+                    }
+                    dead.push((id, generations.get(&id).copied().unwrap_or(0)));
+                }
+                dead
+            };
+
+            for (slot_id, generation) in dead {
+                tauri::async_runtime::spawn(respawn_slot(app.clone(), slot_id, generation));
+            }
+        }
+    });
+}
+
+/// Bring back a crashed slot, with exponential backoff between attempts.
+/// Gives up quietly if the slot is killed, stopped or reloaded meanwhile.
+async fn respawn_slot<R: Runtime>(app: AppHandle<R>, slot_id: u8, generation: u64) {
+    loop {
+        // Re-read the marker each time: the slot may have moved, been stopped or killed
+        let marker = {
+            let state = app.state::<SecondaryMpvState>();
+            let generations = state.generations.lock().unwrap();
+            let slots = state.slots.lock().unwrap();
+            slots.get(&slot_id)
+                .filter(|slot| slot.respawning && generations.get(&slot_id).copied().unwrap_or(0) == generation)
+                .map(|slot| (slot.last_url.clone(), slot.rect, slot.respawns))
+        };
+        let Some((Some(url), rect, respawns)) = marker else {
+            return;
+        };
+
+        if respawns >= MAX_SLOT_RESPAWNS {
+            eprintln!("[MPV-{}] Giving up after {} respawn attempts", slot_id, respawns);
+            {
+                let state = app.state::<SecondaryMpvState>();
+                let _generations = state.generations.lock().unwrap();
+                let mut slots = state.slots.lock().unwrap();
+                if slots.get(&slot_id).is_some_and(|slot| slot.respawning) {
+                    slots.remove(&slot_id);
+                }
+            }
+            let _ = app.emit("mpv-slot-failed", json!({ "slot_id": slot_id, "url": url, "attempts": respawns }));
+            return;
+        }
+
+        tokio::time::sleep(SLOT_RESPAWN_BASE_DELAY * 2u32.pow(respawns)).await;
+        if let Some(slot) = app.state::<SecondaryMpvState>().slots.lock().unwrap().get_mut(&slot_id) {
+            if slot.respawning {
+                slot.respawns += 1;
+            }
+        }
+
+        eprintln!("[MPV-{}] Process died, respawning with last URL (attempt {})", slot_id, respawns + 1);
+        match start_slot_process(&app, slot_id, rect, generation).await {
+            Ok(()) => {
+                let tx = {
+                    let state = app.state::<SecondaryMpvState>();
+                    let mut slots = state.slots.lock().unwrap();
+                    slots.get_mut(&slot_id).and_then(|slot| {
+                        slot.respawned_at = Some(std::time::Instant::now());
+                        slot.ipc_tx.clone()
+                    })
+                };
+                if let Some(tx) = tx {
+                    send_ipc(&tx, "loadfile", vec![json!(url.clone())]).await;
+                }
+                let _ = app.emit("mpv-slot-recovered", json!({ "slot_id": slot_id, "url": url }));
+                return;
+            }
+            Err(e) => eprintln!("[MPV-{}] Respawn failed: {}", slot_id, e),
+        }
+    }
+}
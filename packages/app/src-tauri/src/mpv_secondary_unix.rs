@@ -0,0 +1,469 @@
+//! Secondary MPV instances for multiview slots 2 through 9 on macOS and Linux.
+//!
+//! Each slot is an MPV sidecar controlled over its own unix socket. On Linux
+//! under X11 the slot renders (via `--wid`) into a child window created inside
+//! the app window at its tile's rect, so it moves with the app like the
+//! Windows slots. Elsewhere it is a borderless MPV window placed at the tile's
+//! screen position (via `--geometry`): on macOS behind the transparent window
+//! like the hole-punched main player in `mpv_macos.rs`, on Wayland floating
+//! above the app. Exposes the same API as the Windows `mpv_secondary` module
+//! so the multiview commands are shared.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_shell::ShellExt;
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+
+// ─── State ───────────────────────────────────────────────────────────────────
+
+/// Highest slot id, enough for a 3x3 grid (slot 1 is the main MPV)
+const MAX_SLOT_ID: u8 = 9;
+
+/// macOS slots go behind the hole-punched window; Linux has no hole punch,
+/// so slots that can't be embedded are kept above the app instead
+#[cfg(target_os = "macos")]
+const SLOT_ONTOP: &str = "--ontop=no";
+#[cfg(not(target_os = "macos"))]
+const SLOT_ONTOP: &str = "--ontop=yes";
+
+struct SlotInstance {
+    child: CommandChild,
+    ipc_tx: Option<tokio::sync::mpsc::Sender<String>>,
+    /// Position inside the main window as (x, y, width, height), so the slot
+    /// can be moved back into place when the window itself moves
+    rect: (i32, i32, u32, u32),
+    /// X11 child window the slot renders into; `None` when floating
+    #[cfg(target_os = "linux")]
+    host: Option<x11_host::HostWindow>,
+}
+
+pub struct SecondaryMpvState {
+    slots: Mutex<HashMap<u8, SlotInstance>>,
+    /// Slot whose audio is playing (1 = main MPV); every other slot is muted
+    audio_focus: Mutex<u8>,
+}
+
+impl SecondaryMpvState {
+    pub fn new() -> Self {
+        SecondaryMpvState {
+            slots: Mutex::new(HashMap::new()),
+            audio_focus: Mutex::new(1),
+        }
+    }
+}
+
+// ─── X11 embedding ────────────────────────────────────────────────────────────
+
+#[cfg(target_os = "linux")]
+mod x11_host {
+    use std::os::raw::c_ulong;
+    use x11_dl::xlib;
+
+    /// A child window of the app's X11 window for one slot's MPV to render
+    /// into, on its own display connection
+    pub struct HostWindow {
+        xlib: xlib::Xlib,
+        display: *mut xlib::Display,
+        window: c_ulong,
+    }
+
+    // The display connection is only used by whoever holds the slots lock
+    unsafe impl Send for HostWindow {}
+
+    impl HostWindow {
+        pub fn create(parent: c_ulong, x: i32, y: i32, width: u32, height: u32) -> Result<Self, String> {
+            let xlib = xlib::Xlib::open().map_err(|e| format!("Failed to load Xlib: {}", e))?;
+            unsafe {
+                let display = (xlib.XOpenDisplay)(std::ptr::null());
+                if display.is_null() {
+                    return Err("Failed to open X display".to_string());
+                }
+                let black = (xlib.XBlackPixel)(display, (xlib.XDefaultScreen)(display));
+                let window = (xlib.XCreateSimpleWindow)(
+                    display, parent, x, y, width.max(1), height.max(1), 0, black, black,
+                );
+                (xlib.XMapRaised)(display, window);
+                (xlib.XFlush)(display);
+                Ok(HostWindow { xlib, display, window })
+            }
+        }
+
+        pub fn id(&self) -> c_ulong {
+            self.window
+        }
+
+        /// Move and resize within the app window; MPV follows its parent's size
+        pub fn move_resize(&self, x: i32, y: i32, width: u32, height: u32) {
+            unsafe {
+                (self.xlib.XMoveResizeWindow)(self.display, self.window, x, y, width.max(1), height.max(1));
+                (self.xlib.XRaiseWindow)(self.display, self.window);
+                (self.xlib.XFlush)(self.display);
+            }
+        }
+    }
+
+    impl Drop for HostWindow {
+        fn drop(&mut self) {
+            unsafe {
+                (self.xlib.XDestroyWindow)(self.display, self.window);
+                (self.xlib.XCloseDisplay)(self.display);
+            }
+        }
+    }
+}
+
+/// Create a child window for a slot inside the main window, or `None` when
+/// the app isn't running on X11 (e.g. native Wayland) and the slot has to float
+#[cfg(target_os = "linux")]
+fn embed_host<R: Runtime>(app: &AppHandle<R>, x: i32, y: i32, width: u32, height: u32) -> Option<x11_host::HostWindow> {
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+    let window = app.get_webview_window("main")?;
+    let parent = match window.window_handle().ok()?.as_raw() {
+        RawWindowHandle::Xlib(h) => h.window,
+        _ => return None,
+    };
+    match x11_host::HostWindow::create(parent, x, y, width, height) {
+        Ok(host) => Some(host),
+        Err(e) => {
+            eprintln!("[MPV] Can't embed multiview slot, using a floating window: {}", e);
+            None
+        }
+    }
+}
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+fn check_slot_id(slot_id: u8) -> Result<(), String> {
+    if (2..=MAX_SLOT_ID).contains(&slot_id) {
+        Ok(())
+    } else {
+        Err(format!("Invalid secondary slot {} (expected 2-{})", slot_id, MAX_SLOT_ID))
+    }
+}
+
+fn slot_socket_path(slot_id: u8) -> String {
+    format!("/tmp/ynotv-mpv-slot-{}-{}.sock", slot_id, std::process::id())
+}
+
+/// Convert a rect relative to the main window's content area into an MPV
+/// `--geometry` string in screen coordinates
+fn screen_geometry<R: Runtime>(app: &AppHandle<R>, x: i32, y: i32, width: u32, height: u32) -> Result<String, String> {
+    let window = app.get_webview_window("main")
+        .ok_or("Main window not found")?;
+    let origin = window.inner_position().map_err(|e| e.to_string())?;
+    Ok(format!("{}x{}+{}+{}", width, height, origin.x + x, origin.y + y))
+}
+
+async fn send_ipc(tx: &tokio::sync::mpsc::Sender<String>, command: &str, args: Vec<Value>) {
+    let mut cmd_args = vec![Value::String(command.to_string())];
+    cmd_args.extend(args);
+    let msg = json!({ "command": cmd_args }).to_string();
+    let _ = tx.send(msg).await;
+}
+
+async fn connect_ipc(socket_path: &str) -> Result<tokio::sync::mpsc::Sender<String>, String> {
+    let stream = {
+        let mut retries = 15;
+        loop {
+            match UnixStream::connect(socket_path).await {
+                Ok(s) => break Ok(s),
+                Err(_) if retries > 0 => {
+                    tokio::time::sleep(Duration::from_millis(300)).await;
+                    retries -= 1;
+                }
+                Err(e) => break Err(format!("Secondary IPC connect failed: {}", e)),
+            }
+        }
+    }?;
+
+    let (mut reader, mut writer) = stream.into_split();
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(16);
+
+    // Keep draining the socket so MPV's event loop never blocks on a full buffer
+    tauri::async_runtime::spawn(async move {
+        let mut buf = [0u8; 4096];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            let _ = writer.write_all(msg.as_bytes()).await;
+            let _ = writer.write_all(b"\n").await;
+            let _ = writer.flush().await;
+        }
+    });
+
+    Ok(tx)
+}
+
+/// Mute or unmute the main player (Linux has no separately managed main MPV)
+async fn set_main_mute<R: Runtime>(app: &AppHandle<R>, muted: bool) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        crate::mpv_macos::set_property(app, "mute".to_string(), json!(muted)).await
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (app, muted);
+        Ok(())
+    }
+}
+
+fn slot_tx<R: Runtime>(app: &AppHandle<R>, slot_id: u8) -> Option<tokio::sync::mpsc::Sender<String>> {
+    let state = app.state::<SecondaryMpvState>();
+    let slots = state.slots.lock().unwrap();
+    slots.get(&slot_id).and_then(|s| s.ipc_tx.clone())
+}
+
+// ─── Public API ──────────────────────────────────────────────────────────────
+
+/// Kill any existing secondary MPV for the given slot
+pub async fn kill_slot<R: Runtime>(app: &AppHandle<R>, slot_id: u8) {
+    let state = app.state::<SecondaryMpvState>();
+    let removed = state.slots.lock().unwrap().remove(&slot_id);
+    if let Some(slot) = removed {
+        drop(slot.ipc_tx); // close IPC channel
+        let _ = slot.child.kill();
+        let _ = std::fs::remove_file(slot_socket_path(slot_id));
+    }
+}
+
+/// Close a slot the user is done with; if it had the audio, the main MPV gets it back
+pub async fn close_slot<R: Runtime>(app: &AppHandle<R>, slot_id: u8) {
+    kill_slot(app, slot_id).await;
+
+    let state = app.state::<SecondaryMpvState>();
+    let had_focus = {
+        let mut focus = state.audio_focus.lock().unwrap();
+        let had_focus = *focus == slot_id;
+        if had_focus {
+            *focus = 1;
+        }
+        had_focus
+    };
+    if had_focus {
+        let _ = set_main_mute(app, false).await;
+    }
+}
+
+/// Kill every tracked secondary slot
+pub async fn kill_all<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<SecondaryMpvState>();
+    let drained: Vec<(u8, SlotInstance)> = state.slots.lock().unwrap().drain().collect();
+    for (slot_id, slot) in drained {
+        drop(slot.ipc_tx);
+        let _ = slot.child.kill();
+        let _ = std::fs::remove_file(slot_socket_path(slot_id));
+    }
+
+    // With the secondaries gone, audio belongs to the main MPV again
+    let previous_focus = std::mem::replace(&mut *state.audio_focus.lock().unwrap(), 1);
+    if previous_focus != 1 {
+        let _ = set_main_mute(app, false).await;
+    }
+}
+
+/// Spawn a secondary MPV for the given slot, positioned at (x, y, w, h) inside the main window
+pub async fn spawn_slot<R: Runtime>(
+    app: &AppHandle<R>,
+    slot_id: u8,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+) -> Result<(), String> {
+    check_slot_id(slot_id)?;
+
+    // Kill any existing instance
+    kill_slot(app, slot_id).await;
+
+    let socket_path = slot_socket_path(slot_id);
+    let _ = std::fs::remove_file(&socket_path);
+    let focused = *app.state::<SecondaryMpvState>().audio_focus.lock().unwrap() == slot_id;
+
+    // Secondary slots use default args only - custom params only apply to main MPV
+    let mut args = vec![
+        format!("--input-ipc-server={}", socket_path),
+        format!("--title=YNOTV_MPV_SLOT_{}", slot_id),
+        "--force-window=yes".into(),
+        "--idle=yes".into(),
+        "--keep-open=yes".into(),
+        "--no-osc".into(),
+        "--no-osd-bar".into(),
+        "--osd-level=0".into(),
+        "--no-input-default-bindings".into(),
+        "--cursor-autohide=no".into(),
+        "--no-terminal".into(),
+        "--hwdec=no".into(),
+        "--volume=80".into(),
+        format!("--volume-max={}", crate::MAX_VOLUME),
+        format!("--mute={}", if focused { "no" } else { "yes" }),
+    ];
+
+    #[cfg(target_os = "linux")]
+    let host = embed_host(app, x, y, width, height);
+    #[cfg(target_os = "linux")]
+    let embedded = host.as_ref().map(|h| h.id());
+    #[cfg(not(target_os = "linux"))]
+    let embedded: Option<u64> = None;
+
+    match embedded {
+        Some(wid) => args.push(format!("--wid={}", wid)),
+        None => args.extend([
+            format!("--geometry={}", screen_geometry(app, x, y, width, height)?),
+            "--no-border".into(),
+            SLOT_ONTOP.into(),
+        ]),
+    }
+
+    let sidecar = app.shell().sidecar("mpv")
+        .map_err(|e| format!("Sidecar error: {}", e))?;
+
+    let (mut rx, child) = sidecar.args(&args).spawn()
+        .map_err(|e| format!("Failed to spawn secondary MPV: {}", e))?;
+
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stderr(line) => {
+                    eprintln!("[MPV-{}] {}", slot_id, String::from_utf8_lossy(&line));
+                }
+                CommandEvent::Terminated(_) => break,
+                _ => {}
+            }
+        }
+    });
+
+    let ipc_tx = connect_ipc(&socket_path).await.ok();
+    let state = app.state::<SecondaryMpvState>();
+    state.slots.lock().unwrap().insert(slot_id, SlotInstance {
+        child,
+        ipc_tx,
+        rect: (x, y, width, height),
+        #[cfg(target_os = "linux")]
+        host,
+    });
+
+    Ok(())
+}
+
+/// Load a URL in a secondary slot. Spawns the slot MPV if not yet running.
+pub async fn load_slot<R: Runtime>(
+    app: &AppHandle<R>,
+    slot_id: u8,
+    url: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+) -> Result<(), String> {
+    if slot_tx(app, slot_id).is_none() {
+        spawn_slot(app, slot_id, x, y, width, height).await?;
+    } else {
+        reposition_slot(app, slot_id, x, y, width, height).await?;
+    }
+
+    if let Some(tx) = slot_tx(app, slot_id) {
+        send_ipc(&tx, "loadfile", vec![json!(url)]).await;
+    }
+    Ok(())
+}
+
+/// Stop playback in a slot (keep MPV alive)
+pub async fn stop_slot<R: Runtime>(app: &AppHandle<R>, slot_id: u8) -> Result<(), String> {
+    if let Some(tx) = slot_tx(app, slot_id) {
+        send_ipc(&tx, "stop", vec![]).await;
+    }
+    Ok(())
+}
+
+/// Set an MPV property (like "pause", "volume") for a specific slot
+pub async fn set_property_slot<R: Runtime>(
+    app: &AppHandle<R>,
+    slot_id: u8,
+    property: &str,
+    value: Value,
+) -> Result<(), String> {
+    if let Some(tx) = slot_tx(app, slot_id) {
+        send_ipc(&tx, "set_property", vec![json!(property), value]).await;
+    }
+    Ok(())
+}
+
+/// Move a running slot to a new rect inside the main window.
+/// Also called on window moves, since a floating slot is positioned in screen coordinates.
+pub async fn reposition_slot<R: Runtime>(
+    app: &AppHandle<R>,
+    slot_id: u8,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+) -> Result<(), String> {
+    let tx = {
+        let state = app.state::<SecondaryMpvState>();
+        let mut slots = state.slots.lock().unwrap();
+        slots.get_mut(&slot_id).and_then(|s| {
+            s.rect = (x, y, width, height);
+            #[cfg(target_os = "linux")]
+            if let Some(host) = &s.host {
+                host.move_resize(x, y, width, height);
+                return None;
+            }
+            s.ipc_tx.clone()
+        })
+    };
+
+    if let Some(tx) = tx {
+        let geometry = screen_geometry(app, x, y, width, height)?;
+        send_ipc(&tx, "set_property", vec![json!("geometry"), json!(geometry)]).await;
+    }
+    Ok(())
+}
+
+/// Move every slot back over its tile after the main window moved or resized
+pub async fn sync_slots<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let rects: Vec<(u8, (i32, i32, u32, u32))> = {
+        let state = app.state::<SecondaryMpvState>();
+        let slots = state.slots.lock().unwrap();
+        slots.iter().map(|(id, slot)| (*id, slot.rect)).collect()
+    };
+    for (slot_id, (x, y, width, height)) in rects {
+        reposition_slot(app, slot_id, x, y, width, height).await?;
+    }
+    Ok(())
+}
+
+/// Unmute one slot (1 = main MPV) and mute all the others
+pub async fn set_audio_focus<R: Runtime>(app: &AppHandle<R>, slot_id: u8) -> Result<(), String> {
+    if slot_id != 1 {
+        check_slot_id(slot_id)?;
+    }
+
+    let state = app.state::<SecondaryMpvState>();
+    *state.audio_focus.lock().unwrap() = slot_id;
+    let slot_txs: Vec<(u8, tokio::sync::mpsc::Sender<String>)> = {
+        let slots = state.slots.lock().unwrap();
+        slots.iter()
+            .filter_map(|(id, slot)| slot.ipc_tx.clone().map(|tx| (*id, tx)))
+            .collect()
+    };
+
+    // Mute every player even if the main one fails, so two tiles are never heard at once
+    let main_result = set_main_mute(app, slot_id != 1).await;
+    for (id, tx) in slot_txs {
+        send_ipc(&tx, "set_property", vec![json!("mute"), json!(id != slot_id)]).await;
+    }
+    main_result
+}
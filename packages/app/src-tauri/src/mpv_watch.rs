@@ -0,0 +1,189 @@
+//! Watch progress tracking for the main player
+//!
+//! `mpv_load` registers the URL it is about to load together with what to track.
+//! Tracking only switches over once MPV reports that exact path as loaded, so
+//! position ticks from the previous file (and its stale duration) are never
+//! saved under the new item.
+
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::dvr::database::DvrDatabase;
+use crate::dvr::DvrState;
+
+/// How often the position is written to watch progress while playing
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Media whose playback position is tracked while it plays
+#[derive(Clone, Debug, PartialEq)]
+pub enum WatchTarget {
+    /// Entry in the frontend's VOD watch history (`media_type` is "movie" or "series")
+    Vod { media_id: String, media_type: String },
+    Recording(i64),
+}
+
+/// A load that hasn't been confirmed by MPV yet
+#[derive(Debug)]
+struct PendingLoad {
+    url: String,
+    target: Option<WatchTarget>,
+    resume_at: Option<f64>,
+}
+
+/// What the main player is tracking, switched only on MPV's file-loaded
+#[derive(Debug, Default)]
+pub struct WatchTracker {
+    pending: Option<PendingLoad>,
+    active: Option<WatchTarget>,
+    last_save: Option<Instant>,
+}
+
+impl WatchTracker {
+    /// Register the next file to load; nothing is saved until it has loaded
+    pub fn load(&mut self, url: String, target: Option<WatchTarget>, resume_at: Option<f64>) {
+        self.pending = Some(PendingLoad { url, target, resume_at });
+        self.active = None;
+    }
+
+    /// A new file started (ours or one MPV moved to on its own); stop saving
+    pub fn start_file(&mut self) {
+        self.active = None;
+    }
+
+    /// MPV loaded `path`. Starts tracking if it is the file registered last and
+    /// returns where to resume it.
+    pub fn file_loaded(&mut self, path: &str) -> Option<f64> {
+        if self.pending.as_ref().is_none_or(|p| p.url != path) {
+            self.active = None;
+            return None;
+        }
+        let pending = self.pending.take()?;
+        self.active = pending.target;
+        self.last_save = None;
+        pending.resume_at
+    }
+
+    /// A position tick; returns what to save when a save is due
+    pub fn tick(&mut self, position: f64, duration: f64, now: Instant) -> Option<(WatchTarget, f64, f64)> {
+        let target = self.active.clone()?;
+        if position <= 0.0 || self.last_save.is_some_and(|at| now.duration_since(at) < SAVE_INTERVAL) {
+            return None;
+        }
+        self.last_save = Some(now);
+        Some((target, position, duration))
+    }
+}
+
+/// Persist a progress tick off the IPC reader
+pub fn save<R: Runtime>(app: &AppHandle<R>, target: WatchTarget, position: f64, duration: f64) {
+    let Some(dvr) = app.try_state::<DvrState>() else { return };
+
+    let db = dvr.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = match &target {
+            WatchTarget::Vod { media_id, media_type } => db.save_watch_progress(media_id, media_type, position, duration),
+            WatchTarget::Recording(id) => {
+                db.update_recording_watch_position(*id, position, Some(duration).filter(|d| *d > 0.0))
+            }
+        };
+        if let Err(e) = result {
+            log::warn!("[MPV] Failed to save watch progress for {:?}: {}", target, e);
+        }
+    });
+}
+
+/// Where to resume a VOD item or recording: its saved position unless it was watched
+pub fn saved_resume_position(db: &DvrDatabase, target: &WatchTarget) -> Option<f64> {
+    let saved = match target {
+        WatchTarget::Vod { media_id, media_type } => db
+            .get_watch_progress(media_id, media_type)
+            .map(|p| p.map(|p| (p.position, p.watched))),
+        WatchTarget::Recording(id) => db
+            .get_recording(*id)
+            .map(|r| r.map(|r| (r.watched_position, r.watched))),
+    };
+    match saved {
+        Ok(Some((position, false))) if position > 10.0 => Some(position),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("[MPV] Failed to read watch progress: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vod(id: &str) -> WatchTarget {
+        WatchTarget::Vod { media_id: id.to_string(), media_type: "movie".to_string() }
+    }
+
+    #[test]
+    fn test_ticks_before_file_loaded_are_not_saved() {
+        let mut tracker = WatchTracker::default();
+        let now = Instant::now();
+        tracker.load("http://a/1.mp4".to_string(), Some(vod("1")), None);
+        tracker.file_loaded("http://a/1.mp4");
+        assert_eq!(tracker.tick(30.0, 600.0, now), Some((vod("1"), 30.0, 600.0)));
+
+        // The old file keeps ticking until MPV switches over
+        tracker.load("http://a/2.mp4".to_string(), Some(vod("2")), Some(120.0));
+        assert_eq!(tracker.tick(45.0, 600.0, now + SAVE_INTERVAL), None);
+
+        tracker.start_file();
+        assert_eq!(tracker.file_loaded("http://a/2.mp4"), Some(120.0));
+        assert_eq!(tracker.tick(121.0, 3_000.0, now + SAVE_INTERVAL), Some((vod("2"), 121.0, 3_000.0)));
+    }
+
+    #[test]
+    fn test_file_loaded_for_other_path_stops_tracking() {
+        let mut tracker = WatchTracker::default();
+        let now = Instant::now();
+        tracker.load("http://a/1.mp4".to_string(), Some(vod("1")), None);
+        tracker.file_loaded("http://a/1.mp4");
+
+        // A superseded load finishing late doesn't take the newer item's target
+        tracker.load("http://a/3.mp4".to_string(), Some(vod("3")), Some(60.0));
+        assert_eq!(tracker.file_loaded("http://a/2.mp4"), None);
+        assert_eq!(tracker.tick(10.0, 600.0, now), None);
+        assert_eq!(tracker.file_loaded("http://a/3.mp4"), Some(60.0));
+
+        // MPV moving to a playlist entry on its own isn't tracked as the last item
+        tracker.start_file();
+        assert_eq!(tracker.file_loaded("http://a/4.mp4"), None);
+        assert_eq!(tracker.tick(10.0, 600.0, now), None);
+    }
+
+    #[test]
+    fn test_recording_never_gets_previous_media_position() {
+        let mut tracker = WatchTracker::default();
+        let now = Instant::now();
+        tracker.load("http://a/1.mp4".to_string(), Some(vod("1")), None);
+        tracker.file_loaded("http://a/1.mp4");
+
+        // Near the end of the film, which would mark the recording watched
+        tracker.load("file:///rec.ts".to_string(), Some(WatchTarget::Recording(7)), Some(900.0));
+        assert_eq!(tracker.tick(5_300.0, 5_400.0, now), None);
+
+        tracker.start_file();
+        assert_eq!(tracker.tick(5_301.0, 5_400.0, now), None);
+        assert_eq!(tracker.file_loaded("file:///rec.ts"), Some(900.0));
+        assert_eq!(tracker.tick(901.0, 3_600.0, now), Some((WatchTarget::Recording(7), 901.0, 3_600.0)));
+    }
+
+    #[test]
+    fn test_ticks_are_rate_limited() {
+        let mut tracker = WatchTracker::default();
+        let now = Instant::now();
+        tracker.load("file:///rec.ts".to_string(), Some(WatchTarget::Recording(7)), None);
+        tracker.file_loaded("file:///rec.ts");
+
+        assert!(tracker.tick(0.0, 0.0, now).is_none());
+        assert!(tracker.tick(1.0, 0.0, now).is_some());
+        assert!(tracker.tick(5.0, 0.0, now + Duration::from_secs(4)).is_none());
+        assert!(tracker.tick(11.0, 0.0, now + SAVE_INTERVAL).is_some());
+    }
+}
@@ -14,6 +14,8 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::windows::named_pipe::ClientOptions;
 use serde_json::{json, Value};
 
+use crate::mpv_watch::{self, WatchTarget, WatchTracker};
+
 pub struct MpvState {
     pub process: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
     pub child: Mutex<Option<CommandChild>>,
@@ -23,6 +25,10 @@ pub struct MpvState {
     pub pending_requests: Arc<Mutex<HashMap<u64, tokio::sync::oneshot::Sender<Result<Value, String>>>>>,
     pub request_id_counter: Mutex<u64>,
     pub initializing: Mutex<bool>,
+    /// URL to load when the current file ends cleanly (next episode), consumed once
+    pub next_on_eof: Mutex<Option<String>>,
+    /// VOD item or recording being played, whose position is saved as watch progress
+    pub watch: Mutex<WatchTracker>,
 }
 
 impl MpvState {
@@ -36,6 +42,8 @@ impl MpvState {
             pending_requests: Arc::new(Mutex::new(HashMap::new())),
             request_id_counter: Mutex::new(0),
             initializing: Mutex::new(false),
+            next_on_eof: Mutex::new(None),
+            watch: Mutex::new(WatchTracker::default()),
         }
     }
 }
@@ -75,7 +83,7 @@ fn kill_and_clear_state(state: &tauri::State<'_, MpvState>) {
 
 /// Check if a Windows process with the given PID is still alive.
 #[cfg(target_os = "windows")]
-fn is_process_alive(pid: u32) -> bool {
+pub(crate) fn is_process_alive(pid: u32) -> bool {
     use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
     use windows::Win32::Foundation::CloseHandle;
     unsafe {
@@ -96,7 +104,7 @@ fn is_process_alive(pid: u32) -> bool {
 }
 
 #[cfg(not(target_os = "windows"))]
-fn is_process_alive(_pid: u32) -> bool {
+pub(crate) fn is_process_alive(_pid: u32) -> bool {
     false
 }
 
@@ -109,6 +117,16 @@ pub struct MpvStatus {
     pub duration: f64,
 }
 
+/// Stream details for the current file, emitted as `mpv-media-info`
+#[derive(Clone, Serialize, Debug, Default)]
+pub struct MpvMediaInfo {
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub video_bitrate: Option<f64>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
 enum MpvResponse {
@@ -252,6 +270,7 @@ async fn try_spawn_mpv<R: Runtime>(app: &AppHandle<R>, state: &tauri::State<'_,
         "--input-default-bindings=no".into(),
         "--no-input-cursor".into(),
         "--cursor-autohide=no".into(),
+        format!("--volume-max={}", crate::MAX_VOLUME),
     ];
 
     // Add custom parameters from settings
@@ -293,30 +312,15 @@ async fn try_spawn_mpv<R: Runtime>(app: &AppHandle<R>, state: &tauri::State<'_,
         let mut proc_handle = state.process.lock().unwrap();
         let app_handle_for_stderr = app.clone();
         *proc_handle = Some(tauri::async_runtime::spawn(async move {
-            let parse_and_emit = |line_str: &str, app_handle: &tauri::AppHandle<R>| {
+            let mut decode_errors = crate::DecodeErrorCounter::default();
+            let mut parse_and_emit = |line_str: &str, app_handle: &tauri::AppHandle<R>| {
                 let lower = line_str.to_lowercase();
-                let http_error_code: Option<u16> = if lower.contains("http error") || lower.contains("http error ") {
-                    lower.find("http error")
-                        .and_then(|pos| {
-                            let after = &lower[pos + "http error".len()..];
-                            after.split_whitespace()
-                                .find_map(|part| {
-                                    let clean = part.trim_matches(':').trim_matches(',');
-                                    clean.parse::<u16>().ok().filter(|&c| c >= 400 && c < 600)
-                                })
-                        })
-                } else {
-                    None
-                };
-
-                if let Some(code) = http_error_code {
-                    let error_msg = match code {
-                        401 => "Access Denied (401): Authentication required".to_string(),
-                        403 => "Access Denied (403): Stream blocked by server".to_string(),
-                        404 => "Stream Not Found (404)".to_string(),
-                        _ => format!("HTTP Error ({}): Unable to load stream", code),
-                    };
-                    let _ = app_handle.emit("mpv-http-error", error_msg);
+                if let Some(code) = crate::parse_http_error_code(&lower) {
+                    let _ = app_handle.emit("mpv-http-error", crate::http_error_message(code));
+                }
+
+                if crate::is_decode_error(&lower) && decode_errors.record(std::time::Instant::now()) {
+                    let _ = app_handle.emit("mpv-decode-error", line_str.trim().to_string());
                 }
             };
 
@@ -407,9 +411,10 @@ async fn connect_ipc<R: Runtime>(
     // Spawn reader task
     let app_handle = app.clone();
     let pending_requests = state.pending_requests.clone();
+    let reconnect_path = socket_path.to_string();
 
     tauri::async_runtime::spawn(async move {
-        let mut line = String::new();
+        let mut buf = Vec::new();
         let mut status = MpvStatus {
             playing: false,
             volume: 100.0,
@@ -417,20 +422,47 @@ async fn connect_ipc<R: Runtime>(
             position: 0.0,
             duration: 0.0,
         };
+        let mut media_info = MpvMediaInfo::default();
 
         loop {
-            line.clear();
-            match buf_reader.read_line(&mut line).await {
+            buf.clear();
+            // Read raw bytes: a title or path that isn't valid UTF-8 must not stop the pipe being drained
+            match buf_reader.read_until(b'\n', &mut buf).await {
                 Ok(0) => break,
                 Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf);
                     if let Ok(msg) = serde_json::from_str::<MpvResponse>(&line) {
                         match msg {
                             MpvResponse::Event { event, name, data } => {
                                 if event == "property-change" {
                                     if let (Some(name), Some(data)) = (name, data) {
+                                        let media_changed = match name.as_str() {
+                                            "video-params/w" => { media_info.width = data.as_i64(); true }
+                                            "video-params/h" => { media_info.height = data.as_i64(); true }
+                                            "video-codec" => { media_info.video_codec = data.as_str().map(str::to_string); true }
+                                            "audio-codec" => { media_info.audio_codec = data.as_str().map(str::to_string); true }
+                                            "video-bitrate" => { media_info.video_bitrate = data.as_f64(); true }
+                                            _ => false,
+                                        };
+                                        if media_changed {
+                                            let _ = app_handle.emit("mpv-media-info", media_info.clone());
+                                            continue;
+                                        }
+                                        if name == "eof-reached" {
+                                            // With keep-open=yes MPV parks on the last frame instead of sending end-file
+                                            if data.as_bool() == Some(true) {
+                                                advance_on_eof(&app_handle);
+                                            }
+                                            continue;
+                                        }
+                                        if name == "playlist-count" {
+                                            let _ = app_handle.emit("mpv-playlist-changed", data.as_i64().unwrap_or(0));
+                                            continue;
+                                        }
+
                                         match name.as_str() {
                                             "pause" => status.playing = !data.as_bool().unwrap_or(false),
-                                            "volume" => status.volume = data.as_f64().unwrap_or(100.0),
+                                            "volume" => status.volume = data.as_f64().unwrap_or(100.0).clamp(0.0, crate::MAX_VOLUME),
                                             "mute" => status.muted = data.as_bool().unwrap_or(false),
                                             "time-pos" => status.position = data.as_f64().unwrap_or(0.0),
                                             "duration" => status.duration = data.as_f64().unwrap_or(0.0),
@@ -455,14 +487,34 @@ async fn connect_ipc<R: Runtime>(
                                             }
                                             _ => {}
                                         }
+                                        if name == "time-pos" {
+                                            let due = app_handle.state::<MpvState>().watch.lock().unwrap()
+                                                .tick(status.position, status.duration, std::time::Instant::now());
+                                            if let Some((target, position, duration)) = due {
+                                                mpv_watch::save(&app_handle, target, position, duration);
+                                            }
+                                        }
                                         let _ = app_handle.emit("mpv-status", status.clone());
                                     }
+                                } else if event == "file-loaded" {
+                                    let _ = app_handle.emit("mpv-media-info", media_info.clone());
+                                    // Asked off the reader, which is what delivers the reply
+                                    let app = app_handle.clone();
+                                    tauri::async_runtime::spawn(async move { switch_watch_target(&app).await });
+                                } else if event == "start-file" {
+                                    media_info = MpvMediaInfo::default();
+                                    // The old file's position and duration must not be saved for the new one
+                                    status.position = 0.0;
+                                    status.duration = 0.0;
+                                    app_handle.state::<MpvState>().watch.lock().unwrap().start_file();
                                 } else if event == "end-file" {
                                     // Parse fallback errors if stderr didn't catch them
                                     let reason = data.clone().and_then(|d| d.get("reason").and_then(|r| r.as_str().map(|s| s.to_string())));
                                     let file_error = data.and_then(|d| d.get("file_error").and_then(|e| e.as_str().map(|s| s.to_string())));
                                     
-                                    if reason.as_deref() == Some("error") {
+                                    if reason.as_deref() == Some("eof") {
+                                        advance_on_eof(&app_handle);
+                                    } else if reason.as_deref() == Some("error") {
                                         let error_msg = match file_error.as_deref() {
                                             Some(e) if e.to_lowercase().contains("403") || e.to_lowercase().contains("forbidden") =>
                                                 "Access Denied (403): Stream blocked by server".to_string(),
@@ -497,7 +549,9 @@ async fn connect_ipc<R: Runtime>(
                 Err(_) => break,
             }
         }
-        let _ = app_handle.emit("mpv-error", "IPC connection lost");
+
+        *app_handle.state::<MpvState>().socket_connected.lock().unwrap() = false;
+        reconnect_ipc(app_handle, reconnect_path).await;
     });
 
     // Observe properties
@@ -507,11 +561,100 @@ async fn connect_ipc<R: Runtime>(
     let _ = send_command_internal(state, "observe_property", vec![json!(4), json!("time-pos")]).await;
     let _ = send_command_internal(state, "observe_property", vec![json!(5), json!("duration")]).await;
     let _ = send_command_internal(state, "observe_property", vec![json!(6), json!("demuxer-cache-state")]).await;
+    let _ = send_command_internal(state, "observe_property", vec![json!(7), json!("video-params/w")]).await;
+    let _ = send_command_internal(state, "observe_property", vec![json!(8), json!("video-params/h")]).await;
+    let _ = send_command_internal(state, "observe_property", vec![json!(9), json!("video-codec")]).await;
+    let _ = send_command_internal(state, "observe_property", vec![json!(10), json!("audio-codec")]).await;
+    let _ = send_command_internal(state, "observe_property", vec![json!(11), json!("video-bitrate")]).await;
+    let _ = send_command_internal(state, "observe_property", vec![json!(12), json!("playlist-count")]).await;
+    let _ = send_command_internal(state, "observe_property", vec![json!(13), json!("eof-reached")]).await;
 
     let _ = app.emit("mpv-ready", true);
     Ok(())
 }
 
+/// Start tracking the file MPV just loaded if it is the one `mpv_load` registered,
+/// seeking to its resume position
+async fn switch_watch_target<R: Runtime>(app: &AppHandle<R>) {
+    let path = match get_property(app, "path".to_string()).await {
+        Ok(Value::String(path)) => path,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("[MPV] Failed to read loaded path: {}", e);
+            return;
+        }
+    };
+    let resume = app.state::<MpvState>().watch.lock().unwrap().file_loaded(&path);
+    if let Some(position) = resume {
+        if let Err(e) = seek(app, position).await {
+            log::warn!("[MPV] Failed to resume at {:.0}s: {}", position, e);
+        }
+    }
+}
+
+/// Register the next file to load and what to track for it (see `mpv_watch`)
+pub fn set_watch_target<R: Runtime>(app: &AppHandle<R>, url: &str, target: Option<WatchTarget>, resume_at: Option<f64>) {
+    app.state::<MpvState>().watch.lock().unwrap().load(url.to_string(), target, resume_at);
+}
+
+/// Load the queued next-episode URL, if any, after the current file ended cleanly
+fn advance_on_eof<R: Runtime>(app: &AppHandle<R>) {
+    let Some(url) = app.state::<MpvState>().next_on_eof.lock().unwrap().take() else { return };
+    log::info!("[MPV] End of file, advancing to next: {}", url);
+    // Progress of the finished episode must not be overwritten by the next one
+    set_watch_target(app, &url, None, None);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match load_file(&app, url.clone()).await {
+            Ok(()) => {
+                let _ = app.emit("mpv-next-episode", url);
+            }
+            Err(e) => log::warn!("[MPV] Failed to load next episode: {}", e),
+        }
+    });
+}
+
+/// Queue (or with `None`, clear) the URL to play when the current file reaches its end
+pub fn set_next_on_eof<R: Runtime>(app: &AppHandle<R>, url: Option<String>) {
+    *app.state::<MpvState>().next_on_eof.lock().unwrap() = url;
+}
+
+/// Number of reconnect attempts after the IPC pipe drops
+const IPC_RECONNECT_ATTEMPTS: u32 = 5;
+
+/// Try to reattach to a still-running MPV after its IPC pipe closed.
+/// MPV usually survives (idle) when the pipe drops, so reconnecting restores
+/// playback control without restarting the app. Boxed because it is spawned
+/// from inside `connect_ipc`, which it calls again.
+fn reconnect_ipc<R: Runtime>(
+    app: AppHandle<R>,
+    socket_path: String,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> {
+    Box::pin(async move {
+        let state = app.state::<MpvState>();
+        for attempt in 1..=IPC_RECONNECT_ATTEMPTS {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            let pid = *state.pid.lock().unwrap();
+            if pid == 0 || !is_process_alive(pid) {
+                break;
+            }
+
+            log::warn!("[MPV] IPC connection lost, reconnecting (attempt {}/{})", attempt, IPC_RECONNECT_ATTEMPTS);
+            match connect_ipc(&app, &state, &socket_path).await {
+                Ok(()) => {
+                    log::info!("[MPV] IPC reconnected");
+                    return;
+                }
+                Err(e) => log::warn!("[MPV] IPC reconnect failed: {}", e),
+            }
+        }
+
+        let _ = app.emit("mpv-error", "IPC connection lost");
+    })
+}
+
 async fn send_command_internal(
     state: &tauri::State<'_, MpvState>,
     command: &str,
@@ -641,6 +784,56 @@ pub async fn cycle_sub<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
     send_command_internal(&state, "cycle", vec![json!("sub")]).await.map(|_| ())
 }
 
+pub async fn playlist_append<R: Runtime>(app: &AppHandle<R>, url: &str) -> Result<(), String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "loadfile", vec![json!(url), json!("append-play")]).await.map(|_| ())
+}
+
+pub async fn playlist_clear<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "playlist-clear", vec![]).await.map(|_| ())
+}
+
+pub async fn playlist_next<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "playlist-next", vec![json!("weak")]).await.map(|_| ())
+}
+
+pub async fn playlist_prev<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "playlist-prev", vec![json!("weak")]).await.map(|_| ())
+}
+
+pub async fn get_playlist<R: Runtime>(app: &AppHandle<R>) -> Result<Value, String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "get_property", vec![json!("playlist")]).await
+}
+
+pub async fn screenshot_to_file<R: Runtime>(app: &AppHandle<R>, path: &str) -> Result<(), String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "screenshot-to-file", vec![json!(path), json!("video")]).await.map(|_| ())
+}
+
+pub async fn get_chapter_list<R: Runtime>(app: &AppHandle<R>) -> Result<Value, String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "get_property", vec![json!("chapter-list")]).await
+}
+
+pub async fn get_chapter<R: Runtime>(app: &AppHandle<R>) -> Result<Value, String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "get_property", vec![json!("chapter")]).await
+}
+
+pub async fn add_chapter<R: Runtime>(app: &AppHandle<R>, delta: i64) -> Result<(), String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "add", vec![json!("chapter"), json!(delta)]).await.map(|_| ())
+}
+
+pub async fn set_chapter<R: Runtime>(app: &AppHandle<R>, index: i64) -> Result<(), String> {
+    let state = app.state::<MpvState>();
+    send_command_internal(&state, "set_property", vec![json!("chapter"), json!(index)]).await.map(|_| ())
+}
+
 pub async fn get_track_list<R: Runtime>(app: &AppHandle<R>) -> Result<Value, String> {
     let state = app.state::<MpvState>();
     send_command_internal(&state, "get_property", vec![json!("track-list")]).await
@@ -665,6 +858,21 @@ pub async fn set_property<R: Runtime>(
     send_command_internal(&state, "set_property", vec![json!(name), value]).await.map(|_| ())
 }
 
+/// Whether the MPV process is alive
+pub fn is_running<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let pid = *app.state::<MpvState>().pid.lock().unwrap();
+    pid > 0 && is_process_alive(pid)
+}
+
+/// Whether MPV currently has a file loaded (`path` is unavailable while idle)
+pub async fn is_file_loaded<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let state = app.state::<MpvState>();
+    matches!(
+        send_command_internal(&state, "get_property", vec![json!("path")]).await,
+        Ok(path) if !path.is_null()
+    )
+}
+
 pub async fn get_property<R: Runtime>(app: &AppHandle<R>, name: String) -> Result<Value, String> {
     let state = app.state::<MpvState>();
     send_command_internal(&state, "get_property", vec![json!(name)]).await
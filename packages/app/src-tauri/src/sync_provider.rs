@@ -1,8 +1,9 @@
 use crate::db_bulk_ops::{self, BulkCategory, BulkChannel, BulkResult};
+use crate::dvr::database::DvrDatabase;
 use crate::dvr::DvrState;
+use crate::m3u::{self, ParsedM3u};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
 use tracing::{error, info};
 
 // ============================================================================
@@ -162,7 +163,7 @@ pub async fn sync_xtream_source(
     for b in &bulk_channels {
         parsed_channel_ids.push(b.stream_id.clone());
     }
-    let result_chans = db_bulk_ops::bulk_upsert_channels(&state.db, bulk_channels).map_err(|e| e.to_string())?;
+    let result_chans = db_bulk_ops::bulk_upsert_channels(&state.db, bulk_channels, false).map_err(|e| e.to_string())?;
 
     info!("[Xtream Sync] Competed successfully: {} categories, {} channels", result_cats.inserted + result_cats.updated, result_chans.inserted + result_chans.updated);
 
@@ -178,62 +179,6 @@ pub async fn sync_xtream_source(
 // Sync M3U
 // ============================================================================
 
-/// Basic stable hash implementation mirroring JS local-adapter stableHash logic (DJB2 base36)
-fn stable_hash(s: &str) -> String {
-    let mut hash: i32 = 5381;
-    for b in s.bytes() {
-        hash = (hash << 5).wrapping_add(hash).wrapping_add(b as i32);
-    }
-    let mut n = hash.abs() as u32;
-    if n == 0 {
-        return "0".to_string();
-    }
-    let mut res = String::new();
-    let chars = b"0123456789abcdefghijklmnopqrstuvwxyz";
-    while n > 0 {
-        res.push(chars[(n % 36) as usize] as char);
-        n /= 36;
-    }
-    // Return first 8 chars, exactly like JS `substring(0, 8)` after reversal
-    let reversed: String = res.chars().rev().take(8).collect();
-    reversed
-}
-
-fn generate_stable_stream_id(source_id: &str, tvg_id: &str, url: &str, seen_ids: &mut HashSet<String>) -> String {
-    let sanitized_tvg_id = tvg_id.replace(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '_' && c != '-', "_");
-
-    if !sanitized_tvg_id.is_empty() {
-        let base_id = format!("{}_{}", source_id, sanitized_tvg_id);
-        if !seen_ids.contains(&base_id) {
-            seen_ids.insert(base_id.clone());
-            return base_id;
-        }
-
-        let url_hash = stable_hash(url);
-        let unique_id = format!("{}_{}", base_id, url_hash);
-        seen_ids.insert(unique_id.clone());
-        return unique_id;
-    }
-
-    let url_hash = stable_hash(url);
-    let fallback_id = format!("{}_url_{}", source_id, url_hash);
-    
-    if !seen_ids.contains(&fallback_id) {
-        seen_ids.insert(fallback_id.clone());
-        return fallback_id;
-    }
-
-    let mut counter = 1;
-    loop {
-        let final_id = format!("{}_{}", fallback_id, counter);
-        if !seen_ids.contains(&final_id) {
-            seen_ids.insert(final_id.clone());
-            return final_id;
-        }
-        counter += 1;
-    }
-}
-
 #[derive(Serialize)]
 pub struct M3uSyncResult {
     pub categories: BulkResult,
@@ -319,130 +264,48 @@ pub async fn sync_m3u_source(
     let content = client.get(&url).send().await.map_err(|e| e.to_string())?
         .text().await.map_err(|e| e.to_string())?;
 
-    let mut bulk_channels = Vec::new();
-    let mut bulk_categories = Vec::new();
-    let mut categories_map = HashMap::new();
-    let mut seen_ids = HashSet::new();
-
-    let mut current_extinf: Option<String> = None;
-    let mut channel_counter = 0;
-    let mut epg_url: Option<String> = None;
-
-    for line in content.lines().map(|l| l.trim()) {
-        if line.is_empty() { continue; }
-
-        if line.starts_with("#EXTM3U") {
-            // Extract epg_url from url-tvg=".." or x-tvg-url=".."
-            if let Some(start) = line.find("url-tvg=\"").or_else(|| line.find("x-tvg-url=\"")) {
-                let quote_start = line[start..].find('"').unwrap() + 1;
-                let substr = &line[start + quote_start..];
-                if let Some(end) = substr.find('"') {
-                    epg_url = Some(substr[..end].to_string());
-                }
-            }
-            continue;
-        }
-
-        if line.starts_with("#EXTINF:") {
-            current_extinf = Some(line.to_string());
-            continue;
-        }
-
-        if line.starts_with('#') {
-            continue;
-        }
-
-        if let Some(extinf) = current_extinf.take() {
-            if line.starts_with("http://") || line.starts_with("https://") || line.starts_with("rtmp://") {
-                channel_counter += 1;
-
-                // Simple attribute extraction
-                let extract_attr = |key: &str| -> String {
-                    if let Some(start) = extinf.find(&format!("{}=\"", key)) {
-                        let substr = &extinf[start + key.len() + 2..];
-                        if let Some(end) = substr.find('"') {
-                            return substr[..end].to_string();
-                        }
-                    }
-                    "".to_string()
-                };
-
-                let duration_str = extinf[8..].split_whitespace().next().unwrap_or("-1").replace(",", "");
-                let _duration = duration_str.parse::<i32>().unwrap_or(-1);
-
-                let tvg_id = extract_attr("tvg-id");
-                let tvg_name = extract_attr("tvg-name");
-                let tvg_logo = extract_attr("tvg-logo");
-                let group_title = extract_attr("group-title");
-                let tvg_chno_str = extract_attr("tvg-chno");
-                let tvg_chno = tvg_chno_str.parse::<i32>().ok();
-                
-                let tv_archive = if extract_attr("catchup") != "" || extract_attr("catchup-source") != "" { 1 } else { 0 };
-
-                let display_name = if let Some(comma_pos) = extinf.rfind(',') {
-                    extinf[comma_pos + 1..].trim().to_string()
-                } else {
-                    format!("Channel {}", channel_counter)
-                };
-
-                let stream_id = generate_stable_stream_id(&source_id, &tvg_id, line, &mut seen_ids);
-
-                let mut category_ids = Vec::new();
-                if !group_title.is_empty() {
-                    let cat_slug = group_title.to_lowercase().replace(|c: char| !c.is_ascii_alphanumeric(), "-").trim_matches('-').to_string();
-                    let category_id = format!("{}_{}", source_id, cat_slug);
-                    category_ids.push(category_id.clone());
-
-                    if !categories_map.contains_key(&category_id) {
-                        categories_map.insert(category_id.clone(), true);
-                        bulk_categories.push(BulkCategory {
-                            category_id,
-                            category_name: group_title.clone(),
-                            source_id: source_id.clone(),
-                            parent_id: None,
-                            enabled: None,
-                            display_order: None,
-                            channel_count: None,
-                            filter_words: None,
-                        });
-                    }
-                }
-
-                bulk_channels.push(BulkChannel {
-                    stream_id,
-                    source_id: source_id.clone(),
-                    category_ids: if category_ids.is_empty() { Some("[]".to_string()) } else { Some(format!("[\"{}\"]", category_ids[0])) },
-                    name: if !display_name.is_empty() { display_name } else { tvg_name.clone() },
-                    channel_num: tvg_chno,
-                    is_favorite: None,
-                    enabled: None,
-                    stream_type: Some("live".to_string()),
-                    stream_icon: Some(tvg_logo),
-                    epg_channel_id: Some(tvg_id),
-                    added: None,
-                    custom_sid: None,
-                    tv_archive: Some(tv_archive),
-                    direct_source: None,
-                    direct_url: Some(line.to_string()),
-                    xmltv_id: None,
-                    series_no: None,
-                    live: Some(1),
-                });
-            }
-        }
+    store_m3u(&state.db, &source_id, &content)
+}
+
+/// Import an M3U playlist given as a URL, a local file path, or the raw playlist text
+#[tauri::command]
+pub async fn import_m3u(
+    state: tauri::State<'_, DvrState>,
+    source_id: String,
+    content_or_url: String,
+    user_agent: Option<String>,
+) -> Result<M3uSyncResult, String> {
+    let trimmed = content_or_url.trim();
+
+    if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
+        return sync_m3u_source(state, source_id, trimmed.to_string(), user_agent).await;
     }
 
+    let content = if !trimmed.starts_with("#EXTM3U") && std::path::Path::new(trimmed).is_file() {
+        info!("[M3U Import] Reading playlist file {}", trimmed);
+        tokio::fs::read_to_string(trimmed).await.map_err(|e| format!("Failed to read playlist file: {}", e))?
+    } else {
+        content_or_url
+    };
+
+    store_m3u(&state.db, &source_id, &content)
+}
+
+/// Parse a playlist body and upsert its categories and channels
+fn store_m3u(db: &DvrDatabase, source_id: &str, content: &str) -> Result<M3uSyncResult, String> {
+    let ParsedM3u { channels: bulk_channels, categories: bulk_categories, epg_url } = m3u::parse_m3u(source_id, content);
+
     let mut parsed_category_ids = Vec::with_capacity(bulk_categories.len());
     for b in &bulk_categories {
         parsed_category_ids.push(b.category_id.clone());
     }
-    let result_cats = db_bulk_ops::bulk_upsert_categories(&state.db, bulk_categories).map_err(|e| e.to_string())?;
+    let result_cats = db_bulk_ops::bulk_upsert_categories(db, bulk_categories).map_err(|e| e.to_string())?;
     
     let mut parsed_channel_ids = Vec::with_capacity(bulk_channels.len());
     for b in &bulk_channels {
         parsed_channel_ids.push(b.stream_id.clone());
     }
-    let result_chans = db_bulk_ops::bulk_upsert_channels(&state.db, bulk_channels).map_err(|e| e.to_string())?;
+    let result_chans = db_bulk_ops::bulk_upsert_channels(db, bulk_channels, false).map_err(|e| e.to_string())?;
 
     info!("[M3U Sync] Competed successfully: {} categories, {} channels", result_cats.inserted + result_cats.updated, result_chans.inserted + result_chans.updated);
 
@@ -777,3 +640,77 @@ pub async fn sync_xtream_vod_series(
         parsed_category_ids,
     })
 }
+
+// ============================================================================
+// Sync Xtream (Live + VOD)
+// ============================================================================
+
+/// Run the live, movie and series syncs for an Xtream source in one call
+/// and fold their results into a single summary
+#[tauri::command]
+pub async fn sync_xtream(
+    state: tauri::State<'_, DvrState>,
+    source_id: String,
+    base_url: String,
+    username: String,
+    password: String,
+    user_agent: Option<String>,
+) -> Result<BulkResult, String> {
+    let start = std::time::Instant::now();
+
+    let mut live = sync_xtream_source(
+        state.clone(),
+        source_id.clone(),
+        base_url.clone(),
+        username.clone(),
+        password.clone(),
+        user_agent.clone(),
+    )
+    .await?;
+
+    // Drop channels and categories the provider no longer lists
+    let (channels_deleted, categories_deleted) = db_bulk_ops::prune_source_channels(
+        &state.db,
+        &source_id,
+        &live.parsed_channel_ids,
+        &live.parsed_category_ids,
+    )
+    .map_err(|e| format!("Failed to remove stale channels: {}", e))?;
+    live.channels.deleted += channels_deleted;
+    live.categories.deleted += categories_deleted;
+
+    let movies = sync_xtream_vod_movies(
+        state.clone(),
+        source_id.clone(),
+        base_url.clone(),
+        username.clone(),
+        password.clone(),
+        user_agent.clone(),
+    )
+    .await?;
+    let series = sync_xtream_vod_series(state, source_id.clone(), base_url, username, password, user_agent).await?;
+
+    let parts = [
+        &live.categories,
+        &live.channels,
+        &movies.categories,
+        &movies.content,
+        &series.categories,
+        &series.content,
+    ];
+    let summary = BulkResult {
+        inserted: parts.iter().map(|r| r.inserted).sum(),
+        updated: parts.iter().map(|r| r.updated).sum(),
+        deleted: parts.iter().map(|r| r.deleted).sum(),
+        duration_ms: start.elapsed().as_millis() as u64,
+        inserted_ids: None,
+        updated_ids: None,
+    };
+
+    info!(
+        "[Xtream Sync] Full sync for {} done: {} inserted, {} updated, {} deleted in {}ms",
+        source_id, summary.inserted, summary.updated, summary.deleted, summary.duration_ms
+    );
+
+    Ok(summary)
+}
@@ -7,7 +7,7 @@
 //! - Disk-based caching with TTL (Time To Live)
 //! - Incremental updates (only download if cache is stale)
 //! - Memory-mapped file access for large datasets
-//! - Fast title-based lookups with fuzzy matching support
+//! - Fast title-based lookups with fuzzy matching and year disambiguation
 //!
 //! Expected improvement: Reduces VOD sync time from 5-10s to <500ms after initial cache
 
@@ -17,12 +17,16 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 use anyhow::{Context, Result};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use tokio::fs;
 use tracing::{debug, info};
 
 /// Cache configuration
 const DEFAULT_CACHE_TTL_HOURS: u64 = 168; // 7 days
+/// Bumped whenever the on-disk index layout changes so stale caches are rebuilt
+const CACHE_FORMAT_VERSION: u32 = 2;
+// Default export locations, overridable with `with_export_urls`
 const TMDB_MOVIES_URL: &str = "https://raw.githubusercontent.com/algolia/tmdb-movies-exports/master/movies.json";
 const TMDB_TV_URL: &str = "https://raw.githubusercontent.com/algolia/tmdb-tv-exports/master/tv_series.json";
 
@@ -46,6 +50,8 @@ pub struct TmdbMovie {
     #[serde(rename = "genre_ids")]
     pub genre_ids: Option<Vec<u32>>,
     pub popularity: Option<f32>,
+    #[serde(rename = "imdb_id")]
+    pub imdb_id: Option<String>,
 }
 
 /// TMDB TV Series entry from export
@@ -77,6 +83,283 @@ pub struct MatchResult {
     pub title: String,
     pub year: Option<u32>,
     pub score: f32, // Match confidence 0.0-1.0
+    pub poster_path: Option<String>,
+    pub backdrop_path: Option<String>,
+}
+
+/// Minimum time between progress reports while downloading an export
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Default minimum score for `find_movies`/`find_series` results.
+///
+/// Kept high so automatic matching only accepts exact title hits (which score at
+/// least 0.85, even when a known year disagrees) and very close fuzzy matches;
+/// callers doing interactive search can pass a lower threshold.
+pub const DEFAULT_MIN_MATCH_SCORE: f32 = 0.8;
+
+/// Minimum token similarity for a fuzzy match to be considered at all
+const FUZZY_MIN_SCORE: f32 = 0.5;
+/// Maximum number of fuzzy candidates returned per lookup
+const MAX_FUZZY_RESULTS: usize = 20;
+/// Share of the score decided by the release year when a year is known
+const YEAR_WEIGHT: f32 = 0.1;
+
+/// Fields shared by movie and series export entries for matching
+pub trait TmdbEntry {
+    fn tmdb_id(&self) -> u64;
+    fn display_title(&self) -> &str;
+    fn year(&self) -> Option<u32>;
+    fn poster_path(&self) -> Option<&str>;
+    fn backdrop_path(&self) -> Option<&str>;
+}
+
+impl TmdbEntry for TmdbMovie {
+    fn tmdb_id(&self) -> u64 {
+        self.id
+    }
+    fn display_title(&self) -> &str {
+        &self.title
+    }
+    fn year(&self) -> Option<u32> {
+        self.year
+    }
+    fn poster_path(&self) -> Option<&str> {
+        self.poster_path.as_deref()
+    }
+    fn backdrop_path(&self) -> Option<&str> {
+        self.backdrop_path.as_deref()
+    }
+}
+
+impl TmdbEntry for TmdbSeries {
+    fn tmdb_id(&self) -> u64 {
+        self.id
+    }
+    fn display_title(&self) -> &str {
+        &self.name
+    }
+    fn year(&self) -> Option<u32> {
+        self.year
+    }
+    fn poster_path(&self) -> Option<&str> {
+        self.poster_path.as_deref()
+    }
+    fn backdrop_path(&self) -> Option<&str> {
+        self.backdrop_path.as_deref()
+    }
+}
+
+/// Quality/release tags providers append to VOD titles
+const TITLE_JUNK_TOKENS: &[&str] = &[
+    "4k", "uhd", "fhd", "hd", "sd", "hdr", "hdr10", "sdr", "dv", "2160p", "1080p", "720p", "480p",
+    "x264", "x265", "h264", "h265", "hevc", "multi", "multisub", "vostfr", "remastered",
+];
+
+fn is_year_token(token: &str) -> bool {
+    token.len() == 4
+        && token.chars().all(|c| c.is_ascii_digit())
+        && token.parse::<u32>().map(|y| (1888..=2100).contains(&y)).unwrap_or(false)
+}
+
+/// Normalize a title for indexing and lookup
+///
+/// Lowercases, strips bracketed tags (`[MULTI-SUB]`, `(2003)`), quality tags like `4K`
+/// or `HDR`, release years and punctuation, and collapses whitespace. With
+/// `drop_leading_article` a leading "the" is removed too. The first word is never
+/// dropped, so titles like "1917" or "HD" survive.
+pub fn normalize_title(title: &str, drop_leading_article: bool) -> String {
+    // Remove bracketed sections
+    let mut unbracketed = String::with_capacity(title.len());
+    let mut depth = 0usize;
+    for c in title.chars() {
+        match c {
+            '[' | '(' | '{' => depth += 1,
+            ']' | ')' | '}' => depth = depth.saturating_sub(1),
+            c if depth == 0 => unbracketed.push(c),
+            _ => {}
+        }
+    }
+
+    let lowered = unbracketed.to_lowercase();
+    let tokens: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let mut kept: Vec<&str> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(i, t)| *i == 0 || !(is_year_token(t) || TITLE_JUNK_TOKENS.contains(t)))
+        .map(|(_, t)| *t)
+        .collect();
+
+    if drop_leading_article && kept.len() > 1 && kept[0] == "the" {
+        kept.remove(0);
+    }
+
+    kept.join(" ")
+}
+
+/// Normalize an IMDB id to the `tt0123456` form used as the index key
+fn normalize_imdb_id(imdb_id: &str) -> Option<String> {
+    let id = imdb_id.trim().to_lowercase();
+    let digits = id.strip_prefix("tt").unwrap_or(&id);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!("tt{}", digits))
+}
+
+/// Find a release year in a title: the last year-like word that isn't the first word
+fn extract_year(title: &str) -> Option<u32> {
+    let lowered = title.to_lowercase();
+    lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .skip(1)
+        .filter(|t| is_year_token(t))
+        .last()
+        .and_then(|t| t.parse().ok())
+}
+
+/// Lowercase and replace punctuation with spaces for token comparison
+fn simplify_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Jaccard similarity of the word sets of two simplified titles
+fn token_similarity(a: &str, b: &str) -> f32 {
+    let a: std::collections::HashSet<&str> = a.split_whitespace().collect();
+    let b: std::collections::HashSet<&str> = b.split_whitespace().collect();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let intersection = a.intersection(&b).count();
+    let union = a.union(&b).count();
+    intersection as f32 / union as f32
+}
+
+/// Blend a title score with a year check when the year is known
+fn apply_year_weight(score: f32, wanted: Option<u32>, actual: Option<u32>) -> f32 {
+    match wanted {
+        Some(wanted) => {
+            let year_score = match actual {
+                Some(actual) if actual == wanted => 1.0,
+                Some(actual) if actual.abs_diff(wanted) == 1 => 0.5, // Festival vs. wide release
+                _ => 0.0,
+            };
+            score * (1.0 - YEAR_WEIGHT) + year_score * YEAR_WEIGHT
+        }
+        None => score,
+    }
+}
+
+/// Title-keyed TMDB entries plus a word → title lookup for fuzzy matching
+///
+/// Built once when the cache is loaded or refreshed. Fuzzy matching only scores
+/// titles sharing a word with the query instead of every key in the export.
+pub struct TitleIndex<T> {
+    by_title: HashMap<String, Vec<T>>,
+    titles: Vec<String>,
+    by_word: HashMap<String, Vec<usize>>,
+}
+
+impl<T: TmdbEntry> TitleIndex<T> {
+    pub fn new(by_title: HashMap<String, Vec<T>>) -> Self {
+        let titles: Vec<String> = by_title.keys().cloned().collect();
+        let mut by_word: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, title) in titles.iter().enumerate() {
+            let mut words: Vec<&str> = title.split_whitespace().collect();
+            words.sort_unstable();
+            words.dedup();
+            for word in words {
+                by_word.entry(word.to_string()).or_default().push(i);
+            }
+        }
+        Self { by_title, titles, by_word }
+    }
+
+    /// All entries, in no particular order
+    pub fn entries(&self) -> impl Iterator<Item = &T> {
+        self.by_title.values().flatten()
+    }
+
+    /// Find entries for a title, best match first
+    ///
+    /// Tries the normalized title as an exact key, then a fuzzy word comparison
+    /// against titles sharing at least one word. Candidates matching `year` (or a
+    /// year found in the title) score higher; results below `min_score` are dropped.
+    pub fn find(&self, title: &str, year: Option<u32>, min_score: f32) -> Vec<MatchResult> {
+        let year = year.or_else(|| extract_year(title));
+        let query = normalize_title(title, true);
+        if query.is_empty() {
+            return vec![];
+        }
+
+        let mut scored: Vec<(f32, &T)> = match self.by_title.get(&query) {
+            Some(entries) => {
+                // Keys drop years, so "Blade Runner 2049" and "Blade Runner" share one;
+                // prefer the entry whose full title is what was asked for
+                let wanted = simplify_title(title);
+                entries
+                    .iter()
+                    .map(|e| {
+                        let score = if simplify_title(e.display_title()) == wanted { 1.0 } else { 0.95 };
+                        (score, e)
+                    })
+                    .collect()
+            }
+            None => {
+                // A title sharing no word with the query has a similarity of zero
+                let mut candidates: Vec<usize> = query
+                    .split_whitespace()
+                    .filter_map(|word| self.by_word.get(word))
+                    .flatten()
+                    .copied()
+                    .collect();
+                candidates.sort_unstable();
+                candidates.dedup();
+
+                candidates
+                    .into_iter()
+                    .filter_map(|i| {
+                        let key = &self.titles[i];
+                        let score = token_similarity(&query, key);
+                        (score >= FUZZY_MIN_SCORE).then(|| (score, &self.by_title[key]))
+                    })
+                    .flat_map(|(score, entries)| entries.iter().map(move |e| (score, e)))
+                    .collect()
+            }
+        };
+
+        for (score, entry) in scored.iter_mut() {
+            *score = apply_year_weight(*score, year, entry.year());
+        }
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        // The same entry can be indexed under its title and original title
+        let mut seen = std::collections::HashSet::new();
+        scored
+            .into_iter()
+            .filter(|(score, e)| *score >= min_score && seen.insert(e.tmdb_id()))
+            .take(MAX_FUZZY_RESULTS)
+            .map(|(score, e)| MatchResult {
+                tmdb_id: e.tmdb_id(),
+                title: e.display_title().to_string(),
+                year: e.year(),
+                score,
+                poster_path: e.poster_path().map(str::to_string),
+                backdrop_path: e.backdrop_path().map(str::to_string),
+            })
+            .collect()
+    }
 }
 
 /// Cache metadata stored alongside cached data
@@ -85,14 +368,33 @@ struct CacheMetadata {
     cached_at: SystemTime,
     ttl_hours: u64,
     entry_count: usize,
+    #[serde(default)]
+    format_version: u32,
+}
+
+/// Progress of a TMDB export download, reported while the cache is rebuilt
+#[derive(Debug, Clone, Serialize)]
+pub struct TmdbCacheProgress {
+    pub kind: String,  // "movies" or "series"
+    pub phase: String, // "downloading", "complete"
+    pub bytes_downloaded: u64,
+    pub total_bytes: Option<u64>,
+    pub entries_indexed: usize,
 }
 
+/// Callback invoked with download/index progress
+pub type ProgressCallback = Arc<dyn Fn(TmdbCacheProgress) + Send + Sync>;
+
 /// TMDB Cache manager
 pub struct TmdbCache {
     cache_dir: PathBuf,
     ttl_hours: u64,
-    movies: Option<Arc<HashMap<String, Vec<TmdbMovie>>>>,
-    series: Option<Arc<HashMap<String, Vec<TmdbSeries>>>>,
+    movies: Option<Arc<TitleIndex<TmdbMovie>>>,
+    movies_by_imdb: Option<Arc<HashMap<String, TmdbMovie>>>,
+    series: Option<Arc<TitleIndex<TmdbSeries>>>,
+    progress_callback: Option<ProgressCallback>,
+    movies_url: String,
+    series_url: String,
 }
 
 impl TmdbCache {
@@ -102,7 +404,11 @@ impl TmdbCache {
             cache_dir,
             ttl_hours: DEFAULT_CACHE_TTL_HOURS,
             movies: None,
+            movies_by_imdb: None,
             series: None,
+            progress_callback: None,
+            movies_url: TMDB_MOVIES_URL.to_string(),
+            series_url: TMDB_TV_URL.to_string(),
         }
     }
 
@@ -112,13 +418,79 @@ impl TmdbCache {
         self
     }
 
-    /// Get cache file paths
+    /// Use custom export URLs (e.g. a mirror); `None` or empty keeps the default
+    pub fn with_export_urls(mut self, movies_url: Option<String>, series_url: Option<String>) -> Self {
+        self.set_export_urls(movies_url, series_url);
+        self
+    }
+
+    /// Change the export URLs; `None` or empty restores the default
+    ///
+    /// Takes effect on the next cache update.
+    pub fn set_export_urls(&mut self, movies_url: Option<String>, series_url: Option<String>) {
+        let pick = |url: Option<String>, default: &str| {
+            url.map(|u| u.trim().to_string())
+                .filter(|u| !u.is_empty())
+                .unwrap_or_else(|| default.to_string())
+        };
+        self.movies_url = pick(movies_url, TMDB_MOVIES_URL);
+        self.series_url = pick(series_url, TMDB_TV_URL);
+    }
+
+    /// Current (movies, series) export URLs
+    pub fn export_urls(&self) -> (&str, &str) {
+        (&self.movies_url, &self.series_url)
+    }
+
+    /// Cache lifetime in hours before an export is considered stale
+    pub fn ttl_hours(&self) -> u64 {
+        self.ttl_hours
+    }
+
+    /// Create an empty cache manager sharing this one's directory, TTL and progress callback
+    ///
+    /// Lets a refresh run without holding the shared cache; see [`absorb`](Self::absorb).
+    pub fn detached(&self) -> Self {
+        Self {
+            cache_dir: self.cache_dir.clone(),
+            ttl_hours: self.ttl_hours,
+            movies: None,
+            movies_by_imdb: None,
+            series: None,
+            progress_callback: self.progress_callback.clone(),
+            movies_url: self.movies_url.clone(),
+            series_url: self.series_url.clone(),
+        }
+    }
+
+    /// Take over whatever indexes a detached cache loaded or rebuilt
+    pub fn absorb(&mut self, fresh: TmdbCache) {
+        if fresh.movies.is_some() {
+            self.movies = fresh.movies;
+            self.movies_by_imdb = fresh.movies_by_imdb;
+        }
+        if fresh.series.is_some() {
+            self.series = fresh.series;
+        }
+    }
+
+    /// Report download/index progress through a callback (e.g. to emit a frontend event)
+    pub fn with_progress_callback(mut self, callback: ProgressCallback) -> Self {
+        self.progress_callback = Some(callback);
+        self
+    }
+
+    /// Get cache file paths (data files are gzipped; see `legacy_json_path`)
     fn movies_cache_path(&self) -> PathBuf {
-        self.cache_dir.join("tmdb_movies_cache.json")
+        self.cache_dir.join("tmdb_movies_cache.json.gz")
+    }
+
+    fn movies_imdb_path(&self) -> PathBuf {
+        self.cache_dir.join("tmdb_movies_imdb.json.gz")
     }
 
     fn series_cache_path(&self) -> PathBuf {
-        self.cache_dir.join("tmdb_series_cache.json")
+        self.cache_dir.join("tmdb_series_cache.json.gz")
     }
 
     fn movies_meta_path(&self) -> PathBuf {
@@ -148,85 +520,156 @@ impl TmdbCache {
         match fs::read_to_string(meta_path).await {
             Ok(content) => {
                 match serde_json::from_str::<CacheMetadata>(&content) {
-                    Ok(meta) => {
+                    Ok(meta) if meta.format_version == CACHE_FORMAT_VERSION => {
                         let age = SystemTime::now()
                             .duration_since(meta.cached_at)
                             .unwrap_or(Duration::MAX);
                         let max_age = Duration::from_secs(self.ttl_hours * 3600);
                         age < max_age
                     }
-                    Err(_) => false,
+                    _ => false,
                 }
             }
             Err(_) => false,
         }
     }
 
-    /// Update movies cache from TMDB export
-    pub async fn update_movies_cache(&mut self) -> Result<usize> {
-        info!("Updating TMDB movies cache...");
-        self.ensure_cache_dir().await?;
-
-        // Download and parse
+    /// Download a JSON-lines export and hand each parsed entry to `on_entry`
+    ///
+    /// The body is processed chunk by chunk so the whole export is never held in
+    /// memory as one string. Returns the number of entries parsed.
+    async fn stream_export<T, F>(&self, url: &str, kind: &str, mut on_entry: F) -> Result<usize>
+    where
+        T: serde::de::DeserializeOwned,
+        F: FnMut(T),
+    {
         let client = reqwest::Client::new();
         let response = client
-            .get(TMDB_MOVIES_URL)
+            .get(url)
             .send()
             .await
-            .context("Failed to download TMDB movies export")?;
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("Failed to download TMDB {} export", kind))?;
 
-        let total_size = response.content_length();
-        info!("Downloading TMDB movies export: {:?} bytes", total_size);
+        let total_bytes = response.content_length();
+        info!("Downloading TMDB {} export: {:?} bytes", kind, total_bytes);
 
-        let body = response.text().await?;
-        
-        // Parse JSON lines (each line is a JSON object)
-        let mut movies: HashMap<String, Vec<TmdbMovie>> = HashMap::new();
-        let mut count = 0;
+        let mut progress = TmdbCacheProgress {
+            kind: kind.to_string(),
+            phase: "downloading".to_string(),
+            bytes_downloaded: 0,
+            total_bytes,
+            entries_indexed: 0,
+        };
+        self.report_progress(&progress);
+        let mut last_progress = std::time::Instant::now();
 
-        for line in body.lines() {
-            if line.trim().is_empty() {
-                continue;
+        let mut parse_line = |line: &[u8], count: &mut usize| {
+            if line.iter().all(|b| b.is_ascii_whitespace()) {
+                return;
             }
-
-            match serde_json::from_str::<TmdbMovie>(line) {
-                Ok(movie) => {
-                    // Index by lowercase title for case-insensitive search
-                    let key = movie.title.to_lowercase();
-                    movies.entry(key.clone()).or_default().push(movie.clone());
-
-                    // Also index by original title if different
-                    if let Some(ref orig) = movie.original_title {
-                        let orig_key = orig.to_lowercase();
-                        if orig_key != key {
-                            movies.entry(orig_key).or_default().push(movie);
-                        }
-                    }
-                    count += 1;
+            match serde_json::from_slice::<T>(line) {
+                Ok(entry) => {
+                    on_entry(entry);
+                    *count += 1;
                 }
-                Err(e) => {
-                    debug!("Failed to parse movie line: {}", e);
+                Err(e) => debug!("Failed to parse {} line: {}", kind, e),
+            }
+        };
+
+        let mut stream = response.bytes_stream();
+        let mut pending: Vec<u8> = Vec::new();
+        let mut count = 0usize;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.with_context(|| format!("TMDB {} export download interrupted", kind))?;
+            progress.bytes_downloaded += chunk.len() as u64;
+            pending.extend_from_slice(&chunk);
+
+            // Parse every complete line, keep the trailing partial one for the next chunk
+            if let Some(last_newline) = pending.iter().rposition(|&b| b == b'\n') {
+                for line in pending[..last_newline].split(|&b| b == b'\n') {
+                    parse_line(line, &mut count);
                 }
+                pending.drain(..=last_newline);
+            }
+
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                progress.entries_indexed = count;
+                self.report_progress(&progress);
+                last_progress = std::time::Instant::now();
             }
         }
+        parse_line(&pending, &mut count);
+
+        progress.entries_indexed = count;
+        progress.phase = "complete".to_string();
+        self.report_progress(&progress);
+
+        Ok(count)
+    }
 
-        info!("Indexed {} unique movie titles ({} total)", movies.len(), count);
+    fn report_progress(&self, progress: &TmdbCacheProgress) {
+        if let Some(ref callback) = self.progress_callback {
+            callback(progress.clone());
+        }
+    }
+
+    /// Update movies cache from TMDB export
+    pub async fn update_movies_cache(&mut self) -> Result<usize> {
+        info!("Updating TMDB movies cache...");
+        self.ensure_cache_dir().await?;
+
+        // Stream and index JSON lines (each line is a JSON object)
+        let mut movies: HashMap<String, Vec<TmdbMovie>> = HashMap::new();
+        let mut movies_by_imdb: HashMap<String, TmdbMovie> = HashMap::new();
+
+        let url = self.movies_url.clone();
+        let count = self
+            .stream_export(&url, "movies", |movie: TmdbMovie| {
+                // Secondary index for exact matches via the provider's IMDB id
+                if let Some(imdb_key) = movie.imdb_id.as_deref().and_then(normalize_imdb_id) {
+                    movies_by_imdb.insert(imdb_key, movie.clone());
+                }
+
+                // Index by normalized title so messy provider titles still hit
+                let key = normalize_title(&movie.title, true);
+                movies.entry(key.clone()).or_default().push(movie.clone());
+
+                // Also index by original title if different
+                if let Some(ref orig) = movie.original_title {
+                    let orig_key = normalize_title(orig, true);
+                    if orig_key != key {
+                        movies.entry(orig_key).or_default().push(movie);
+                    }
+                }
+            })
+            .await?;
+
+        info!(
+            "Indexed {} unique movie titles, {} IMDB ids ({} total)",
+            movies.len(),
+            movies_by_imdb.len(),
+            count
+        );
 
         // Save to disk
-        let cache_data = serde_json::to_string(&movies)?;
-        fs::write(self.movies_cache_path(), cache_data).await?;
+        write_cache_file(&self.movies_cache_path(), &movies).await?;
+        write_cache_file(&self.movies_imdb_path(), &movies_by_imdb).await?;
 
         // Save metadata
         let meta = CacheMetadata {
             cached_at: SystemTime::now(),
             ttl_hours: self.ttl_hours,
             entry_count: count,
+            format_version: CACHE_FORMAT_VERSION,
         };
         let meta_json = serde_json::to_string(&meta)?;
         fs::write(self.movies_meta_path(), meta_json).await?;
 
         // Update in-memory cache
-        self.movies = Some(Arc::new(movies));
+        self.movies = Some(Arc::new(TitleIndex::new(movies)));
+        self.movies_by_imdb = Some(Arc::new(movies_by_imdb));
 
         info!("TMDB movies cache updated: {} entries", count);
         Ok(count)
@@ -237,64 +680,41 @@ impl TmdbCache {
         info!("Updating TMDB series cache...");
         self.ensure_cache_dir().await?;
 
-        // Download and parse
-        let client = reqwest::Client::new();
-        let response = client
-            .get(TMDB_TV_URL)
-            .send()
-            .await
-            .context("Failed to download TMDB TV export")?;
-
-        let total_size = response.content_length();
-        info!("Downloading TMDB TV export: {:?} bytes", total_size);
-
-        let body = response.text().await?;
-        
-        // Parse JSON lines
+        // Stream and index JSON lines
         let mut series: HashMap<String, Vec<TmdbSeries>> = HashMap::new();
-        let mut count = 0;
-
-        for line in body.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
 
-            match serde_json::from_str::<TmdbSeries>(line) {
-                Ok(s) => {
-                    let key = s.name.to_lowercase();
-                    series.entry(key.clone()).or_default().push(s.clone());
+        let url = self.series_url.clone();
+        let count = self
+            .stream_export(&url, "series", |s: TmdbSeries| {
+                let key = normalize_title(&s.name, true);
+                series.entry(key.clone()).or_default().push(s.clone());
 
-                    if let Some(ref orig) = s.original_name {
-                        let orig_key = orig.to_lowercase();
-                        if orig_key != key {
-                            series.entry(orig_key).or_default().push(s);
-                        }
+                if let Some(ref orig) = s.original_name {
+                    let orig_key = normalize_title(orig, true);
+                    if orig_key != key {
+                        series.entry(orig_key).or_default().push(s);
                     }
-                    count += 1;
                 }
-                Err(e) => {
-                    debug!("Failed to parse series line: {}", e);
-                }
-            }
-        }
+            })
+            .await?;
 
         info!("Indexed {} unique series titles ({} total)", series.len(), count);
 
         // Save to disk
-        let cache_data = serde_json::to_string(&series)?;
-        fs::write(self.series_cache_path(), cache_data).await?;
+        write_cache_file(&self.series_cache_path(), &series).await?;
 
         // Save metadata
         let meta = CacheMetadata {
             cached_at: SystemTime::now(),
             ttl_hours: self.ttl_hours,
             entry_count: count,
+            format_version: CACHE_FORMAT_VERSION,
         };
         let meta_json = serde_json::to_string(&meta)?;
         fs::write(self.series_meta_path(), meta_json).await?;
 
         // Update in-memory cache
-        self.series = Some(Arc::new(series));
+        self.series = Some(Arc::new(TitleIndex::new(series)));
 
         info!("TMDB series cache updated: {} entries", count);
         Ok(count)
@@ -306,17 +726,27 @@ impl TmdbCache {
             return Ok(());
         }
 
-        let cache_path = self.movies_cache_path();
-        if !cache_path.exists() {
-            return Err(anyhow::anyhow!("Movies cache not found"));
-        }
-
         info!("Loading TMDB movies cache from disk...");
-        let data = fs::read_to_string(&cache_path).await?;
-        let movies: HashMap<String, Vec<TmdbMovie>> = serde_json::from_str(&data)?;
-        
-        info!("Loaded {} unique movie titles", movies.len());
-        self.movies = Some(Arc::new(movies));
+        let movies: HashMap<String, Vec<TmdbMovie>> = read_cache_file(&self.movies_cache_path())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Movies cache not found"))?;
+
+        // Rebuild the IMDB index from the titles if its file is missing or unreadable
+        let movies_by_imdb = read_cache_file::<HashMap<String, TmdbMovie>>(&self.movies_imdb_path())
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| {
+                movies
+                    .values()
+                    .flatten()
+                    .filter_map(|m| Some((normalize_imdb_id(m.imdb_id.as_deref()?)?, m.clone())))
+                    .collect()
+            });
+
+        info!("Loaded {} unique movie titles, {} IMDB ids", movies.len(), movies_by_imdb.len());
+        self.movies = Some(Arc::new(TitleIndex::new(movies)));
+        self.movies_by_imdb = Some(Arc::new(movies_by_imdb));
         
         Ok(())
     }
@@ -327,17 +757,13 @@ impl TmdbCache {
             return Ok(());
         }
 
-        let cache_path = self.series_cache_path();
-        if !cache_path.exists() {
-            return Err(anyhow::anyhow!("Series cache not found"));
-        }
-
         info!("Loading TMDB series cache from disk...");
-        let data = fs::read_to_string(&cache_path).await?;
-        let series: HashMap<String, Vec<TmdbSeries>> = serde_json::from_str(&data)?;
+        let series: HashMap<String, Vec<TmdbSeries>> = read_cache_file(&self.series_cache_path())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Series cache not found"))?;
         
         info!("Loaded {} unique series titles", series.len());
-        self.series = Some(Arc::new(series));
+        self.series = Some(Arc::new(TitleIndex::new(series)));
         
         Ok(())
     }
@@ -374,50 +800,59 @@ impl TmdbCache {
         Ok(())
     }
 
-    /// Search for movies by title (exact match)
-    pub async fn find_movies(&mut self, title: &str) -> Result<Vec<MatchResult>> {
+    /// Shared handle to the movie title index, loading or building it first if needed
+    ///
+    /// Callers holding the cache behind a lock can take the index and release the
+    /// lock before matching.
+    pub async fn movies_index(&mut self) -> Result<Arc<TitleIndex<TmdbMovie>>> {
         self.ensure_movies_cache().await?;
-        
-        let key = title.to_lowercase();
-        let movies = self.movies.as_ref().unwrap();
-        
-        match movies.get(&key) {
-            Some(matches) => {
-                Ok(matches
-                    .iter()
-                    .map(|m| MatchResult {
-                        tmdb_id: m.id,
-                        title: m.title.clone(),
-                        year: m.year,
-                        score: 1.0, // Exact match
-                    })
-                    .collect())
-            }
-            None => Ok(vec![]),
-        }
+        Ok(self.movies.clone().unwrap())
     }
 
-    /// Search for series by title (exact match)
-    pub async fn find_series(&mut self, title: &str) -> Result<Vec<MatchResult>> {
+    /// Shared handle to the series title index; see [`movies_index`](Self::movies_index)
+    pub async fn series_index(&mut self) -> Result<Arc<TitleIndex<TmdbSeries>>> {
         self.ensure_series_cache().await?;
-        
-        let key = title.to_lowercase();
-        let series = self.series.as_ref().unwrap();
-        
-        match series.get(&key) {
-            Some(matches) => {
-                Ok(matches
-                    .iter()
-                    .map(|s| MatchResult {
-                        tmdb_id: s.id,
-                        title: s.name.clone(),
-                        year: s.year,
-                        score: 1.0,
-                    })
-                    .collect())
-            }
-            None => Ok(vec![]),
-        }
+        Ok(self.series.clone().unwrap())
+    }
+
+    /// Search for movies by title
+    ///
+    /// Tries an exact key first, then a fuzzy word comparison. Candidates matching
+    /// `year` (or a year found in the title) score higher. Results scoring below
+    /// `min_score` are dropped; the rest are sorted by score, best first.
+    pub async fn find_movies(&mut self, title: &str, year: Option<u32>, min_score: f32) -> Result<Vec<MatchResult>> {
+        Ok(self.movies_index().await?.find(title, year, min_score))
+    }
+
+    /// Find a movie by its IMDB id (e.g. `tt0133093`)
+    ///
+    /// This is an exact, full-confidence match and should be tried before title matching.
+    pub async fn find_movie_by_imdb(&mut self, imdb_id: &str) -> Result<Option<MatchResult>> {
+        self.ensure_movies_cache().await?;
+
+        let Some(key) = normalize_imdb_id(imdb_id) else {
+            return Ok(None);
+        };
+
+        Ok(self
+            .movies_by_imdb
+            .as_ref()
+            .and_then(|index| index.get(&key))
+            .map(|m| MatchResult {
+                tmdb_id: m.id,
+                title: m.title.clone(),
+                year: m.year,
+                score: 1.0,
+                poster_path: m.poster_path.clone(),
+                backdrop_path: m.backdrop_path.clone(),
+            }))
+    }
+
+    /// Search for series by title
+    ///
+    /// Uses the same exact → fuzzy strategy as [`find_movies`](Self::find_movies).
+    pub async fn find_series(&mut self, title: &str, year: Option<u32>, min_score: f32) -> Result<Vec<MatchResult>> {
+        Ok(self.series_index().await?.find(title, year, min_score))
     }
 
     /// Get movie details by ID
@@ -425,16 +860,7 @@ impl TmdbCache {
         self.ensure_movies_cache().await?;
         
         let movies = self.movies.as_ref().unwrap();
-        
-        for entry in movies.values() {
-            for movie in entry {
-                if movie.id == tmdb_id {
-                    return Ok(Some(movie.clone()));
-                }
-            }
-        }
-        
-        Ok(None)
+        Ok(movies.entries().find(|m| m.id == tmdb_id).cloned())
     }
 
     /// Get series details by ID
@@ -442,16 +868,7 @@ impl TmdbCache {
         self.ensure_series_cache().await?;
         
         let series = self.series.as_ref().unwrap();
-        
-        for entry in series.values() {
-            for s in entry {
-                if s.id == tmdb_id {
-                    return Ok(Some(s.clone()));
-                }
-            }
-        }
-        
-        Ok(None)
+        Ok(series.entries().find(|s| s.id == tmdb_id).cloned())
     }
 
     /// Clear all caches
@@ -460,7 +877,11 @@ impl TmdbCache {
         
         let files = [
             self.movies_cache_path(),
+            legacy_json_path(&self.movies_cache_path()),
+            self.movies_imdb_path(),
+            legacy_json_path(&self.movies_imdb_path()),
             self.series_cache_path(),
+            legacy_json_path(&self.series_cache_path()),
             self.movies_meta_path(),
             self.series_meta_path(),
         ];
@@ -517,6 +938,66 @@ impl TmdbCache {
     }
 }
 
+/// Plain `.json` path older versions wrote before the cache was gzipped
+fn legacy_json_path(gz_path: &Path) -> PathBuf {
+    gz_path.with_extension("")
+}
+
+/// Serialize to gzipped JSON, replacing any legacy plain-JSON file
+async fn write_cache_file<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let json = serde_json::to_vec(value)?;
+
+    let compressed = tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::with_capacity(json.len() / 4), Compression::fast());
+        encoder.write_all(&json)?;
+        Ok(encoder.finish()?)
+    })
+    .await
+    .context("Cache compression task panicked")??;
+
+    fs::write(path, compressed).await?;
+
+    let legacy = legacy_json_path(path);
+    if legacy.exists() {
+        let _ = fs::remove_file(&legacy).await;
+    }
+    Ok(())
+}
+
+/// Read a gzipped JSON cache file, falling back to the legacy plain `.json` file
+///
+/// Returns `Ok(None)` if neither file exists.
+async fn read_cache_file<T: serde::de::DeserializeOwned + Send + 'static>(path: &Path) -> Result<Option<T>> {
+    if path.exists() {
+        let compressed = fs::read(path).await?;
+        let value = tokio::task::spawn_blocking(move || -> Result<T> {
+            use flate2::read::GzDecoder;
+            use std::io::Read;
+
+            let mut json = Vec::new();
+            GzDecoder::new(&compressed[..])
+                .read_to_end(&mut json)
+                .context("Failed to decompress TMDB cache")?;
+            Ok(serde_json::from_slice(&json)?)
+        })
+        .await
+        .context("Cache decompression task panicked")??;
+        return Ok(Some(value));
+    }
+
+    let legacy = legacy_json_path(path);
+    if legacy.exists() {
+        let data = fs::read(&legacy).await?;
+        return Ok(Some(serde_json::from_slice(&data)?));
+    }
+
+    Ok(None)
+}
+
 /// Cache statistics
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct CacheStats {
@@ -546,4 +1027,84 @@ mod tests {
         assert!(!stats.movies_cached);
         assert!(!stats.series_cached);
     }
+
+    #[test]
+    fn test_normalize_title_strips_release_junk() {
+        assert_eq!(normalize_title("The Matrix Reloaded - 2003 4K", true), "matrix reloaded");
+        assert_eq!(normalize_title("The Matrix Reloaded", true), "matrix reloaded");
+        assert_eq!(normalize_title("[MULTI-SUB] Parasite HDR", true), "parasite");
+        assert_eq!(normalize_title("Spider-Man (2002)", true), "spider man");
+        assert_eq!(normalize_title("Inception  1080p (2010) [EN]", true), "inception");
+        assert_eq!(normalize_title("Amélie 2001 UHD x265", true), "amélie");
+    }
+
+    #[test]
+    fn test_normalize_title_leading_article() {
+        assert_eq!(normalize_title("The Office", false), "the office");
+        assert_eq!(normalize_title("The Office", true), "office");
+        // A lone "The" is the whole title, not an article
+        assert_eq!(normalize_title("The", true), "the");
+    }
+
+    #[test]
+    fn test_normalize_title_keeps_first_word() {
+        assert_eq!(normalize_title("1917", true), "1917");
+        assert_eq!(normalize_title("2001: A Space Odyssey", true), "2001 a space odyssey");
+        assert_eq!(normalize_title("   ", true), "");
+    }
+
+    fn movie(id: u64, title: &str, year: u32) -> TmdbMovie {
+        TmdbMovie {
+            id,
+            title: title.to_string(),
+            original_title: None,
+            release_date: None,
+            year: Some(year),
+            overview: None,
+            poster_path: None,
+            backdrop_path: None,
+            vote_average: None,
+            genre_ids: None,
+            popularity: None,
+            imdb_id: None,
+        }
+    }
+
+    fn index(movies: Vec<TmdbMovie>) -> TitleIndex<TmdbMovie> {
+        let mut by_title: HashMap<String, Vec<TmdbMovie>> = HashMap::new();
+        for m in movies {
+            by_title.entry(normalize_title(&m.title, true)).or_default().push(m);
+        }
+        TitleIndex::new(by_title)
+    }
+
+    #[test]
+    fn test_find_exact_prefers_year() {
+        let index = index(vec![movie(1, "Dune", 1984), movie(2, "Dune", 2021)]);
+        let results = index.find("Dune (2021) 4K", None, 0.0);
+        assert_eq!(results[0].tmdb_id, 2);
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_find_fuzzy_uses_shared_words() {
+        let index = index(vec![
+            movie(1, "The Lord of the Rings: The Fellowship of the Ring", 2001),
+            movie(2, "Heat", 1995),
+        ]);
+        let results = index.find("Lord of the Rings Fellowship of the Ring Extended", None, 0.5);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].tmdb_id, 1);
+        assert!(results[0].score < 1.0);
+
+        assert!(index.find("Completely Unrelated", None, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_extract_year() {
+        assert_eq!(extract_year("The Matrix Reloaded - 2003 4K"), Some(2003));
+        assert_eq!(extract_year("Spider-Man (2002)"), Some(2002));
+        assert_eq!(extract_year("1917"), None);
+        assert_eq!(extract_year("Heat"), None);
+    }
 }
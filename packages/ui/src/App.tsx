@@ -42,7 +42,7 @@ import { VideoErrorOverlay } from './components/VideoErrorOverlay';
 import { syncSource, syncVodForSource, isEpgStale, isVodStale } from './db/sync';
 import { bulkOps } from './services/bulk-ops';
 import { Bridge } from './services/tauri-bridge';
-import { getCurrentWindow, LogicalSize } from '@tauri-apps/api/window';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import { addToRecentChannels } from './utils/recentChannels';
 import { WatchlistNotificationContainer } from './components/WatchlistNotification';
 import { MultiviewLayout } from './components/MultiviewLayout/MultiviewLayout';
@@ -755,7 +755,8 @@ function App() {
         if (isMaximized) {
           await appWindow.unmaximize();
         }
-        await appWindow.setSize(new LogicalSize(width, height));
+        // Clamped to the monitor's work area so a large startup size can't open off-screen
+        await invoke('apply_startup_window_size', { width, height });
       } catch (err) {
         console.error('[App] Failed to resize window on startup:', err);
       }
@@ -5,7 +5,12 @@ import {
     getCompletedRecordings,
     getActiveRecordings,
     cancelRecording,
+    pauseRecording,
+    resumeRecording,
+    stopAndKeepRecording,
+    getDvrInitStatus,
     deleteRecording,
+    setRecordingProtected,
     updateSchedulePaddings,
     type DvrSchedule,
     type DvrRecording,
@@ -28,6 +33,7 @@ export function DvrDashboard({ onPlay, onClose }: DvrDashboardProps) {
     const [recorded, setRecorded] = useState<DvrRecording[]>([]);
     const [activeRecordings, setActiveRecordings] = useState<RecordingProgress[]>([]);
     const [loading, setLoading] = useState(true);
+    const [initError, setInitError] = useState<string | null>(null);
 
     // Edit schedule state
     const [editingSchedule, setEditingSchedule] = useState<DvrSchedule | null>(null);
@@ -54,6 +60,12 @@ export function DvrDashboard({ onPlay, onClose }: DvrDashboardProps) {
         }
     }
 
+    useEffect(() => {
+        getDvrInitStatus().then((status) => {
+            if (!status.available) setInitError(status.error ?? 'Unknown error');
+        });
+    }, []);
+
     useEffect(() => {
         loadData();
 
@@ -308,6 +320,12 @@ export function DvrDashboard({ onPlay, onClose }: DvrDashboardProps) {
                     </h1>
                 </header>
 
+                {initError && (
+                    <div className="dvr-init-error">
+                        DVR is unavailable: {initError}
+                    </div>
+                )}
+
                 <div className="dvr-content">
                     {loading ? (
                         <div className="dvr-loading">
@@ -577,6 +595,20 @@ function RecordedTab({ recorded, onPlay, onDelete, formatDateTime }: RecordedTab
                             </p>
                         )}
                     </div>
+                    <button
+                        className={`dvr-media-protect${item.is_protected ? ' active' : ''}`}
+                        onClick={() => {
+                            setRecordingProtected(item.id!, !item.is_protected).catch((error) => {
+                                console.error('[DVR Dashboard] Failed to update protection:', error);
+                            });
+                        }}
+                        title={item.is_protected ? 'Protected from auto-cleanup (click to unprotect)' : 'Protect from auto-cleanup'}
+                    >
+                        <svg width="18" height="18" viewBox="0 0 24 24" fill={item.is_protected ? 'currentColor' : 'none'} stroke="currentColor" strokeWidth="2">
+                            <rect x="3" y="11" width="18" height="11" rx="2" ry="2" />
+                            <path d="M7 11V7a5 5 0 0 1 10 0v4" />
+                        </svg>
+                    </button>
                     <button
                         className="dvr-media-delete"
                         onClick={() => onDelete(item.id!, item.file_path)}
@@ -606,14 +638,56 @@ interface RecordingCardProps {
 
 function RecordingCard({ item, progress, onEdit, onCancel, formatDateTime, formatDuration, formatElapsed }: RecordingCardProps) {
     const percent = progress
-        ? Math.min(100, (progress.elapsed_seconds / progress.scheduled_duration) * 100)
+        ? Math.min(100, (progress.recorded_seconds / progress.scheduled_duration) * 100)
         : 0;
+    const paused = progress?.paused ?? false;
+
+    async function togglePause() {
+        try {
+            if (paused) {
+                await resumeRecording(item.id!);
+            } else {
+                await pauseRecording(item.id!);
+            }
+        } catch (error) {
+            console.error('[DVR Dashboard] Failed to toggle pause:', error);
+        }
+    }
+
+    async function stopAndKeep() {
+        try {
+            await stopAndKeepRecording(item.id!);
+        } catch (error) {
+            console.error('[DVR Dashboard] Failed to stop recording:', error);
+        }
+    }
 
     return (
         <div className="dvr-card recording">
             <div className="dvr-card-header">
-                <span className="dvr-card-status-badge recording">REC</span>
+                <span className="dvr-card-status-badge recording">{paused ? 'PAUSED' : 'REC'}</span>
                 <div className="dvr-card-actions">
+                    {progress && (
+                        <button className="dvr-btn-icon" onClick={togglePause} title={paused ? 'Resume recording' : 'Pause recording'}>
+                            {paused ? (
+                                <svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+                                    <polygon points="5 3 19 12 5 21 5 3" />
+                                </svg>
+                            ) : (
+                                <svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+                                    <rect x="6" y="4" width="4" height="16" />
+                                    <rect x="14" y="4" width="4" height="16" />
+                                </svg>
+                            )}
+                        </button>
+                    )}
+                    {progress && (
+                        <button className="dvr-btn-icon" onClick={stopAndKeep} title="Stop now and keep recording">
+                            <svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
+                                <polyline points="20 6 9 17 4 12" />
+                            </svg>
+                        </button>
+                    )}
                     <button className="dvr-btn-icon" onClick={onEdit} title="Edit padding">
                         <svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
                             <path d="M11 4H4a2 2 0 0 0-2 2v14a2 2 0 0 0 2 2h14a2 2 0 0 0 2-2v-7" />
@@ -656,8 +730,8 @@ function RecordingCard({ item, progress, onEdit, onCancel, formatDateTime, forma
                 {progress && (
                     <div className="dvr-card-progress">
                         <div className="dvr-progress-header">
-                            <span className="dvr-progress-label">Recording in progress</span>
-                            <span className="dvr-progress-time">{formatElapsed(progress.elapsed_seconds)}</span>
+                            <span className="dvr-progress-label">{paused ? 'Recording paused' : 'Recording in progress'}</span>
+                            <span className="dvr-progress-time">{formatElapsed(progress.recorded_seconds)}</span>
                         </div>
                         <div className="dvr-progress-bar">
                             <div className="dvr-progress-fill" style={{ width: `${percent}%` }} />
@@ -743,7 +743,7 @@ export function NowPlayingBar({
                 type="range"
                 className="npb-volume-slider"
                 min="0"
-                max="100"
+                max="150"
                 value={volume}
                 onChange={onVolumeChange}
                 onMouseDown={onVolumeDragStart}
@@ -835,7 +835,7 @@ export function NowPlayingBar({
               type="range"
               className="npb-volume-slider"
               min="0"
-              max="100"
+              max="150"
               value={volume}
               onChange={onVolumeChange}
               onMouseDown={onVolumeDragStart}
@@ -1,4 +1,19 @@
 import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open, save } from '@tauri-apps/plugin-dialog';
+
+interface VacuumResult {
+  mode: string;
+  size_before: number;
+  size_after: number;
+  reclaimed_bytes: number;
+  duration_ms: number;
+}
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
 
 interface DebugTabProps {
   debugLoggingEnabled: boolean;
@@ -14,6 +29,8 @@ export function DebugTab({
   onLogRetentionChange,
 }: DebugTabProps) {
   const [logPath, setLogPath] = useState<string>('');
+  const [maintenanceBusy, setMaintenanceBusy] = useState(false);
+  const [maintenanceStatus, setMaintenanceStatus] = useState<string | null>(null);
 
   useEffect(() => {
     // Get log file path on mount
@@ -37,6 +54,12 @@ export function DebugTab({
     if (window.debug?.setDebugLoggingEnabled) {
       window.debug.setDebugLoggingEnabled(enabled);
     }
+    // Backend console and log file follow the toggle too
+    try {
+      await invoke('set_log_level', { level: enabled ? 'debug' : 'default' });
+    } catch (err) {
+      console.error('[DebugTab] Failed to set log level:', err);
+    }
   }
 
   async function handleOpenLogFolder() {
@@ -45,6 +68,74 @@ export function DebugTab({
     }
   }
 
+  async function handleVacuum() {
+    setMaintenanceBusy(true);
+    setMaintenanceStatus('Compacting database...');
+    try {
+      const result = await invoke<VacuumResult>('vacuum_database');
+      setMaintenanceStatus(
+        `Reclaimed ${formatBytes(result.reclaimed_bytes)} (${formatBytes(result.size_before)} → ${formatBytes(result.size_after)})`
+      );
+    } catch (e) {
+      setMaintenanceStatus(`Compaction failed: ${e}`);
+    } finally {
+      setMaintenanceBusy(false);
+    }
+  }
+
+  async function handleIntegrityCheck() {
+    setMaintenanceBusy(true);
+    setMaintenanceStatus('Checking database integrity...');
+    try {
+      const result = await invoke<string>('integrity_check');
+      setMaintenanceStatus(result === 'ok' ? 'Database integrity check passed' : `Problems found:\n${result}`);
+    } catch (e) {
+      setMaintenanceStatus(`Integrity check failed: ${e}`);
+    } finally {
+      setMaintenanceBusy(false);
+    }
+  }
+
+  async function handleBackup() {
+    const dest = await save({
+      title: 'Save Database Backup',
+      defaultPath: `ynotv-backup-${new Date().toISOString().slice(0, 10)}.db`,
+      filters: [{ name: 'SQLite Database', extensions: ['db'] }],
+    });
+    if (!dest) return;
+
+    setMaintenanceBusy(true);
+    setMaintenanceStatus('Backing up database...');
+    try {
+      const size = await invoke<number>('backup_database', { destPath: dest });
+      setMaintenanceStatus(`Backup saved (${formatBytes(size)})`);
+    } catch (e) {
+      setMaintenanceStatus(`Backup failed: ${e}`);
+    } finally {
+      setMaintenanceBusy(false);
+    }
+  }
+
+  async function handleRestore() {
+    const src = await open({
+      title: 'Select Database Backup',
+      multiple: false,
+      filters: [{ name: 'SQLite Database', extensions: ['db'] }],
+    });
+    if (!src || typeof src !== 'string') return;
+
+    setMaintenanceBusy(true);
+    setMaintenanceStatus('Validating backup...');
+    try {
+      await invoke<number>('restore_database', { srcPath: src });
+      setMaintenanceStatus('Backup validated. It will replace the current database the next time the app starts.');
+    } catch (e) {
+      setMaintenanceStatus(`Restore failed: ${e}`);
+    } finally {
+      setMaintenanceBusy(false);
+    }
+  }
+
   return (
     <div className="settings-tab-content">
       <div className="settings-section">
@@ -134,6 +225,40 @@ export function DebugTab({
         )}
       </div>
 
+      <div className="settings-section">
+        <div className="section-header">
+          <h3>Database Maintenance</h3>
+        </div>
+
+        <p className="section-description">
+          Frequent syncs leave unused space behind in the local database.
+          Compacting rebuilds the file to reclaim it; the integrity check
+          verifies the database is not corrupted. Back up regularly to keep
+          your favorites and settings safe.
+        </p>
+
+        <div style={{ display: 'flex', gap: '0.75rem', marginTop: '1rem' }}>
+          <button className="sync-button" onClick={handleVacuum} disabled={maintenanceBusy}>
+            Compact Database
+          </button>
+          <button className="sync-button" onClick={handleIntegrityCheck} disabled={maintenanceBusy}>
+            Check Integrity
+          </button>
+          <button className="sync-button" onClick={handleBackup} disabled={maintenanceBusy}>
+            Back Up
+          </button>
+          <button className="sync-button" onClick={handleRestore} disabled={maintenanceBusy}>
+            Restore
+          </button>
+        </div>
+
+        {maintenanceStatus && (
+          <p className="form-hint" style={{ marginTop: '0.75rem', whiteSpace: 'pre-wrap' }}>
+            {maintenanceStatus}
+          </p>
+        )}
+      </div>
+
       <p className="settings-disclaimer">
         Debug logs may contain sensitive information like stream URLs. Only share
         logs with trusted parties when troubleshooting issues.
@@ -1,11 +1,13 @@
 import { useState, useEffect } from 'react';
-import { getDvrSettings, saveDvrSetting } from '../../db';
+import { getDvrSettings, saveDvrSetting, migrateDvrStorage } from '../../db';
 import { open } from '@tauri-apps/plugin-dialog';
+import './PlaybackTab.css'; // Reuse existing tab styles for toggle
 
 export function DvrTab() {
     const [storagePath, setStoragePath] = useState('');
     const [startPadding, setStartPadding] = useState(60);
     const [endPadding, setEndPadding] = useState(300);
+    const [notificationsEnabled, setNotificationsEnabled] = useState(true);
     const [loading, setLoading] = useState(true);
 
     useEffect(() => {
@@ -19,6 +21,7 @@ export function DvrTab() {
             setStoragePath(settings.storage_path || '');
             setStartPadding(settings.default_start_padding_sec || 60);
             setEndPadding(settings.default_end_padding_sec || 300);
+            setNotificationsEnabled(settings.notifications_enabled !== false);
         } catch (error) {
             console.error('Failed to load DVR settings:', error);
         } finally {
@@ -35,12 +38,17 @@ export function DvrTab() {
             });
 
             if (selected && typeof selected === 'string') {
+                const moveFiles = selected !== storagePath
+                    && confirm('Move existing recordings to the new location?');
+                const info = await migrateDvrStorage(selected, moveFiles);
                 setStoragePath(selected);
-                await saveDvrSetting('storage_path', selected);
+                if (info.free_bytes !== null && info.free_bytes < 10 * 1024 * 1024 * 1024) {
+                    alert(`Only ${(info.free_bytes / (1024 * 1024 * 1024)).toFixed(1)} GB free in this location`);
+                }
             }
         } catch (error) {
             console.error('Failed to select directory:', error);
-            alert('Failed to select directory');
+            alert(`Failed to set storage directory: ${error}`);
         }
     }
 
@@ -54,6 +62,11 @@ export function DvrTab() {
         await saveDvrSetting('default_end_padding_sec', value);
     }
 
+    async function handleNotificationsToggle(enabled: boolean) {
+        setNotificationsEnabled(enabled);
+        await saveDvrSetting('notifications_enabled', enabled);
+    }
+
     const formatDuration = (seconds: number): string => {
         const mins = Math.floor(seconds / 60);
         if (mins < 1) return `${seconds}s`;
@@ -192,6 +205,27 @@ export function DvrTab() {
                     </div>
                 </div>
             </div>
+
+            {/* Notifications */}
+            <div className="settings-section" style={{ paddingTop: '8px', paddingBottom: '8px' }}>
+                <div className="section-header">
+                    <h3>Notifications</h3>
+                </div>
+                <div className="timeshift-toggle-row">
+                    <div className="timeshift-toggle-info">
+                        <span className="timeshift-toggle-label">Recording notifications</span>
+                        <span className="timeshift-toggle-sub">Show a desktop notification when a recording finishes or fails.</span>
+                    </div>
+                    <label className="toggle-switch">
+                        <input
+                            type="checkbox"
+                            checked={notificationsEnabled}
+                            onChange={(e) => handleNotificationsToggle(e.target.checked)}
+                        />
+                        <span className="toggle-slider" />
+                    </label>
+                </div>
+            </div>
         </div>
     );
 }
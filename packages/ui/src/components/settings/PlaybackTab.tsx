@@ -27,11 +27,29 @@ export function PlaybackTab({ mpvParams, mpvDisableWhitelist, onMpvParamsChange,
   const [hasChanges, setHasChanges] = useState(false);
   const [debugInfo, setDebugInfo] = useState<string | null>(null);
   const [showRestartModal, setShowRestartModal] = useState(false);
+  const [configPath, setConfigPath] = useState('');
+  const [configPathStatus, setConfigPathStatus] = useState<string | null>(null);
 
   useEffect(() => {
     setLocalParams(mpvParams);
   }, [mpvParams]);
 
+  useEffect(() => {
+    invoke<{ args: string[]; config_path: string | null }>('get_mpv_extra_args')
+      .then(result => setConfigPath(result.config_path ?? ''))
+      .catch(() => { });
+  }, []);
+
+  const saveConfigPath = async () => {
+    try {
+      const args = mpvParams.split('\n').map(l => l.trim()).filter(l => l && !l.startsWith('#'));
+      await invoke('set_mpv_extra_args', { args, configPath: configPath.trim() || null });
+      setConfigPathStatus('Saved. Takes effect the next time MPV starts.');
+    } catch (e) {
+      setConfigPathStatus(`Error: ${e}`);
+    }
+  };
+
   const handleChange = (value: string) => {
     setLocalParams(value);
     setHasChanges(value !== mpvParams);
@@ -112,6 +130,23 @@ export function PlaybackTab({ mpvParams, mpvDisableWhitelist, onMpvParamsChange,
             spellCheck={false}
           />
 
+          <div className="playback-label">
+            <span>mpv.conf</span>
+            <small>Optional path to your own mpv.conf. Its options are read when MPV starts and filtered like the parameters above.</small>
+          </div>
+          <div className="mpv-config-path">
+            <input
+              type="text"
+              className="mpv-config-path-input"
+              value={configPath}
+              onChange={(e) => { setConfigPath(e.target.value); setConfigPathStatus(null); }}
+              placeholder="C:\Users\me\mpv\mpv.conf"
+              spellCheck={false}
+            />
+            <button className="sync-btn" onClick={saveConfigPath}>Save</button>
+          </div>
+          {configPathStatus && <small className="mpv-config-path-status">{configPathStatus}</small>}
+
           <div className="playback-help">
             <h4>Common Parameters</h4>
             <div className="help-grid">
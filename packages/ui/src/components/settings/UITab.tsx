@@ -1,4 +1,5 @@
 import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 
 interface UITabProps {
@@ -242,6 +243,40 @@ export function UITab({ settings, onSettingsChange }: UITabProps) {
             />
           </div>
         </div>
+
+        {/* Reset Window Geometry */}
+        <div
+          style={{
+            display: 'flex',
+            alignItems: 'center',
+            justifyContent: 'space-between',
+            padding: '0.75rem 0',
+            borderTop: '1px solid rgba(255,255,255,0.08)',
+          }}
+        >
+          <div style={{ flex: 1 }}>
+            <div style={{ color: 'rgba(255,255,255,0.9)', fontSize: '0.95rem' }}>
+              Reset window size and position
+            </div>
+            <div style={{ color: 'rgba(255,255,255,0.5)', fontSize: '0.8rem', marginTop: '0.25rem' }}>
+              Use this if the window opens off-screen or larger than your display.
+            </div>
+          </div>
+          <button
+            className="sync-btn"
+            onClick={async () => {
+              try {
+                await invoke('reset_window_geometry');
+                onSettingsChange({ ...settings, startupWidth: 1280, startupHeight: 720 });
+              } catch (err) {
+                console.error('[UITab] Failed to reset window geometry:', err);
+              }
+            }}
+            style={{ marginLeft: '1rem' }}
+          >
+            Reset
+          </button>
+        </div>
       </div>
 
       {/* Font Size Section */}
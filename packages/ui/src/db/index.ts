@@ -20,6 +20,7 @@ export interface StoredChannel extends Omit<Channel, 'stream_icon' | 'epg_channe
   added?: string;
   stream_type?: string;
   stream_icon?: string;
+  captured_icon?: string;   // Frame captured from the stream, shown when the provider has no logo
   epg_channel_id?: string;
   custom_sid?: string;
   tv_archive?: number;
@@ -115,6 +116,11 @@ export interface StoredProgram {
   start: Date | string;
   end: Date | string;
   source_id: string;
+  season?: number | null;    // 1-based, from XMLTV <episode-num>
+  episode?: number | null;   // 1-based, from XMLTV <episode-num>
+  category?: string | null;  // Comma-separated XMLTV <category> values
+  rating?: string | null;    // First XMLTV <rating><value>
+  icon?: string | null;      // First http(s) XMLTV <icon src>
 }
 
 // EPG channel entry (from XMLTV for fallback matching)
@@ -155,6 +161,8 @@ export interface DvrSchedule {
   series_match_title?: string;    // For future series recording
   recurrence?: 'once' | 'daily' | 'weekly';
 
+  priority?: number;              // Higher wins when the source runs out of connections (default 0)
+
   created_at: number;
   started_at?: number;
 
@@ -179,7 +187,7 @@ export interface DvrRecording {
   actual_end?: number;
   duration_sec?: number;
 
-  status: 'recording' | 'completed' | 'partial' | 'failed';
+  status: 'recording' | 'completed' | 'partial' | 'failed' | 'orphaned';
   error_message?: string;
 
   keep_until?: number;               // Unix timestamp (NULL = forever)
@@ -188,6 +196,12 @@ export interface DvrRecording {
   created_at: number;
 
   thumbnail_path?: string;           // Path to thumbnail image
+
+  part_of?: number;                  // First part's recording id, for later parts of a split recording
+  part_number?: number;              // 1-based part number of a split recording
+  is_protected?: boolean;            // Never removed by automatic cleanup
+  watched_position?: number;         // Last playback position in seconds
+  watched?: boolean;                 // Played to the end or marked watched
 }
 
 // DVR Settings
@@ -196,6 +210,13 @@ export interface DvrSettings {
   value: string;
 }
 
+// Per-source padding override (undefined = use the global default)
+export interface SourcePadding {
+  source_id: string;
+  start_padding_sec?: number | null;
+  end_padding_sec?: number | null;
+}
+
 // Watchlist Item (saved EPG programs)
 export interface WatchlistItem {
   id?: number;
@@ -411,7 +432,7 @@ class YnotvDatabase extends SqliteDatabase {
     // Each version block runs exactly ONCE. To add new columns in the future,
     // increment DB_VERSION and add a new case (do NOT modify existing cases).
     // ─────────────────────────────────────────────────────────────────────────
-    const DB_VERSION = 6;
+    const DB_VERSION = 7;
     const versionResult = await db.select('PRAGMA user_version') as Array<{ user_version: number }>;
     const currentVersion = versionResult[0]?.user_version ?? 0;
 
@@ -468,6 +489,14 @@ class YnotvDatabase extends SqliteDatabase {
         await addColumn('sourcesMeta', 'epg_timeshift_hours', 'REAL DEFAULT 0');
       }
 
+      // v7: Logos captured from the stream live apart from stream_icon, which syncs overwrite
+      if (currentVersion < 7) {
+        const addColumn = async (table: string, col: string, type: string) => {
+          try { await db.execute(`ALTER TABLE ${table} ADD COLUMN ${col} ${type}`); } catch { /* already exists */ }
+        };
+        await addColumn('channels', 'captured_icon', 'TEXT');
+      }
+
       if (currentVersion < 2) {
         // v2: EPG Editor — new override tables and views (safe to run on existing DBs)
         // Tables are created via CREATE TABLE IF NOT EXISTS below, so this block only
@@ -562,6 +591,12 @@ class YnotvDatabase extends SqliteDatabase {
     await db.execute(`CREATE INDEX IF NOT EXISTS idx_programs_source ON programs(source_id)`);
     // Index for fast title search (LIKE queries)
     await db.execute(`CREATE INDEX IF NOT EXISTS idx_programs_title ON programs(title COLLATE NOCASE)`);
+    // Migration: XMLTV episode numbering, categories and ratings
+    try { await db.execute(`ALTER TABLE programs ADD COLUMN season INTEGER`); } catch (e) {}
+    try { await db.execute(`ALTER TABLE programs ADD COLUMN episode INTEGER`); } catch (e) {}
+    try { await db.execute(`ALTER TABLE programs ADD COLUMN category TEXT`); } catch (e) {}
+    try { await db.execute(`ALTER TABLE programs ADD COLUMN rating TEXT`); } catch (e) {}
+    try { await db.execute(`ALTER TABLE programs ADD COLUMN icon TEXT`); } catch (e) {}
 
     // EPG Channels (for fallback matching when tvg-id doesn't match)
     await db.execute(`CREATE TABLE IF NOT EXISTS epg_channels (
@@ -944,7 +979,7 @@ class YnotvDatabase extends SqliteDatabase {
         c.stream_id,
         c.source_id,
         c.name,
-        COALESCE(o.stream_icon,      c.stream_icon)      AS stream_icon,
+        COALESCE(o.stream_icon, NULLIF(c.stream_icon, ''), c.captured_icon) AS stream_icon,
         COALESCE(o.epg_channel_id,   c.epg_channel_id)   AS epg_channel_id,
         c.channel_num,
         c.is_favorite,
@@ -1236,6 +1271,20 @@ export async function getFavoriteChannelCount(): Promise<number> {
 // DVR Functions
 // ============================================================================
 
+export interface DvrInitStatus {
+  available: boolean;
+  error: string | null;  // Why the DVR failed to start, if it did
+}
+
+/** Whether the DVR backend started; DVR commands all fail when it didn't */
+export async function getDvrInitStatus(): Promise<DvrInitStatus> {
+  try {
+    return await invoke<DvrInitStatus>('get_dvr_init_status');
+  } catch (error) {
+    return { available: false, error: String(error) };
+  }
+}
+
 /** Get DVR settings with defaults */
 export async function getDvrSettings(): Promise<Record<string, any>> {
   const settings = await db.dvrSettings.toArray();
@@ -1243,6 +1292,7 @@ export async function getDvrSettings(): Promise<Record<string, any>> {
     storage_path: '',
     max_disk_usage_percent: 80,
     auto_cleanup_enabled: true,
+    notifications_enabled: true,
     default_start_padding_sec: 60,
     default_end_padding_sec: 300,
   };
@@ -1267,13 +1317,34 @@ export async function saveDvrSetting(key: string, value: any): Promise<void> {
   dbEvents.notify('dvr_settings', 'update');
 }
 
+export interface StoragePathInfo {
+  path: string;
+  free_bytes: number | null;
+}
+
+/** Set the recordings directory; rejects if it can't be created or written to */
+export async function setDvrStoragePath(path: string): Promise<StoragePathInfo> {
+  const info = await invoke<StoragePathInfo>('set_storage_path', { path });
+  dbEvents.notify('dvr_settings', 'update');
+  return info;
+}
+
+/**
+ * Switch the recordings directory and optionally move finished recordings there.
+ * Files move in the background; progress arrives as `dvr:storage_migration` events.
+ * The completed event lists `skipped` video files that had no recording and stayed behind.
+ */
+export async function migrateDvrStorage(newPath: string, moveFiles: boolean): Promise<StoragePathInfo> {
+  const info = await invoke<StoragePathInfo>('migrate_storage', { newPath, moveFiles });
+  dbEvents.notify('dvr_settings', 'update');
+  return info;
+}
+
 /** Schedule a recording via Rust backend */
 export async function scheduleRecording(schedule: Omit<DvrSchedule, 'id' | 'created_at' | 'status'>): Promise<number> {
   console.log('[DVR] Scheduling recording:', schedule.program_title, 'at', new Date(schedule.scheduled_start * 1000).toISOString());
 
-  const settings = await getDvrSettings();
-
-  // Call Rust backend to schedule recording
+  // Call Rust backend to schedule recording; omitted paddings fall back to source then global defaults
   const request = {
     source_id: schedule.source_id,
     channel_id: schedule.channel_id,
@@ -1281,8 +1352,8 @@ export async function scheduleRecording(schedule: Omit<DvrSchedule, 'id' | 'crea
     program_title: schedule.program_title,
     scheduled_start: schedule.scheduled_start,
     scheduled_end: schedule.scheduled_end,
-    start_padding_sec: schedule.start_padding_sec ?? settings.default_start_padding_sec,
-    end_padding_sec: schedule.end_padding_sec ?? settings.default_end_padding_sec,
+    start_padding_sec: schedule.start_padding_sec ?? null,
+    end_padding_sec: schedule.end_padding_sec ?? null,
     series_match_title: schedule.series_match_title,
     recurrence: schedule.recurrence,
     stream_url: schedule.stream_url,
@@ -1303,6 +1374,18 @@ export async function scheduleRecording(schedule: Omit<DvrSchedule, 'id' | 'crea
   }
 }
 
+/** Record the currently playing channel starting now */
+export async function recordNow(durationMinutes: number): Promise<number> {
+  try {
+    const id = await invoke<number>('record_now', { durationMinutes });
+    dbEvents.notify('dvr_schedules', 'add');
+    return id;
+  } catch (error) {
+    console.error('[DVR] Failed to start record now:', error);
+    throw error;
+  }
+}
+
 /** Cancel a scheduled recording */
 export async function cancelRecording(scheduleId: number): Promise<void> {
   console.log('[DVR] Canceling recording:', scheduleId);
@@ -1323,6 +1406,130 @@ export async function cancelRecording(scheduleId: number): Promise<void> {
   console.log('[DVR] Recording canceled:', scheduleId);
 }
 
+/** Pause an in-progress recording (FFmpeg stops; resuming records into a new part) */
+export async function pauseRecording(scheduleId: number): Promise<void> {
+  await invoke('pause_recording', { id: scheduleId });
+}
+
+/** Resume a paused recording */
+export async function resumeRecording(scheduleId: number): Promise<void> {
+  await invoke('resume_recording', { id: scheduleId });
+}
+
+/** Stop an in-progress recording now, keeping what was recorded as a completed recording */
+export async function stopAndKeepRecording(scheduleId: number): Promise<void> {
+  await invoke('stop_and_keep_recording', { id: scheduleId });
+}
+
+export interface StreamProbe {
+  format: string | null;
+  bitrate: number | null;  // bits per second
+  video: {
+    codec: string;
+    width: number | null;
+    height: number | null;
+    frame_rate: number | null;
+    bitrate: number | null;
+  } | null;
+  audio: {
+    codec: string;
+    channels: number | null;
+    sample_rate: number | null;
+    language: string | null;
+    bitrate: number | null;
+  }[];
+}
+
+/** Probe a stream URL, or a channel's resolved URL, for codec/resolution/bitrate details */
+export async function probeStream(
+  target: { url: string } | { sourceId: string; channelId: string }
+): Promise<StreamProbe> {
+  return invoke<StreamProbe>('probe_stream', target);
+}
+
+/** Result of a short test recording of a channel */
+export interface TestRecordingResult {
+  success: boolean;
+  seconds: number;
+  file_size: number;
+  probe: StreamProbe | null;
+  error: string | null;
+}
+
+/** Record a short clip of a channel, probe it and delete it, to check the channel is recordable */
+export async function testRecording(sourceId: string, channelId: string, seconds?: number): Promise<TestRecordingResult> {
+  return invoke<TestRecordingResult>('test_recording', { sourceId, channelId, seconds });
+}
+
+/** FFmpeg output captured while a recording ran, to show why it failed */
+export async function getRecordingLog(id: number): Promise<string> {
+  return invoke<string>('get_recording_log', { id });
+}
+
+/** Protect a recording from automatic cleanup, or remove the protection */
+export async function setRecordingProtected(id: number, isProtected: boolean): Promise<void> {
+  await invoke('set_recording_protected', { id, protected: isProtected });
+  dbEvents.notify('dvr_recordings', 'update');
+}
+
+export interface BulkRecordingDeletion {
+  deleted: number;
+  failed: { id: number; error: string }[];
+  /** Video files of deleted recordings that could not be removed */
+  undeleted_files: { id: number; path: string; error: string }[];
+}
+
+/** Delete several recordings at once; in-progress ones are stopped first */
+export async function bulkDeleteRecordings(ids: number[]): Promise<BulkRecordingDeletion> {
+  const result = await invoke<BulkRecordingDeletion>('bulk_delete_recordings', { ids });
+  dbEvents.notify('dvr_recordings', 'delete');
+  return result;
+}
+
+/**
+ * Save a copy of a recording to a directory or file path outside the library.
+ * remuxTo ('mp4' | 'mkv') rewraps without re-encoding. Progress arrives as dvr:export_progress.
+ * Returns the path written.
+ */
+export async function exportRecording(id: number, destPath: string, remuxTo?: 'mp4' | 'mkv'): Promise<string> {
+  return invoke<string>('export_recording', { id, destPath, remuxTo });
+}
+
+/** Mark a recording watched (or unwatched, which also resets its position) */
+export async function setRecordingWatched(id: number, watched: boolean): Promise<void> {
+  await invoke('set_recording_watched', { id, watched });
+  dbEvents.notify('dvr_recordings', 'update');
+}
+
+/** Save how far a recording has been played (MPV playback saves this automatically) */
+export async function updateRecordingWatchPosition(id: number, position: number, duration?: number): Promise<void> {
+  await invoke('update_recording_watch_position', { id, position, duration });
+}
+
+/** Recompute a recording's size and duration from its file (fixes 0 MB / wrong length after a crash) */
+export async function repairRecordingMetadata(recordingId: number): Promise<DvrRecording> {
+  const recording = await invoke<DvrRecording>('repair_recording_metadata', { recordingId });
+  dbEvents.notify('dvr_recordings', 'update');
+  return recording;
+}
+
+/** Join recordings (in the given order) into one file; returns the new recording id */
+export async function concatRecordings(recordingIds: number[]): Promise<number> {
+  const id = await invoke<number>('concat_recording', { recordingIds });
+  dbEvents.notify('dvr_recordings', 'update');
+  return id;
+}
+
+/**
+ * Cut a recording to startSeconds..endSeconds (start snaps back to the previous keyframe).
+ * keepOriginal adds the trimmed copy as a new recording instead of replacing it. Returns its ID.
+ */
+export async function trimRecording(id: number, startSeconds: number, endSeconds: number, keepOriginal = false): Promise<number> {
+  const trimmedId = await invoke<number>('trim_recording', { id, startSeconds, endSeconds, keepOriginal });
+  dbEvents.notify('dvr_recordings', 'update');
+  return trimmedId;
+}
+
 /** Delete a recording file and DB entry */
 export async function deleteRecording(recordingId: number): Promise<void> {
   await db.dvrRecordings.delete(recordingId);
@@ -1345,13 +1552,73 @@ export async function getCompletedRecordings(): Promise<DvrRecording[]> {
     .sort((a, b) => (b.created_at || 0) - (a.created_at || 0));
 }
 
+export type RecordingSort = 'date_desc' | 'date_asc' | 'size_desc' | 'size_asc' | 'channel' | 'title';
+
+export interface RecordingQuery {
+  status?: DvrRecording['status'];
+  channelName?: string;
+  fromTs?: number;
+  toTs?: number;
+  sort?: RecordingSort;
+  limit?: number;
+  offset?: number;
+}
+
+export interface RecordingPage {
+  recordings: DvrRecording[];
+  total: number;
+}
+
+/** Filtered, sorted page of recordings straight from the backend (for large libraries) */
+export async function queryRecordings(query: RecordingQuery = {}): Promise<RecordingPage> {
+  return await invoke<RecordingPage>('query_recordings', { ...query });
+}
+
+export interface RecordingsDiskUsage {
+  recordings_bytes: number;
+  recording_count: number;
+  volume: {
+    total_bytes: number;
+    available_bytes: number;
+    used_bytes: number;
+    usage_percent: number;
+  };
+}
+
+/** Space used by finished recordings and the storage volume's totals */
+export async function getRecordingsDiskUsage(): Promise<RecordingsDiskUsage> {
+  return await invoke<RecordingsDiskUsage>('get_recordings_disk_usage');
+}
+
+export interface QuotaCleanupReport {
+  dry_run: boolean;
+  usage_percent: number;
+  max_usage_percent: number;
+  bytes_to_free: number;
+  bytes_freed: number;
+  deletions: { recording_id: number; program_title: string; channel_name: string; size_bytes: number }[];
+}
+
+/** Free space down to max_disk_usage_percent; with dryRun, only report what would be deleted */
+export async function enforceDiskQuota(dryRun = false): Promise<QuotaCleanupReport> {
+  const report = await invoke<QuotaCleanupReport>('enforce_disk_quota', { dryRun });
+  if (!dryRun && report.deletions.length > 0) {
+    dbEvents.notify('dvr_recordings', 'delete');
+  }
+  return report;
+}
+
 /** Get active recordings with live progress from backend */
 export interface RecordingProgress {
   schedule_id: number;
   recording_id: number;
   channel_name: string;
   program_title: string;
-  elapsed_seconds: number;
+  elapsed_seconds: number;   // Wall-clock time since the recording started
+  recorded_seconds: number;  // Media time FFmpeg has actually written
+  size_bytes: number;
+  paused: boolean;
+  queued: boolean;           // Waiting for a connection; not recording yet
   scheduled_duration: number;
 }
 
@@ -1365,6 +1632,37 @@ export async function getActiveRecordings(): Promise<RecordingProgress[]> {
   }
 }
 
+export async function getRecordingStatus(scheduleId: number): Promise<RecordingProgress | null> {
+  try {
+    return await invoke<RecordingProgress | null>('get_recording_status', { scheduleId });
+  } catch (error) {
+    console.error('[DVR] Failed to get recording status:', error);
+    return null;
+  }
+}
+
+/** Recordings that are due but waiting for their source to free a connection */
+export interface QueuedRecording {
+  schedule_id: number;
+  source_id: string;
+  channel_name: string;
+  program_title: string;
+  scheduled_start: number;
+  scheduled_end: number;
+  priority: number;
+  position: number;
+}
+
+export async function getQueuedRecordings(): Promise<QueuedRecording[]> {
+  try {
+    const result = await invoke<QueuedRecording[]>('get_queued_recordings');
+    return result || [];
+  } catch (error) {
+    console.error('[DVR] Failed to get queued recordings:', error);
+    return [];
+  }
+}
+
 /** Get thumbnail image data for a recording */
 export async function getRecordingThumbnail(recordingId: number): Promise<Uint8Array | null> {
   try {
@@ -1377,6 +1675,28 @@ export async function getRecordingThumbnail(recordingId: number): Promise<Uint8A
   }
 }
 
+/** Set a schedule's priority for connection contention (higher wins) */
+export async function setSchedulePriority(scheduleId: number, priority: number): Promise<void> {
+  await invoke('set_schedule_priority', { id: scheduleId, priority });
+  await db.dvrSchedules.update(scheduleId, { priority });
+  dbEvents.notify('dvr_schedules', 'update');
+}
+
+/** Get a source's padding override */
+export async function getSourcePadding(sourceId: string): Promise<SourcePadding> {
+  return invoke<SourcePadding>('get_source_padding', { sourceId });
+}
+
+/** Set or clear (null) a source's padding override */
+export async function setSourcePadding(
+  sourceId: string,
+  startPaddingSec: number | null,
+  endPaddingSec: number | null
+): Promise<void> {
+  await invoke('set_source_padding', { sourceId, startPaddingSec, endPaddingSec });
+  dbEvents.notify('dvr_settings', 'update');
+}
+
 /** Update schedule padding times */
 export async function updateSchedulePaddings(
   scheduleId: number,
@@ -1408,7 +1728,16 @@ export async function updateSchedulePaddings(
 }
 
 /** Detect conflicts for a new schedule - uses backend for comprehensive checking */
-export async function detectScheduleConflicts(schedule: Omit<DvrSchedule, 'id' | 'created_at' | 'status'>): Promise<{ hasConflict: boolean; conflicts: DvrSchedule[]; message?: string }> {
+export interface ScheduleConflictSuggestions {
+  /** Earliest start (same duration) with a free connection on the source */
+  suggestedStart?: number;
+  /** Overlapping schedules with lower priority; one would be preempted */
+  preemptableScheduleIds?: number[];
+  /** Lowest priority at which this recording would preempt an overlapping one */
+  priorityToPreempt?: number;
+}
+
+export async function detectScheduleConflicts(schedule: Omit<DvrSchedule, 'id' | 'created_at' | 'status'>): Promise<{ hasConflict: boolean; conflicts: DvrSchedule[]; message?: string } & ScheduleConflictSuggestions> {
   try {
     // Use backend command for comprehensive conflict checking including viewing conflicts
     const result = await invoke('check_schedule_conflicts', {
@@ -1416,12 +1745,23 @@ export async function detectScheduleConflicts(schedule: Omit<DvrSchedule, 'id' |
       channelId: schedule.channel_id,
       start: schedule.scheduled_start,
       end: schedule.scheduled_end,
-    }) as { has_conflict: boolean; conflicts: DvrSchedule[]; message?: string };
+      priority: schedule.priority,
+    }) as {
+      has_conflict: boolean;
+      conflicts: DvrSchedule[];
+      message?: string;
+      suggested_start?: number | null;
+      preemptable_schedule_ids?: number[];
+      priority_to_preempt?: number | null;
+    };
 
     return {
       hasConflict: result.has_conflict,
       conflicts: result.conflicts || [],
-      message: result.message
+      message: result.message,
+      suggestedStart: result.suggested_start ?? undefined,
+      preemptableScheduleIds: result.preemptable_schedule_ids ?? [],
+      priorityToPreempt: result.priority_to_preempt ?? undefined,
     };
   } catch (error) {
     console.error('[DVR] Failed to check conflicts via backend, falling back to local check:', error);
@@ -280,6 +280,17 @@ export class SqliteTable<T, TKey> {
         return this.db;
     }
 
+    // Upsert clause for an insert of `keys`. Unlike INSERT OR REPLACE this updates the row in
+    // place, so UPDATE triggers (e.g. the channel search index) fire instead of a silent delete.
+    private upsertClause(keys: string[]): string {
+        const updates = keys
+            .filter(key => key !== this.primaryKey)
+            .map(key => `${key} = excluded.${key}`);
+        return updates.length > 0
+            ? `ON CONFLICT(${this.primaryKey}) DO UPDATE SET ${updates.join(', ')}`
+            : `ON CONFLICT(${this.primaryKey}) DO NOTHING`;
+    }
+
 
     async toArray(): Promise<T[]> {
         const db = await this.getDb();
@@ -332,7 +343,6 @@ export class SqliteTable<T, TKey> {
 
     async put(item: T): Promise<TKey> {
         return writeLock.run(async () => {
-            // Upsert is dialect specific. SQLite supports INSERT OR REPLACE
             const db = await this.getDb();
             const keys = Object.keys(item as any);
             const placeholders = keys.map((_, i) => `$${i + 1}`).join(',');
@@ -340,7 +350,7 @@ export class SqliteTable<T, TKey> {
             const columns = keys.join(',');
 
             await db.execute(
-                `INSERT OR REPLACE INTO ${this.tableName} (${columns}) VALUES (${placeholders})`,
+                `INSERT INTO ${this.tableName} (${columns}) VALUES (${placeholders}) ${this.upsertClause(keys)}`,
                 values
             );
 
@@ -433,7 +443,7 @@ export class SqliteTable<T, TKey> {
         }
     }
 
-    private async nativeBulkInsert(items: T[], operation: 'insert' | 'replace'): Promise<void> {
+    private async nativeBulkInsert(items: T[], operation: 'insert' | 'replace' | 'upsert'): Promise<void> {
         // Get all unique keys from ALL items, not just the first one
         const keysSet = new Set<string>();
         for (const item of items) {
@@ -464,7 +474,9 @@ export class SqliteTable<T, TKey> {
                 table: this.tableName,
                 columns: keys,
                 rows,
-                operation
+                operation,
+                // upsert updates existing rows in place instead of deleting them like replace
+                conflict_columns: operation === 'upsert' ? [this.primaryKey] : []
             }
         });
     }
@@ -532,7 +544,7 @@ export class SqliteTable<T, TKey> {
                 }
             }
 
-            const sql = `INSERT OR REPLACE INTO ${this.tableName} (${columns}) VALUES ${rowPlaceholders.join(',')}`;
+            const sql = `INSERT INTO ${this.tableName} (${columns}) VALUES ${rowPlaceholders.join(',')} ${this.upsertClause(keys)}`;
             await db.execute(sql, allValues);
         }
     }
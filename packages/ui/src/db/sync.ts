@@ -660,6 +660,7 @@ async function syncEpgFromUrlLegacy(
       start: p.start instanceof Date ? p.start.toISOString() : p.start,
       end: p.end instanceof Date ? p.end.toISOString() : p.end,
       source_id: p.source_id,
+      icon: p.icon,
     }));
 
     await bulkOps.replacePrograms(source.id, bulkPrograms);
@@ -833,7 +834,8 @@ async function syncEpgForStalker(source: Source, channels: Channel[]): Promise<n
       description: p.description || '',
       start: p.start instanceof Date ? p.start.toISOString() : p.start,
       end: p.end instanceof Date ? p.end.toISOString() : p.end,
-      source_id: p.source_id
+      source_id: p.source_id,
+      icon: p.icon
     }));
 
     const result = await bulkOps.replacePrograms(source.id, bulkPrograms);
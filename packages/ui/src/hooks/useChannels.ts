@@ -6,6 +6,7 @@ import { getRecentChannels, onRecentChannelsUpdate } from '../utils/recentChanne
 import { useState, useEffect, useCallback, useRef, useMemo } from 'react';
 import { useSourceVersion } from '../contexts/SourceVersionContext';
 import { applyFilterWords } from './useFilterWords';
+import { searchChannels } from '../services/db-queries';
 import type { Source } from '@ynotv/core';
 
 // Hook to get enabled source IDs (for filtering data from disabled sources)
@@ -335,6 +336,11 @@ export function useChannels(categoryId: string | null, sortOrder: 'alphabetical'
         }));
       }
 
+      // Fall back to a logo captured from the stream when the provider has none
+      results = results.map(ch =>
+        !ch.stream_icon && ch.captured_icon ? { ...ch, stream_icon: ch.captured_icon } : ch
+      );
+
       // Apply logo overrides from epg_channel_overrides so the guide shows
       // the user-set channel icon without needing a full sync.
       try {
@@ -546,22 +552,13 @@ export function useChannelSearch(query: string, limit = 50, includeSourceInSearc
           [...wordLikeParams, ...sourceNameMatches, ...sourceIdsList, ...Array.from(enabledCategoryIds), limit]
         );
       } else {
-        // Multi-word AND search — each word must appear somewhere in the channel name
-        const orderByClause = order === 'alphabetical' ? 'ORDER BY c.name COLLATE NOCASE ASC' : '';
-        console.log('[useChannelSearch] Building query with orderByClause:', orderByClause);
-        const queryStr = `SELECT DISTINCT c.*
-           FROM channels c
-           CROSS JOIN json_each(c.category_ids) AS cat
-           WHERE (${wordLikeClauses})
-           AND c.source_id IN (${sourcePlaceholders})
-           AND (c.enabled IS NULL OR c.enabled != 0)
-           AND cat.value IN (${categoryPlaceholders})
-           ${orderByClause}
-           LIMIT ?`;
-        console.log('[useChannelSearch] Full query:', queryStr);
-        filteredChannels = await dbInstance.select(
-          queryStr,
-          [...wordLikeParams, ...sourceIdsList, ...Array.from(enabledCategoryIds), limit]
+        // Full-text search in the backend — each word must prefix a word of the channel name
+        filteredChannels = await searchChannels(
+          query,
+          order === 'alphabetical' ? 'alpha_asc' : 'relevance',
+          limit,
+          0,
+          sourceIdsList
         );
       }
 
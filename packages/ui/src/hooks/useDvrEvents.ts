@@ -2,7 +2,9 @@
  * useDvrEvents.ts
  *
  * Subscribes to the Tauri `dvr:event` channel and logs recording lifecycle
- * events (started / completed / failed). Previously a useEffect in App.tsx.
+ * events (started / completed / failed). Desktop notifications are shown
+ * natively by the backend; `dvr:notify` is only logged here. Previously a
+ * useEffect in App.tsx.
  *
  * This intentionally does NO state management — it is a pure side-effect
  * listener so the caller doesn't need to handle any returned values.
@@ -22,6 +24,23 @@ interface DvrEventPayload {
     message?: string;
 }
 
+interface DvrNotifyPayload {
+    outcome: 'completed' | 'partial' | 'failed';
+    schedule_id: number;
+    recording_id: number;
+    program_title: string;
+    channel_name: string;
+    size_bytes: number;
+    message: string;
+}
+
+interface DvrHttpErrorPayload {
+    schedule_id: number;
+    recording_id: number;
+    code: number;
+    message: string;
+}
+
 /**
  * Registers a `dvr:event` listener for the lifetime of the component.
  *
@@ -48,7 +67,23 @@ export function useDvrEvents(): void {
                     }
                 });
 
-                unlistenFn = unlisten;
+                // Sent only when notifications_enabled is on in the DVR settings
+                const unlistenNotify = await listen<DvrNotifyPayload>('dvr:notify', (event) => {
+                    const data = event.payload;
+                    console.log(`[DVR] Notification (${data.outcome}): ${data.message}`);
+                });
+
+                // 401/403 mean credentials or provider blocking, not a network problem
+                const unlistenHttpError = await listen<DvrHttpErrorPayload>('dvr:http_error', (event) => {
+                    const data = event.payload;
+                    console.error(`[DVR] HTTP ${data.code} for recording #${data.recording_id}: ${data.message}`);
+                });
+
+                unlistenFn = () => {
+                    unlisten();
+                    unlistenNotify();
+                    unlistenHttpError();
+                };
             } catch (error) {
                 console.error('[useDvrEvents] Failed to setup DVR listener:', error);
             }
@@ -1,5 +1,5 @@
 import { useState, useEffect, useRef } from 'react';
-import type { MpvStatus } from '../types/app';
+import type { MpvStatus, MpvMediaInfo } from '../types/app';
 import { Bridge } from '../services/tauri-bridge';
 
 export interface MpvState {
@@ -39,6 +39,7 @@ export function useMpvListeners(options: UseMpvListenersOptions = {}) {
     const [position, setPosition] = useState(0);
     const [duration, setDuration] = useState(0);
     const [error, setError] = useState<string | null>(null);
+    const [mediaInfo, setMediaInfo] = useState<MpvMediaInfo | null>(null);
 
     const volumeDraggingRef = useRef(false);
     const seekingRef = useRef(false);
@@ -82,6 +83,13 @@ export function useMpvListeners(options: UseMpvListenersOptions = {}) {
                 if (status.duration !== undefined) setDuration(status.duration);
             });
 
+            // Stays null where the backend doesn't report stream details
+            const unlistenMediaInfo = Bridge.supportsMediaInfo()
+                ? await listen('mpv-media-info', (e: any) => {
+                    setMediaInfo(e.payload as MpvMediaInfo);
+                })
+                : () => { };
+
             const unlistenError = await listen('mpv-error', (e: any) => {
                 const err: string = e.payload;
                 setError(prev => {
@@ -108,14 +116,25 @@ export function useMpvListeners(options: UseMpvListenersOptions = {}) {
                 setError(prev => prev ? prev : e.payload);
             });
 
+            const unlistenDecodeError = await listen('mpv-decode-error', (e: any) => {
+                console.warn('[MPV] Hardware decode error:', e.payload);
+                setError(prev => prev ? prev : 'Hardware decoding failed. Try setting hardware decoding to "no" in Settings > Playback.');
+            });
+
             unlistenFns = [
-                unlistenReady, unlistenStatus, unlistenError,
-                unlistenHttpError, unlistenEndFileError,
+                unlistenReady, unlistenStatus, unlistenMediaInfo, unlistenError,
+                unlistenHttpError, unlistenEndFileError, unlistenDecodeError,
             ];
 
             // Init MPV after listeners are registered to catch the ready event
             // Pass timeshift settings from frontend state (already loaded from store)
-            Bridge.initMpv(options.timeshiftEnabled, options.timeshiftCacheBytes);
+            Bridge.initMpv(options.timeshiftEnabled, options.timeshiftCacheBytes)
+                .then(() => Bridge.isMpvReady())
+                .then(state => {
+                    // mpv-ready fires once per connection; if it was emitted before we listened, catch up here
+                    if (state.connected) setMpvReady(true);
+                })
+                .catch(() => { });
         });
 
         return () => { unlistenFns.forEach(fn => fn()); };
@@ -126,7 +145,7 @@ export function useMpvListeners(options: UseMpvListenersOptions = {}) {
     ]); // Re-run when settings load or change
 
     return {
-        mpvReady, playing, volume, muted, position, duration, error,
+        mpvReady, playing, volume, muted, position, duration, error, mediaInfo,
         volumeDraggingRef, seekingRef,
         setError, setPlaying, setPosition, setVolume, setMuted,
         setDuration, setMpvReady,
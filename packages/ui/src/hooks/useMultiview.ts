@@ -1,5 +1,6 @@
 import { useState, useCallback, useRef, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 export type LayoutMode = 'main' | 'pip' | '2x2' | 'bigbottom';
 
@@ -11,6 +12,15 @@ export interface ViewerSlot {
     active: boolean;
 }
 
+/** Playback state of a secondary slot, from the `mpv-slot-status` event */
+export interface SlotStatus {
+    slot_id: number;
+    paused: boolean;
+    /** Not advancing playback: paused, buffering or stalled */
+    idle: boolean;
+    position: number;
+}
+
 export interface MainSlot {
     channelName: string | null;
     channelUrl: string | null;
@@ -132,9 +142,19 @@ export function useMultiview() {
     const savedStateRef = useRef<{ layout: LayoutMode; slots: ViewerSlot[] } | null>(null);
     const isTabModeRef = useRef(false);
 
+    const [slotStatus, setSlotStatus] = useState<Record<number, SlotStatus>>({});
+
     useEffect(() => { layoutRef.current = layout; }, [layout]);
     useEffect(() => { slotsRef.current = slots; }, [slots]);
 
+    // Secondary MPVs report pause/idle/position so tiles can show playing vs stalled
+    useEffect(() => {
+        const unlisten = listen<SlotStatus>('mpv-slot-status', (event) => {
+            setSlotStatus(prev => ({ ...prev, [event.payload.slot_id]: event.payload }));
+        });
+        return () => { unlisten.then(fn => fn()).catch(() => { }); };
+    }, []);
+
     /** Resize primary MPV HWND to match the current layout mode */
     const syncMpvGeometry = useCallback(async (mode?: LayoutMode) => {
         // Do not enforce multiview quadrant geometry if we are currently inside a full-screen Tab!
@@ -463,6 +483,15 @@ export function useMultiview() {
         }
     }, []);
 
+    /** Hear one tile (1 = main player); the backend mutes every other slot */
+    const setAudioFocus = useCallback(async (slotId: number) => {
+        try {
+            await invoke('multiview_set_audio_focus', { slotId });
+        } catch (e) {
+            // Ignore audio focus errors
+        }
+    }, []);
+
     /** Enter tab mode: push secondary MPVs off-screen to keep them buffering/playing */
     const enterTabMode = useCallback(async (tabName?: string) => {
         if (isTabModeRef.current) return;
@@ -545,12 +574,14 @@ export function useMultiview() {
     return {
         layout,
         slots,
+        slotStatus,
         visibleSlots: slots.filter(s => (visibleSlotIds as number[]).includes(s.id)),
         switchLayout,
         sendToSlot,
         swapWithMain,
         stopSlot,
         setSlotProperty,
+        setAudioFocus,
         repositionSecondarySlots,
         notifyMainLoaded,
         syncMpvGeometry,
@@ -5,7 +5,7 @@ import type { VodPlayInfo } from '../types/media';
 import { Bridge, registerOnAppClose, unregisterOnAppClose } from '../services/tauri-bridge';
 import { resolvePlayUrl } from '../services/stream-resolver';
 import { addToRecentChannels } from '../utils/recentChannels';
-import { db, recordVodWatch, updateVodWatchProgress, getVodWatchProgress, recordEpisodeWatch, getEpisodeProgress } from '../db';
+import { db, recordVodWatch, updateVodWatchProgress, getVodWatchProgress, recordEpisodeWatch, getEpisodeProgress, updateRecordingWatchPosition } from '../db';
 import type { useMpvListeners } from './useMpvListeners';
 import { logInfo, logWarn, logError } from '../utils/logger';
 
@@ -84,7 +84,8 @@ async function tryLoadWithFallbacks(
   primaryUrl: string,
   isLive: boolean,
   userAgent?: string,
-  onError?: (msg: string) => void
+  onError?: (msg: string) => void,
+  watch?: Parameters<typeof Bridge.loadVideo>[1]
 ): Promise<{ success: boolean; url: string; error?: string }> {
   logInfo('[Playback] Setting User-Agent:', userAgent || '(using default)');
 
@@ -97,7 +98,7 @@ async function tryLoadWithFallbacks(
   }
 
   logInfo('[Playback] Loading URL:', primaryUrl);
-  const result = await Bridge.loadVideo(primaryUrl);
+  const result = await Bridge.loadVideo(primaryUrl, watch);
 
   if (result.success) {
     logInfo('[Playback] Successfully loaded:', primaryUrl);
@@ -114,7 +115,7 @@ async function tryLoadWithFallbacks(
 
   for (const fallbackUrl of fallbacks) {
     logInfo('[Playback] Trying fallback:', fallbackUrl);
-    const fallbackResult = await Bridge.loadVideo(fallbackUrl);
+    const fallbackResult = await Bridge.loadVideo(fallbackUrl, watch);
     if (fallbackResult.success) {
       logInfo('[Playback] Fallback succeeded:', fallbackUrl);
       return { success: true, url: fallbackUrl };
@@ -280,6 +281,15 @@ export function usePlayback(options: UsePlaybackOptions): PlaybackState {
         ? `${currentVodInfo.source_id}_${currentVodInfo.url}`
         : null);
       
+      // Recordings are tracked by the player where it can (see Bridge.loadVideo)
+      if (currentVodInfo.type === 'recording') {
+        if (!Bridge.supportsWatchTracking() && currentVodInfo.mediaId && currentPosition > 0) {
+          updateRecordingWatchPosition(Number(currentVodInfo.mediaId), currentPosition, currentDuration || undefined)
+            .catch(e => logWarn('[Playback] Failed to save recording position:', e));
+        }
+        return;
+      }
+
       if (mediaId && currentVodInfo.type !== 'recording' && currentPosition > 0) {
         console.log('[Playback] Auto-saving progress:', Math.floor(currentPosition), '/', Math.floor(currentDuration));
         
@@ -415,6 +425,7 @@ export function usePlayback(options: UsePlaybackOptions): PlaybackState {
       // causing the new stream to load but not start playing.
       Bridge.play().catch(e => console.warn('[usePlayback] play() after load failed:', e));
       notifyMainLoaded?.(channel.name, result.url, resolved.sourceName ?? null);
+      Bridge.applyChannelAspect(channel.stream_id).catch(e => console.warn('[usePlayback] applyChannelAspect failed:', e));
 
       import('../services/video-metadata').then(({ captureAndSaveMetadata }) => {
         captureAndSaveMetadata(channel.stream_id, channel.source_id).catch(console.error);
@@ -587,7 +598,14 @@ export function usePlayback(options: UsePlaybackOptions): PlaybackState {
     const isLocal = isLocalUrl(resolved.url);
     setIgnoreHttpErrors(isStalker || isLocal);
 
-    const result = await tryLoadWithFallbacks(resolved.url, false, resolved.userAgent);
+    // The player also saves progress into the same watch history entry the auto-save
+    // below uses (series-level for episodes)
+    const historyId = info.mediaId || (info.source_id && info.url ? `${info.source_id}_${info.url}` : null);
+    const watch = historyId && info.type !== 'recording'
+      ? { vodMediaId: info.type === 'series' ? historyId.split('_ep_')[0] : historyId, vodMediaType: info.type }
+      : undefined;
+
+    const result = await tryLoadWithFallbacks(resolved.url, false, resolved.userAgent, undefined, watch);
     if (!result.success) {
       setIgnoreHttpErrors(false);
       setError(result.error ?? 'Failed to load stream');
@@ -691,7 +709,7 @@ export function usePlayback(options: UsePlaybackOptions): PlaybackState {
 
     try {
       const url = recording.file_path.startsWith('file://') ? recording.file_path : `file://${recording.file_path}`;
-      const result = await Bridge.loadVideo(url);
+      const result = await Bridge.loadVideo(url, { recordingId: recording.id, resume: true });
 
       if (result.success) {
         setCurrentChannel({
@@ -708,9 +726,19 @@ export function usePlayback(options: UsePlaybackOptions): PlaybackState {
           url: url,
           type: 'recording',
           source_id: 'dvr',
+          mediaId: String(recording.id),
         });
         setCatchupInfo(null);
         setPlaying(true);
+
+        // The player resumes itself when it tracks progress; otherwise seek once it has loaded
+        const resumeAt = recording.watched_position ?? 0;
+        if (!Bridge.supportsWatchTracking() && !recording.watched && resumeAt > 10) {
+          setPosition(resumeAt);
+          setTimeout(() => {
+            Bridge.seek(resumeAt).catch(e => logWarn('[Playback] Recording resume seek failed:', e));
+          }, 1000);
+        }
         // Close DVR dashboard when playing
         onCloseView?.();
       } else {
@@ -720,7 +748,7 @@ export function usePlayback(options: UsePlaybackOptions): PlaybackState {
     } catch (error: any) {
       setError(error?.message || 'Failed to play recording');
     }
-  }, []);
+  }, [setPosition]);
 
   const handleStop = useCallback(async () => {
     // Save progress before stopping if playing VOD
@@ -28,6 +28,29 @@ export async function healthCheck(): Promise<boolean> {
   }
 }
 
+export interface ComponentHealth {
+  ok: boolean;
+  message: string;
+}
+
+export interface HealthStatus {
+  healthy: boolean;
+  database: ComponentHealth;
+  ffmpeg: ComponentHealth;
+  storage: ComponentHealth;
+  mpv: ComponentHealth;
+}
+
+// Per-component diagnostics (database, FFmpeg, storage, MPV)
+export async function getHealthStatus(): Promise<HealthStatus | null> {
+  try {
+    return await invoke<HealthStatus>('health_check');
+  } catch (e) {
+    console.error('[BulkOps] Health check failed:', e);
+    return null;
+  }
+}
+
 // Type definitions matching Rust structures
 export interface BulkChannel {
   stream_id: string;
@@ -69,6 +92,7 @@ export interface BulkProgram {
   start: string; // ISO 8601 datetime
   end: string;
   source_id: string;
+  icon?: string | null; // Programme artwork URL from XMLTV <icon src>
 }
 
 export interface BulkMovie {
@@ -134,6 +158,8 @@ export interface BulkResult {
   updated: number;
   deleted: number;
   duration_ms: number;
+  inserted_ids?: string[];
+  updated_ids?: string[];
 }
 
 export interface SourceMetaUpdate {
@@ -160,7 +186,7 @@ export interface SourceMetaUpdate {
  * Upsert channels in bulk
  * Uses a single IPC call for all channels
  */
-export async function upsertChannels(channels: BulkChannel[]): Promise<BulkResult> {
+export async function upsertChannels(channels: BulkChannel[], returnIds = false): Promise<BulkResult> {
   if (channels.length === 0) {
     return { inserted: 0, updated: 0, deleted: 0, duration_ms: 0 };
   }
@@ -181,7 +207,8 @@ export async function upsertChannels(channels: BulkChannel[]): Promise<BulkResul
     }));
 
     const result = await invoke<BulkResult>('bulk_upsert_channels', {
-      channels: serializedChannels
+      channels: serializedChannels,
+      returnIds
     });
 
     console.timeEnd(timerName);
@@ -247,11 +274,14 @@ export async function upsertCategories(categories: BulkCategory[]): Promise<Bulk
 
 /**
  * Replace all EPG programs for a source
- * Deletes existing programs and inserts new ones in a single transaction
+ * Deletes existing programs and inserts new ones in a single transaction.
+ * In 'merge' mode programs are upserted instead and only ones past the
+ * retention window are pruned, so existing (catch-up) data is kept.
  */
 export async function replacePrograms(
   sourceId: string,
-  programs: BulkProgram[]
+  programs: BulkProgram[],
+  mode: 'replace' | 'merge' = 'replace'
 ): Promise<BulkResult> {
   if (programs.length === 0) {
     return { inserted: 0, updated: 0, deleted: 0, duration_ms: 0 };
@@ -263,17 +293,23 @@ export async function replacePrograms(
   try {
     const result = await invoke<BulkResult>('bulk_replace_programs', {
       sourceId,
-      programs
+      programs,
+      mode
     });
 
     console.timeEnd(timerName);
     console.log(`[BulkOps] Programs: ${result.deleted} deleted, ${result.inserted} inserted in ${result.duration_ms}ms`);
 
     // Notify UI of changes
-    dbEvents.notify('programs', 'clear');
+    if (mode === 'replace') {
+      dbEvents.notify('programs', 'clear');
+    }
     if (result.inserted > 0) {
       dbEvents.notify('programs', 'add');
     }
+    if (mode === 'merge' && (result.updated > 0 || result.deleted > 0)) {
+      dbEvents.notify('programs', 'update');
+    }
 
     return result;
   } catch (error) {
@@ -367,6 +403,30 @@ export async function upsertSeries(series: BulkSeries[]): Promise<BulkResult> {
   }
 }
 
+export interface ChannelDiff {
+  to_insert: BulkChannel[];
+  to_update: BulkChannel[];
+  to_delete: string[];
+  unchanged: number;
+}
+
+/**
+ * Compare a provider's channel list against the stored channels for a source.
+ * Feed the result to upsertChannels/deleteChannels to write only what changed.
+ */
+export async function diffChannels(sourceId: string, incoming: BulkChannel[]): Promise<ChannelDiff> {
+  const serialized = incoming.map(ch => ({
+    ...ch,
+    category_ids: Array.isArray(ch.category_ids)
+      ? JSON.stringify(ch.category_ids)
+      : ch.category_ids,
+  }));
+
+  const diff = await invoke<ChannelDiff>('diff_channels', { sourceId, incoming: serialized });
+  console.log(`[BulkOps] Channel diff: ${diff.to_insert.length} new, ${diff.to_update.length} changed, ${diff.to_delete.length} removed, ${diff.unchanged} unchanged`);
+  return diff;
+}
+
 /**
  * Delete channels by stream_id
  */
@@ -499,12 +559,14 @@ export const bulkOps = {
   replacePrograms,
   upsertMovies,
   upsertSeries,
+  diffChannels,
   deleteChannels,
   deleteCategories,
   updateSourceMeta,
   applySyncChanges,
   logPerformance,
   healthCheck,
+  getHealthStatus,
 };
 
 export default bulkOps;
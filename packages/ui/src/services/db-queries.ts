@@ -0,0 +1,26 @@
+/**
+ * Native Database Queries
+ *
+ * Thin wrappers around the Rust-side read commands. These run the SQL in the
+ * backend and return only the requested page, so large playlists never have
+ * to cross IPC in full.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import type { StoredChannel } from '../db';
+
+export type ChannelSort = 'alpha_asc' | 'alpha_desc' | 'channel_num' | 'recently_added' | 'relevance';
+
+/**
+ * Full-text search over channel names. Every word must match (as a prefix).
+ * Only channels in enabled categories are returned, and only from `sourceIds` when given.
+ */
+export async function searchChannels(
+  query: string,
+  sort: ChannelSort = 'relevance',
+  limit = 100,
+  offset = 0,
+  sourceIds?: string[]
+): Promise<StoredChannel[]> {
+  return invoke<StoredChannel[]>('search_channels', { query, sourceIds, sort, limit, offset });
+}
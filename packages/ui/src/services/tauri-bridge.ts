@@ -178,15 +178,38 @@ export const Bridge = {
         }
     },
 
-    async loadVideo(url: string) {
+    /**
+     * Load a URL. Pass vodMediaId/vodMediaType (the VOD's watch history entry) or
+     * recordingId (DVR recording) to save watch progress while playing, and resume to
+     * continue where it left off. Only the Windows player tracks progress; elsewhere
+     * the watch options are dropped and usePlayback's periodic save covers VOD.
+     */
+    async loadVideo(url: string, watch?: { vodMediaId?: string; vodMediaType?: 'movie' | 'series'; recordingId?: number; resume?: boolean }) {
         try {
-            await invoke('mpv_load', { url });
+            const tracked = Bridge.supportsWatchTracking() ? watch : undefined;
+            await invoke('mpv_load', {
+                url,
+                vodMediaId: tracked?.vodMediaId,
+                vodMediaType: tracked?.vodMediaType,
+                recordingId: tracked?.recordingId,
+                resume: tracked?.resume,
+            });
             return { success: true };
         } catch (e: any) {
             return { success: false, error: typeof e === 'string' ? e : e.message || 'Unknown error' };
         }
     },
 
+    /** Whether the player saves watch progress itself (mpv_load refuses it on macOS) */
+    supportsWatchTracking(): boolean {
+        return !navigator.platform.toLowerCase().includes('mac');
+    },
+
+    /** Whether the player emits mpv-media-info (the macOS backend only polls status) */
+    supportsMediaInfo(): boolean {
+        return !navigator.platform.toLowerCase().includes('mac');
+    },
+
     async play() {
         return invoke('mpv_play');
     },
@@ -231,6 +254,86 @@ export const Bridge = {
         return invoke('mpv_toggle_fullscreen');
     },
 
+    /** Deinterlace on/off, or 'auto' to only deinterlace interlaced frames */
+    async setDeinterlace(enabled: boolean | 'auto') {
+        return invoke('mpv_set_deinterlace', { enabled });
+    },
+
+    async getDeinterlace(): Promise<'yes' | 'no' | 'auto'> {
+        return invoke('mpv_get_deinterlace');
+    },
+
+    /** Force aspect ratio ('16:9', '4:3', '-1' = auto); saved for the channel if one is given */
+    async setAspect(ratio: string, channelId?: string) {
+        return invoke('mpv_set_aspect', { ratio, channelId });
+    },
+
+    /** Crop black bars (0 = off, 1 = fill); saved for the channel if one is given */
+    async setPanScan(value: number, channelId?: string) {
+        return invoke('mpv_set_pan_scan', { value, channelId });
+    },
+
+    async resetAspect(channelId?: string) {
+        return invoke('mpv_reset_aspect', { channelId });
+    },
+
+    /** Apply a channel's saved aspect/pan-scan, or auto if it has none */
+    async applyChannelAspect(channelId: string): Promise<{ aspect: string; pan_scan: number }> {
+        return invoke('mpv_apply_channel_aspect', { channelId });
+    },
+
+    /** Save the playing frame as the logo of a channel that has none; returns the new logo URL */
+    async captureChannelLogo(streamId: string, delayMs?: number): Promise<string | null> {
+        return invoke('capture_channel_logo', { streamId, delayMs });
+    },
+
+    /** Load this URL when the current file ends cleanly (next episode); null cancels */
+    async setNextOnEof(url: string | null) {
+        return invoke('set_next_on_eof', { url });
+    },
+
+    /** Queue a URL after the current playlist entry (starts it if nothing is playing) */
+    async playlistAppend(url: string) {
+        return invoke('mpv_playlist_append', { url });
+    },
+
+    async playlistClear() {
+        return invoke('mpv_playlist_clear');
+    },
+
+    async playlistNext() {
+        return invoke('mpv_playlist_next');
+    },
+
+    async playlistPrev() {
+        return invoke('mpv_playlist_prev');
+    },
+
+    async getPlaylist(): Promise<{ filename: string; title?: string; current?: boolean; playing?: boolean }[]> {
+        const result = await invoke('mpv_get_playlist');
+        return result as any[] || [];
+    },
+
+    async getWatchProgress(mediaId: string, mediaType: 'movie' | 'series'): Promise<{ media_id: string; media_type: string; position: number; duration: number; watched: boolean } | null> {
+        return invoke('get_watch_progress', { mediaId, mediaType });
+    },
+
+    async getChapters(): Promise<{ chapters: { index: number; title: string | null; time: number }[]; current: number | null }> {
+        return invoke('mpv_get_chapters');
+    },
+
+    async chapterNext() {
+        return invoke('mpv_chapter_next');
+    },
+
+    async chapterPrev() {
+        return invoke('mpv_chapter_prev');
+    },
+
+    async setChapter(index: number) {
+        return invoke('mpv_set_chapter', { index });
+    },
+
     async getTrackList(): Promise<any[]> {
         const result = await invoke('mpv_get_track_list');
         return result as any[] || [];
@@ -257,6 +360,16 @@ export const Bridge = {
         return invoke('mpv_get_property', { name });
     },
 
+    /** Whether MPV is running, connected over IPC, and has a file loaded */
+    async isMpvReady(): Promise<{ running: boolean; connected: boolean; file_loaded: boolean }> {
+        return invoke('mpv_is_ready');
+    },
+
+    /** Switch hardware decoding ('auto-safe', 'no', 'nvdec', 'd3d11va', ...) and remember it */
+    async setHwdec(mode: string) {
+        return invoke('mpv_set_hwdec', { mode });
+    },
+
     // Window Controls
     async minimize() {
         console.log('[Bridge] minimize called');
@@ -25,6 +25,7 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
 /// Cache statistics
 export interface CacheStats {
@@ -38,12 +39,23 @@ export interface CacheStats {
   series_age_hours: number;
 }
 
+/// Download/index progress emitted while a cache is rebuilt
+export interface TmdbCacheProgress {
+  kind: 'movies' | 'series';
+  phase: 'downloading' | 'complete';
+  bytes_downloaded: number;
+  total_bytes: number | null;
+  entries_indexed: number;
+}
+
 /// Match result from TMDB lookup
 export interface MatchResult {
   tmdb_id: number;
   title: string;
   year: number | null;
   score: number; // 0.0-1.0 match confidence
+  poster_path: string | null;
+  backdrop_path: string | null;
 }
 
 /// TMDB Movie entry
@@ -100,6 +112,32 @@ export async function getTmdbCacheStats(): Promise<CacheStats> {
   };
 }
 
+/**
+ * Subscribe to TMDB cache download/index progress
+ *
+ * @param callback - Called with each progress update
+ * @returns Function that removes the listener
+ */
+export async function onTmdbCacheProgress(
+  callback: (progress: TmdbCacheProgress) => void
+): Promise<UnlistenFn> {
+  return listen<TmdbCacheProgress>('tmdb:cache_progress', (event) => callback(event.payload));
+}
+
+/**
+ * Point the cache at custom TMDB export URLs (e.g. a mirror)
+ * Pass null/empty to restore the default. Takes effect on the next cache update.
+ *
+ * @param moviesUrl - Movies export URL (JSON lines)
+ * @param seriesUrl - TV series export URL (JSON lines)
+ */
+export async function setTmdbExportUrls(
+  moviesUrl: string | null,
+  seriesUrl: string | null
+): Promise<void> {
+  await invoke('set_tmdb_export_urls', { moviesUrl, seriesUrl });
+}
+
 /**
  * Update TMDB movies cache
  * Downloads and indexes the latest TMDB movies export
@@ -189,34 +227,66 @@ export async function clearTmdbCache(): Promise<void> {
  * Find movies by title
  *
  * @param title - Movie title to search for
+ * @param year - Optional release year used to rank candidates
+ * @param minScore - Drop matches scoring below this (backend default 0.8)
  * @returns Array of match results sorted by relevance
  */
-export async function findTmdbMovies(title: string): Promise<MatchResult[]> {
+export async function findTmdbMovies(
+  title: string,
+  year?: number,
+  minScore?: number
+): Promise<MatchResult[]> {
   if (!title || title.trim().length === 0) {
     return [];
   }
 
   const results = await invoke<MatchResult[]>('find_tmdb_movies', {
     title: title.trim(),
+    year: year ?? null,
+    minScore: minScore ?? null,
   });
 
   // Sort by score (highest first)
   return results.sort((a, b) => b.score - a.score);
 }
 
+/**
+ * Find a movie by IMDB id (exact, full-confidence match)
+ *
+ * @param imdbId - IMDB id such as "tt0133093"
+ * @returns Match or null if the id isn't in the cache
+ */
+export async function findTmdbMovieByImdb(imdbId: string): Promise<MatchResult | null> {
+  if (!imdbId || imdbId.trim().length === 0) {
+    return null;
+  }
+
+  return invoke<MatchResult | null>('find_tmdb_movie_by_imdb', {
+    imdbId: imdbId.trim(),
+  });
+}
+
 /**
  * Find TV series by title
  *
  * @param title - Series title to search for
+ * @param year - Optional release year used to rank candidates
+ * @param minScore - Drop matches scoring below this (backend default 0.8)
  * @returns Array of match results sorted by relevance
  */
-export async function findTmdbSeries(title: string): Promise<MatchResult[]> {
+export async function findTmdbSeries(
+  title: string,
+  year?: number,
+  minScore?: number
+): Promise<MatchResult[]> {
   if (!title || title.trim().length === 0) {
     return [];
   }
 
   const results = await invoke<MatchResult[]>('find_tmdb_series', {
     title: title.trim(),
+    year: year ?? null,
+    minScore: minScore ?? null,
   });
 
   // Sort by score (highest first)
@@ -228,11 +298,20 @@ export async function findTmdbSeries(title: string): Promise<MatchResult[]> {
  * Returns the highest-scoring match or null if no matches
  *
  * @param title - Movie title to search for
+ * @param imdbId - Optional IMDB id, tried before title matching
  * @returns Best match or null
  */
 export async function findBestMovieMatch(
-  title: string
+  title: string,
+  imdbId?: string | null
 ): Promise<MatchResult | null> {
+  if (imdbId) {
+    const byImdb = await findTmdbMovieByImdb(imdbId);
+    if (byImdb) {
+      return byImdb;
+    }
+  }
+
   const matches = await findTmdbMovies(title);
   return matches.length > 0 ? matches[0] : null;
 }
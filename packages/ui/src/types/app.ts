@@ -67,6 +67,7 @@ export interface AppSettings {
     startupWidth?: number;
     startupHeight?: number;
     dontSaveWindowSizeOnClose?: boolean;
+    alwaysOnTop?: boolean;
     rememberLastChannels?: boolean;
     savedLayoutState?: SavedLayoutState;
     searchResultsOrder?: 'default' | 'alphabetical';
@@ -82,3 +83,12 @@ export interface MpvStatus {
     pause?: boolean;
     Idle?: boolean;
 }
+
+/** Stream details for the current file (from the mpv-media-info event; not reported on macOS) */
+export interface MpvMediaInfo {
+    width: number | null;
+    height: number | null;
+    video_codec: string | null;
+    audio_codec: string | null;
+    video_bitrate: number | null;
+}